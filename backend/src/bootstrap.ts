@@ -0,0 +1,45 @@
+/**
+ * Startup configuration. Imported before anything else by the server entry
+ * point so settings from a config file are in the environment before other
+ * modules read them.
+ */
+
+import { cpSync, existsSync } from "fs";
+import { join } from "path";
+import {
+  applyConfig,
+  checkDataDir,
+  configFileFromArgs,
+  describeConfig,
+  loadConfig,
+} from "./config";
+import { getBundledDataDir, getDataDir } from "./utils/data-dir";
+
+const args = process.argv.slice(2);
+const config = loadConfig(configFileFromArgs(args));
+applyConfig(config);
+
+const issues = [...config.issues, ...checkDataDir(getDataDir())];
+
+if (args.includes("--check-config")) {
+  console.log(describeConfig(config));
+  for (const issue of issues) {
+    console.error(`✗ ${issue.setting}: ${issue.message}`);
+  }
+  if (issues.length === 0) console.log("Configuration OK");
+  process.exit(issues.length === 0 ? 0 : 1);
+}
+
+if (issues.length > 0) {
+  for (const issue of issues) {
+    console.error(`Invalid configuration: ${issue.setting}: ${issue.message}`);
+  }
+  process.exit(1);
+}
+
+// A fresh data volume starts with the cost libraries bundled with the server
+const libraries = join(getDataDir(), "costing");
+if (!existsSync(libraries) && getDataDir() !== getBundledDataDir()) {
+  console.log(`Seeding ${libraries} with the bundled cost libraries`);
+  cpSync(join(getBundledDataDir(), "costing"), libraries, { recursive: true });
+}
@@ -0,0 +1,269 @@
+/**
+ * cost-library: command-line tools for cost library curation, for use in CI
+ * pipelines and scripts without the HTTP service.
+ *
+ * Usage: npm run cost-library -- <command> [options]
+ *
+ * Exit codes: 0 success, 1 invalid library or differences found, 2 usage error.
+ */
+
+import { readFile, writeFile } from "fs/promises";
+import { basename, dirname, resolve } from "path";
+import { parseArgs } from "util";
+import {
+  importCostLibraryCsv,
+  exportCostLibraryCsv,
+  validateCostLibrary,
+  diffCostLibraries,
+  costLibraryStats,
+  anonymizeCostLibrary,
+  loadCostLibrary,
+  signLibrary,
+  type CostLibrary,
+} from "../services/costing";
+
+const USAGE = `Usage: cost-library <command> [options]
+
+Commands:
+  validate <file.json>          Check a library; lists issues
+  diff <a.json> <b.json>        Compare two libraries
+  stats <file.json>             Summarise a library
+  convert --from <fmt> --to <fmt> <file>
+                                Convert between json and csv
+  import <file.csv>             Same as convert --from csv --to json
+  anonymize <file.json> --vendor <name> [--vendor <name> ...]
+                                Replace vendor names and drop source notes
+  sign <file.json> --key <private.pem> --key-id <id>
+                                Write a detached ed25519 signature
+                                (cost-library.sig.json) for a library
+
+Options:
+  --out <file>      Write output here instead of stdout
+  --base <id>       (csv to json) take currency conversion, inflation and
+                    defaults from this library
+  --delimiter <c>   (csv) field delimiter (default: ",")
+  --json            (validate, diff, stats) print JSON
+  --library <id>    (sign) library ID (default: the file's directory name)
+`;
+
+const OPTIONS = {
+  out: { type: "string" },
+  base: { type: "string" },
+  delimiter: { type: "string" },
+  json: { type: "boolean" },
+  from: { type: "string" },
+  to: { type: "string" },
+  vendor: { type: "string", multiple: true },
+  key: { type: "string" },
+  "key-id": { type: "string" },
+  library: { type: "string" },
+} as const;
+
+type Args = {
+  values: {
+    out?: string;
+    base?: string;
+    delimiter?: string;
+    json?: boolean;
+    from?: string;
+    to?: string;
+    vendor?: string[];
+    key?: string;
+    "key-id"?: string;
+    library?: string;
+  };
+  positionals: string[];
+};
+
+class UsageError extends Error {}
+
+async function readLibrary(file: string): Promise<CostLibrary> {
+  return JSON.parse(await readFile(file, "utf-8")) as CostLibrary;
+}
+
+async function output(text: string, out?: string): Promise<void> {
+  if (out) {
+    await writeFile(out, text);
+  } else {
+    process.stdout.write(text);
+  }
+}
+
+function toJson(value: unknown): string {
+  return JSON.stringify(value, null, 2) + "\n";
+}
+
+async function runValidate({ values, positionals }: Args): Promise<number> {
+  const [file] = positionals;
+  if (!file) throw new UsageError();
+
+  const issues = validateCostLibrary(await readLibrary(file));
+  if (values.json) {
+    await output(toJson({ isValid: issues.length === 0, issues }), values.out);
+  } else {
+    for (const issue of issues) {
+      console.log(`${issue.path}: ${issue.message}`);
+    }
+    console.log(issues.length === 0 ? "OK" : `${issues.length} issue(s)`);
+  }
+  return issues.length === 0 ? 0 : 1;
+}
+
+async function runDiff({ values, positionals }: Args): Promise<number> {
+  const [a, b] = positionals;
+  if (!a || !b) throw new UsageError();
+
+  const diff = diffCostLibraries(await readLibrary(a), await readLibrary(b));
+  if (values.json) {
+    await output(toJson(diff), values.out);
+  } else {
+    const list = (label: string, ids: string[]) => {
+      if (ids.length > 0) console.log(`${label}: ${ids.join(", ")}`);
+    };
+    list("Modules added", diff.modules.added);
+    list("Modules removed", diff.modules.removed);
+    list("Modules changed", diff.modules.changed);
+    list("Cost items added", diff.costItems.added);
+    list("Cost items removed", diff.costItems.removed);
+    for (const { id, fields } of diff.costItems.changed) {
+      console.log(`Cost item ${id} changed: ${fields.join(", ")}`);
+    }
+    list("Currency rates changed", diff.currencyRates);
+    list("Inflation factors changed", diff.inflationFactors);
+    if (diff.identical) console.log("No differences");
+  }
+  return diff.identical ? 0 : 1;
+}
+
+async function runStats({ values, positionals }: Args): Promise<number> {
+  const [file] = positionals;
+  if (!file) throw new UsageError();
+
+  const stats = costLibraryStats(await readLibrary(file));
+  if (values.json) {
+    await output(toJson(stats), values.out);
+  } else {
+    console.log(`Modules: ${stats.moduleCount}`);
+    console.log(`Cost items: ${stats.costItemCount}`);
+    if (stats.costYears) {
+      console.log(`Cost years: ${stats.costYears.min}-${stats.costYears.max}`);
+    }
+    for (const [label, counts] of [
+      ["Modules by type", stats.modulesByType],
+      ["Curve types", stats.curveTypes],
+      ["Currencies", stats.currencies],
+    ] as const) {
+      console.log(`${label}:`);
+      for (const [key, count] of Object.entries(counts)) {
+        console.log(`  ${key}: ${count}`);
+      }
+    }
+    console.log("Capex coverage:");
+    for (const [currency, byYear] of Object.entries(stats.capexCoverage)) {
+      const years = Object.entries(byYear)
+        .map(([year, count]) => `${year} (${count})`)
+        .join(", ");
+      console.log(`  ${currency}: ${years}`);
+    }
+    console.log(`Parameters: ${stats.parameters.join(", ")}`);
+  }
+  return 0;
+}
+
+async function runConvert({ values, positionals }: Args): Promise<number> {
+  const [file] = positionals;
+  const { from, to } = values;
+  if (!file || !from || !to) throw new UsageError();
+
+  if (from === "csv" && to === "json") {
+    const result = importCostLibraryCsv(await readFile(file, "utf-8"), {
+      base: values.base ? await loadCostLibrary(values.base) : undefined,
+      delimiter: values.delimiter,
+    });
+
+    for (const e of result.rowErrors) {
+      const column = e.column ? ` (${e.column})` : "";
+      console.error(`row ${e.row}${column}: ${e.message}`);
+    }
+    for (const issue of result.issues) {
+      const where = issue.row !== undefined ? `row ${issue.row}` : issue.path;
+      console.error(`${where}: ${issue.message}`);
+    }
+    if (!result.isValid) return 1;
+
+    await output(toJson(result.library), values.out);
+    return 0;
+  }
+
+  if (from === "json" && to === "csv") {
+    const csv = exportCostLibraryCsv(await readLibrary(file), values.delimiter);
+    await output(csv, values.out);
+    return 0;
+  }
+
+  throw new UsageError(`Cannot convert from ${from} to ${to}`);
+}
+
+async function runAnonymize({ values, positionals }: Args): Promise<number> {
+  const [file] = positionals;
+  if (!file) throw new UsageError();
+
+  const anonymized = anonymizeCostLibrary(await readLibrary(file), {
+    vendors: values.vendor ?? [],
+  });
+  await output(toJson(anonymized), values.out);
+  return 0;
+}
+
+async function runSign({ values, positionals }: Args): Promise<number> {
+  const [file] = positionals;
+  const { key, "key-id": keyId } = values;
+  if (!file || !key || !keyId) throw new UsageError();
+
+  const libraryId = values.library ?? basename(dirname(resolve(file)));
+  const signature = signLibrary(
+    await readFile(file),
+    await readFile(key, "utf-8"),
+    keyId,
+    libraryId,
+  );
+  await output(toJson(signature), values.out);
+  return 0;
+}
+
+const commands: Record<string, (args: Args) => Promise<number>> = {
+  validate: runValidate,
+  diff: runDiff,
+  stats: runStats,
+  convert: runConvert,
+  import: ({ values, positionals }) =>
+    runConvert({ values: { ...values, from: "csv", to: "json" }, positionals }),
+  anonymize: runAnonymize,
+  sign: runSign,
+};
+
+async function main(argv: string[]): Promise<number> {
+  const [command, ...rest] = argv;
+  const run = command ? commands[command] : undefined;
+  try {
+    if (!run) throw new UsageError();
+    return await run(
+      parseArgs({ args: rest, options: OPTIONS, allowPositionals: true }),
+    );
+  } catch (error) {
+    if (error instanceof UsageError) {
+      if (error.message) console.error(error.message);
+      console.error(USAGE);
+      return 2;
+    }
+    throw error;
+  }
+}
+
+main(process.argv.slice(2)).then(
+  (code) => process.exit(code),
+  (error) => {
+    console.error(error instanceof Error ? error.message : error);
+    process.exit(1);
+  },
+);
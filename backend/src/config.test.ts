@@ -0,0 +1,94 @@
+import { describe, it, expect, beforeEach, afterEach } from "vitest";
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { configFileFromArgs, describeConfig, loadConfig } from "./config";
+
+describe("config", () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await mkdtemp(join(tmpdir(), "config-"));
+  });
+
+  afterEach(async () => {
+    await rm(dir, { recursive: true, force: true });
+  });
+
+  async function configFile(content: unknown): Promise<string> {
+    const path = join(dir, "config.json");
+    await writeFile(path, JSON.stringify(content));
+    return path;
+  }
+
+  const valueOf = (config: ReturnType<typeof loadConfig>, env: string) =>
+    config.values.find((v) => v.setting.env === env);
+
+  it("finds the config file from flags or CONFIG_FILE", () => {
+    expect(configFileFromArgs(["--config", "a.json"], {})).toBe("a.json");
+    expect(configFileFromArgs(["--config=b.json"], {})).toBe("b.json");
+    expect(configFileFromArgs([], { CONFIG_FILE: "c.json" })).toBe("c.json");
+    expect(configFileFromArgs([], {})).toBeUndefined();
+  });
+
+  it("lets environment variables override the file", async () => {
+    const file = await configFile({ port: 4000, costingTimeoutMs: 5000 });
+
+    const config = loadConfig(file, { PORT: "5000" });
+
+    expect(valueOf(config, "PORT")).toMatchObject({
+      value: "5000",
+      source: "env",
+    });
+    expect(valueOf(config, "COSTING_TIMEOUT_MS")).toMatchObject({
+      value: "5000",
+      source: "file",
+    });
+    expect(valueOf(config, "SNAPSHOT_USE_MOCK")?.source).toBe("default");
+    expect(config.issues).toEqual([]);
+  });
+
+  it("resolves the data directory relative to the config file", async () => {
+    const file = await configFile({ dataDir: "volume" });
+
+    expect(valueOf(loadConfig(file, {}), "DATA_DIR")?.value).toBe(
+      join(dir, "volume"),
+    );
+  });
+
+  it("reports invalid values, unknown keys and bad auth settings", async () => {
+    const file = await configFile({ port: "eighty", colour: "blue" });
+
+    const config = loadConfig(file, {
+      OIDC_ISSUER: "https://login.example.com",
+      API_KEYS: "ci:owner:key",
+    });
+
+    expect(config.issues.map((i) => i.setting).sort()).toEqual([
+      "API_KEYS",
+      "OIDC",
+      "PORT",
+      "colour",
+    ]);
+  });
+
+  it("rejects a zero costing timeout, prune interval or sweep concurrency", () => {
+    const config = loadConfig(undefined, {
+      COSTING_TIMEOUT_MS: "0",
+      ESTIMATE_PRUNE_INTERVAL_MINUTES: "0",
+      SWEEP_CONCURRENCY: "0",
+    });
+
+    expect(config.issues.map((i) => i.setting).sort()).toEqual([
+      "COSTING_TIMEOUT_MS",
+      "ESTIMATE_PRUNE_INTERVAL_MINUTES",
+      "SWEEP_CONCURRENCY",
+    ]);
+  });
+
+  it("redacts secrets when describing the configuration", () => {
+    const config = loadConfig(undefined, { API_KEYS: "ci:viewer:s3cret" });
+
+    expect(describeConfig(config)).not.toContain("s3cret");
+  });
+});
@@ -0,0 +1,451 @@
+/**
+ * Server configuration.
+ *
+ * Every setting can come from an environment variable or from a JSON
+ * config file passed with `--config <path>` (or CONFIG_FILE). Environment
+ * variables take precedence over the file, so a container image can ship a
+ * config file and override single settings at deploy time. Modules read
+ * their settings from the environment, so a loaded file is applied by
+ * filling in the variables the environment doesn't set.
+ *
+ * `--check-config` validates the resulting configuration, prints it with
+ * secrets redacted, and exits.
+ */
+
+import { accessSync, constants, mkdirSync, readFileSync } from "fs";
+import { dirname, resolve } from "path";
+import { parseApiKeys } from "./services/auth/api-keys";
+import { oidcConfigFromEnv } from "./services/auth/oidc";
+import { parseQuotas } from "./services/usage/quotas";
+import { signaturePolicyFromEnv } from "./services/costing/library-signing";
+import { storageBackendFromEnv } from "./services/storage";
+import {
+  pruneIntervalFromEnv,
+  retentionPolicyFromEnv,
+} from "./services/costing/estimate-retention";
+import { costingTimeoutFromEnv } from "./services/costing/client";
+import { sweepConcurrencyFromEnv } from "./services/costing/sweep";
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+type SettingKind = "string" | "path" | "url" | "integer" | "boolean";
+
+type Setting = {
+  /** Key in the config file */
+  key: string;
+  env: string;
+  kind: SettingKind;
+  default?: string;
+  /** Redacted when the configuration is printed */
+  secret?: boolean;
+  description: string;
+};
+
+export const SETTINGS: Setting[] = [
+  {
+    key: "port",
+    env: "PORT",
+    kind: "integer",
+    default: "3000",
+    description: "Server port",
+  },
+  {
+    key: "dataDir",
+    env: "DATA_DIR",
+    kind: "path",
+    default: "./data",
+    description: "Libraries, drafts, version history and audit log",
+  },
+  {
+    key: "storageBackend",
+    env: "STORAGE_BACKEND",
+    kind: "string",
+    default: "file",
+    description:
+      "Estimates, drafts, history and audit log: file, sqlite or memory",
+  },
+  {
+    key: "estimateRetentionDays",
+    env: "ESTIMATE_RETENTION_DAYS",
+    kind: "integer",
+    description: "Archive saved estimates older than this many days",
+  },
+  {
+    key: "estimateRetentionCount",
+    env: "ESTIMATE_RETENTION_COUNT",
+    kind: "integer",
+    description: "Archive all but this many of the newest saved estimates",
+  },
+  {
+    key: "estimatePurgeDays",
+    env: "ESTIMATE_PURGE_DAYS",
+    kind: "integer",
+    description: "Delete estimates archived more than this many days ago",
+  },
+  {
+    key: "estimatePruneIntervalMinutes",
+    env: "ESTIMATE_PRUNE_INTERVAL_MINUTES",
+    kind: "integer",
+    default: "60",
+    description: "How often the estimate retention policy is applied",
+  },
+  {
+    key: "costingServerUrl",
+    env: "COSTING_SERVER_URL",
+    kind: "url",
+    default: "http://localhost:8080",
+    description: "Costing server",
+  },
+  {
+    key: "costingTimeoutMs",
+    env: "COSTING_TIMEOUT_MS",
+    kind: "integer",
+    default: "120000",
+    description: "Longest an estimate may take",
+  },
+  {
+    key: "sweepConcurrency",
+    env: "SWEEP_CONCURRENCY",
+    kind: "integer",
+    default: "4",
+    description: "Costing server calls a parameter sweep makes at once",
+  },
+  {
+    key: "snapshotServerUrl",
+    env: "SNAPSHOT_SERVER_URL",
+    kind: "url",
+    default: "http://localhost:5000",
+    description: "Scenario Modeller API",
+  },
+  {
+    key: "snapshotUseMock",
+    env: "SNAPSHOT_USE_MOCK",
+    kind: "boolean",
+    default: "true",
+    description: "Serve mock snapshots when the Scenario Modeller is down",
+  },
+  {
+    key: "compressionThreshold",
+    env: "COMPRESSION_THRESHOLD",
+    kind: "integer",
+    default: "1024",
+    description: "Smallest response body to compress, in bytes",
+  },
+  {
+    key: "requestMaxBodyBytes",
+    env: "REQUEST_MAX_BODY_BYTES",
+    kind: "integer",
+    default: "26214400",
+    description: "Largest request body as sent, compressed or not, in bytes",
+  },
+  {
+    key: "requestMaxDecodedBytes",
+    env: "REQUEST_MAX_DECODED_BYTES",
+    kind: "integer",
+    default: "104857600",
+    description: "Largest request body after decompression, in bytes",
+  },
+  {
+    key: "apiKeys",
+    env: "API_KEYS",
+    kind: "string",
+    secret: true,
+    description: "name:role:key entries, comma-separated",
+  },
+  {
+    key: "oidcIssuer",
+    env: "OIDC_ISSUER",
+    kind: "url",
+    description: "OIDC token issuer",
+  },
+  {
+    key: "oidcAudience",
+    env: "OIDC_AUDIENCE",
+    kind: "string",
+    description: "Audience OIDC tokens must be issued for",
+  },
+  {
+    key: "oidcJwksUri",
+    env: "OIDC_JWKS_URI",
+    kind: "url",
+    description: "Issuer signing keys (default: from discovery)",
+  },
+  {
+    key: "oidcRoleClaim",
+    env: "OIDC_ROLE_CLAIM",
+    kind: "string",
+    default: "roles",
+    description: "Token claim holding the caller's roles",
+  },
+  {
+    key: "oidcDefaultRole",
+    env: "OIDC_DEFAULT_ROLE",
+    kind: "string",
+    description: "Role for tokens without a recognised role claim",
+  },
+  {
+    key: "quotas",
+    env: "QUOTAS",
+    kind: "string",
+    description: "Monthly usage quotas per principal, as JSON",
+  },
+  {
+    key: "librarySignatures",
+    env: "LIBRARY_SIGNATURES",
+    kind: "string",
+    default: "verify",
+    description: "Cost library signature policy: off, verify or require",
+  },
+  {
+    key: "librarySigningKeys",
+    env: "LIBRARY_SIGNING_KEYS",
+    kind: "string",
+    description: "Trusted library signing keys, as keyId:publicKey",
+  },
+  {
+    key: "requestLog",
+    env: "REQUEST_LOG",
+    kind: "string",
+    default: "on",
+    description: "Set to off to disable request logging",
+  },
+  {
+    key: "requestLogFile",
+    env: "REQUEST_LOG_FILE",
+    kind: "string",
+    description: "Request log file, relative to the data directory",
+  },
+];
+
+export type ConfigIssue = { setting: string; message: string };
+
+export type LoadedConfig = {
+  /** Config file the settings were read from, if any */
+  file?: string;
+  /** Resolved value and origin of each setting that has a value */
+  values: Array<{
+    setting: Setting;
+    value: string;
+    source: "env" | "file" | "default";
+  }>;
+  issues: ConfigIssue[];
+};
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/**
+ * The config file named by `--config <path>`, `--config=<path>` or
+ * CONFIG_FILE.
+ */
+export function configFileFromArgs(
+  argv: string[],
+  env: Record<string, string | undefined> = process.env,
+): string | undefined {
+  const index = argv.indexOf("--config");
+  if (index !== -1) return argv[index + 1];
+  const inline = argv.find((arg) => arg.startsWith("--config="));
+  return inline?.slice("--config=".length) ?? env.CONFIG_FILE;
+}
+
+function readConfigFile(
+  path: string,
+  issues: ConfigIssue[],
+): Record<string, string> {
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(readFileSync(path, "utf-8"));
+  } catch (error) {
+    issues.push({
+      setting: "config",
+      message: `Cannot read ${path}: ${(error as Error).message}`,
+    });
+    return {};
+  }
+  if (typeof parsed !== "object" || parsed === null || Array.isArray(parsed)) {
+    issues.push({
+      setting: "config",
+      message: `${path} must contain a JSON object`,
+    });
+    return {};
+  }
+
+  const values: Record<string, string> = {};
+  for (const [key, value] of Object.entries(parsed)) {
+    const setting = SETTINGS.find((s) => s.key === key);
+    if (!setting) {
+      issues.push({ setting: key, message: `Unknown setting in ${path}` });
+    } else if (value !== null) {
+      const text = String(value);
+      // Paths in the file are relative to the file, not the working directory
+      values[key] =
+        setting.kind === "path" ? resolve(dirname(path), text) : text;
+    }
+  }
+  return values;
+}
+
+/**
+ * Resolve every setting from the environment, the config file and defaults,
+ * and validate the result.
+ */
+export function loadConfig(
+  file: string | undefined,
+  env: Record<string, string | undefined> = process.env,
+): LoadedConfig {
+  const issues: ConfigIssue[] = [];
+  const fromFile = file ? readConfigFile(resolve(file), issues) : {};
+
+  const values: LoadedConfig["values"] = [];
+  for (const setting of SETTINGS) {
+    const envValue = env[setting.env];
+    if (envValue !== undefined && envValue !== "") {
+      values.push({ setting, value: envValue, source: "env" });
+    } else if (fromFile[setting.key] !== undefined) {
+      values.push({ setting, value: fromFile[setting.key], source: "file" });
+    } else if (setting.default !== undefined) {
+      values.push({ setting, value: setting.default, source: "default" });
+    }
+  }
+
+  issues.push(
+    ...values.flatMap(({ setting, value }) => checkValue(setting, value)),
+    ...checkParsed(
+      Object.fromEntries(values.map((v) => [v.setting.env, v.value])),
+    ),
+  );
+  return { ...(file && { file: resolve(file) }), values, issues };
+}
+
+/**
+ * Set the environment variables for settings taken from the config file.
+ */
+export function applyConfig(config: LoadedConfig): void {
+  for (const { setting, value, source } of config.values) {
+    if (source === "file") {
+      process.env[setting.env] = value;
+    }
+  }
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+function checkValue(setting: Setting, value: string): ConfigIssue[] {
+  const issue = (message: string) => [{ setting: setting.env, message }];
+  switch (setting.kind) {
+    case "integer":
+      return /^\d+$/.test(value) ? [] : issue("must be a non-negative integer");
+    case "boolean":
+      return ["true", "false"].includes(value)
+        ? []
+        : issue("must be true or false");
+    case "url":
+      return URL.canParse(value) ? [] : issue(`"${value}" is not a URL`);
+    default:
+      return [];
+  }
+}
+
+function checkParsed(env: Record<string, string>): ConfigIssue[] {
+  const issues: ConfigIssue[] = [];
+  try {
+    parseApiKeys(env.API_KEYS);
+  } catch (error) {
+    issues.push({ setting: "API_KEYS", message: (error as Error).message });
+  }
+  try {
+    oidcConfigFromEnv(env);
+  } catch (error) {
+    issues.push({ setting: "OIDC", message: (error as Error).message });
+  }
+  try {
+    parseQuotas(env.QUOTAS);
+  } catch (error) {
+    issues.push({ setting: "QUOTAS", message: (error as Error).message });
+  }
+  try {
+    signaturePolicyFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "LIBRARY_SIGNATURES",
+      message: (error as Error).message,
+    });
+  }
+  try {
+    storageBackendFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "STORAGE_BACKEND",
+      message: (error as Error).message,
+    });
+  }
+  try {
+    retentionPolicyFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "ESTIMATE_RETENTION",
+      message: (error as Error).message,
+    });
+  }
+  try {
+    pruneIntervalFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "ESTIMATE_PRUNE_INTERVAL_MINUTES",
+      message: (error as Error).message,
+    });
+  }
+  try {
+    costingTimeoutFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "COSTING_TIMEOUT_MS",
+      message: (error as Error).message,
+    });
+  }
+  try {
+    sweepConcurrencyFromEnv(env);
+  } catch (error) {
+    issues.push({
+      setting: "SWEEP_CONCURRENCY",
+      message: (error as Error).message,
+    });
+  }
+  return issues;
+}
+
+/**
+ * Check that the data directory exists (creating it if needed) and is
+ * writable. Only done by `--check-config` and at startup, not on load, so
+ * loading stays free of side effects.
+ */
+export function checkDataDir(dataDir: string): ConfigIssue[] {
+  try {
+    mkdirSync(dataDir, { recursive: true });
+    accessSync(dataDir, constants.R_OK | constants.W_OK);
+    return [];
+  } catch (error) {
+    return [
+      {
+        setting: "DATA_DIR",
+        message: `${dataDir} is not writable: ${(error as Error).message}`,
+      },
+    ];
+  }
+}
+
+/**
+ * Describe the configuration for `--check-config`, one setting per line.
+ */
+export function describeConfig(config: LoadedConfig): string {
+  const lines = config.values.map(({ setting, value, source }) => {
+    const shown = setting.secret ? "(set)" : value;
+    return `${setting.env.padEnd(22)} ${shown}  [${source}]`;
+  });
+  return [`Config file: ${config.file ?? "(none)"}`, ...lines].join("\n");
+}
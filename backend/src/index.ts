@@ -1,3 +1,5 @@
+// Must be first: applies the config file before other modules read settings
+import "./bootstrap";
 import { serve } from "@hono/node-server";
 import { Hono } from "hono";
 import { cors } from "hono/cors";
@@ -6,7 +8,29 @@ import { networkRoutes } from "./routes/network";
 import { schemaRoutes } from "./routes/schema";
 import { costingRoutes } from "./routes/costing";
 import { snapshotRoutes } from "./routes/snapshot";
+import { auditRoutes } from "./routes/audit";
+import { usageRoutes } from "./routes/usage";
 import dim from "./services/dim";
+import {
+  getLibraryLoadStatus,
+  prewarmLibraries,
+} from "./services/costing/module-lookup";
+import {
+  retentionPolicyFromEnv,
+  startEstimatePruning,
+  pruneIntervalFromEnv,
+} from "./services/costing/estimate-retention";
+import { authenticate } from "./services/auth";
+import { meterUsage } from "./services/usage";
+import { problemJson } from "./utils/problem";
+import { API_VERSIONS, apiVersioning } from "./utils/api-version";
+import { requestLog } from "./utils/request-log";
+import {
+  compressResponses,
+  decompressRequests,
+  getCompressionStats,
+} from "./utils/compression";
+import { SERVICE_NAME, VERSION } from "./version";
 
 const app = new Hono();
 
@@ -16,20 +40,95 @@ dim.init().catch((err) => {
   process.exit(1);
 });
 
+// Load cost libraries in the background; /health reports their progress
+prewarmLibraries().catch((err) => {
+  console.error("Failed to pre-warm cost libraries:", err);
+});
+
+// Archive and purge saved estimates per the ESTIMATE_RETENTION_* settings
+startEstimatePruning(
+  retentionPolicyFromEnv(process.env),
+  pruneIntervalFromEnv(process.env),
+);
+
 // CORS middleware
 app.use("/*", cors());
 
+// Compress large responses
+app.use(
+  "/*",
+  compressResponses({
+    threshold: process.env.COMPRESSION_THRESHOLD
+      ? parseInt(process.env.COMPRESSION_THRESHOLD)
+      : undefined,
+  }),
+);
+
+// Error responses are served as RFC 7807 application/problem+json
+app.use("/*", problemJson());
+
+// Accept gzip, deflate and brotli encoded request bodies
+app.use(
+  "/*",
+  decompressRequests({
+    maxBodyBytes: process.env.REQUEST_MAX_BODY_BYTES
+      ? parseInt(process.env.REQUEST_MAX_BODY_BYTES)
+      : undefined,
+    maxDecodedBytes: process.env.REQUEST_MAX_DECODED_BYTES
+      ? parseInt(process.env.REQUEST_MAX_DECODED_BYTES)
+      : undefined,
+  }),
+);
+
+// Resolve /api/v1, /api/v2 (unprefixed /api is v1) and adapt bodies to
+// the version's contract
+app.use("/api/*", apiVersioning());
+
+// Log API requests with the user that made them, including rejected ones
+app.use("/api/*", requestLog());
+
+// API callers authenticate with an API key or OIDC bearer token when
+// API_KEYS or OIDC_ISSUER is configured
+app.use("/api/*", authenticate());
+
+// Count usage per caller and enforce QUOTAS
+app.use("/api/*", meterUsage());
+
 // Health check
 app.get("/health", (c) => {
-  return c.json({ status: "ok", service: "dagger-api" });
+  return c.json({
+    status: "ok",
+    service: SERVICE_NAME,
+    libraries: getLibraryLoadStatus(),
+    compression: getCompressionStats(),
+  });
 });
 
-// API routes
-app.route("/api/query", queryRoutes);
-app.route("/api/network", networkRoutes);
-app.route("/api/schema", schemaRoutes);
-app.route("/api/operations/costing", costingRoutes);
-app.route("/api/operations/snapshot", snapshotRoutes);
+// Version (used by desktop hosts for compatibility checks)
+app.get("/version", (c) => {
+  return c.json({
+    service: SERVICE_NAME,
+    version: VERSION,
+    apiVersions: Object.fromEntries(
+      Object.entries(API_VERSIONS).map(([v, info]) => [v, info.status]),
+    ),
+  });
+});
+
+// API routes, shared by every API version
+const api = new Hono();
+api.route("/query", queryRoutes);
+api.route("/network", networkRoutes);
+api.route("/schema", schemaRoutes);
+api.route("/operations/costing", costingRoutes);
+api.route("/operations/snapshot", snapshotRoutes);
+api.route("/audit", auditRoutes);
+api.route("/usage", usageRoutes);
+
+for (const version of Object.keys(API_VERSIONS)) {
+  app.route(`/api/${version}`, api);
+}
+app.route("/api", api);
 
 // Export app type for type inference in frontend
 export type App = typeof app;
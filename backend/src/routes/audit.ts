@@ -0,0 +1,81 @@
+import { Hono } from "hono";
+import { requireRole } from "../services/auth";
+import {
+  AUDIT_ACTIONS,
+  isAuditAction,
+  queryAuditLog,
+} from "../services/audit";
+
+export const auditRoutes = new Hono();
+
+// The audit log names users, so it is limited to librarians and admins
+auditRoutes.use("/*", requireRole("librarian"));
+
+/**
+ * GET /api/audit
+ *
+ * List audit log entries, newest first.
+ *
+ * Query params:
+ * - libraryId: Only entries for this library
+ * - user: Only entries made by this principal ID
+ * - action: Only entries with this action (e.g., "library.publish")
+ * - since: ISO timestamp; entries at or after it
+ * - until: ISO timestamp; entries before it
+ * - limit: Maximum number of entries (default: 100, max: 1000)
+ */
+auditRoutes.get("/", async (c) => {
+  const { libraryId, user, action, since, until, limit } = c.req.query();
+
+  if (action !== undefined && !isAuditAction(action)) {
+    return c.json(
+      {
+        error: "Invalid request",
+        message: `action must be one of: ${AUDIT_ACTIONS.join(", ")}`,
+      },
+      400,
+    );
+  }
+  for (const [name, value] of Object.entries({ since, until })) {
+    if (value !== undefined && isNaN(Date.parse(value))) {
+      return c.json(
+        {
+          error: "Invalid request",
+          message: `${name} must be an ISO timestamp`,
+        },
+        400,
+      );
+    }
+  }
+  const parsedLimit = limit === undefined ? 100 : Number(limit);
+  if (!Number.isInteger(parsedLimit) || parsedLimit < 1 || parsedLimit > 1000) {
+    return c.json(
+      {
+        error: "Invalid request",
+        message: "limit must be an integer between 1 and 1000",
+      },
+      400,
+    );
+  }
+
+  try {
+    const entries = await queryAuditLog({
+      libraryId,
+      user,
+      action,
+      since: since && new Date(since).toISOString(),
+      until: until && new Date(until).toISOString(),
+      limit: parsedLimit,
+    });
+    return c.json({ entries });
+  } catch (error) {
+    console.error("Audit log query error:", error);
+    return c.json(
+      {
+        error: "Failed to read audit log",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
@@ -1,23 +1,112 @@
-import { Hono } from "hono";
+import { Hono, type Context } from "hono";
 import { Either } from "effect";
 import {
   transformNetworkToCostingRequest,
   transformCostingResponse,
+  applyThroughputProfiles,
+  applyUtilityPrices,
+  applyScenario,
+  resolveScenario,
+  ScenarioError,
+  type ResolvedScenario,
+  defaultedParameters,
+  inheritedParameters,
   listCostLibraries,
   getModuleLookupService,
+  listEstimatePresets,
+  listModules,
+  parseModuleListingQuery,
+  isModuleListingQuery,
+  negotiateLanguage,
+  importCostLibraryCsv,
+  loadCostLibrary,
+  hashCostLibrary,
+  clearModuleLookupCache,
+  prewarmLibraries,
+  buildCashflow,
+  buildAuditTrail,
+  applyRounding,
+  applyCashflowRounding,
+  applyAccuracyBands,
+  applyFormatting,
+  buildDryRunReport,
+  requestCostEstimate,
+  saveEstimate,
+  getEstimate,
+  getEstimateLibrary,
+  archiveEstimate,
+  unarchiveEstimate,
+  ESTIMATE_SORTS,
+  type EstimateSort,
+  listEstimates,
+  compareEstimates,
+  summarizeEstimateChanges,
+  EstimateChangesError,
+  costItemIdsByAsset,
+  EstimateNotFoundError,
+  applyWhatIf,
+  WhatIfError,
+  migrateEstimateRequest,
+  migrateCostItemIds,
+  checkAssetDependencies,
+  dependencyWarnings,
+  checkThresholds,
+  thresholdWarnings,
+  mergeCbsMappings,
+  RequestMigrationError,
+  estimateTable,
+  tableToTsv,
+  tableToHtml,
+  tableToParquet,
+  isEstimateTableName,
+  EstimateTableError,
+  ESTIMATE_TABLES,
+  runSweep,
+  sweepConcurrencyFromEnv,
+  SweepError,
+  CostingServerError,
+  CostingServerUnavailableError,
+  CostingServerTimeoutError,
+  CostingRequestCancelledError,
+  COSTING_SERVER_URL,
+  realWacc,
+  convertRequestCostParameters,
+  CostParameterError,
+  resolvePriceBase,
+  applyPriceBase,
+  PriceBaseError,
+  type PriceBase,
+  QuantityExpressionError,
+  applyNominalBasis,
+  REAL_PRICE_BASIS,
+  type PriceBasis,
+  type AssetMetadata,
 } from "../services/costing";
 import {
   CostingEstimateRequestSchema,
+  CostingSweepSchema,
+  WhatIfOverridesSchema,
+  EstimateChangesOptionsSchema,
   validateRequest,
   formatValidationErrors,
+  type CostingEstimateRequestInput,
 } from "../services/costing/schemas";
-import type { CostEstimateResponse } from "../services/costing/types";
+import type {
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostLibrary,
+} from "../services/costing/types";
+import type { CostingEstimateResponse } from "../services/costing/request-types";
+import { hasRole, requireRole } from "../services/auth";
+import { recordAudit } from "../services/audit";
+import { recordUsage } from "../services/usage";
+import { notModified } from "../utils/etag";
+import { libraryDraftRoutes } from "./library-drafts";
 
 export const costingRoutes = new Hono();
 
-// Default costing server URL (can be overridden via environment variable)
-const COSTING_SERVER_URL =
-  process.env.COSTING_SERVER_URL || "http://localhost:8080";
+// Library drafts, publishing and version history
+costingRoutes.route("/libraries", libraryDraftRoutes);
 
 /**
  * POST /api/operations/costing/estimate
@@ -25,11 +114,53 @@ const COSTING_SERVER_URL =
  * Run a costing estimate for a network.
  *
  * Request body: CostingEstimateRequest
- * - networkPath: Path to network directory (or preset name like "preset1")
+ * - source: { type: "networkId", networkId } with a preset name or network
+ *   directory path, or { type: "data", network } with inline network data
  * - libraryId: Cost library ID (e.g., "V1.1_working")
  * - targetCurrency: Optional target currency (default: "USD")
  * - assetDefaults: Optional default asset properties
  * - assetOverrides: Optional per-asset property overrides
+ * - globalParameters: Optional cost item parameter values (e.g. electricity
+ *   price) for every block that doesn't set them
+ * - assetParameters: Optional per-asset parameter values, over
+ *   globalParameters
+ * - utilityPrices: Optional per-year utility prices (e.g. electricity) that
+ *   index each year's variable opex against the library's base price
+ * - includeAudit: Optional flag to attach a per-cost-item audit trail
+ * - includeSources: Optional flag to echo source references in the audit
+ * - explainTotals: Optional flag to attach a per-asset breakdown of how
+ *   total installed cost was derived
+ * - topDrivers: Optional number of largest capex and annual opex
+ *   contributors to rank per asset and for the network
+ * - carbonPrice: Optional carbon price by year (and capture rate) to value
+ *   capture assets' avoided emissions and net present value
+ * - discountRates: Optional further discount rates to report net present
+ *   cost at, per asset and for the network
+ * - rounding: Optional rounding policy for monetary outputs
+ * - accuracy: Optional accuracy range overriding preset ranges
+ * - includeContingencyInTotals: Optional flag to add contingency to totals
+ * - thresholds: Optional limits (e.g. total installed cost, any cost line's
+ *   share of capex); each one crossed is reported in the warnings
+ * - financing: Optional gearing, cost of debt and equity, and tenor; the
+ *   WACC is the discount rate for assets that don't set their own
+ * - scenario: Optional exchange rates and inflation to use instead of the
+ *   library's, recorded in each asset's provenance
+ * - priceBaseYear: Optional year to express all costs in, using the
+ *   library's inflation table, recorded in each asset's provenance
+ * - priceBasis: Optional "real" (default) or "nominal" cash flows; nominal
+ *   needs an escalationRate. Discount rates are real either way, and the
+ *   basis is stamped on the response
+ * - locale: Optional locale for formatted headline totals (e.g., "de-DE")
+ * - save: Optional flag to save the estimate as a baseline (estimator role);
+ *   the response then includes its estimateId
+ * - label: Optional label for a saved estimate
+ *
+ * Estimates that take longer than COSTING_TIMEOUT_MS return 408 with
+ * diagnostics, and are abandoned if the client disconnects.
+ *
+ * Query params:
+ * - dryRun: If "true", resolve cost items and report their required
+ *   parameters and the defaults that would apply, without computing costs
  */
 costingRoutes.post("/estimate", async (c) => {
   try {
@@ -41,22 +172,487 @@ costingRoutes.post("/estimate", async (c) => {
       return c.json(formatValidationErrors(parseResult.left), 400);
     }
     const body = parseResult.right;
-    const currency = body.targetCurrency || "USD";
 
-    // Transform network to costing request
-    const { request, assetMetadata } = await transformNetworkToCostingRequest(
+    if (body.save && !hasRole(c.get("principal").role, "estimator")) {
+      return c.json(
+        {
+          error: "Forbidden",
+          message: "Saving estimates requires the estimator role",
+          requiredRole: "estimator",
+        },
+        403,
+      );
+    }
+
+    if (c.req.query("dryRun") === "true") {
+      try {
+        const { assetMetadata } = await transformNetworkToCostingRequest(
+          body.source,
+          "v1.0-costing",
+          {
+            libraryId: body.libraryId,
+            assetDefaults: financedAssetDefaults(body),
+            assetOverrides: body.assetOverrides,
+            globalParameters: body.globalParameters,
+            assetParameters: body.assetParameters,
+            utilityPrices: body.utilityPrices,
+          },
+        );
+        return c.json(buildDryRunReport(assetMetadata, body.libraryId));
+      } catch (error) {
+        if (error instanceof QuantityExpressionError) {
+          return c.json(
+            { error: "Invalid quantity", message: error.message },
+            400,
+          );
+        }
+        throw error;
+      }
+    }
+
+    const run = await runEstimate(c, body);
+    if (!run.ok) {
+      return run.response;
+    }
+
+    const result = await buildEstimateResult(run, body);
+
+    if (body.save) {
+      const stored = await saveEstimate({
+        request: body,
+        result,
+        library: await loadCostLibrary(body.libraryId),
+        costingRequest: run.request,
+        costingResponse: run.costingResponse,
+        assetMetadata: run.assetMetadata,
+        createdBy: c.get("principal").id,
+        label: body.label,
+        labels: body.labels,
+      });
+      return c.json({ ...result, estimateId: stored.id });
+    }
+
+    return c.json(result);
+  } catch (error) {
+    console.error("Costing estimate error:", error);
+    return c.json(
+      {
+        error: "Internal error",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimate/cashflow
+ *
+ * Run a costing estimate and return it as year-aligned arrays for plotting.
+ *
+ * Request body: CostingEstimateRequest (same as /estimate)
+ *
+ * Query params:
+ * - maxPoints: Optional maximum number of points per series. Longer series
+ *   are downsampled by summing consecutive years.
+ *
+ * With `capexBudget` set, the response includes a `funding` section listing
+ * years where portfolio capex exceeds the cap and, if requested, suggested
+ * construction start delays that fit.
+ *
+ * With `financing` set, the response includes a `financing` section with
+ * the debt/equity split, interest during construction and each asset's debt
+ * schedule; the WACC is used as the discount rate.
+ */
+costingRoutes.post("/estimate/cashflow", async (c) => {
+  try {
+    const rawBody = await c.req.json();
+
+    const parseResult = validateRequest(CostingEstimateRequestSchema, rawBody);
+    if (Either.isLeft(parseResult)) {
+      return c.json(formatValidationErrors(parseResult.left), 400);
+    }
+
+    const maxPointsParam = c.req.query("maxPoints");
+    const maxPoints = maxPointsParam ? parseInt(maxPointsParam) : undefined;
+    if (maxPoints !== undefined && (isNaN(maxPoints) || maxPoints < 1)) {
+      return c.json(
+        {
+          error: "Invalid query parameter",
+          message: "maxPoints must be a positive integer",
+        },
+        400,
+      );
+    }
+
+    const body = parseResult.right;
+    const run = await runEstimate(c, body);
+    if (!run.ok) {
+      return run.response;
+    }
+
+    const cashflow = buildCashflow(
+      run.costingResponse,
+      run.assetMetadata,
+      run.currency,
+      {
+        maxPoints,
+        includeContingency: body.includeContingencyInTotals,
+        capexBudget: body.capexBudget,
+        financing: body.financing,
+        timelines: new Map(run.request.assets.map((a) => [a.id, a.timeline])),
+      },
+    );
+    cashflow.priceBasis = run.priceBasis;
+
+    return c.json(
+      body.rounding ? applyCashflowRounding(cashflow, body.rounding) : cashflow,
+    );
+  } catch (error) {
+    console.error("Costing cashflow error:", error);
+    return c.json(
+      {
+        error: "Internal error",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimate/migrate
+ *
+ * Upgrade an estimate request saved in an earlier shape (e.g. by an older
+ * project file) to the current one. Nothing is costed.
+ *
+ * Request body: the saved request
+ *
+ * Item-specific block properties named after cost item IDs the request's
+ * library has since renamed are rewritten to the new IDs.
+ *
+ * Returns the upgraded request, the migrations applied with a warning for
+ * each, and whether the result is now a valid estimate request (with the
+ * validation errors if not).
+ */
+costingRoutes.post("/estimate/migrate", async (c) => {
+  try {
+    const migrated = migrateEstimateRequest(await c.req.json());
+    const { libraryId } = migrated.request;
+    const library =
+      typeof libraryId === "string"
+        ? await loadCostLibrary(libraryId).catch(() => undefined)
+        : undefined;
+    if (library) {
+      const renamed = migrateCostItemIds(migrated.request, library);
+      if (renamed.warnings.length > 0) {
+        migrated.request = renamed.request;
+        migrated.applied.push("cost-item-ids");
+        migrated.warnings.push(...renamed.warnings);
+      }
+    }
+    const validation = validateRequest(
+      CostingEstimateRequestSchema,
+      migrated.request,
+    );
+    return c.json({
+      ...migrated,
+      valid: Either.isRight(validation),
+      ...(Either.isLeft(validation) && {
+        errors: formatValidationErrors(validation.left).details,
+      }),
+    });
+  } catch (error) {
+    if (
+      error instanceof RequestMigrationError ||
+      error instanceof SyntaxError
+    ) {
+      return c.json(
+        { error: "Invalid request body", message: error.message },
+        400,
+      );
+    }
+    console.error("Request migration error:", error);
+    return c.json(
+      {
+        error: "Internal error",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+/**
+ * Build the estimate response from a costing run: audit trail, response
+ * transformation, asset dependency and threshold warnings, rounding, accuracy bands and
+ * formatting as requested.
+ */
+async function buildEstimateResult(
+  run: Extract<EstimateRun, { ok: true }>,
+  body: CostingEstimateRequestInput,
+): Promise<CostingEstimateResponse> {
+  const service = await getModuleLookupService(body.libraryId);
+  const audit = body.includeAudit
+    ? buildAuditTrail(
+        run.request,
+        service,
+        run.currency,
+        {
+          includeSources: body.includeSources,
+          defaultedParameters: defaultedParameters(run.assetMetadata),
+          inheritedParameters: inheritedParameters(run.assetMetadata),
+        },
+      )
+    : undefined;
+
+  // Transform response to our format
+  const result = transformCostingResponse(
+    run.costingResponse,
+    run.assetMetadata,
+    run.currency,
+    {
+      audit,
+      includeContingencyInTotals: body.includeContingencyInTotals,
+      explainTotalInstalledCost: body.explainTotals,
+      topDrivers: body.topDrivers,
+      carbonPrice: body.carbonPrice,
+      discountRates: body.discountRates,
+      timelines: new Map(run.request.assets.map((a) => [a.id, a.timeline])),
+      priceBasis: run.priceBasis,
+      cbsMapping: mergeCbsMappings(service.getCbsMapping(), body.cbsMapping),
+    },
+  );
+
+  const dependencyIssues = checkAssetDependencies(
+    run.request.assets,
+    body.dependencies ?? [],
+  );
+  if (dependencyIssues.length > 0) {
+    result.warnings = [
+      ...(result.warnings ?? []),
+      ...dependencyWarnings(dependencyIssues),
+    ];
+  }
+
+  // Checked before rounding, so values just over a limit still alert
+  const alerts = checkThresholds(result, body.thresholds ?? []);
+  if (alerts.length > 0) {
+    result.warnings = [
+      ...(result.warnings ?? []),
+      ...thresholdWarnings(alerts, result.currency),
+    ];
+  }
+
+  result.priceBasis = run.priceBasis;
+
+  const rounded = body.rounding ? applyRounding(result, body.rounding) : result;
+
+  const banded = applyAccuracyBands(rounded, body.accuracy, body.rounding);
+
+  return body.locale ? applyFormatting(banded, body.locale) : banded;
+}
+
+/**
+ * POST /api/operations/costing/estimate/sweep
+ *
+ * Cost the network over a grid of values for one or two cost item
+ * parameters, for plotting cost curves and finding minima. Grid points are
+ * costed in parallel (SWEEP_CONCURRENCY at a time).
+ *
+ * Request body: CostingEstimateRequest plus
+ * - sweep: One or two axes, each with
+ *   - parameter: Cost item parameter name (e.g., "Diameter"), in library units
+ *   - values: Values to use, or from/to/step for a range
+ *   - assetIds: Optional assets to vary the parameter on (default: all)
+ *
+ * Returns the total, total installed cost and net present cost at each grid
+ * point (varying the last axis fastest) and the point with the lowest total.
+ * Sweeps are limited to MAX_SWEEP_POINTS points.
+ */
+costingRoutes.post("/estimate/sweep", async (c) => {
+  try {
+    const rawBody = await c.req.json();
+
+    const parseResult = validateRequest(CostingEstimateRequestSchema, rawBody);
+    if (Either.isLeft(parseResult)) {
+      return c.json(formatValidationErrors(parseResult.left), 400);
+    }
+    const sweepResult = validateRequest(CostingSweepSchema, rawBody);
+    if (Either.isLeft(sweepResult)) {
+      return c.json(formatValidationErrors(sweepResult.left), 400);
+    }
+    const body = parseResult.right;
+
+    const prepared = await prepareEstimate(c, body);
+    if (!prepared.ok) {
+      return prepared.response;
+    }
+    const { request, assetMetadata, currency } = prepared;
+
+    try {
+      const result = await runSweep(
+        request,
+        sweepResult.right.sweep,
+        async (pointRequest) => {
+          const { costingResponse } = adjustCostingResponse(
+            prepared,
+            pointRequest,
+            await callCostingServer(
+              c,
+              pointRequest,
+              assetMetadata,
+              body.libraryId,
+              currency,
+            ),
+          );
+          const { lifetimeCosts, lifetimeNpcCosts } = transformCostingResponse(
+            costingResponse,
+            assetMetadata,
+            currency,
+            { includeContingencyInTotals: body.includeContingencyInTotals },
+          );
+          return {
+            total: lifetimeCosts.total,
+            totalInstalledCost: lifetimeCosts.totalInstalledCost,
+            netPresentCost: lifetimeNpcCosts.total,
+          };
+        },
+        { concurrency: sweepConcurrencyFromEnv() },
+      );
+      return c.json({ currency, ...result });
+    } catch (error) {
+      if (error instanceof SweepError) {
+        return c.json({ error: "Invalid sweep", message: error.message }, 400);
+      }
+      const response = costingErrorResponse(c, error, request);
+      if (response) {
+        return response;
+      }
+      throw error;
+    }
+  } catch (error) {
+    console.error("Costing sweep error:", error);
+    return c.json(
+      {
+        error: "Internal error",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+type EstimateRun =
+  | {
+      ok: true;
+      request: CostEstimateRequest;
+      costingResponse: CostEstimateResponse;
+      assetMetadata: AssetMetadata[];
+      currency: string;
+      priceBasis: PriceBasis;
+    }
+  | { ok: false; response: Response };
+
+type PreparedEstimate =
+  | {
+      ok: true;
+      request: CostEstimateRequest;
+      assetMetadata: AssetMetadata[];
+      currency: string;
+      scenario?: ResolvedScenario;
+      priceBase?: PriceBase;
+      priceBasis: PriceBasis;
+    }
+  | { ok: false; response: Response };
+
+/**
+ * The request's asset defaults, with the financing's WACC as the discount
+ * rate when it has one. Discount rates are real, so on a nominal basis the
+ * escalation is taken out of the WACC's market rates.
+ */
+function financedAssetDefaults(body: CostingEstimateRequestInput) {
+  const escalationRate =
+    body.priceBasis === "nominal" ? (body.escalationRate ?? 0) : 0;
+  return body.financing
+    ? {
+        ...body.assetDefaults,
+        discount_rate: realWacc(body.financing, escalationRate),
+      }
+    : body.assetDefaults;
+}
+
+/**
+ * Transform the network into a costing server request, convert its cost
+ * parameters into the estimate currency and check the request's scenario,
+ * price base year and price basis against the library. A saved estimate's
+ * library snapshot can be given to check them against instead of the
+ * library as currently published.
+ * Returns an error response if a block's quantity expression is invalid, a
+ * block breaks its module's cost item constraints, nothing in the network
+ * can be costed, the estimate currency or a cost parameter's currency can't
+ * be converted, or the scenario, price base or price basis can't be applied.
+ */
+async function prepareEstimate(
+  c: Context,
+  body: CostingEstimateRequestInput,
+  snapshot?: CostLibrary,
+): Promise<PreparedEstimate> {
+  const currency = body.targetCurrency || "USD";
+  const library = async () => snapshot ?? loadCostLibrary(body.libraryId);
+
+  // Transform network to costing request
+  let transformed: Awaited<ReturnType<typeof transformNetworkToCostingRequest>>;
+  try {
+    transformed = await transformNetworkToCostingRequest(
       body.source,
       "v1.0-costing",
       {
         libraryId: body.libraryId,
-        assetDefaults: body.assetDefaults,
+        assetDefaults: financedAssetDefaults(body),
         assetOverrides: body.assetOverrides,
+        globalParameters: body.globalParameters,
+        assetParameters: body.assetParameters,
+        utilityPrices: body.utilityPrices,
       },
     );
+  } catch (error) {
+    if (error instanceof QuantityExpressionError) {
+      return {
+        ok: false,
+        response: c.json(
+          { error: "Invalid quantity", message: error.message },
+          400,
+        ),
+      };
+    }
+    throw error;
+  }
+  const { request, assetMetadata } = transformed;
 
-    // Check if we have any assets to cost
-    if (request.assets.length === 0) {
-      return c.json(
+  const violations = assetMetadata.flatMap((m) =>
+    m.blocks.flatMap((block) => block.constraintViolations ?? []),
+  );
+  if (violations.length > 0) {
+    return {
+      ok: false,
+      response: c.json(
+        {
+          error: "Cost item constraints violated",
+          message:
+            "Some blocks select cost items their cost library module does not allow together",
+          violations,
+        },
+        422,
+      ),
+    };
+  }
+
+  // Check if we have any assets to cost
+  if (request.assets.length === 0) {
+    return {
+      ok: false,
+      response: c.json(
         {
           error: "No costable assets found",
           message:
@@ -65,72 +661,658 @@ costingRoutes.post("/estimate", async (c) => {
           assetCount: 0,
         },
         400,
+      ),
+    };
+  }
+
+  const invalidCurrency = (message: string) => ({
+    ok: false as const,
+    response: c.json({ error: "Invalid currency", message }, 400),
+  });
+  const rates = (await library()).currency_conversion?.rates;
+  if (rates && rates[currency] === undefined) {
+    return invalidCurrency(`The library has no exchange rate for ${currency}`);
+  }
+
+  let costedRequest = request;
+  if (
+    request.assets.some(
+      (a) => a.labour_average_salary.currency_code !== currency,
+    )
+  ) {
+    try {
+      costedRequest = convertRequestCostParameters(
+        request,
+        currency,
+        (await library()).currency_conversion,
       );
+    } catch (error) {
+      if (error instanceof CostParameterError) {
+        return invalidCurrency(error.message);
+      }
+      throw error;
+    }
+  }
+
+  let scenario: ResolvedScenario | undefined;
+  if (body.scenario) {
+    try {
+      scenario = resolveScenario(body.scenario, await library());
+    } catch (error) {
+      if (error instanceof ScenarioError) {
+        return {
+          ok: false,
+          response: c.json(
+            { error: "Invalid scenario", message: error.message },
+            400,
+          ),
+        };
+      }
+      throw error;
     }
+  }
 
-    // Call the costing server
-    let costingResponse: CostEstimateResponse;
+  let priceBase: PriceBase | undefined;
+  if (body.priceBaseYear !== undefined) {
     try {
-      const response = await fetch(
-        `${COSTING_SERVER_URL}/api/cost/estimate?library_id=${body.libraryId}&target_currency_code=${currency}`,
+      priceBase = resolvePriceBase(body.priceBaseYear, await library());
+    } catch (error) {
+      if (error instanceof PriceBaseError) {
+        return {
+          ok: false,
+          response: c.json(
+            { error: "Invalid price base year", message: error.message },
+            400,
+          ),
+        };
+      }
+      throw error;
+    }
+  }
+
+  let priceBasis = REAL_PRICE_BASIS;
+  if (body.priceBasis === "nominal") {
+    const invalid = (message: string) => ({
+      ok: false as const,
+      response: c.json({ error: "Invalid price basis", message }, 400),
+    });
+    if (body.escalationRate === undefined) {
+      return invalid("Nominal cash flows need an escalationRate");
+    }
+    const priceBaseYear =
+      priceBase?.year ??
+      parseInt((await library()).inflation?.current_year ?? "");
+    if (isNaN(priceBaseYear)) {
+      return invalid("The library has no price base year to escalate from");
+    }
+    priceBasis = {
+      mode: "nominal",
+      escalationRate: body.escalationRate,
+      priceBaseYear,
+      discountRates: "real",
+    };
+  }
+
+  return {
+    ok: true,
+    request: costedRequest,
+    assetMetadata,
+    currency,
+    scenario,
+    priceBase,
+    priceBasis,
+  };
+}
+
+/**
+ * Apply the prepared scenario, price base and price basis to a costing
+ * server response, in that order.
+ */
+function adjustCostingResponse(
+  prepared: Extract<PreparedEstimate, { ok: true }>,
+  request: CostEstimateRequest,
+  costingResponse: CostEstimateResponse,
+): { costingResponse: CostEstimateResponse; assetMetadata: AssetMetadata[] } {
+  const { scenario, priceBase, priceBasis, currency } = prepared;
+  let run = { costingResponse, assetMetadata: prepared.assetMetadata };
+  if (scenario) {
+    run = applyScenario(
+      run.costingResponse,
+      request,
+      run.assetMetadata,
+      scenario,
+      currency,
+    );
+  }
+  if (priceBase) {
+    run = applyPriceBase(run.costingResponse, run.assetMetadata, priceBase);
+  }
+  if (priceBasis.mode === "nominal") {
+    run = {
+      ...run,
+      costingResponse: applyNominalBasis(
+        run.costingResponse,
+        request,
+        priceBasis.escalationRate,
+        priceBasis.priceBaseYear,
+      ),
+    };
+  }
+  return run;
+}
+
+/**
+ * Call the costing server, abandoning the estimate if the client
+ * disconnects, and count the assets and compute time against the caller.
+ * Assets' throughput ramp-up profiles and utility prices are applied to the
+ * response.
+ */
+async function callCostingServer(
+  c: Context,
+  request: CostEstimateRequest,
+  assetMetadata: AssetMetadata[],
+  libraryId: string,
+  currency: string,
+): Promise<CostEstimateResponse> {
+  const start = performance.now();
+  const costingResponse = await requestCostEstimate(
+    request,
+    libraryId,
+    currency,
+    { signal: c.req.raw.signal },
+  );
+  recordUsage(c.get("principal").id, {
+    assets: request.assets.length,
+    cpuSeconds: (performance.now() - start) / 1000,
+  });
+  return applyUtilityPrices(
+    applyThroughputProfiles(costingResponse, request, assetMetadata),
+    assetMetadata,
+  );
+}
+
+/**
+ * Transform the network and call the costing server.
+ * Returns the raw costing response, or an error response to send back.
+ */
+async function runEstimate(
+  c: Context,
+  body: CostingEstimateRequestInput,
+): Promise<EstimateRun> {
+  const prepared = await prepareEstimate(c, body);
+  if (!prepared.ok) {
+    return prepared;
+  }
+  const { request, assetMetadata, currency, priceBasis } = prepared;
+
+  try {
+    const costingResponse = await callCostingServer(
+      c,
+      request,
+      assetMetadata,
+      body.libraryId,
+      currency,
+    );
+    return {
+      ok: true,
+      request,
+      ...adjustCostingResponse(prepared, request, costingResponse),
+      currency,
+      priceBasis,
+    };
+  } catch (error) {
+    const response = costingErrorResponse(c, error, request);
+    if (response) {
+      return { ok: false, response };
+    }
+    throw error;
+  }
+}
+
+/**
+ * The response for a failed costing server call: 408 for timeouts and
+ * cancellations, 502 for server errors and 503 when it is unreachable.
+ * Returns undefined for other errors.
+ */
+function costingErrorResponse(
+  c: Context,
+  error: unknown,
+  request: CostEstimateRequest,
+): Response | undefined {
+  if (
+    error instanceof CostingServerTimeoutError ||
+    error instanceof CostingRequestCancelledError
+  ) {
+    const timedOut = error instanceof CostingServerTimeoutError;
+    return c.json(
+      {
+        error: timedOut ? "Estimate timed out" : "Estimate cancelled",
+        message: error.message,
+        ...(timedOut && {
+          timeoutMs: error.timeoutMs,
+          elapsedMs: error.elapsedMs,
+        }),
+        diagnostics: {
+          stage: "costing-server",
+          assetCount: request.assets.length,
+          costItemCount: request.assets.reduce(
+            (sum, a) => sum + a.cost_items.length,
+            0,
+          ),
+        },
+      },
+      408,
+    );
+  }
+  if (error instanceof CostingServerError) {
+    return c.json(
+      {
+        error: "Costing server error",
+        status: error.status,
+        message: error.body,
+      },
+      502,
+    );
+  }
+  if (error instanceof CostingServerUnavailableError) {
+    return c.json(
+      {
+        error: "Costing server unavailable",
+        message: error.message,
+        details:
+          error.cause instanceof Error
+            ? error.cause.message
+            : String(error.cause),
+      },
+      503,
+    );
+  }
+  return undefined;
+}
+
+// Saved estimates are for estimators
+costingRoutes.use("/estimates", requireRole("estimator"));
+costingRoutes.use("/estimates/*", requireRole("estimator"));
+
+/**
+ * GET /api/operations/costing/estimates
+ *
+ * List or search saved estimates, newest first.
+ *
+ * Query params:
+ * - libraryId (or library): Only estimates computed with this library
+ * - createdBy: Only estimates saved by this user
+ * - label: Text to find in the label, project, scenario, author or tags
+ * - project, scenario: Only estimates saved with this project or scenario
+ * - tag: Only estimates with this tag; repeat for several
+ * - from, to: ISO dates; estimates created at or after `from`, before `to`
+ * - sort: "-createdAt" (default), "createdAt", "-total" or "total"
+ * - archived: "exclude" (default), "include" or "only"
+ */
+costingRoutes.get("/estimates", async (c) => {
+  try {
+    const invalid = (message: string) =>
+      c.json({ error: "Invalid query parameter", message }, 400);
+
+    const archived = c.req.query("archived") ?? "exclude";
+    if (!["exclude", "include", "only"].includes(archived)) {
+      return invalid("archived must be exclude, include or only");
+    }
+    const sort = c.req.query("sort") ?? "-createdAt";
+    if (!(ESTIMATE_SORTS as readonly string[]).includes(sort)) {
+      return invalid(`sort must be one of ${ESTIMATE_SORTS.join(", ")}`);
+    }
+    const from = c.req.query("from");
+    const to = c.req.query("to");
+    if ([from, to].some((d) => d !== undefined && isNaN(Date.parse(d)))) {
+      return invalid("from and to must be ISO dates");
+    }
+
+    const estimates = await listEstimates({
+      libraryId: c.req.query("libraryId") ?? c.req.query("library"),
+      createdBy: c.req.query("createdBy"),
+      label: c.req.query("label"),
+      project: c.req.query("project"),
+      scenario: c.req.query("scenario"),
+      tags: c.req.queries("tag"),
+      from,
+      to,
+      sort: sort as EstimateSort,
+      archived: archived as "exclude" | "include" | "only",
+    });
+    return c.json({ estimates });
+  } catch (error) {
+    console.error("List estimates error:", error);
+    return c.json(
+      {
+        error: "Failed to list estimates",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+/**
+ * GET /api/operations/costing/estimates/:id
+ *
+ * Get a saved estimate: its request, result and provenance.
+ */
+costingRoutes.get("/estimates/:id", async (c) => {
+  try {
+    return c.json(await getEstimate(c.req.param("id")));
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to get estimate");
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimates/:id/archive
+ *
+ * Archive a saved estimate: it is left out of listings but can still be
+ * fetched, and is deleted once ESTIMATE_PURGE_DAYS have passed.
+ */
+costingRoutes.post("/estimates/:id/archive", async (c) => {
+  try {
+    return c.json(await archiveEstimate(c.req.param("id")));
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to archive estimate");
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimates/:id/unarchive
+ *
+ * Return an archived estimate to the listings.
+ */
+costingRoutes.post("/estimates/:id/unarchive", async (c) => {
+  try {
+    return c.json(await unarchiveEstimate(c.req.param("id")));
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to unarchive estimate");
+  }
+});
+
+/**
+ * GET /api/operations/costing/estimates/:id/table
+ *
+ * A saved estimate as a table for pasting into Excel or Word, or loading
+ * into pandas or DuckDB, with amounts rounded by the estimate's rounding
+ * policy.
+ *
+ * Query params:
+ * - table: "summary" (per asset, default), "by-year", "by-item", "by-cbs"
+ *   (summed by CBS code) or "by-item-year" (one row per asset, cost item,
+ *   year and cost category)
+ * - format: "tsv" (default), "html" (a <table> fragment) or "parquet"
+ */
+costingRoutes.get("/estimates/:id/table", async (c) => {
+  try {
+    const name = c.req.query("table") ?? "summary";
+    const format = c.req.query("format") ?? "tsv";
+    if (
+      !isEstimateTableName(name) ||
+      !["tsv", "html", "parquet"].includes(format)
+    ) {
+      return c.json(
         {
-          method: "POST",
-          headers: {
-            "Content-Type": "application/json",
-          },
-          body: JSON.stringify(request),
+          error: "Invalid query parameter",
+          message: `table must be one of ${ESTIMATE_TABLES.join(", ")} and format tsv, html or parquet`,
         },
+        400,
       );
+    }
 
-      if (!response.ok) {
-        const errorText = await response.text();
+    const estimate = await getEstimate(c.req.param("id"));
+    let table;
+    try {
+      table = estimateTable(estimate, name);
+    } catch (error) {
+      if (error instanceof EstimateTableError) {
         return c.json(
-          {
-            error: "Costing server error",
-            status: response.status,
-            message: errorText,
-          },
-          502,
+          { error: "Table unavailable", message: error.message },
+          409,
         );
       }
+      throw error;
+    }
+
+    if (format === "parquet") {
+      return c.body(tableToParquet(table, name), 200, {
+        "Content-Type": "application/vnd.apache.parquet",
+      });
+    }
+    return format === "html"
+      ? c.html(tableToHtml(table))
+      : c.body(tableToTsv(table), 200, {
+          "Content-Type": "text/tab-separated-values; charset=utf-8",
+        });
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to build estimate table");
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimates/:id/compare
+ *
+ * Re-run a saved estimate's request and compare the result with the saved
+ * one: deltas per lifetime cost category and per asset, and the library
+ * changes since the baseline that affect the cost items it uses.
+ *
+ * Request body (optional):
+ * - libraryId: Library to re-run against (default: the baseline's library,
+ *   as currently published)
+ */
+costingRoutes.post("/estimates/:id/compare", async (c) => {
+  try {
+    const baseline = await getEstimate(c.req.param("id"));
+    const options = await c.req.json().catch(() => ({}));
+    const libraryId =
+      typeof options?.libraryId === "string"
+        ? options.libraryId
+        : baseline.libraryId;
 
-      costingResponse = await response.json();
-    } catch (fetchError) {
+    let library: CostLibrary;
+    try {
+      library = await loadCostLibrary(libraryId);
+    } catch (error) {
       return c.json(
         {
-          error: "Costing server unavailable",
-          message:
-            `Failed to connect to costing server at ${COSTING_SERVER_URL}. ` +
-            "Ensure the costing server is running.",
-          details:
-            fetchError instanceof Error
-              ? fetchError.message
-              : String(fetchError),
+          error: "Failed to get library",
+          message: error instanceof Error ? error.message : String(error),
         },
-        503,
+        404,
       );
     }
 
-    // Transform response to our format
-    const result = transformCostingResponse(
-      costingResponse,
-      assetMetadata,
-      currency,
+    const request = { ...baseline.request, libraryId, save: false };
+    const run = await runEstimate(c, request);
+    if (!run.ok) {
+      return run.response;
+    }
+
+    return c.json(
+      compareEstimates(baseline, await getEstimateLibrary(baseline), {
+        result: await buildEstimateResult(run, request),
+        library,
+        libraryHash: hashCostLibrary(library),
+        costItemIds: costItemIdsByAsset(run.request),
+      }),
     );
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to compare estimate");
+  }
+});
 
-    return c.json(result);
+/**
+ * POST /api/operations/costing/estimates/:id/changes
+ *
+ * Summarize the change from a baseline estimate to this one, as structured
+ * deltas and sentences such as "Total installed cost up 12% driven by
+ * Item 074 (+£2.1M) due to Captured CO2 20→28". The largest contributing
+ * cost items are re-costed with one changed input at a time to attribute
+ * their change.
+ *
+ * Request body:
+ * - baselineId: Saved estimate to compare from
+ * - top: Number of contributing cost items to report (default: 3)
+ * - attribute: Re-cost contributors to attribute changes (default: true)
+ * - locale: Locale for amounts in the sentences (default: "en-GB")
+ */
+costingRoutes.post("/estimates/:id/changes", async (c) => {
+  try {
+    const parseResult = validateRequest(
+      EstimateChangesOptionsSchema,
+      await c.req.json().catch(() => ({})),
+    );
+    if (Either.isLeft(parseResult)) {
+      return c.json(formatValidationErrors(parseResult.left), 400);
+    }
+    const options = parseResult.right;
+
+    const current = await getEstimate(c.req.param("id"));
+    const baseline = await getEstimate(options.baselineId);
+
+    // Attribution re-costs baseline cost items, so costs them the way the
+    // baseline was: its scenario, price base and price basis resolved
+    // against its library snapshot, and its asset metadata
+    const baselinePrepared =
+      options.attribute !== false
+        ? await prepareEstimate(
+            c,
+            baseline.request,
+            await getEstimateLibrary(baseline),
+          )
+        : undefined;
+    if (baselinePrepared && !baselinePrepared.ok) {
+      return baselinePrepared.response;
+    }
+    const prepared = baselinePrepared && {
+      ...baselinePrepared,
+      assetMetadata:
+        baseline.intermediate?.assetMetadata ?? baselinePrepared.assetMetadata,
+    };
+
+    // The request being re-costed, for the costing server's diagnostics
+    let recosted: CostEstimateRequest = { assets: [] };
+    try {
+      return c.json(
+        await summarizeEstimateChanges(baseline, current, {
+          top: options.top,
+          locale: options.locale,
+          ...(prepared && {
+            rerun: async (request: CostEstimateRequest) => {
+              recosted = request;
+              return adjustCostingResponse(
+                prepared,
+                request,
+                await callCostingServer(
+                  c,
+                  request,
+                  prepared.assetMetadata,
+                  baseline.libraryId,
+                  baseline.result.currency,
+                ),
+              ).costingResponse;
+            },
+          }),
+        }),
+      );
+    } catch (error) {
+      if (error instanceof EstimateChangesError) {
+        return c.json(
+          { error: "Changes unavailable", message: error.message },
+          409,
+        );
+      }
+      const response = costingErrorResponse(c, error, recosted);
+      if (response) {
+        return response;
+      }
+      throw error;
+    }
   } catch (error) {
-    console.error("Costing estimate error:", error);
-    return c.json(
+    return estimateErrorResponse(c, error, "Failed to summarize changes");
+  }
+});
+
+/**
+ * POST /api/operations/costing/estimates/:id/what-if
+ *
+ * Re-aggregate a saved estimate with factor-level overrides, without
+ * calling the costing server. Cost item results stored with the estimate
+ * are rediscounted, rescaled and converted, so this is fast enough to drive
+ * sliders. Audit trails are not included.
+ *
+ * Request body:
+ * - discountRate: Discount rate for every asset
+ * - contingency: Lang contingency factor for every asset
+ * - includeContingencyInTotals: Add contingency to totals (default: as saved)
+ * - targetCurrency: Result currency, converted with the estimate's library
+ */
+costingRoutes.post("/estimates/:id/what-if", async (c) => {
+  try {
+    const parseResult = validateRequest(
+      WhatIfOverridesSchema,
+      await c.req.json().catch(() => ({})),
+    );
+    if (Either.isLeft(parseResult)) {
+      return c.json(formatValidationErrors(parseResult.left), 400);
+    }
+    const overrides = parseResult.right;
+
+    const baseline = await getEstimate(c.req.param("id"));
+    let run;
+    try {
+      run = applyWhatIf(
+        baseline,
+        await getEstimateLibrary(baseline),
+        overrides,
+      );
+    } catch (error) {
+      if (error instanceof WhatIfError) {
+        return c.json({ error: "Invalid what-if", message: error.message }, 400);
+      }
+      throw error;
+    }
+
+    const body: CostingEstimateRequestInput = {
+      ...baseline.request,
+      targetCurrency: run.currency,
+      includeContingencyInTotals:
+        overrides.includeContingencyInTotals ??
+        baseline.request.includeContingencyInTotals,
+      includeAudit: false,
+      save: false,
+    };
+    const result = await buildEstimateResult(
       {
-        error: "Internal error",
-        message: error instanceof Error ? error.message : String(error),
+        ok: true,
+        ...run,
+        priceBasis: baseline.result.priceBasis ?? REAL_PRICE_BASIS,
       },
-      500,
+      body,
     );
+    return c.json({ ...result, estimateId: baseline.id, overrides });
+  } catch (error) {
+    return estimateErrorResponse(c, error, "Failed to run what-if");
   }
 });
 
+function estimateErrorResponse(c: Context, error: unknown, fallback: string) {
+  if (error instanceof EstimateNotFoundError) {
+    return c.json({ error: "Estimate not found", message: error.message }, 404);
+  }
+  console.error(`${fallback}:`, error);
+  return c.json(
+    {
+      error: fallback,
+      message: error instanceof Error ? error.message : String(error),
+    },
+    500,
+  );
+}
+
 /**
  * POST /api/operations/costing/validate
  *
@@ -221,15 +1403,95 @@ costingRoutes.get("/libraries", async (c) => {
   }
 });
 
+/**
+ * POST /api/operations/costing/libraries/import
+ *
+ * Convert a cost-data spreadsheet (CSV, one row per cost item) into a cost
+ * library. Requires the librarian role. Returns the library with row-level
+ * errors and validation issues; the status is 422 if there are any.
+ *
+ * Query params:
+ * - base: Library to take currency conversion, inflation and defaults from
+ * - delimiter: Field delimiter (default: ",")
+ */
+costingRoutes.post("/libraries/import", requireRole("librarian"), async (c) => {
+  try {
+    const baseId = c.req.query("base");
+    const result = importCostLibraryCsv(await c.req.text(), {
+      base: baseId ? await loadCostLibrary(baseId) : undefined,
+      delimiter: c.req.query("delimiter"),
+    });
+    await recordAudit(c.get("principal"), {
+      action: "library.import",
+      contentHash: hashCostLibrary(result.library),
+      details: {
+        ...(baseId && { base: baseId }),
+        isValid: result.isValid,
+      },
+    });
+    return c.json(result, result.isValid ? 200 : 422);
+  } catch (error) {
+    console.error("Import library error:", error);
+    return c.json(
+      {
+        error: "Failed to import library",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
+/**
+ * POST /api/operations/costing/libraries/reload
+ *
+ * Drop every cached library and load them again from disk, e.g. after
+ * library files were replaced. Requires the admin role. Returns each
+ * library's load status.
+ */
+costingRoutes.post("/libraries/reload", requireRole("admin"), async (c) => {
+  try {
+    clearModuleLookupCache();
+    const libraries = await prewarmLibraries();
+    await Promise.all(
+      libraries.map((library) =>
+        recordAudit(c.get("principal"), {
+          action: "library.reload",
+          libraryId: library.libraryId,
+          ...(library.contentHash && { contentHash: library.contentHash }),
+          details: { status: library.status },
+        }),
+      ),
+    );
+    return c.json({ libraries });
+  } catch (error) {
+    console.error("Reload libraries error:", error);
+    return c.json(
+      {
+        error: "Failed to reload libraries",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      500,
+    );
+  }
+});
+
 /**
  * GET /api/operations/costing/libraries/:id
  *
  * Get details about a specific cost library.
+ *
+ * Library responses carry an ETag from the library's content hash and
+ * answer If-None-Match with 304 Not Modified.
  */
 costingRoutes.get("/libraries/:id", async (c) => {
   try {
     const libraryId = c.req.param("id");
     const service = await getModuleLookupService(libraryId);
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
 
     const types = service.listTypes();
 
@@ -253,31 +1515,141 @@ costingRoutes.get("/libraries/:id", async (c) => {
   }
 });
 
+/**
+ * GET /api/operations/costing/libraries/:id/stats
+ *
+ * Summary statistics for a cost library: modules by type, cost curve types,
+ * capex currency and year coverage, and the distinct parameter names its
+ * cost items use.
+ */
+costingRoutes.get("/libraries/:id/stats", async (c) => {
+  try {
+    const libraryId = c.req.param("id");
+    const service = await getModuleLookupService(libraryId);
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
+
+    return c.json({ id: libraryId, ...service.getStats() });
+  } catch (error) {
+    console.error("Get library stats error:", error);
+    return c.json(
+      {
+        error: "Failed to get library stats",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      404,
+    );
+  }
+});
+
+/**
+ * GET /api/operations/costing/libraries/:id/parameters
+ *
+ * The canonical parameters a cost library's cost items use: ID (the block
+ * property the value is read from), display name, units and the other
+ * names cost items use for it.
+ */
+costingRoutes.get("/libraries/:id/parameters", async (c) => {
+  try {
+    const libraryId = c.req.param("id");
+    const service = await getModuleLookupService(libraryId);
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
+
+    return c.json({ id: libraryId, parameters: service.listParameters() });
+  } catch (error) {
+    console.error("Get library parameters error:", error);
+    return c.json(
+      {
+        error: "Failed to get library parameters",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      404,
+    );
+  }
+});
+
+/**
+ * GET /api/operations/costing/libraries/:id/defaults
+ *
+ * Get the recommended Lang and fixed OPEX factors for a cost library.
+ * These are used for any factor an estimate request doesn't specify.
+ */
+costingRoutes.get("/libraries/:id/defaults", async (c) => {
+  try {
+    const libraryId = c.req.param("id");
+    const service = await getModuleLookupService(libraryId);
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
+
+    return c.json({
+      id: libraryId,
+      ...service.getFactorDefaults(),
+    });
+  } catch (error) {
+    console.error("Get library defaults error:", error);
+    return c.json(
+      {
+        error: "Failed to get library defaults",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      404,
+    );
+  }
+});
+
 /**
  * GET /api/operations/costing/libraries/:id/modules
  *
- * List modules in a cost library.
+ * List modules in a cost library. Without query params, returns the module
+ * types with their subtypes; any of the params below returns a module list.
  *
  * Query params:
  * - type: Filter by module type (e.g., "CaptureUnit")
+ * - ids: Comma-separated module IDs to return
+ * - fields: Comma-separated fields per module (default: id, type, subtype,
+ *   requiredParameters; "costItems" adds full cost curves)
+ * - page, per_page: Paginate the list (per_page default 50, max 500)
+ *
+ * Cost item names and descriptions are translated according to
+ * Accept-Language when the library provides translations.
  */
 costingRoutes.get("/libraries/:id/modules", async (c) => {
   try {
     const libraryId = c.req.param("id");
-    const typeFilter = c.req.query("type");
+    const query = c.req.query();
 
     const service = await getModuleLookupService(libraryId);
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
 
-    if (typeFilter) {
-      const modules = service.findByType(typeFilter);
-      return c.json({
-        type: typeFilter,
-        modules: modules.map((m) => ({
-          id: m.id,
-          subtype: m.subtype,
-          requiredParameters: m.requiredParameters,
-        })),
-      });
+    if (isModuleListingQuery(query)) {
+      const parsed = parseModuleListingQuery(query);
+      if (!parsed.ok) {
+        return c.json(
+          { error: "Invalid query parameter", message: parsed.message },
+          400,
+        );
+      }
+
+      // Cost item text follows Accept-Language when the library has it
+      const language = negotiateLanguage(
+        c.req.header("accept-language"),
+        service.getLanguages(),
+      );
+      c.header("Vary", "Accept-Language");
+      if (language) {
+        c.header("Content-Language", language);
+      }
+      return c.json(listModules(service, { ...parsed.query, language }));
     }
 
     // Return all types with their subtypes
@@ -301,6 +1673,67 @@ costingRoutes.get("/libraries/:id/modules", async (c) => {
   }
 });
 
+/**
+ * GET /api/operations/costing/libraries/:id/cost-items/:itemId
+ *
+ * Get a cost item's full definition, its source references and the modules
+ * that use it. An ID the library has renamed returns the renamed item, with
+ * the requested ID as `aliasedFrom`.
+ */
+costingRoutes.get("/libraries/:id/cost-items/:itemId", async (c) => {
+  try {
+    const libraryId = c.req.param("id");
+    const itemId = c.req.param("itemId");
+    const service = await getModuleLookupService(libraryId);
+
+    const item = service.findCostItem(itemId);
+    if (!item) {
+      return c.json(
+        {
+          error: "Cost item not found",
+          message: `Library ${libraryId} has no cost item ${itemId}`,
+        },
+        404,
+      );
+    }
+
+    const cached = notModified(c, service.contentHash);
+    if (cached) {
+      return cached;
+    }
+
+    return c.json({
+      ...item,
+      sourceReference: item.info.source_reference,
+      sources: item.info.sources ?? [],
+      moduleIds: service
+        .listAll()
+        .filter((m) => m.costItemIds.includes(item.id))
+        .map((m) => m.id),
+      ...(item.id !== itemId && { aliasedFrom: itemId }),
+    });
+  } catch (error) {
+    console.error("Get cost item error:", error);
+    return c.json(
+      {
+        error: "Failed to get library",
+        message: error instanceof Error ? error.message : String(error),
+      },
+      404,
+    );
+  }
+});
+
+/**
+ * GET /api/operations/costing/presets
+ *
+ * List named factor presets (estimate classes). A preset name can be given
+ * as `preset` in assetDefaults or assetOverrides.
+ */
+costingRoutes.get("/presets", (c) => {
+  return c.json({ presets: listEstimatePresets() });
+});
+
 /**
  * GET /api/operations/costing/health
  *
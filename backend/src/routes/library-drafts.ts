@@ -0,0 +1,348 @@
+import { Hono, type Context } from "hono";
+import {
+  createDraft,
+  getDraft,
+  discardDraft,
+  putModule,
+  deleteModule,
+  addCostItem,
+  patchCostItem,
+  deleteCostItem,
+  publishDraft,
+  getDraftPackage,
+  listLibraryVersions,
+  getLibraryVersion,
+  validateCostLibrary,
+  LibraryDraftError,
+  hashCostLibrary,
+  loadCostLibrary,
+  type CostLibrary,
+  type CostLibraryModule,
+  type CostLibraryCostItem,
+  type LibrarySignature,
+} from "../services/costing";
+import { requireRole } from "../services/auth";
+import { recordAudit, type AuditRecord } from "../services/audit";
+
+/**
+ * Cost library editing routes, mounted under
+ * /api/operations/costing/libraries.
+ *
+ * Edits go to a draft of the library; POST /:id/publish validates the draft
+ * and makes it the library's next published version.
+ *
+ * Drafts and publishing need the librarian role; version history is open to
+ * every authenticated caller. Every change is recorded in the audit log.
+ */
+export const libraryDraftRoutes = new Hono();
+
+libraryDraftRoutes.use("/:id/draft", requireRole("librarian"));
+libraryDraftRoutes.use("/:id/draft/*", requireRole("librarian"));
+libraryDraftRoutes.use("/:id/publish", requireRole("librarian"));
+
+/**
+ * POST /libraries/:id/draft
+ *
+ * Start a draft from the published library.
+ */
+libraryDraftRoutes.post("/:id/draft", async (c) => {
+  try {
+    const draft = await createDraft(c.req.param("id"));
+    await audit(c, { action: "draft.create" }, draft);
+    return c.json(draft, 201);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to create draft");
+  }
+});
+
+/**
+ * GET /libraries/:id/draft
+ */
+libraryDraftRoutes.get("/:id/draft", async (c) => {
+  try {
+    return c.json(await getDraft(c.req.param("id")));
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to get draft");
+  }
+});
+
+/**
+ * DELETE /libraries/:id/draft
+ *
+ * Discard the draft without publishing.
+ */
+libraryDraftRoutes.delete("/:id/draft", async (c) => {
+  try {
+    await discardDraft(c.req.param("id"));
+    await audit(c, { action: "draft.discard" });
+    return c.body(null, 204);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to discard draft");
+  }
+});
+
+/**
+ * GET /libraries/:id/draft/validate
+ *
+ * Report the issues that would block publishing the draft.
+ */
+libraryDraftRoutes.get("/:id/draft/validate", async (c) => {
+  try {
+    const issues = validateCostLibrary(await getDraft(c.req.param("id")));
+    return c.json({ isValid: issues.length === 0, issues });
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to validate draft");
+  }
+});
+
+/**
+ * GET /libraries/:id/draft/package
+ *
+ * The library file publishing the draft would write, byte for byte. Sign
+ * it with `cost-library sign` to publish when signatures are required.
+ */
+libraryDraftRoutes.get("/:id/draft/package", async (c) => {
+  try {
+    return c.body(await getDraftPackage(c.req.param("id")), 200, {
+      "Content-Type": "application/json",
+    });
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to get draft package");
+  }
+});
+
+/**
+ * PUT /libraries/:id/draft/modules/:moduleId
+ *
+ * Create or replace a module. Request body: CostLibraryModule.
+ */
+libraryDraftRoutes.put("/:id/draft/modules/:moduleId", async (c) => {
+  try {
+    const moduleId = c.req.param("moduleId");
+    const body = await c.req.json();
+    if (!isObject(body) || (body.id !== undefined && body.id !== moduleId)) {
+      return invalidBody(c, "Body must be a module whose id matches the URL");
+    }
+
+    const draft = await putModule(c.req.param("id"), {
+      ...body,
+      id: moduleId,
+    } as CostLibraryModule);
+    await audit(c, { action: "draft.module.put", target: moduleId }, draft);
+    return c.json(draft.modules.find((m) => m.id === moduleId));
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to save module");
+  }
+});
+
+/**
+ * DELETE /libraries/:id/draft/modules/:moduleId
+ */
+libraryDraftRoutes.delete("/:id/draft/modules/:moduleId", async (c) => {
+  try {
+    const moduleId = c.req.param("moduleId");
+    const draft = await deleteModule(c.req.param("id"), moduleId);
+    await audit(c, { action: "draft.module.delete", target: moduleId }, draft);
+    return c.body(null, 204);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to delete module");
+  }
+});
+
+/**
+ * POST /libraries/:id/draft/modules/:moduleId/items
+ *
+ * Add a cost item to a module. Request body: CostLibraryCostItem.
+ */
+libraryDraftRoutes.post("/:id/draft/modules/:moduleId/items", async (c) => {
+  try {
+    const body = await c.req.json();
+    if (!isObject(body) || typeof body.id !== "string") {
+      return invalidBody(c, "Body must be a cost item with an id");
+    }
+
+    const draft = await addCostItem(
+      c.req.param("id"),
+      c.req.param("moduleId"),
+      body as CostLibraryCostItem,
+    );
+    await audit(
+      c,
+      {
+        action: "draft.item.add",
+        target: body.id,
+        details: { moduleId: c.req.param("moduleId") },
+      },
+      draft,
+    );
+    return c.json(body, 201);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to add cost item");
+  }
+});
+
+/**
+ * PATCH /libraries/:id/draft/items/:itemId
+ *
+ * Update a cost item with a JSON merge patch. The item is updated in every
+ * module that shares it.
+ */
+libraryDraftRoutes.patch("/:id/draft/items/:itemId", async (c) => {
+  try {
+    const itemId = c.req.param("itemId");
+    const body = await c.req.json();
+    if (!isObject(body)) {
+      return invalidBody(c, "Body must be a JSON merge patch object");
+    }
+
+    const draft = await patchCostItem(c.req.param("id"), itemId, body);
+    await audit(c, { action: "draft.item.update", target: itemId }, draft);
+    const item = draft.modules
+      .flatMap((m) => m.cost_items ?? [])
+      .find((i) => i.id === itemId);
+    return c.json(item);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to update cost item");
+  }
+});
+
+/**
+ * DELETE /libraries/:id/draft/modules/:moduleId/items/:itemId
+ */
+libraryDraftRoutes.delete(
+  "/:id/draft/modules/:moduleId/items/:itemId",
+  async (c) => {
+    try {
+      const itemId = c.req.param("itemId");
+      const draft = await deleteCostItem(
+        c.req.param("id"),
+        c.req.param("moduleId"),
+        itemId,
+      );
+      await audit(
+        c,
+        {
+          action: "draft.item.delete",
+          target: itemId,
+          details: { moduleId: c.req.param("moduleId") },
+        },
+        draft,
+      );
+      return c.body(null, 204);
+    } catch (error) {
+      return draftErrorResponse(c, error, "Failed to delete cost item");
+    }
+  },
+);
+
+/**
+ * POST /libraries/:id/publish
+ *
+ * Validate the draft and publish it. Returns 422 with the validation issues
+ * if the draft is invalid.
+ *
+ * Request body (optional):
+ * - signature: Detached signature over the draft package, published with
+ *   the library. Required (409 without one) when signatures are required:
+ *   under LIBRARY_SIGNATURES=require, or verify with trusted keys configured.
+ */
+libraryDraftRoutes.post("/:id/publish", async (c) => {
+  try {
+    const body = await c.req.json().catch(() => ({}));
+    if (
+      !isObject(body) ||
+      (body.signature !== undefined && !isObject(body.signature))
+    ) {
+      return invalidBody(c, "signature must be a library signature object");
+    }
+    const version = await publishDraft(
+      c.req.param("id"),
+      c.get("principal")?.id,
+      body.signature as LibrarySignature | undefined,
+    );
+    await audit(
+      c,
+      { action: "library.publish", details: { version: version.version } },
+      await loadCostLibrary(c.req.param("id")),
+    );
+    return c.json(version, 201);
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to publish draft");
+  }
+});
+
+/**
+ * GET /libraries/:id/versions
+ *
+ * List the library's published versions.
+ */
+libraryDraftRoutes.get("/:id/versions", async (c) => {
+  try {
+    const versions = await listLibraryVersions(c.req.param("id"));
+    return c.json({ versions });
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to list versions");
+  }
+});
+
+/**
+ * GET /libraries/:id/versions/:version
+ *
+ * Get a published version of the library.
+ */
+libraryDraftRoutes.get("/:id/versions/:version", async (c) => {
+  try {
+    const version = parseInt(c.req.param("version"));
+    if (isNaN(version)) {
+      return invalidBody(c, "version must be an integer");
+    }
+    return c.json(await getLibraryVersion(c.req.param("id"), version));
+  } catch (error) {
+    return draftErrorResponse(c, error, "Failed to get version");
+  }
+});
+
+function isObject(value: unknown): value is Record<string, unknown> {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+/**
+ * Record a change to the library in the audit log, with the content hash of
+ * the library (or draft) it produced.
+ */
+async function audit(
+  c: Context,
+  record: Omit<AuditRecord, "libraryId" | "contentHash">,
+  library?: CostLibrary,
+): Promise<void> {
+  await recordAudit(c.get("principal"), {
+    ...record,
+    libraryId: c.req.param("id"),
+    ...(library && { contentHash: hashCostLibrary(library) }),
+  });
+}
+
+function invalidBody(c: Context, message: string) {
+  return c.json({ error: "Invalid request", message }, 400);
+}
+
+function draftErrorResponse(c: Context, error: unknown, fallback: string) {
+  if (error instanceof LibraryDraftError) {
+    return c.json(
+      {
+        error: fallback,
+        message: error.message,
+        ...(error.issues.length > 0 && { issues: error.issues }),
+      },
+      error.status,
+    );
+  }
+  console.error(`${fallback}:`, error);
+  return c.json(
+    {
+      error: fallback,
+      message: error instanceof Error ? error.message : String(error),
+    },
+    500,
+  );
+}
@@ -0,0 +1,64 @@
+import { Hono } from "hono";
+import { hasRole } from "../services/auth";
+import {
+  evaluateQuotas,
+  getUsage,
+  listUsage,
+  parseQuotas,
+  quotaPolicyFor,
+  usagePeriod,
+  usagePeriodEnd,
+} from "../services/usage";
+
+export const usageRoutes = new Hono();
+
+const quotas = parseQuotas(process.env.QUOTAS);
+
+/**
+ * GET /api/usage
+ *
+ * Usage and quota status for the caller in the current month.
+ *
+ * Query params:
+ * - period: Month to report, as "YYYY-MM" (default: current month)
+ * - principal: Report another caller's usage (admin only)
+ * - all: If "true", report every caller's usage (admin only)
+ */
+usageRoutes.get("/", (c) => {
+  const caller = c.get("principal");
+  const period = c.req.query("period") ?? usagePeriod();
+  const principal = c.req.query("principal") ?? caller.id;
+  const all = c.req.query("all") === "true";
+
+  if (!/^\d{4}-(0[1-9]|1[0-2])$/.test(period)) {
+    return c.json(
+      { error: "Invalid request", message: "period must be YYYY-MM" },
+      400,
+    );
+  }
+  if ((all || principal !== caller.id) && !hasRole(caller.role, "admin")) {
+    return c.json(
+      {
+        error: "Forbidden",
+        message: "Only admins can see other callers' usage",
+        requiredRole: "admin",
+      },
+      403,
+    );
+  }
+
+  if (all) {
+    return c.json({ period, usage: listUsage(period) });
+  }
+
+  const usage = getUsage(principal, period);
+  return c.json({
+    period,
+    ...(period === usagePeriod() && {
+      resetsAt: usagePeriodEnd().toISOString(),
+    }),
+    principal,
+    usage,
+    quotas: evaluateQuotas(usage, quotaPolicyFor(quotas, principal)),
+  });
+});
@@ -0,0 +1,77 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { queryAuditLog, recordAudit } from "./audit-log";
+import type { Principal } from "../auth";
+
+const alice: Principal = { id: "alice", role: "librarian", via: "oidc" };
+const ci: Principal = { id: "ci", role: "admin", via: "api-key" };
+
+describe("audit-log", () => {
+  let dataRoot: string;
+
+  beforeEach(async () => {
+    dataRoot = await mkdtemp(join(tmpdir(), "audit-log-"));
+    vi.spyOn(process, "cwd").mockReturnValue(dataRoot);
+  });
+
+  afterEach(async () => {
+    vi.restoreAllMocks();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("returns no entries before anything is recorded", async () => {
+    expect(await queryAuditLog()).toEqual([]);
+  });
+
+  it("appends one line per entry, including concurrent writes", async () => {
+    await Promise.all(
+      ["M0101", "M0201", "M0301"].map((target) =>
+        recordAudit(alice, {
+          action: "draft.module.delete",
+          libraryId: "V1.3",
+          target,
+          contentHash: "abc",
+        }),
+      ),
+    );
+
+    const lines = (
+      await readFile(join(dataRoot, "data/audit/audit-log.jsonl"), "utf-8")
+    )
+      .trim()
+      .split("\n");
+    expect(lines).toHaveLength(3);
+    expect(JSON.parse(lines[0])).toMatchObject({
+      user: "alice",
+      via: "oidc",
+      action: "draft.module.delete",
+      contentHash: "abc",
+    });
+  });
+
+  it("filters by library, user and action, newest first", async () => {
+    await recordAudit(alice, { action: "draft.create", libraryId: "V1.3" });
+    await recordAudit(alice, {
+      action: "library.publish",
+      libraryId: "V1.3",
+      details: { version: 2 },
+    });
+    await recordAudit(ci, { action: "library.reload", libraryId: "V2.0" });
+
+    const v13 = await queryAuditLog({ libraryId: "V1.3" });
+    expect(v13.map((e) => e.action)).toEqual([
+      "library.publish",
+      "draft.create",
+    ]);
+    expect(await queryAuditLog({ user: "ci" })).toHaveLength(1);
+    expect(await queryAuditLog({ action: "library.publish" })).toHaveLength(1);
+    expect(await queryAuditLog({ limit: 1 })).toMatchObject([
+      { action: "library.reload" },
+    ]);
+    expect(
+      await queryAuditLog({ since: new Date(Date.now() + 60_000).toISOString() }),
+    ).toEqual([]);
+  });
+});
@@ -0,0 +1,115 @@
+/**
+ * Append-only audit log of changes to costing data.
+ *
+ * Every library import, reload, draft edit and publish is recorded with who
+ * made it, when, and the content hash of the library it produced, so QA can
+ * trace any published figure back to the change that introduced it.
+ * Entries are appended to the audit/audit-log log of the configured
+ * storage (a JSON-lines file in the data directory by default) and are
+ * never rewritten.
+ */
+
+import { randomUUID } from "crypto";
+import type { Principal } from "../auth";
+import { getStorage } from "../storage";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const AUDIT_ACTIONS = [
+  "library.import",
+  "library.reload",
+  "library.publish",
+  "draft.create",
+  "draft.discard",
+  "draft.module.put",
+  "draft.module.delete",
+  "draft.item.add",
+  "draft.item.update",
+  "draft.item.delete",
+] as const;
+
+export type AuditAction = (typeof AUDIT_ACTIONS)[number];
+
+export type AuditEntry = {
+  id: string;
+  /** ISO timestamp */
+  timestamp: string;
+  /** Principal ID of the caller */
+  user: string;
+  via: Principal["via"];
+  action: AuditAction;
+  libraryId?: string;
+  /** Module or cost item the action applied to */
+  target?: string;
+  /** SHA-256 of the library content after the action */
+  contentHash?: string;
+  /** Action-specific details, e.g. the published version */
+  details?: Record<string, unknown>;
+};
+
+export type AuditRecord = Omit<AuditEntry, "id" | "timestamp" | "user" | "via">;
+
+export type AuditQuery = {
+  libraryId?: string;
+  user?: string;
+  action?: AuditAction;
+  /** ISO timestamp; entries at or after it */
+  since?: string;
+  /** ISO timestamp; entries before it */
+  until?: string;
+  /** Maximum number of entries, newest first (default: 100) */
+  limit?: number;
+};
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+const AUDIT_LOG = "audit/audit-log";
+
+/**
+ * Append an entry for an action performed by `principal`.
+ */
+export function recordAudit(
+  principal: Principal,
+  record: AuditRecord,
+): Promise<AuditEntry> {
+  const entry: AuditEntry = {
+    id: randomUUID(),
+    timestamp: new Date().toISOString(),
+    user: principal.id,
+    via: principal.via,
+    ...record,
+  };
+
+  return getStorage().append(AUDIT_LOG, entry).then(() => entry);
+}
+
+// ============================================================================
+// Query
+// ============================================================================
+
+/**
+ * Find audit entries matching the query, newest first.
+ */
+export async function queryAuditLog(
+  query: AuditQuery = {},
+): Promise<AuditEntry[]> {
+  const entries = (
+    await getStorage().readLog<AuditEntry>(AUDIT_LOG)
+  ).filter(
+    (entry) =>
+      (query.libraryId === undefined || entry.libraryId === query.libraryId) &&
+      (query.user === undefined || entry.user === query.user) &&
+      (query.action === undefined || entry.action === query.action) &&
+      (query.since === undefined || entry.timestamp >= query.since) &&
+      (query.until === undefined || entry.timestamp < query.until),
+  );
+  return entries.reverse().slice(0, query.limit ?? 100);
+}
+
+export function isAuditAction(value: unknown): value is AuditAction {
+  return (AUDIT_ACTIONS as readonly unknown[]).includes(value);
+}
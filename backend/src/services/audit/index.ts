@@ -0,0 +1,14 @@
+/**
+ * Audit logging of changes to costing data.
+ */
+
+export {
+  AUDIT_ACTIONS,
+  recordAudit,
+  queryAuditLog,
+  isAuditAction,
+  type AuditAction,
+  type AuditEntry,
+  type AuditRecord,
+  type AuditQuery,
+} from "./audit-log";
@@ -0,0 +1,59 @@
+/**
+ * API key authentication.
+ *
+ * Keys are configured with the API_KEYS environment variable as a
+ * comma-separated list of `name:role:key` entries, e.g.
+ * `ci:estimator:3f9c...,alice:librarian:a71d...`. Only SHA-256 hashes of
+ * the keys are kept in memory.
+ */
+
+import { createHash } from "crypto";
+import { isRole, type Principal, type Role } from "./roles";
+
+export type ApiKeyEntry = {
+  name: string;
+  role: Role;
+  keyHash: string;
+};
+
+export function hashApiKey(key: string): string {
+  return createHash("sha256").update(key).digest("hex");
+}
+
+/**
+ * Parse an API_KEYS value. Throws on malformed entries or unknown roles so
+ * a typo can't silently lock users out or grant the wrong role.
+ */
+export function parseApiKeys(value: string | undefined): ApiKeyEntry[] {
+  if (!value?.trim()) return [];
+
+  return value
+    .split(",")
+    .map((entry) => entry.trim())
+    .filter((entry) => entry !== "")
+    .map((entry) => {
+      const [name, role, ...rest] = entry.split(":");
+      const key = rest.join(":");
+      if (!name || !key) {
+        throw new Error(`API key entry "${name ?? entry}" must be name:role:key`);
+      }
+      if (!isRole(role)) {
+        throw new Error(`API key ${name} has unknown role "${role}"`);
+      }
+      return { name, role, keyHash: hashApiKey(key) };
+    });
+}
+
+/**
+ * Look up the principal for a presented API key.
+ */
+export function authenticateApiKey(
+  entries: ApiKeyEntry[],
+  key: string,
+): Principal | undefined {
+  const keyHash = hashApiKey(key);
+  const entry = entries.find((e) => e.keyHash === keyHash);
+  return entry
+    ? { id: entry.name, name: entry.name, role: entry.role, via: "api-key" }
+    : undefined;
+}
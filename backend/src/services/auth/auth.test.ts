@@ -0,0 +1,76 @@
+import { describe, it, expect } from "vitest";
+import { Hono } from "hono";
+import { authenticate, requireRole } from "./middleware";
+import { parseApiKeys } from "./api-keys";
+import { hasRole } from "./roles";
+
+describe("auth", () => {
+  const apiKeys = parseApiKeys("ci:viewer:k-viewer, alice:librarian:k-lib");
+
+  const app = new Hono();
+  app.use("/*", authenticate({ apiKeys }));
+  app.get("/estimate", (c) => c.json({ caller: c.get("principal").id }));
+  app.post("/publish", requireRole("librarian"), (c) => c.json({ ok: true }));
+
+  describe("roles", () => {
+    it("orders roles", () => {
+      expect(hasRole("admin", "librarian")).toBe(true);
+      expect(hasRole("librarian", "estimator")).toBe(true);
+      expect(hasRole("viewer", "estimator")).toBe(false);
+    });
+  });
+
+  describe("parseApiKeys", () => {
+    it("rejects unknown roles and malformed entries", () => {
+      expect(() => parseApiKeys("bob:owner:key")).toThrow(/unknown role/);
+      expect(() => parseApiKeys("bob:viewer")).toThrow(/name:role:key/);
+      expect(parseApiKeys(undefined)).toEqual([]);
+    });
+
+    it("keeps only key hashes", () => {
+      expect(JSON.stringify(apiKeys)).not.toContain("k-viewer");
+    });
+  });
+
+  it("rejects requests without a valid key", async () => {
+    expect((await app.request("/estimate")).status).toBe(401);
+    const wrong = await app.request("/estimate", {
+      headers: { "X-API-Key": "nope" },
+    });
+    expect(wrong.status).toBe(401);
+  });
+
+  it("accepts X-API-Key and bearer keys", async () => {
+    const res = await app.request("/estimate", {
+      headers: { Authorization: "Bearer k-viewer" },
+    });
+
+    expect(await res.json()).toEqual({ caller: "ci" });
+  });
+
+  it("enforces route roles", async () => {
+    const viewer = await app.request("/publish", {
+      method: "POST",
+      headers: { "X-API-Key": "k-viewer" },
+    });
+    const librarian = await app.request("/publish", {
+      method: "POST",
+      headers: { "X-API-Key": "k-lib" },
+    });
+
+    expect(viewer.status).toBe(403);
+    expect(librarian.status).toBe(200);
+  });
+
+  it("runs as anonymous admin when no keys are configured", async () => {
+    const open = new Hono();
+    open.use("/*", authenticate({ apiKeys: [] }));
+    open.post("/publish", requireRole("admin"), (c) =>
+      c.json(c.get("principal")),
+    );
+
+    const res = await open.request("/publish", { method: "POST" });
+
+    expect(await res.json()).toMatchObject({ id: "anonymous", role: "admin" });
+  });
+});
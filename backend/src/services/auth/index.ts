@@ -0,0 +1,38 @@
+/**
+ * Authentication and authorization.
+ */
+
+export {
+  ROLES,
+  isRole,
+  hasRole,
+  type Role,
+  type Principal,
+} from "./roles";
+
+export {
+  parseApiKeys,
+  hashApiKey,
+  authenticateApiKey,
+  type ApiKeyEntry,
+} from "./api-keys";
+
+export {
+  oidcConfigFromEnv,
+  verifyToken,
+  principalFromClaims,
+  looksLikeJwt,
+  JwksCache,
+  TokenError,
+  IssuerUnavailableError,
+  type OidcConfig,
+  type SigningKey,
+  type TokenClaims,
+} from "./oidc";
+
+export {
+  authenticate,
+  authOptionsFromEnv,
+  requireRole,
+  type AuthOptions,
+} from "./middleware";
@@ -0,0 +1,140 @@
+/**
+ * Authentication middleware and per-route role guards.
+ *
+ * `authenticate` identifies the caller from an `X-API-Key` header, or from an
+ * `Authorization: Bearer` value that is either an OIDC token (when OIDC is
+ * configured) or an API key, and stores the principal on the context. When
+ * neither API keys nor OIDC are configured, authentication is disabled and
+ * every request runs as an anonymous admin, which keeps local and desktop
+ * use working without setup.
+ */
+
+import type { Context, MiddlewareHandler } from "hono";
+import { authenticateApiKey, parseApiKeys, type ApiKeyEntry } from "./api-keys";
+import {
+  IssuerUnavailableError,
+  JwksCache,
+  looksLikeJwt,
+  oidcConfigFromEnv,
+  principalFromClaims,
+  TokenError,
+  verifyToken,
+  type OidcConfig,
+} from "./oidc";
+import { hasRole, type Principal, type Role } from "./roles";
+
+declare module "hono" {
+  interface ContextVariableMap {
+    principal: Principal;
+  }
+}
+
+export type AuthOptions = {
+  apiKeys?: ApiKeyEntry[];
+  oidc?: { config: OidcConfig; jwks: JwksCache };
+};
+
+/**
+ * Authentication settings from API_KEYS and the OIDC_* variables.
+ */
+export function authOptionsFromEnv(): AuthOptions {
+  const config = oidcConfigFromEnv();
+  return {
+    apiKeys: parseApiKeys(process.env.API_KEYS),
+    ...(config && { oidc: { config, jwks: new JwksCache(config) } }),
+  };
+}
+
+const ANONYMOUS: Principal = { id: "anonymous", role: "admin", via: "anonymous" };
+
+function bearerToken(c: Context): string | undefined {
+  const match = c.req.header("authorization")?.match(/^Bearer\s+(.+)$/i);
+  return match?.[1].trim();
+}
+
+/**
+ * Identify the caller. Requests without valid credentials are rejected with
+ * 401 unless authentication is disabled, and bearer tokens get 503 while
+ * the issuer's signing keys can't be fetched.
+ */
+export function authenticate(
+  options: AuthOptions = authOptionsFromEnv(),
+): MiddlewareHandler {
+  const apiKeys = options.apiKeys ?? [];
+  const { oidc } = options;
+
+  return async (c, next) => {
+    if (apiKeys.length === 0 && !oidc) {
+      c.set("principal", ANONYMOUS);
+      return next();
+    }
+
+    const unauthorized = (message: string) => {
+      c.header("WWW-Authenticate", 'Bearer realm="dagger-api"');
+      return c.json({ error: "Unauthorized", message }, 401);
+    };
+
+    const apiKey = c.req.header("x-api-key")?.trim();
+    const bearer = bearerToken(c);
+    let principal: Principal | undefined;
+
+    if (oidc && !apiKey && bearer && looksLikeJwt(bearer)) {
+      try {
+        const claims = await verifyToken(bearer, oidc.config, oidc.jwks);
+        principal = principalFromClaims(claims, oidc.config);
+      } catch (error) {
+        if (error instanceof TokenError) {
+          return unauthorized(error.message);
+        }
+        if (error instanceof IssuerUnavailableError) {
+          c.header("Retry-After", "60");
+          return c.json(
+            { error: "Identity provider unavailable", message: error.message },
+            503,
+          );
+        }
+        throw error;
+      }
+    } else {
+      const key = apiKey ?? bearer;
+      if (!key) {
+        return unauthorized(
+          "Send a bearer token, or an API key in the X-API-Key header",
+        );
+      }
+      principal = authenticateApiKey(apiKeys, key);
+      if (!principal) {
+        return unauthorized("The API key is not recognised");
+      }
+    }
+
+    c.set("principal", principal);
+    return next();
+  };
+}
+
+/**
+ * Guard a route so only callers with at least `role` can use it.
+ */
+export function requireRole(role: Role): MiddlewareHandler {
+  return async (c, next) => {
+    const principal = c.get("principal");
+    if (!principal) {
+      return c.json(
+        { error: "Unauthorized", message: "Authentication required" },
+        401,
+      );
+    }
+    if (!hasRole(principal.role, role)) {
+      return c.json(
+        {
+          error: "Forbidden",
+          message: `This action requires the ${role} role; ${principal.id} has ${principal.role}`,
+          requiredRole: role,
+        },
+        403,
+      );
+    }
+    return next();
+  };
+}
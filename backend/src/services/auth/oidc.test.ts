@@ -0,0 +1,194 @@
+import { describe, it, expect, vi } from "vitest";
+import { Hono } from "hono";
+import { generateKeyPairSync, sign } from "crypto";
+import { authenticate } from "./middleware";
+import {
+  IssuerUnavailableError,
+  JwksCache,
+  principalFromClaims,
+  TokenError,
+  verifyToken,
+  type OidcConfig,
+} from "./oidc";
+
+const ISSUER = "https://login.example.com/tenant";
+
+const config: OidcConfig = {
+  issuer: ISSUER,
+  audience: "dagger-api",
+  roleClaim: "roles",
+};
+
+const { privateKey, publicKey } = generateKeyPairSync("rsa", {
+  modulusLength: 2048,
+});
+
+function fakeIdp() {
+  return vi.fn(async (url: string) => {
+    if (url === `${ISSUER}/.well-known/openid-configuration`) {
+      return { jwks_uri: `${ISSUER}/keys` };
+    }
+    if (url === `${ISSUER}/keys`) {
+      return {
+        keys: [{ ...publicKey.export({ format: "jwk" }), kid: "k1", use: "sig" }],
+      };
+    }
+    throw new Error(`Unexpected fetch ${url}`);
+  });
+}
+
+function signToken(
+  claims: Record<string, unknown>,
+  header: Record<string, unknown> = { alg: "RS256", kid: "k1" },
+): string {
+  const encode = (value: object) =>
+    Buffer.from(JSON.stringify(value)).toString("base64url");
+  const body = `${encode({ typ: "JWT", ...header })}.${encode(claims)}`;
+  return `${body}.${sign("sha256", Buffer.from(body), privateKey).toString("base64url")}`;
+}
+
+const now = Math.floor(Date.now() / 1000);
+const validClaims = {
+  iss: ISSUER,
+  aud: "dagger-api",
+  sub: "u-123",
+  preferred_username: "jsmith",
+  roles: ["viewer", "estimator"],
+  exp: now + 300,
+};
+
+describe("oidc", () => {
+  it("verifies a token against keys found through discovery", async () => {
+    const fetchJson = fakeIdp();
+    const jwks = new JwksCache(config, fetchJson);
+
+    const claims = await verifyToken(signToken(validClaims), config, jwks);
+    await verifyToken(signToken(validClaims), config, jwks);
+
+    expect(claims.sub).toBe("u-123");
+    expect(fetchJson).toHaveBeenCalledTimes(2);
+  });
+
+  it("rejects wrong audience, expired and forged tokens", async () => {
+    const jwks = new JwksCache(config, fakeIdp());
+    const check = (token: string) => verifyToken(token, config, jwks);
+
+    await expect(
+      check(signToken({ ...validClaims, aud: "other-api" })),
+    ).rejects.toThrow(/not intended/);
+    await expect(
+      check(signToken({ ...validClaims, exp: now - 3600 })),
+    ).rejects.toThrow(/expired/);
+    const [header, , signature] = signToken(validClaims).split(".");
+    const forged = Buffer.from(
+      JSON.stringify({ ...validClaims, roles: ["admin"] }),
+    ).toString("base64url");
+    await expect(check(`${header}.${forged}.${signature}`)).rejects.toThrow(
+      /signature/,
+    );
+    await expect(
+      check(signToken(validClaims, { alg: "none", kid: "k1" })),
+    ).rejects.toBeInstanceOf(TokenError);
+  });
+
+  it("rejects algorithms that don't suit the signing key", async () => {
+    const jwks = new JwksCache(config, fakeIdp());
+
+    await expect(
+      verifyToken(
+        signToken(validClaims, { alg: "ES256", kid: "k1" }),
+        config,
+        jwks,
+      ),
+    ).rejects.toThrow(/does not match signing key k1/);
+
+    const restricted = new JwksCache(config, async (url) =>
+      url.endsWith("/keys")
+        ? {
+            keys: [
+              { ...publicKey.export({ format: "jwk" }), kid: "k1", alg: "RS512" },
+            ],
+          }
+        : { jwks_uri: `${ISSUER}/keys` },
+    );
+    await expect(
+      verifyToken(signToken(validClaims), config, restricted),
+    ).rejects.toThrow(/does not match signing key k1/);
+  });
+
+  it("reports an unreachable issuer", async () => {
+    const jwks = new JwksCache(config, async () => {
+      throw new Error("connect ECONNREFUSED");
+    });
+
+    await expect(
+      verifyToken(signToken(validClaims), config, jwks),
+    ).rejects.toBeInstanceOf(IssuerUnavailableError);
+  });
+
+  it("maps the highest recognised role claim", () => {
+    expect(principalFromClaims(validClaims, config)).toEqual({
+      id: "u-123",
+      name: "jsmith",
+      role: "estimator",
+      via: "oidc",
+    });
+    expect(() =>
+      principalFromClaims({ ...validClaims, roles: ["guest"] }, config),
+    ).toThrow(/no recognised roles claim/);
+    expect(
+      principalFromClaims(
+        { ...validClaims, roles: undefined },
+        { ...config, defaultRole: "viewer" },
+      ).role,
+    ).toBe("viewer");
+    expect(() =>
+      principalFromClaims(
+        { ...validClaims, sub: undefined, preferred_username: undefined },
+        config,
+      ),
+    ).toThrow(/no sub or username/);
+  });
+
+  it("authenticates bearer tokens alongside API keys", async () => {
+    const app = new Hono();
+    app.use(
+      "/*",
+      authenticate({
+        apiKeys: [],
+        oidc: { config, jwks: new JwksCache(config, fakeIdp()) },
+      }),
+    );
+    app.get("/me", (c) => c.json(c.get("principal")));
+
+    const ok = await app.request("/me", {
+      headers: { Authorization: `Bearer ${signToken(validClaims)}` },
+    });
+    const expired = await app.request("/me", {
+      headers: {
+        Authorization: `Bearer ${signToken({ ...validClaims, exp: now - 3600 })}`,
+      },
+    });
+
+    expect(ok.status).toBe(200);
+    expect(await ok.json()).toMatchObject({ id: "u-123", via: "oidc" });
+    expect(expired.status).toBe(401);
+    expect(await expired.json()).toMatchObject({ message: "Token has expired" });
+  });
+
+  it("responds 503 while the issuer is unreachable", async () => {
+    const app = new Hono();
+    const jwks = new JwksCache(config, async () => {
+      throw new Error("connect ECONNREFUSED");
+    });
+    app.use("/*", authenticate({ apiKeys: [], oidc: { config, jwks } }));
+    app.get("/me", (c) => c.json(c.get("principal")));
+
+    const response = await app.request("/me", {
+      headers: { Authorization: `Bearer ${signToken(validClaims)}` },
+    });
+
+    expect(response.status).toBe(503);
+    expect(response.headers.get("Retry-After")).toBe("60");
+  });
+});
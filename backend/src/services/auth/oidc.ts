@@ -0,0 +1,309 @@
+/**
+ * OIDC bearer token authentication.
+ *
+ * Validates JWTs issued by the corporate identity provider: signature against
+ * the issuer's JWKS (fetched via OIDC discovery and cached), issuer,
+ * audience, expiry and not-before. The token's algorithm must suit the
+ * signing key it names. The caller's role is read from a token claim; the
+ * highest recognised role wins, and the caller is identified by the token
+ * subject or username.
+ *
+ * Configured with OIDC_ISSUER and OIDC_AUDIENCE, plus optionally
+ * OIDC_JWKS_URI (skips discovery), OIDC_ROLE_CLAIM (default "roles") and
+ * OIDC_DEFAULT_ROLE (role for tokens without a recognised role claim;
+ * unset means such tokens are rejected).
+ */
+
+import { createPublicKey, verify, type JsonWebKey, type KeyObject } from "crypto";
+import { isRole, ROLES, type Principal, type Role } from "./roles";
+
+export type OidcConfig = {
+  issuer: string;
+  audience: string;
+  jwksUri?: string;
+  roleClaim: string;
+  defaultRole?: Role;
+  /** Allowed clock difference in seconds (default: 60) */
+  clockToleranceSeconds?: number;
+};
+
+export class TokenError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "TokenError";
+  }
+}
+
+/**
+ * The issuer's signing keys couldn't be fetched, so tokens can't be checked
+ * until it is reachable again.
+ */
+export class IssuerUnavailableError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "IssuerUnavailableError";
+  }
+}
+
+/**
+ * Read OIDC settings from the environment, or undefined when OIDC is off.
+ */
+export function oidcConfigFromEnv(
+  env: Record<string, string | undefined> = process.env,
+): OidcConfig | undefined {
+  if (!env.OIDC_ISSUER) return undefined;
+  if (!env.OIDC_AUDIENCE) {
+    throw new Error("OIDC_AUDIENCE is required when OIDC_ISSUER is set");
+  }
+  if (env.OIDC_DEFAULT_ROLE && !isRole(env.OIDC_DEFAULT_ROLE)) {
+    throw new Error(`OIDC_DEFAULT_ROLE "${env.OIDC_DEFAULT_ROLE}" is not a role`);
+  }
+  return {
+    issuer: env.OIDC_ISSUER,
+    audience: env.OIDC_AUDIENCE,
+    jwksUri: env.OIDC_JWKS_URI,
+    roleClaim: env.OIDC_ROLE_CLAIM || "roles",
+    defaultRole: env.OIDC_DEFAULT_ROLE as Role | undefined,
+  };
+}
+
+// ============================================================================
+// JWKS
+// ============================================================================
+
+type Jwk = JsonWebKey & { kid?: string; alg?: string; use?: string };
+
+export type SigningKey = {
+  key: KeyObject;
+  /** Algorithm the issuer restricts the key to, if any */
+  alg?: string;
+};
+
+const JWKS_TTL_MS = 10 * 60 * 1000;
+
+/**
+ * Fetches and caches an issuer's signing keys. Unknown key IDs trigger a
+ * refetch (rate-limited to one per minute) so key rotation is picked up.
+ */
+export class JwksCache {
+  private keys = new Map<string, SigningKey>();
+  private fetchedAt = 0;
+  private pending?: Promise<void>;
+
+  constructor(
+    private readonly config: Pick<OidcConfig, "issuer" | "jwksUri">,
+    private readonly fetchJson: (url: string) => Promise<unknown> = async (
+      url,
+    ) => {
+      const response = await fetch(url, { signal: AbortSignal.timeout(5000) });
+      if (!response.ok) {
+        throw new Error(`GET ${url} responded with status ${response.status}`);
+      }
+      return response.json();
+    },
+  ) {}
+
+  /**
+   * @throws TokenError if the issuer has no key `kid`
+   * @throws IssuerUnavailableError if the keys can't be fetched and `kid`
+   *   isn't cached
+   */
+  async getKey(kid: string | undefined): Promise<SigningKey> {
+    const age = Date.now() - this.fetchedAt;
+    const known = this.lookup(kid);
+    if (known && age < JWKS_TTL_MS) return known;
+
+    if (age > 60_000 || this.fetchedAt === 0) {
+      this.pending ??= this.refresh().finally(() => {
+        this.pending = undefined;
+      });
+      // Keep using a key the issuer already gave us while it is unreachable
+      await this.pending.catch((error) => {
+        if (!known) throw error;
+      });
+    }
+
+    const key = this.lookup(kid);
+    if (!key) {
+      throw new TokenError(`No signing key ${kid ?? "(no kid)"} in issuer JWKS`);
+    }
+    return key;
+  }
+
+  private lookup(kid: string | undefined): SigningKey | undefined {
+    if (kid !== undefined) return this.keys.get(kid);
+    return this.keys.size === 1 ? [...this.keys.values()][0] : undefined;
+  }
+
+  private async refresh(): Promise<void> {
+    try {
+      let jwksUri = this.config.jwksUri;
+      if (!jwksUri) {
+        const discovery = (await this.fetchJson(
+          `${this.config.issuer.replace(/\/$/, "")}/.well-known/openid-configuration`,
+        )) as { jwks_uri?: string };
+        if (!discovery.jwks_uri) {
+          throw new Error("OIDC discovery document has no jwks_uri");
+        }
+        jwksUri = discovery.jwks_uri;
+      }
+
+      const { keys = [] } = (await this.fetchJson(jwksUri)) as { keys?: Jwk[] };
+      const next = new Map<string, SigningKey>();
+      keys
+        .filter((jwk) => jwk.use === undefined || jwk.use === "sig")
+        .forEach((jwk, i) => {
+          next.set(jwk.kid ?? `#${i}`, {
+            key: createPublicKey({ key: jwk, format: "jwk" }),
+            ...(jwk.alg && { alg: jwk.alg }),
+          });
+        });
+      this.keys = next;
+      this.fetchedAt = Date.now();
+    } catch (error) {
+      throw new IssuerUnavailableError(
+        `Can't fetch the issuer's signing keys: ${
+          error instanceof Error ? error.message : String(error)
+        }`,
+      );
+    }
+  }
+}
+
+// ============================================================================
+// Verification
+// ============================================================================
+
+/** Hash and key type of each supported algorithm, and the curve for ECDSA */
+const ALGORITHMS: Record<
+  string,
+  { hash: string; keyType: "rsa" | "ec"; curve?: string }
+> = {
+  RS256: { hash: "sha256", keyType: "rsa" },
+  RS384: { hash: "sha384", keyType: "rsa" },
+  RS512: { hash: "sha512", keyType: "rsa" },
+  ES256: { hash: "sha256", keyType: "ec", curve: "prime256v1" },
+  ES384: { hash: "sha384", keyType: "ec", curve: "secp384r1" },
+};
+
+export type TokenClaims = Record<string, unknown> & {
+  iss?: string;
+  sub?: string;
+  aud?: string | string[];
+  exp?: number;
+  nbf?: number;
+};
+
+export function looksLikeJwt(token: string): boolean {
+  return /^[\w-]+\.[\w-]+\.[\w-]+$/.test(token);
+}
+
+function decodeSegment(segment: string): Record<string, unknown> {
+  try {
+    return JSON.parse(Buffer.from(segment, "base64url").toString("utf-8"));
+  } catch {
+    throw new TokenError("Token is not a valid JWT");
+  }
+}
+
+/**
+ * Verify a JWT and return its claims.
+ *
+ * @throws TokenError if the token is malformed, forged, expired or not meant
+ *   for this service, or its algorithm doesn't suit its signing key
+ * @throws IssuerUnavailableError if the issuer's keys can't be fetched
+ */
+export async function verifyToken(
+  token: string,
+  config: OidcConfig,
+  jwks: JwksCache,
+  now: number = Date.now() / 1000,
+): Promise<TokenClaims> {
+  if (!looksLikeJwt(token)) {
+    throw new TokenError("Token is not a valid JWT");
+  }
+  const [headerPart, payloadPart, signaturePart] = token.split(".");
+  const header = decodeSegment(headerPart);
+  const claims = decodeSegment(payloadPart) as TokenClaims;
+
+  const algorithm = ALGORITHMS[header.alg as string];
+  if (!algorithm) {
+    throw new TokenError(`Unsupported token algorithm ${String(header.alg)}`);
+  }
+
+  const kid = header.kid as string | undefined;
+  const { key, alg } = await jwks.getKey(kid);
+  // A token naming the wrong algorithm for its key must not be checked
+  // with that algorithm, e.g. an HMAC or RSA token against an EC key
+  if (
+    key.asymmetricKeyType !== algorithm.keyType ||
+    (algorithm.curve !== undefined &&
+      key.asymmetricKeyDetails?.namedCurve !== algorithm.curve) ||
+    (alg !== undefined && alg !== header.alg)
+  ) {
+    throw new TokenError(
+      `Token algorithm ${String(header.alg)} does not match signing key ${kid ?? "(no kid)"}`,
+    );
+  }
+  const valid = verify(
+    algorithm.hash,
+    Buffer.from(`${headerPart}.${payloadPart}`),
+    algorithm.keyType === "ec" ? { key, dsaEncoding: "ieee-p1363" } : key,
+    Buffer.from(signaturePart, "base64url"),
+  );
+  if (!valid) {
+    throw new TokenError("Token signature is invalid");
+  }
+
+  const tolerance = config.clockToleranceSeconds ?? 60;
+  if (claims.iss !== config.issuer) {
+    throw new TokenError("Token was issued by another issuer");
+  }
+  const audiences = Array.isArray(claims.aud) ? claims.aud : [claims.aud];
+  if (!audiences.includes(config.audience)) {
+    throw new TokenError("Token is not intended for this service");
+  }
+  if (typeof claims.exp !== "number" || claims.exp + tolerance < now) {
+    throw new TokenError("Token has expired");
+  }
+  if (typeof claims.nbf === "number" && claims.nbf - tolerance > now) {
+    throw new TokenError("Token is not valid yet");
+  }
+  return claims;
+}
+
+/**
+ * Build the principal for verified claims. The role claim may be a string
+ * or an array; the highest recognised role is used.
+ *
+ * @throws TokenError if the token carries no recognised role and there is no
+ *   default role, or identifies no one
+ */
+export function principalFromClaims(
+  claims: TokenClaims,
+  config: OidcConfig,
+): Principal {
+  const claim = claims[config.roleClaim];
+  const values = Array.isArray(claim) ? claim : [claim];
+  const roles = values.filter(isRole);
+  const role = roles.length
+    ? roles.reduce((a, b) => (ROLES.indexOf(a) >= ROLES.indexOf(b) ? a : b))
+    : config.defaultRole;
+  if (!role) {
+    throw new TokenError(`Token has no recognised ${config.roleClaim} claim`);
+  }
+
+  const name = [claims.preferred_username, claims.email, claims.name].find(
+    (v): v is string => typeof v === "string" && v !== "",
+  );
+  const id = typeof claims.sub === "string" && claims.sub ? claims.sub : name;
+  if (!id) {
+    throw new TokenError("Token has no sub or username claim");
+  }
+  return {
+    id,
+    ...(name && { name }),
+    role,
+    via: "oidc",
+  };
+}
@@ -0,0 +1,36 @@
+/**
+ * Role model for authorization.
+ *
+ * Roles are ordered: each role can do everything the roles before it can.
+ * - viewer: read libraries, run estimates
+ * - estimator: viewer, plus estimate history and comparisons
+ * - librarian: estimator, plus library drafts, publishing and uploads
+ * - admin: everything, including administrative endpoints
+ */
+
+export const ROLES = ["viewer", "estimator", "librarian", "admin"] as const;
+
+export type Role = (typeof ROLES)[number];
+
+/**
+ * The authenticated caller of a request.
+ */
+export type Principal = {
+  /** Stable identifier, e.g. an API key name or the token subject */
+  id: string;
+  name?: string;
+  role: Role;
+  /** How the caller authenticated */
+  via: "api-key" | "oidc" | "anonymous";
+};
+
+export function isRole(value: unknown): value is Role {
+  return (ROLES as readonly unknown[]).includes(value);
+}
+
+/**
+ * Whether `role` includes the permissions of `required`.
+ */
+export function hasRole(role: Role, required: Role): boolean {
+  return ROLES.indexOf(role) >= ROLES.indexOf(required);
+}
@@ -0,0 +1,93 @@
+import { describe, it, expect } from "vitest";
+import { applyAccuracyBands } from "./accuracy";
+import { transformCostingResponse, type AssetMetadata } from "./adapter";
+import { DEFAULT_OPEX_FACTORS } from "./defaults";
+import { ESTIMATE_PRESETS } from "./presets";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+describe("accuracy", () => {
+  const estimate = {
+    assets: ["asset-1", "asset-2"].map((id) =>
+      mockAssetEstimate(
+        id,
+        [mockYearCosts(2025, { total_installed_cost: 1000 })],
+        [mockCostItem(`${id}/blocks/0/Item 001`, { total_installed_cost: 1000 })],
+      ),
+    ),
+  };
+
+  function metadata(
+    assetId: string,
+    preset?: "AACE Class 5" | "AACE Class 3",
+  ): AssetMetadata {
+    return {
+      assetId,
+      isGroup: false,
+      branchIds: [assetId],
+      blockCount: 1,
+      costableBlockCount: 1,
+      usingDefaults: [],
+      provenance: preset && {
+        preset,
+        capexLangFactors: ESTIMATE_PRESETS[preset].capex_lang_factors,
+        opexFactors: DEFAULT_OPEX_FACTORS,
+      },
+      blocks: [],
+    };
+  }
+
+  it("uses each asset's preset range", () => {
+    const response = transformCostingResponse(
+      estimate,
+      [metadata("asset-1", "AACE Class 5"), metadata("asset-2", "AACE Class 3")],
+      "USD",
+    );
+
+    const result = applyAccuracyBands(response);
+
+    expect(result.assets[0].accuracy).toEqual({
+      range: { low: -0.3, high: 0.5 },
+      source: "preset",
+      lifetimeCost: { low: 700, high: 1500 },
+      lifetimeNpcCost: { low: 700, high: 1500 },
+    });
+    expect(result.assets[1].accuracy?.lifetimeCost).toEqual({
+      low: 850,
+      high: 1200,
+    });
+    expect(result.accuracy?.lifetimeCost).toEqual({ low: 1550, high: 2700 });
+  });
+
+  it("prefers an explicit range over presets", () => {
+    const response = transformCostingResponse(
+      estimate,
+      [metadata("asset-1", "AACE Class 5"), metadata("asset-2")],
+      "USD",
+    );
+
+    const result = applyAccuracyBands(response, { low: -0.25, high: 0.25 });
+
+    expect(result.assets[0].accuracy?.source).toBe("request");
+    expect(result.assets[1].accuracy?.lifetimeCost).toEqual({
+      low: 750,
+      high: 1250,
+    });
+  });
+
+  it("omits the network band unless every asset has one", () => {
+    const response = transformCostingResponse(
+      estimate,
+      [metadata("asset-1", "AACE Class 5"), metadata("asset-2")],
+      "USD",
+    );
+
+    const result = applyAccuracyBands(response);
+
+    expect(result.assets[1].accuracy).toBeUndefined();
+    expect(result.accuracy).toBeUndefined();
+  });
+});
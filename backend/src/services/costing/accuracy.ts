@@ -0,0 +1,103 @@
+/**
+ * Accuracy bands.
+ *
+ * Turns point estimates into low/high ranges using the accuracy range of the
+ * asset's estimate class, or one given explicitly in the request, so results
+ * aren't presented as more precise than the estimate class supports.
+ */
+
+import type { CostingEstimateResponse, AssetCostResult } from "./request-types";
+import { ESTIMATE_PRESETS, type AccuracyRange } from "./presets";
+import { roundValue, type RoundingPolicy } from "./rounding";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CostRange = {
+  low: number;
+  high: number;
+};
+
+export type AccuracyBand = {
+  /** Fractional range applied to the point estimates */
+  range: AccuracyRange;
+  /** Whether the range came from the asset's preset or the request */
+  source: "preset" | "request";
+  /** Band around lifetime total cost (undiscounted) */
+  lifetimeCost: CostRange;
+  /** Band around lifetime total cost (discounted) */
+  lifetimeNpcCost: CostRange;
+};
+
+export type NetworkAccuracyBand = Pick<
+  AccuracyBand,
+  "lifetimeCost" | "lifetimeNpcCost"
+>;
+
+// ============================================================================
+// Bands
+// ============================================================================
+
+/**
+ * Attach accuracy bands to each asset and to the network.
+ *
+ * An explicit range applies to every asset; otherwise each asset uses the
+ * range of its preset, and assets without a preset get no band. The network
+ * band is the sum of the asset bands and is only given when every asset has
+ * one. Band values are rounded with the response's rounding policy.
+ */
+export function applyAccuracyBands(
+  response: CostingEstimateResponse,
+  explicitRange?: AccuracyRange,
+  rounding?: RoundingPolicy,
+): CostingEstimateResponse {
+  const round = (value: number) =>
+    rounding ? roundValue(value, rounding) : value;
+  const band = (total: number, range: AccuracyRange): CostRange => ({
+    low: round(total * (1 + range.low)),
+    high: round(total * (1 + range.high)),
+  });
+
+  const assets = response.assets.map((asset): AssetCostResult => {
+    const preset = asset.provenance?.preset;
+    const range =
+      explicitRange ?? (preset ? ESTIMATE_PRESETS[preset].accuracy : undefined);
+    if (!range) return asset;
+
+    return {
+      ...asset,
+      accuracy: {
+        range,
+        source: explicitRange ? "request" : "preset",
+        lifetimeCost: band(asset.lifetimeCosts.total, range),
+        lifetimeNpcCost: band(asset.lifetimeNpcCosts.total, range),
+      },
+    };
+  });
+
+  const bands = assets.map((a) => a.accuracy);
+  const allBanded =
+    bands.length > 0 && bands.every((b): b is AccuracyBand => !!b);
+
+  return {
+    ...response,
+    assets,
+    ...(allBanded && {
+      accuracy: {
+        lifetimeCost: sumRanges(bands.map((b) => b.lifetimeCost), round),
+        lifetimeNpcCost: sumRanges(bands.map((b) => b.lifetimeNpcCost), round),
+      },
+    }),
+  };
+}
+
+function sumRanges(
+  ranges: CostRange[],
+  normalize: (value: number) => number,
+): CostRange {
+  return {
+    low: normalize(ranges.reduce((sum, r) => sum + r.low, 0)),
+    high: normalize(ranges.reduce((sum, r) => sum + r.high, 0)),
+  };
+}
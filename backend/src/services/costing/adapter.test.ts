@@ -2,6 +2,9 @@ import { describe, it, expect } from "vitest";
 import {
   transformNetworkToCostingRequest,
   transformCostingResponse,
+  defaultedParameters,
+  inheritedParameters,
+  type AssetMetadata,
 } from "./adapter";
 import type { CostEstimateResponse } from "./types";
 import type { NetworkSource } from "./request-types";
@@ -281,4 +284,96 @@ describe("adapter", () => {
       expect(result.lifetimeCosts.totalInstalledCost).toBe(1800000);
     });
   });
+
+  describe("defaultedParameters", () => {
+    it("collects parameters costed with library defaults", () => {
+      const metadata: AssetMetadata = {
+        assetId: "group-1",
+        name: "Capture plant",
+        isGroup: true,
+        branchIds: ["branch-1"],
+        blockCount: 1,
+        costableBlockCount: 1,
+        usingDefaults: [],
+        blocks: [
+          {
+            id: "branch-1/blocks/0",
+            type: "CaptureUnit",
+            status: "costable",
+            definedProperties: {},
+            missingProperties: [],
+            costItems: [
+              {
+                id: "branch-1/blocks/0/Item 023",
+                ref: "Item 023",
+                included: true,
+                parameters: [
+                  {
+                    name: "Mass flow",
+                    units: "kg/h",
+                    satisfied: true,
+                    value: 100000,
+                    defaulted: true,
+                  },
+                ],
+              },
+            ],
+          },
+        ],
+      };
+
+      expect(defaultedParameters([metadata])).toEqual({
+        "branch-1/blocks/0/Item 023": ["Mass flow"],
+      });
+    });
+  });
+
+  describe("inheritedParameters", () => {
+    it("collects parameters supplied by the request, with their level", () => {
+      const parameter = (name: string, inherited?: "asset" | "global") => ({
+        name,
+        units: "kW",
+        satisfied: true,
+        value: 10,
+        ...(inherited && { inherited }),
+      });
+      const metadata: AssetMetadata = {
+        assetId: "branch-1",
+        isGroup: false,
+        branchIds: ["branch-1"],
+        blockCount: 2,
+        costableBlockCount: 2,
+        usingDefaults: [],
+        blocks: [0, 1].map((i) => ({
+          id: `branch-1/blocks/${i}`,
+          type: "Compressor",
+          status: "costable" as const,
+          definedProperties: {},
+          missingProperties: [],
+          costItems: [
+            {
+              id: `branch-1/blocks/${i}/Item 007`,
+              ref: "Item 007",
+              included: true,
+              parameters:
+                i === 0
+                  ? [
+                      parameter("Compressor Duty"),
+                      parameter("Electrical power", "asset"),
+                      parameter("Electricity price", "global"),
+                    ]
+                  : [parameter("Compressor Duty")],
+            },
+          ],
+        })),
+      };
+
+      expect(inheritedParameters([metadata])).toEqual({
+        "branch-1/blocks/0/Item 007": {
+          "Electrical power": "asset",
+          "Electricity price": "global",
+        },
+      });
+    });
+  });
 });
@@ -14,6 +14,9 @@ import type {
   AssetParameters,
   CostItemParameters,
   CostEstimateResponse,
+  CostItemBundle,
+  CbsMapping,
+  Timeline,
 } from "./types";
 import type {
   CostingEstimateResponse,
@@ -28,6 +31,10 @@ import type {
   NetworkGroup,
   NetworkBranch,
   NetworkBlock,
+  AssetProvenance,
+  ResolvedAssetProperties,
+  ParameterLevel,
+  ParameterValues,
 } from "./request-types";
 import { resolveAssetProperties } from "./request-types";
 import {
@@ -35,6 +42,37 @@ import {
   mapBlockToModuleDetailed,
 } from "./block-to-module-mapper";
 import { getModuleLookupService } from "./module-lookup";
+import type { ParameterRegistry } from "./parameter-registry";
+import { costItemPropertySuffix } from "./cost-item-aliases";
+import {
+  checkItemConstraints,
+  type BlockConstraintViolation,
+} from "./item-constraints";
+import type { CostItemAudit } from "./audit";
+import { cbsCodeFor, cbsLines } from "./cbs-coding";
+import { explainTotalInstalledCost } from "./explain";
+import { assetTopDrivers, portfolioTopDrivers } from "./top-drivers";
+import {
+  computeAssetKpis,
+  missingKpiInputs,
+  unitTransportCost,
+  type KpiInputs,
+} from "./kpis";
+import { aggregateLifetimeCosts, withTotal } from "./totals";
+import type { UtilityPrices } from "./utility-prices";
+import {
+  carbonValue,
+  networkCarbonValue,
+  type CarbonPriceTrajectory,
+} from "./carbon-value";
+import { discountedLifetimeCosts } from "./discount-rates";
+import {
+  discountRateFor,
+  escalationFactor,
+  REAL_PRICE_BASIS,
+  type PriceBasis,
+} from "./price-basis";
+import { evaluateQuantity } from "./quantity-expressions";
 import dim from "../dim";
 import { getDagger } from "../../utils/getDagger";
 
@@ -145,6 +183,9 @@ export type CostingTransformOptions = {
   libraryId: string;
   assetDefaults?: AssetPropertyOverrides;
   assetOverrides?: Record<string, AssetPropertyOverrides>;
+  globalParameters?: ParameterValues;
+  assetParameters?: Record<string, ParameterValues>;
+  utilityPrices?: UtilityPrices;
 };
 
 export type CostingTransformResult = {
@@ -163,6 +204,40 @@ export type BlockValidation = {
   /** Module it maps to (if costable) */
   moduleType?: string;
   moduleSubtype?: string;
+  /** Cost library module ID (if found in the library) */
+  moduleId?: string;
+  /** Required parameters of each cost item in the module (if costable) */
+  costItems?: CostItemReport[];
+  /** Module selection rules the block's cost items break (if any) */
+  constraintViolations?: BlockConstraintViolation[];
+};
+
+export type CostItemReport = {
+  /** Cost item ID as it would be sent to the costing server */
+  id: string;
+  ref: string;
+  /** Bundle the cost item is a component of (if any) */
+  bundle?: string;
+  /** Whether all required parameters are satisfied, so the item is costed */
+  included: boolean;
+  parameters: ParameterReport[];
+};
+
+export type ParameterReport = {
+  name: string;
+  units: string;
+  satisfied: boolean;
+  /** Value taken from the block (undefined if not satisfied) */
+  value?: number;
+  /**
+   * Earlier cost item ID, from the library's aliases, that named the
+   * item-specific block property the value was read from
+   */
+  aliasedFrom?: string;
+  /** The block didn't supply the value; it is the library default */
+  defaulted?: boolean;
+  /** The block didn't supply the value; the request did, at this level */
+  inherited?: ParameterLevel;
 };
 
 export type AssetMetadata = {
@@ -176,8 +251,12 @@ export type AssetMetadata = {
   costableBlockCount: number;
   /** Which asset-level properties are using defaults */
   usingDefaults: string[];
+  /** Resolved factors sent to the costing server */
+  provenance?: AssetProvenance;
   /** Per-block validation details */
   blocks: BlockValidation[];
+  /** Type and throughput for the asset's KPIs (when asset_type is set) */
+  kpiInputs?: KpiInputs;
 };
 
 /**
@@ -342,6 +421,7 @@ async function transformGroupToAsset(
   const allCostItems: CostItemParameters[] = [];
   const branchIds: string[] = [];
   const blockValidations: BlockValidation[] = [];
+  const requestParameters = requestParametersFor(options, group.id);
 
   for (const branch of branches) {
     branchIds.push(branch.id);
@@ -364,19 +444,31 @@ async function transformGroupToAsset(
 
       // Transform to cost items if costable
       if (validation.status === "costable") {
-        const costItems = await transformBlockToCostItems(
-          enrichedBlock,
-          blockId,
-          moduleLookup
-        );
+        const { costItems, report, violations, moduleId } =
+          await transformBlockToCostItems(
+            enrichedBlock,
+            blockId,
+            moduleLookup,
+            requestParameters,
+            branch.blocks
+          );
         allCostItems.push(...costItems);
+        validation.moduleId = moduleId;
+        validation.costItems = report;
+        if (violations.length > 0) {
+          validation.constraintViolations = violations;
+        }
       }
     }
   }
 
   // Resolve asset properties (apply overrides)
   const overrides = options.assetOverrides?.[group.id];
-  const resolved = resolveAssetProperties(overrides, options.assetDefaults);
+  const resolved = resolveAssetProperties(
+    overrides,
+    options.assetDefaults,
+    moduleLookup.getFactorDefaults()
+  );
 
   const asset: AssetParameters = {
     id: group.id,
@@ -390,6 +482,7 @@ async function transformGroupToAsset(
     discount_rate: resolved.discount_rate,
   };
 
+  const kpiInputs = kpiInputsFor(resolved);
   const metadata: AssetMetadata = {
     assetId: group.id,
     name: group.label || group.id,
@@ -399,7 +492,17 @@ async function transformGroupToAsset(
     costableBlockCount: blockValidations.filter((b) => b.status === "costable")
       .length,
     usingDefaults: Array.from(resolved.usingDefaults),
+    provenance: {
+      preset: resolved.preset,
+      capexLangFactors: resolved.capex_lang_factors,
+      opexFactors: resolved.opex_factors,
+      ...(resolved.throughput_profile && {
+        throughputProfile: resolved.throughput_profile,
+      }),
+      ...(options.utilityPrices && { utilityPrices: options.utilityPrices }),
+    },
     blocks: blockValidations,
+    ...(kpiInputs && { kpiInputs }),
   };
 
   return { asset, metadata };
@@ -417,6 +520,7 @@ async function transformBranchToAsset(
 ): Promise<{ asset: AssetParameters; metadata: AssetMetadata }> {
   const costItems: CostItemParameters[] = [];
   const blockValidations: BlockValidation[] = [];
+  const requestParameters = requestParametersFor(options, branch.id);
 
   for (let i = 0; i < branch.blocks.length; i++) {
     const block = branch.blocks[i];
@@ -436,18 +540,30 @@ async function transformBranchToAsset(
 
     // Transform to cost items if costable
     if (validation.status === "costable") {
-      const blockCostItems = await transformBlockToCostItems(
-        enrichedBlock,
-        blockId,
-        moduleLookup
-      );
+      const { costItems: blockCostItems, report, violations, moduleId } =
+        await transformBlockToCostItems(
+          enrichedBlock,
+          blockId,
+          moduleLookup,
+          requestParameters,
+          branch.blocks
+        );
       costItems.push(...blockCostItems);
+      validation.moduleId = moduleId;
+      validation.costItems = report;
+      if (violations.length > 0) {
+        validation.constraintViolations = violations;
+      }
     }
   }
 
   // Resolve asset properties (ungrouped branches use defaults unless overridden)
   const overrides = options.assetOverrides?.[branch.id];
-  const resolved = resolveAssetProperties(overrides, options.assetDefaults);
+  const resolved = resolveAssetProperties(
+    overrides,
+    options.assetDefaults,
+    moduleLookup.getFactorDefaults()
+  );
 
   const asset: AssetParameters = {
     id: branch.id,
@@ -461,6 +577,7 @@ async function transformBranchToAsset(
     discount_rate: resolved.discount_rate,
   };
 
+  const kpiInputs = kpiInputsFor(resolved);
   const metadata: AssetMetadata = {
     assetId: branch.id,
     name: branch.label || branch.id,
@@ -470,12 +587,76 @@ async function transformBranchToAsset(
     costableBlockCount: blockValidations.filter((b) => b.status === "costable")
       .length,
     usingDefaults: Array.from(resolved.usingDefaults),
+    provenance: {
+      preset: resolved.preset,
+      capexLangFactors: resolved.capex_lang_factors,
+      opexFactors: resolved.opex_factors,
+      ...(resolved.throughput_profile && {
+        throughputProfile: resolved.throughput_profile,
+      }),
+      ...(options.utilityPrices && { utilityPrices: options.utilityPrices }),
+    },
     blocks: blockValidations,
+    ...(kpiInputs && { kpiInputs }),
   };
 
   return { asset, metadata };
 }
 
+/**
+ * KPI inputs from an asset's resolved properties; undefined for an untyped
+ * asset.
+ */
+function kpiInputsFor(
+  resolved: ResolvedAssetProperties
+): KpiInputs | undefined {
+  if (!resolved.asset_type) return undefined;
+  return {
+    assetType: resolved.asset_type,
+    throughput: resolved.throughput,
+    lengthKm: resolved.length_km,
+    timeline: resolved.timeline,
+    discountRate: resolved.discount_rate,
+  };
+}
+
+// ============================================================================
+// Request Parameters
+// ============================================================================
+
+/** Parameter values the request supplies to an asset's blocks, by level */
+type RequestParameters = Partial<Record<ParameterLevel, ParameterValues>>;
+
+/** Levels in order of precedence */
+const PARAMETER_LEVELS: ParameterLevel[] = ["asset", "global"];
+
+function requestParametersFor(
+  options: CostingTransformOptions,
+  assetId: string
+): RequestParameters {
+  return {
+    asset: options.assetParameters?.[assetId],
+    global: options.globalParameters,
+  };
+}
+
+/**
+ * The request's value for any of a parameter's block properties, from the
+ * most specific level that supplies one.
+ */
+function requestParameterValue(
+  requestParameters: RequestParameters,
+  blockProperties: string[]
+): { value: number | string; level: ParameterLevel } | undefined {
+  for (const level of PARAMETER_LEVELS) {
+    const value = blockProperties
+      .map((prop) => requestParameters[level]?.[prop])
+      .find((v) => v !== undefined);
+    if (value !== undefined) return { value, level };
+  }
+  return undefined;
+}
+
 // ============================================================================
 // Block → Cost Item Transformation
 // ============================================================================
@@ -485,17 +666,25 @@ async function transformBranchToAsset(
  *
  * A single block can produce multiple cost items because cost library modules
  * often have multiple components (e.g., LP Compression has a compressor + cooler).
+ * The block's quantity may be an expression over the blocks on its branch.
  */
 async function transformBlockToCostItems(
   block: NetworkBlock,
   blockPath: string,
-  moduleLookup: Awaited<ReturnType<typeof getModuleLookupService>>
-): Promise<CostItemParameters[]> {
+  moduleLookup: Awaited<ReturnType<typeof getModuleLookupService>>,
+  requestParameters: RequestParameters,
+  branchBlocks: NetworkBlock[]
+): Promise<{
+  costItems: CostItemParameters[];
+  report: CostItemReport[];
+  violations: BlockConstraintViolation[];
+  moduleId?: string;
+}> {
   // Map generic block to cost library module
   const mapping = mapBlockToModule(block);
   if (!mapping) {
     console.warn(`No module mapping found for block type: ${block.type}`);
-    return [];
+    return { costItems: [], report: [], violations: [] };
   }
 
   // Look up the module in the cost library
@@ -507,30 +696,31 @@ async function transformBlockToCostItems(
     console.warn(
       `Module not found in cost library: ${mapping.moduleType}/${mapping.subtype}`
     );
-    return [];
+    return { costItems: [], report: [], violations: [] };
   }
 
   const costItems: CostItemParameters[] = [];
-  const quantity = block.quantity ?? 1;
+  const report: CostItemReport[] = [];
+  const quantity = evaluateQuantity(block.quantity, branchBlocks);
 
   // Create a cost item for each cost reference item in the module
   for (const costItemRef of moduleInfo.costItemIds) {
     const costItem = moduleLookup.getCostItem?.(moduleInfo.id, costItemRef);
 
-    // Collect all required parameter names for this cost item
-    const requiredParams: string[] = [];
-    for (const sf of costItem?.scaling_factors ?? []) {
-      requiredParams.push(sf.name);
-    }
-    for (const opex of costItem?.variable_opex_contributions ?? []) {
-      requiredParams.push(opex.name);
-    }
+    // Collect all required parameters for this cost item
+    const required = [
+      ...(costItem?.scaling_factors ?? []),
+      ...(costItem?.variable_opex_contributions ?? []),
+    ];
+    const requiredParams = required.map((p) => p.name);
 
-    const parameters = await extractParametersForCostItem(
+    const { parameters, aliasedFrom, defaulted, inherited } =
+      await extractParametersForCostItem(
       block,
       costItemRef,
       moduleInfo,
-      moduleLookup
+      moduleLookup,
+      requestParameters
     );
 
     // Check if we have ALL required parameters
@@ -542,7 +732,8 @@ async function transformBlockToCostItems(
       providedParams.has(name)
     );
 
-    if (requiredParams.length === 0 || hasAllRequiredParams) {
+    const included = requiredParams.length === 0 || hasAllRequiredParams;
+    if (included) {
       costItems.push({
         id: `${blockPath}/${costItemRef}`,
         ref: costItemRef,
@@ -550,17 +741,173 @@ async function transformBlockToCostItems(
         parameters,
       });
     }
+
+    report.push({
+      id: `${blockPath}/${costItemRef}`,
+      ref: costItemRef,
+      included,
+      parameters: required.map((p) => ({
+        name: p.name,
+        units: p.units,
+        satisfied: providedParams.has(p.name),
+        value: parameters[p.name],
+        ...(aliasedFrom[p.name] && { aliasedFrom: aliasedFrom[p.name] }),
+        ...(defaulted.includes(p.name) && { defaulted: true }),
+        ...(inherited[p.name] && { inherited: inherited[p.name] }),
+      })),
+    });
   }
 
-  return costItems;
+  // Expand the module's bundles into their component cost items
+  for (const bundleId of moduleInfo.bundleIds) {
+    const bundle = moduleLookup.getBundle(bundleId);
+    if (!bundle) {
+      console.warn(`Bundle not found in cost library: ${bundleId}`);
+      continue;
+    }
+    const expanded = await expandBundle(
+      block,
+      blockPath,
+      bundle,
+      quantity,
+      moduleLookup,
+      requestParameters
+    );
+    costItems.push(...expanded.costItems);
+    report.push(...expanded.report);
+  }
+
+  // Check the items the block selected against the module's rules
+  const selected = [
+    ...new Set(
+      report.filter((r) => r.included).map((r) => r.bundle ?? r.ref)
+    ),
+  ];
+  const violations = checkItemConstraints(
+    moduleLookup.getConstraints(moduleInfo.id),
+    selected
+  ).map((v) => ({ ...v, blockId: blockPath, moduleId: moduleInfo.id }));
+
+  return { costItems, report, violations, moduleId: moduleInfo.id };
+}
+
+/**
+ * Expand a bundle into one cost item per component. The block (or the
+ * request) supplies the bundle's parameters, which pass through to the
+ * components; a bundle is costed only when every component's parameters are
+ * satisfied.
+ */
+async function expandBundle(
+  block: NetworkBlock,
+  blockPath: string,
+  bundle: CostItemBundle,
+  quantity: number,
+  moduleLookup: Awaited<ReturnType<typeof getModuleLookupService>>,
+  requestParameters: RequestParameters
+): Promise<{ costItems: CostItemParameters[]; report: CostItemReport[] }> {
+  const components = await Promise.all(
+    bundle.components.map(async (component) => {
+      const costItem = moduleLookup.findCostItem(component.ref);
+      const ref = costItem?.id ?? component.ref;
+      const required = [
+        ...(costItem?.scaling_factors ?? []),
+        ...(costItem?.variable_opex_contributions ?? []),
+      ];
+
+      const parameters: Record<string, number> = {};
+      const defaulted: string[] = [];
+      const inherited: Record<string, ParameterLevel> = {};
+      for (const p of required) {
+        const bundleParam = component.parameters?.[p.name] ?? p.name;
+        const blockProps = moduleLookup.parameters.blockProperties(bundleParam);
+        const blockValue = blockProps
+          .map((prop) => block[prop])
+          .find((v) => v !== undefined && v !== null);
+        const fromRequest =
+          blockValue === undefined || blockValue === null
+            ? requestParameterValue(requestParameters, blockProps)
+            : undefined;
+        const value = fromRequest?.value ?? blockValue;
+        if (value !== undefined && value !== null) {
+          const numericValue = await convertParameterValue(value, p.units);
+          if (numericValue !== null) {
+            parameters[p.name] = numericValue;
+            if (fromRequest) inherited[p.name] = fromRequest.level;
+          }
+        } else if (p.default_value !== undefined) {
+          parameters[p.name] = p.default_value;
+          defaulted.push(p.name);
+        }
+      }
+
+      return {
+        id: `${blockPath}/bundles/${bundle.id}/${ref}`,
+        ref,
+        quantity: quantity * (component.quantity ?? 1),
+        parameters,
+        defaulted,
+        inherited,
+        found: costItem !== undefined,
+        required: required.map((p) => ({
+          name: component.parameters?.[p.name] ?? p.name,
+          itemName: p.name,
+          units: p.units,
+        })),
+      };
+    })
+  );
+
+  const included = components.every(
+    (c) => c.found && c.required.every((p) => p.itemName in c.parameters)
+  );
+
+  return {
+    costItems: included
+      ? components.map(({ id, ref, quantity, parameters }) => ({
+          id,
+          ref,
+          quantity,
+          parameters,
+        }))
+      : [],
+    report: components.map((c) => ({
+      id: c.id,
+      ref: c.ref,
+      bundle: bundle.id,
+      included,
+      parameters: c.required.map((p) => ({
+        name: p.name,
+        units: p.units,
+        satisfied: p.itemName in c.parameters,
+        value: c.parameters[p.itemName],
+        ...(c.defaulted.includes(p.itemName) && { defaulted: true }),
+        ...(c.inherited[p.itemName] && {
+          inherited: c.inherited[p.itemName],
+        }),
+      })),
+    })),
+  };
 }
 
+type ExtractedParameters = {
+  parameters: Record<string, number>;
+  /** Earlier cost item ID whose item-specific property supplied a value */
+  aliasedFrom: Record<string, string>;
+  /** Parameters the block didn't supply, taken from the library default */
+  defaulted: string[];
+  /** Parameters the block didn't supply, taken from the request */
+  inherited: Record<string, ParameterLevel>;
+};
+
 /**
  * Extract parameters from a block that apply to a specific cost item.
  *
  * Block properties can be mapped to cost items in several ways:
  * 1. Direct match: block.compressor_duty → "Compressor Duty"
  * 2. Item-specific: block.electrical_power_compressor → "Electrical power" for Item 007
+ *
+ * Values the block doesn't supply are inherited from the request's asset
+ * then global parameters, and only then taken from the library default.
  */
 async function extractParametersForCostItem(
   block: NetworkBlock,
@@ -573,46 +920,71 @@ async function extractParametersForCostItem(
       costItemId?: string;
     }>;
   },
-  moduleLookup: Awaited<ReturnType<typeof getModuleLookupService>>
-): Promise<Record<string, number>> {
+  moduleLookup: Awaited<ReturnType<typeof getModuleLookupService>>,
+  requestParameters: RequestParameters
+): Promise<ExtractedParameters> {
   const parameters: Record<string, number> = {};
+  const aliasedFrom: Record<string, string> = {};
+  const inherited: Record<string, ParameterLevel> = {};
 
   // Get required parameters for this specific cost item
   const costItem = moduleLookup.getCostItem?.(moduleInfo.id, costItemRef);
   if (!costItem) {
     // Fall back to module-level parameters if we can't get item-specific ones
-    return extractModuleLevelParameters(block, moduleInfo);
+    return {
+      parameters: await extractModuleLevelParameters(
+        block,
+        moduleInfo,
+        moduleLookup.parameters
+      ),
+      aliasedFrom,
+      defaulted: [],
+      inherited,
+    };
   }
 
   // Collect all parameter names needed for this cost item
-  const requiredParams: Array<{ name: string; units: string }> = [];
-
-  // Add scaling factors
-  for (const sf of costItem.scaling_factors || []) {
-    requiredParams.push({ name: sf.name, units: sf.units });
-  }
-
-  // Add variable OPEX contributions
-  for (const opex of costItem.variable_opex_contributions || []) {
-    requiredParams.push({ name: opex.name, units: opex.units });
-  }
+  const requiredParams: Array<{
+    name: string;
+    units: string;
+    default_value?: number;
+  }> = [
+    // Scaling factors, then variable OPEX contributions
+    ...(costItem.scaling_factors || []),
+    ...(costItem.variable_opex_contributions || []),
+  ];
+  const defaulted: string[] = [];
+
+  // Item-specific properties may still be named after an ID the cost item
+  // had in an earlier library version
+  const itemSuffixes = [
+    { suffix: getItemSuffix(costItemRef, costItem) },
+    ...moduleLookup.previousCostItemIds(costItemRef).map((previousId) => ({
+      suffix: costItemPropertySuffix(previousId),
+      previousId,
+    })),
+  ];
 
   // Try to find each parameter in the block
   for (const param of requiredParams) {
-    const itemSuffix = getItemSuffix(costItemRef, costItem);
-    const possibleBlockProps = mapCostParamToBlockProp(param.name);
+    const possibleBlockProps = moduleLookup.parameters.blockProperties(
+      param.name
+    );
 
     let value: unknown = undefined;
 
     // Try item-specific properties first (e.g., electrical_power_compressor)
-    for (const prop of possibleBlockProps) {
-      const itemSpecificProp = `${prop}_${itemSuffix}`;
-      if (
-        block[itemSpecificProp] !== undefined &&
-        block[itemSpecificProp] !== null
-      ) {
-        value = block[itemSpecificProp];
-        break;
+    itemSpecific: for (const { suffix, previousId } of itemSuffixes) {
+      for (const prop of possibleBlockProps) {
+        const itemSpecificProp = `${prop}_${suffix}`;
+        if (
+          block[itemSpecificProp] !== undefined &&
+          block[itemSpecificProp] !== null
+        ) {
+          value = block[itemSpecificProp];
+          if (previousId) aliasedFrom[param.name] = previousId;
+          break itemSpecific;
+        }
       }
     }
 
@@ -626,15 +998,31 @@ async function extractParametersForCostItem(
       }
     }
 
+    // Then to the request's parameters
+    let level: ParameterLevel | undefined;
+    if (value === undefined || value === null) {
+      const fromRequest = requestParameterValue(
+        requestParameters,
+        possibleBlockProps
+      );
+      value = fromRequest?.value;
+      level = fromRequest?.level;
+    }
+
     if (value !== undefined && value !== null) {
       const numericValue = await convertParameterValue(value, param.units);
       if (numericValue !== null) {
         parameters[param.name] = numericValue;
+        if (level) inherited[param.name] = level;
       }
+    } else if (param.default_value !== undefined) {
+      // Defaults are already in the parameter's units
+      parameters[param.name] = param.default_value;
+      defaulted.push(param.name);
     }
   }
 
-  return parameters;
+  return { parameters, aliasedFrom, defaulted, inherited };
 }
 
 /**
@@ -662,42 +1050,7 @@ function getItemSuffix(
   }
 
   // Fall back to item number (e.g., "item_007")
-  return costItemRef.toLowerCase().replace(/\s+/g, "_");
-}
-
-/**
- * Map cost library parameter names to block property names.
- * Handles typos, special characters, and naming conventions.
- */
-function mapCostParamToBlockProp(costParamName: string): string[] {
-  // Explicit mappings for parameters that don't normalize cleanly
-  const explicitMappings: Record<string, string[]> = {
-    // Cost library typo: "moter" instead of "motor"
-    "Pump moter rating": ["pump_motor_rating", "pump_moter_rating"],
-    // Volumetric flowrate
-    "Pump flowrate (volumetric)": ["pump_flowrate", "pump_flowrate_volumetric"],
-    // Cooling water with temperature specification
-    "Cooling water (10degC temp rise)": [
-      "cooling_water",
-      "cooling_water_10degc_temp_rise",
-    ],
-    // Heater duty capitalization
-    "Heater Duty": ["heater_duty"],
-    // Pipeline crossings - V1.1/V1.3 uses "Frequency of crossings per 10 km"
-    "Frequency of crossings per 10 km": [
-      "crossings_frequency",
-      "frequency_of_crossings_per_10_km",
-    ],
-    // V2.0 uses "Number of crossings" - keep both for compatibility
-    "Number of crossings": ["number_of_crossings", "crossings_frequency"],
-  };
-
-  if (explicitMappings[costParamName]) {
-    return explicitMappings[costParamName];
-  }
-
-  // Default: normalize the parameter name
-  return [normalizeParameterName(costParamName)];
+  return costItemPropertySuffix(costItemRef);
 }
 
 /**
@@ -705,13 +1058,16 @@ function mapCostParamToBlockProp(costParamName: string): string[] {
  */
 async function extractModuleLevelParameters(
   block: NetworkBlock,
-  moduleInfo: { requiredParameters: Array<{ name: string; units: string }> }
+  moduleInfo: { requiredParameters: Array<{ name: string; units: string }> },
+  registry: ParameterRegistry
 ): Promise<Record<string, number>> {
   const parameters: Record<string, number> = {};
 
   for (const param of moduleInfo.requiredParameters) {
-    const blockParamName = normalizeParameterName(param.name);
-    const value = block[blockParamName];
+    const value = registry
+      .blockProperties(param.name)
+      .map((prop) => block[prop])
+      .find((v) => v !== undefined && v !== null);
 
     if (value !== undefined && value !== null) {
       const numericValue = await convertParameterValue(value, param.units);
@@ -724,17 +1080,6 @@ async function extractModuleLevelParameters(
   return parameters;
 }
 
-/**
- * Normalize parameter name from cost library format to block property format.
- * e.g., "Mass flow" → "mass_flow"
- */
-function normalizeParameterName(name: string): string {
-  return name
-    .toLowerCase()
-    .replace(/\s+/g, "_")
-    .replace(/[^a-z0-9_]/g, "");
-}
-
 /**
  * Normalize unit strings for dim compatibility.
  * The cost library uses "m3/h" and "m3/hr" but dim requires "m^3/h".
@@ -803,30 +1148,134 @@ async function convertParameterValue(
 // Transform: CostEstimateResponse → CostingEstimateResponse
 // ============================================================================
 
+export type CostingResponseOptions = {
+  /** Per-cost-item audit trail keyed by asset ID */
+  audit?: Record<string, CostItemAudit[]>;
+  /** Add Lang-factored contingency to totals (default: excluded) */
+  includeContingencyInTotals?: boolean;
+  /** Attach a breakdown of how each asset's total installed cost was derived */
+  explainTotalInstalledCost?: boolean;
+  /** Tag each cost line with its CBS code */
+  cbsMapping?: CbsMapping;
+  /** Rank this many of the largest capex and opex contributors */
+  topDrivers?: number;
+  /** Value capture assets at this carbon price trajectory */
+  carbonPrice?: CarbonPriceTrajectory;
+  /** Also report net present cost at these discount rates (needs `timelines`) */
+  discountRates?: number[];
+  /** Assets' timelines, by asset ID */
+  timelines?: Map<string, Timeline>;
+  /**
+   * Basis of the response's yearly costs. On a nominal basis, derived
+   * metrics discount at nominal rates and escalate carbon prices to match.
+   */
+  priceBasis?: PriceBasis;
+};
+
 /**
  * Transform the costing server response into our format.
  */
 export function transformCostingResponse(
   response: CostEstimateResponse,
   assetMetadata: AssetMetadata[],
-  currency: string
+  currency: string,
+  options: CostingResponseOptions = {}
 ): CostingEstimateResponse {
   const metadataMap = new Map(assetMetadata.map((m) => [m.assetId, m]));
+  const includeContingency = options.includeContingencyInTotals ?? false;
+  const priceBasis = options.priceBasis ?? REAL_PRICE_BASIS;
+  const lines = options.cbsMapping ? cbsLines(assetMetadata) : undefined;
+  const cbsCode = (lineId: string) => {
+    const line = lines?.get(lineId);
+    return line && options.cbsMapping
+      ? cbsCodeFor(options.cbsMapping, line)
+      : undefined;
+  };
 
   // Build per-asset results
   const assets: AssetCostResult[] = response.assets.map((assetResponse) => {
     const metadata = metadataMap.get(assetResponse.id);
+    const lifetimeCosts = transformLifetimeCosts(
+      assetResponse.lifetime_costs,
+      includeContingency
+    );
+    const lifetimeNpcCosts = transformLifetimeCosts(
+      assetResponse.lifetime_dcf_costs,
+      includeContingency
+    );
+    // Discount tonnes and carbon value like the yearly costs
+    const kpiInputs = metadata?.kpiInputs && {
+      ...metadata.kpiInputs,
+      discountRate: discountRateFor(
+        priceBasis,
+        metadata.kpiInputs.discountRate ?? 0
+      ),
+    };
+    const throughputProfile = metadata?.provenance?.throughputProfile;
+    const transportCost =
+      kpiInputs &&
+      unitTransportCost(
+        kpiInputs,
+        lifetimeCosts.total,
+        lifetimeNpcCosts.total,
+        currency,
+        throughputProfile
+      );
+    const carbon =
+      options.carbonPrice &&
+      kpiInputs &&
+      carbonValue(
+        kpiInputs,
+        options.carbonPrice,
+        lifetimeNpcCosts.total,
+        currency,
+        throughputProfile,
+        (year) => escalationFactor(priceBasis, year)
+      );
+    const timeline = options.timelines?.get(assetResponse.id);
+    const npcByDiscountRate =
+      options.discountRates && timeline
+        ? discountedLifetimeCosts(
+            assetResponse,
+            timeline.construction_start,
+            options.discountRates.map((rate) =>
+              discountRateFor(priceBasis, rate)
+            )
+          ).map((costs, i) => ({
+            discountRate: options.discountRates![i],
+            lifetimeNpcCosts: transformLifetimeCosts(costs, includeContingency),
+          }))
+        : undefined;
 
     return {
       id: assetResponse.id,
       name: metadata?.name,
       isUsingDefaults: (metadata?.usingDefaults.length ?? 0) > 0,
       propertiesUsingDefaults: metadata?.usingDefaults ?? [],
-      lifetimeCosts: transformLifetimeCosts(assetResponse.lifetime_costs),
-      lifetimeNpcCosts: transformLifetimeCosts(
-        assetResponse.lifetime_dcf_costs
+      provenance: metadata?.provenance,
+      lifetimeCosts,
+      lifetimeNpcCosts,
+      blocks: assetResponse.cost_items.map((item) =>
+        transformBlockCost(item, cbsCode(item.id))
       ),
-      blocks: assetResponse.cost_items.map((item) => transformBlockCost(item)),
+      ...(options.audit && { audit: options.audit[assetResponse.id] ?? [] }),
+      ...(options.explainTotalInstalledCost && {
+        ticExplanation: explainTotalInstalledCost(assetResponse),
+      }),
+      ...(metadata?.kpiInputs && {
+        kpis: computeAssetKpis(
+          metadata.kpiInputs,
+          lifetimeCosts.total,
+          currency,
+          throughputProfile
+        ),
+      }),
+      ...(transportCost && { unitTransportCost: transportCost }),
+      ...(options.topDrivers && {
+        topDrivers: assetTopDrivers(assetResponse, options.topDrivers),
+      }),
+      ...(carbon && { carbonValue: carbon }),
+      ...(npcByDiscountRate && { npcByDiscountRate }),
     };
   });
 
@@ -837,6 +1286,14 @@ export function transformCostingResponse(
   const networkLifetimeNpcCosts = aggregateLifetimeCosts(
     assets.map((a) => a.lifetimeNpcCosts)
   );
+  const warnings = [
+    ...costItemAliasWarnings(assetMetadata),
+    ...defaultedParameterWarnings(assetMetadata),
+    ...kpiInputWarnings(assetMetadata),
+  ];
+  const networkCarbon = networkCarbonValue(
+    assets.flatMap((a) => (a.carbonValue ? [a.carbonValue] : []))
+  );
 
   return {
     networkId: "network",
@@ -844,25 +1301,137 @@ export function transformCostingResponse(
     lifetimeCosts: networkLifetimeCosts,
     lifetimeNpcCosts: networkLifetimeNpcCosts,
     assets,
+    ...(options.topDrivers && {
+      topDrivers: portfolioTopDrivers(response.assets, options.topDrivers),
+    }),
+    ...(networkCarbon && { carbonValue: networkCarbon }),
+    ...(options.discountRates && {
+      npcByDiscountRate: options.discountRates.map((discountRate, i) => ({
+        discountRate,
+        lifetimeNpcCosts: aggregateLifetimeCosts(
+          assets.flatMap((a) =>
+            a.npcByDiscountRate ? [a.npcByDiscountRate[i].lifetimeNpcCosts] : []
+          )
+        ),
+      })),
+    }),
     assetsUsingDefaults: assets
       .filter((a) => a.isUsingDefaults)
       .map((a) => a.id),
+    ...(warnings.length > 0 && { warnings }),
   };
 }
 
+/**
+ * Warnings for parameters the blocks didn't supply, costed with the
+ * library's default values.
+ */
+function defaultedParameterWarnings(assetMetadata: AssetMetadata[]): string[] {
+  return assetMetadata.flatMap((metadata) =>
+    metadata.blocks.flatMap((block) =>
+      (block.costItems ?? []).flatMap((item) =>
+        item.parameters
+          .filter((p) => p.defaulted)
+          .map(
+            (p) =>
+              `${block.id}: "${p.name}" for cost item ${item.ref} was not ` +
+              `supplied; used the library default ${p.value} ${p.units}`
+          )
+      )
+    )
+  );
+}
+
+/**
+ * Warnings for typed assets missing the inputs their KPIs need.
+ */
+function kpiInputWarnings(assetMetadata: AssetMetadata[]): string[] {
+  return assetMetadata.flatMap((metadata) => {
+    if (!metadata.kpiInputs) return [];
+    const missing = missingKpiInputs(metadata.kpiInputs);
+    return missing.length > 0
+      ? [
+          `${metadata.assetId}: no KPIs for ${metadata.kpiInputs.assetType} ` +
+            `asset without ${missing.join(" and ")}`,
+        ]
+      : [];
+  });
+}
+
+/**
+ * Names of the parameters taken from library defaults, by cost item ID.
+ */
+export function defaultedParameters(
+  assetMetadata: AssetMetadata[]
+): Record<string, string[]> {
+  const result: Record<string, string[]> = {};
+  for (const block of assetMetadata.flatMap((m) => m.blocks)) {
+    for (const item of block.costItems ?? []) {
+      const names = item.parameters
+        .filter((p) => p.defaulted)
+        .map((p) => p.name);
+      if (names.length > 0) result[item.id] = names;
+    }
+  }
+  return result;
+}
+
+/**
+ * Parameters inherited from the request, with the level that supplied each,
+ * by cost item ID.
+ */
+export function inheritedParameters(
+  assetMetadata: AssetMetadata[]
+): Record<string, Record<string, ParameterLevel>> {
+  const result: Record<string, Record<string, ParameterLevel>> = {};
+  for (const block of assetMetadata.flatMap((m) => m.blocks)) {
+    for (const item of block.costItems ?? []) {
+      const inherited = item.parameters.flatMap((p) =>
+        p.inherited ? [[p.name, p.inherited] as const] : []
+      );
+      if (inherited.length > 0) result[item.id] = Object.fromEntries(inherited);
+    }
+  }
+  return result;
+}
+
+/**
+ * Warnings for parameters read from block properties named after a cost
+ * item's earlier ID.
+ */
+function costItemAliasWarnings(assetMetadata: AssetMetadata[]): string[] {
+  return assetMetadata.flatMap((metadata) =>
+    metadata.blocks.flatMap((block) =>
+      (block.costItems ?? []).flatMap((item) =>
+        item.parameters
+          .filter((p) => p.aliasedFrom)
+          .map(
+            (p) =>
+              `${block.id}: "${p.name}" for cost item ${item.ref} was read ` +
+              `from a property named after its earlier ID ${p.aliasedFrom}`
+          )
+      )
+    )
+  );
+}
+
 function transformLifetimeCosts(
-  costs: CostEstimateResponse["assets"][0]["lifetime_costs"]
+  costs: CostEstimateResponse["assets"][0]["lifetime_costs"],
+  includeContingency: boolean
 ): LifetimeCosts {
-  return {
+  return withTotal({
     directEquipmentCost: costs.direct_equipment_cost,
     langFactoredCapitalCost: transformLangFactoredCosts(
       costs.lang_factored_capital_cost
     ),
     totalInstalledCost: costs.total_installed_cost,
+    contingencyApplied: includeContingency
+      ? costs.lang_factored_capital_cost.contingency
+      : 0,
     fixedOpexCost: transformFixedOpexCosts(costs.fixed_opex_cost),
     variableOpexCost: transformVariableOpexCosts(costs.variable_opex_cost),
     decommissioningCost: costs.decommissioning_cost,
-  };
+  });
 }
 
 function transformLangFactoredCosts(
@@ -913,73 +1482,18 @@ function transformVariableOpexCosts(
 }
 
 function transformBlockCost(
-  item: CostEstimateResponse["assets"][0]["cost_items"][0]
+  item: CostEstimateResponse["assets"][0]["cost_items"][0],
+  cbsCode?: string
 ): BlockCostResult {
+  const bundle = /\/bundles\/([^/]+)\/[^/]+$/.exec(item.id)?.[1];
   return {
     id: item.id,
     blockType: "", // Would need to be looked up from the original block
     moduleRef: "", // Would need the ref from the request
+    ...(bundle !== undefined && { bundle }),
+    ...(cbsCode !== undefined && { cbsCode }),
     quantity: item.quantity,
     directEquipmentCost: item.lifetime_costs.direct_equipment_cost ?? 0,
     totalInstalledCost: item.lifetime_costs.total_installed_cost ?? 0,
   };
 }
-
-function aggregateLifetimeCosts(costs: LifetimeCosts[]): LifetimeCosts {
-  const sum = (arr: number[]) => arr.reduce((a, b) => a + b, 0);
-
-  return {
-    directEquipmentCost: sum(costs.map((c) => c.directEquipmentCost)),
-    langFactoredCapitalCost: {
-      equipmentErection: sum(
-        costs.map((c) => c.langFactoredCapitalCost.equipmentErection)
-      ),
-      piping: sum(costs.map((c) => c.langFactoredCapitalCost.piping)),
-      instrumentation: sum(
-        costs.map((c) => c.langFactoredCapitalCost.instrumentation)
-      ),
-      electrical: sum(costs.map((c) => c.langFactoredCapitalCost.electrical)),
-      buildingsAndProcess: sum(
-        costs.map((c) => c.langFactoredCapitalCost.buildingsAndProcess)
-      ),
-      utilities: sum(costs.map((c) => c.langFactoredCapitalCost.utilities)),
-      storages: sum(costs.map((c) => c.langFactoredCapitalCost.storages)),
-      siteDevelopment: sum(
-        costs.map((c) => c.langFactoredCapitalCost.siteDevelopment)
-      ),
-      ancillaryBuildings: sum(
-        costs.map((c) => c.langFactoredCapitalCost.ancillaryBuildings)
-      ),
-      designAndEngineering: sum(
-        costs.map((c) => c.langFactoredCapitalCost.designAndEngineering)
-      ),
-      contractorsFee: sum(
-        costs.map((c) => c.langFactoredCapitalCost.contractorsFee)
-      ),
-      contingency: sum(costs.map((c) => c.langFactoredCapitalCost.contingency)),
-    },
-    totalInstalledCost: sum(costs.map((c) => c.totalInstalledCost)),
-    fixedOpexCost: {
-      maintenance: sum(costs.map((c) => c.fixedOpexCost.maintenance)),
-      controlRoomFacilities: sum(
-        costs.map((c) => c.fixedOpexCost.controlRoomFacilities)
-      ),
-      insuranceLiability: sum(
-        costs.map((c) => c.fixedOpexCost.insuranceLiability)
-      ),
-      insuranceEquipmentLoss: sum(
-        costs.map((c) => c.fixedOpexCost.insuranceEquipmentLoss)
-      ),
-      costOfCapital: sum(costs.map((c) => c.fixedOpexCost.costOfCapital)),
-      majorTurnarounds: sum(costs.map((c) => c.fixedOpexCost.majorTurnarounds)),
-      labourCost: sum(costs.map((c) => c.fixedOpexCost.labourCost)),
-    },
-    variableOpexCost: {
-      electricity: sum(costs.map((c) => c.variableOpexCost.electricity)),
-      naturalGas: sum(costs.map((c) => c.variableOpexCost.naturalGas)),
-      water: sum(costs.map((c) => c.variableOpexCost.water)),
-      other: sum(costs.map((c) => c.variableOpexCost.other)),
-    },
-    decommissioningCost: sum(costs.map((c) => c.decommissioningCost)),
-  };
-}
@@ -0,0 +1,67 @@
+import { describe, it, expect } from "vitest";
+import {
+  checkAssetDependencies,
+  dependencyWarnings,
+} from "./asset-dependencies";
+import type { Timeline } from "./types";
+
+describe("asset-dependencies", () => {
+  const timeline = (
+    operationStart: number,
+    operationFinish: number,
+  ): Timeline => ({
+    construction_start: operationStart - 3,
+    construction_finish: operationStart,
+    operation_start: operationStart,
+    operation_finish: operationFinish,
+    decommissioning_start: operationFinish,
+    decommissioning_finish: operationFinish + 2,
+  });
+
+  const assets = [
+    { id: "store", timeline: timeline(2030, 2055) },
+    { id: "pipeline", timeline: timeline(2029, 2060) },
+  ];
+
+  it("flags a dependent asset that starts or runs beyond its dependency", () => {
+    const issues = checkAssetDependencies(assets, [
+      { asset: "pipeline", dependsOn: "store" },
+    ]);
+
+    expect(issues.map((i) => i.message)).toEqual([
+      "pipeline starts operating in 2029, but store is not ready until 2030",
+      "store stops operating in 2055, before pipeline stops in 2060",
+    ]);
+    expect(dependencyWarnings(issues)[0]).toMatch(
+      /^Asset dependency pipeline → store: /,
+    );
+  });
+
+  it("applies lead years and optional overlap", () => {
+    expect(
+      checkAssetDependencies(assets, [
+        { asset: "store", dependsOn: "pipeline", leadYears: 1 },
+      ]),
+    ).toEqual([]);
+    expect(
+      checkAssetDependencies(assets, [
+        {
+          asset: "store",
+          dependsOn: "pipeline",
+          leadYears: 2,
+          throughOperation: false,
+        },
+      ]).map((i) => i.message),
+    ).toEqual([
+      "store starts operating in 2030, but pipeline is not ready until 2031",
+    ]);
+  });
+
+  it("reports dependencies on assets that weren't costed", () => {
+    expect(
+      checkAssetDependencies(assets, [{ asset: "pipeline", dependsOn: "ship" }]),
+    ).toEqual([
+      { asset: "pipeline", dependsOn: "ship", message: "no costed asset ship" },
+    ]);
+  });
+});
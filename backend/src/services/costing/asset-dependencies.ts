@@ -0,0 +1,94 @@
+/**
+ * Dependencies between assets.
+ *
+ * A request can declare that one asset depends on another, e.g. a pipeline
+ * can't start operating until the store it feeds is operational. The checks
+ * compare the resolved timelines of both assets and report the
+ * inconsistencies as warnings, so mismatched schedules show up in the
+ * estimate instead of on a Gantt chart review.
+ */
+
+import type { Timeline } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type AssetDependency = {
+  /** Asset (group or branch ID) that depends on the other */
+  asset: string;
+  /** Asset that must be operational first */
+  dependsOn: string;
+  /** Years `dependsOn` must operate before `asset` starts (default: 0) */
+  leadYears?: number;
+  /**
+   * Whether `dependsOn` must keep operating until `asset` stops
+   * (default: true)
+   */
+  throughOperation?: boolean;
+};
+
+export type DependencyIssue = {
+  asset: string;
+  dependsOn: string;
+  message: string;
+};
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+/**
+ * Check declared dependencies against the assets' resolved timelines.
+ */
+export function checkAssetDependencies(
+  assets: Array<{ id: string; timeline: Timeline }>,
+  dependencies: readonly AssetDependency[],
+): DependencyIssue[] {
+  const timelines = new Map(assets.map((a) => [a.id, a.timeline]));
+  const issues: DependencyIssue[] = [];
+
+  for (const dependency of dependencies) {
+    const { asset, dependsOn } = dependency;
+    const issue = (message: string) =>
+      issues.push({ asset, dependsOn, message });
+
+    const dependent = timelines.get(asset);
+    const required = timelines.get(dependsOn);
+    if (!dependent || !required) {
+      const missing = [asset, dependsOn].filter((id) => !timelines.has(id));
+      issue(`no costed asset ${missing.join(" or ")}`);
+      continue;
+    }
+
+    const lead = dependency.leadYears ?? 0;
+    const readyBy = required.operation_start + lead;
+    if (readyBy > dependent.operation_start) {
+      issue(
+        `${asset} starts operating in ${dependent.operation_start}, but ` +
+          `${dependsOn} is not ready until ${readyBy}`,
+      );
+    }
+    if (
+      (dependency.throughOperation ?? true) &&
+      required.operation_finish < dependent.operation_finish
+    ) {
+      issue(
+        `${dependsOn} stops operating in ${required.operation_finish}, ` +
+          `before ${asset} stops in ${dependent.operation_finish}`,
+      );
+    }
+  }
+
+  return issues;
+}
+
+/**
+ * Format dependency issues for the response's warnings.
+ */
+export function dependencyWarnings(issues: DependencyIssue[]): string[] {
+  return issues.map(
+    (issue) =>
+      `Asset dependency ${issue.asset} → ${issue.dependsOn}: ${issue.message}`,
+  );
+}
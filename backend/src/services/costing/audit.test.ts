@@ -0,0 +1,111 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { buildAuditTrail } from "./audit";
+import { loadCostLibrary, ModuleLookupService } from "./module-lookup";
+import { resolveAssetProperties } from "./request-types";
+import type { CostEstimateRequest } from "./types";
+
+describe("audit", () => {
+  let service: ModuleLookupService;
+
+  beforeAll(async () => {
+    service = new ModuleLookupService(await loadCostLibrary("V1.1_working"));
+  });
+
+  function requestWithItems(
+    costItems: CostEstimateRequest["assets"][0]["cost_items"],
+  ): CostEstimateRequest {
+    const { usingDefaults: _, ...properties } = resolveAssetProperties();
+    return {
+      assets: [{ id: "asset-1", ...properties, cost_items: costItems }],
+    };
+  }
+
+  describe("buildAuditTrail", () => {
+    it("records inputs alongside library cost data", () => {
+      const request = requestWithItems([
+        {
+          id: "branch-1/blocks/0/Item 023",
+          ref: "Item 023",
+          quantity: 2,
+          parameters: { "Mass flow": 100000 },
+        },
+      ]);
+
+      const audit = buildAuditTrail(request, service, "EUR");
+      const [item] = audit["asset-1"];
+
+      expect(item.blockId).toBe("branch-1/blocks/0");
+      expect(item.quantity).toBe(2);
+      expect(item.parameters).toEqual({ "Mass flow": 100000 });
+      expect(item.scalingFactors).toEqual([
+        {
+          name: "Mass flow",
+          units: "kg/h",
+          sourceValue: 342465.7534246575,
+          value: 100000,
+        },
+      ]);
+      expect(item.formula?.type).toBe("linear");
+      expect(item.costYear).toBe(2011);
+      expect(item.costCurrency).toBe("USD");
+      expect(item.priceBaseYear).toBe(2025);
+      expect(item.inflationFactor).toBeCloseTo(1.9438, 4);
+      expect(item.currencyRate).toBeCloseTo(0.923, 6);
+      expect(item.targetCurrency).toBe("EUR");
+    });
+
+    it("lists parameters taken from library defaults", () => {
+      const id = "branch-1/blocks/0/Item 023";
+      const request = requestWithItems([
+        { id, ref: "Item 023", quantity: 1, parameters: { "Mass flow": 5 } },
+      ]);
+
+      const audit = buildAuditTrail(request, service, "EUR", {
+        defaultedParameters: { [id]: ["Mass flow"] },
+      });
+
+      expect(audit["asset-1"][0].defaultedParameters).toEqual(["Mass flow"]);
+      expect(
+        buildAuditTrail(request, service, "EUR")["asset-1"][0],
+      ).not.toHaveProperty("defaultedParameters");
+    });
+
+    it("leaves library fields null for unknown items", () => {
+      const request = requestWithItems([
+        { id: "b/blocks/0/Item 999", ref: "Item 999", quantity: 1, parameters: {} },
+      ]);
+
+      const [item] = buildAuditTrail(request, service, "USD")["asset-1"];
+
+      expect(item.formula).toBeNull();
+      expect(item.costYear).toBeNull();
+      expect(item.inflationFactor).toBeNull();
+      expect(item.currencyRate).toBeNull();
+    });
+
+    it("returns null currency rate for currencies unknown to the library", () => {
+      const request = requestWithItems([
+        { id: "b/blocks/0/Item 023", ref: "Item 023", quantity: 1, parameters: {} },
+      ]);
+
+      const [item] = buildAuditTrail(request, service, "XYZ")["asset-1"];
+
+      expect(item.currencyRate).toBeNull();
+    });
+
+    it("echoes source references on request", () => {
+      const request = requestWithItems([
+        { id: "b/blocks/0/Item 023", ref: "Item 023", quantity: 1, parameters: {} },
+      ]);
+
+      const [plain] = buildAuditTrail(request, service, "EUR")["asset-1"];
+      const [withSources] = buildAuditTrail(request, service, "EUR", {
+        includeSources: true,
+      })["asset-1"];
+
+      expect(plain.sourceReference).toBeUndefined();
+      expect(withSources.sourceReference).toBe("Ref 008");
+      expect(withSources.sources).toEqual([]);
+    });
+  });
+});
@@ -0,0 +1,141 @@
+/**
+ * Calculation audit trail.
+ *
+ * Records, per cost item, the inputs sent to the costing server together with
+ * the library data those inputs were evaluated against (cost curve, cost year,
+ * inflation factor, currency rate), so estimates can be reviewed and signed
+ * off without cross-referencing the library by hand.
+ */
+
+import type {
+  CostEstimateRequest,
+  CapexCost,
+  SourceReference,
+} from "./types";
+import type { ModuleLookupService } from "./module-lookup";
+import type { ParameterLevel } from "./request-types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type ScalingFactorAudit = {
+  name: string;
+  units: string;
+  /** Value of the parameter in the library's source cost */
+  sourceValue: number;
+  /** Value supplied for this estimate (undefined if not supplied) */
+  value?: number;
+};
+
+export type CostItemAudit = {
+  /** Cost item ID as sent to the costing server */
+  id: string;
+  /** Cost library item reference (e.g., "Item 023") */
+  ref: string;
+  /** Block the cost item was derived from */
+  blockId: string;
+  quantity: number;
+  /** Parameters sent to the costing server */
+  parameters: Record<string, number>;
+  /** Parameters the block didn't supply, taken from library defaults */
+  defaultedParameters?: string[];
+  /** Parameters the block didn't supply, inherited from the request */
+  inheritedParameters?: Record<string, ParameterLevel>;
+  scalingFactors: ScalingFactorAudit[];
+  /** Cost curve from the library (type and coefficients) */
+  formula: CapexCost | null;
+  /** Year and currency of the library's source cost */
+  costYear: number | null;
+  costCurrency: string | null;
+  /** Library inflation factor from costYear to the price base year */
+  inflationFactor: number | null;
+  priceBaseYear: number | null;
+  /** Rate converting costCurrency to the target currency */
+  currencyRate: number | null;
+  targetCurrency: string;
+  /** Library source reference and documents (only with includeSources) */
+  sourceReference?: string | null;
+  sources?: SourceReference[];
+};
+
+export type AuditOptions = {
+  /** Echo each cost item's source references */
+  includeSources?: boolean;
+  /** Parameters taken from library defaults, by cost item ID */
+  defaultedParameters?: Record<string, string[]>;
+  /** Parameters inherited from the request, by cost item ID */
+  inheritedParameters?: Record<string, Record<string, ParameterLevel>>;
+};
+
+// ============================================================================
+// Builder
+// ============================================================================
+
+/**
+ * Build the audit trail for every cost item in a request, keyed by asset ID.
+ */
+export function buildAuditTrail(
+  request: CostEstimateRequest,
+  moduleLookup: ModuleLookupService,
+  targetCurrency: string,
+  options: AuditOptions = {},
+): Record<string, CostItemAudit[]> {
+  const priceBaseYear = moduleLookup.getPriceBaseYear() ?? null;
+  const audit: Record<string, CostItemAudit[]> = {};
+
+  for (const asset of request.assets) {
+    audit[asset.id] = asset.cost_items.map((item) => {
+      const costItem = moduleLookup.findCostItem(item.ref);
+      const capex = costItem?.capex_contribution;
+
+      return {
+        id: item.id,
+        ref: item.ref,
+        blockId: blockIdOf(item.id, item.ref),
+        quantity: item.quantity,
+        parameters: item.parameters,
+        ...(options.defaultedParameters?.[item.id] && {
+          defaultedParameters: options.defaultedParameters[item.id],
+        }),
+        ...(options.inheritedParameters?.[item.id] && {
+          inheritedParameters: options.inheritedParameters[item.id],
+        }),
+        scalingFactors: (costItem?.scaling_factors ?? []).map((sf) => ({
+          name: sf.name,
+          units: sf.units,
+          sourceValue: sf.source_value,
+          value: item.parameters[sf.name],
+        })),
+        formula: capex?.cost ?? null,
+        costYear: capex?.year ?? null,
+        costCurrency: capex?.currency ?? null,
+        inflationFactor: capex
+          ? (moduleLookup.getInflationFactor(capex.year) ?? null)
+          : null,
+        priceBaseYear,
+        currencyRate: capex
+          ? (moduleLookup.getCurrencyRate(capex.currency, targetCurrency) ??
+            null)
+          : null,
+        targetCurrency,
+        ...(options.includeSources && {
+          sourceReference: costItem?.info.source_reference ?? null,
+          sources: costItem?.info.sources ?? [],
+        }),
+      };
+    });
+  }
+
+  return audit;
+}
+
+/**
+ * Cost item IDs are "{blockId}/{ref}" - strip the ref to recover the block.
+ */
+function blockIdOf(costItemId: string, ref: string): string {
+  const suffix = `/${ref}`;
+  return costItemId.endsWith(suffix)
+    ? costItemId.slice(0, -suffix.length)
+    : costItemId;
+}
@@ -0,0 +1,77 @@
+import { describe, it, expect } from "vitest";
+import { carbonValue, networkCarbonValue } from "./carbon-value";
+import type { KpiInputs } from "./kpis";
+
+const timeline = {
+  construction_start: 2028,
+  construction_finish: 2029,
+  operation_start: 2030,
+  operation_finish: 2032,
+  decommissioning_start: 2033,
+  decommissioning_finish: 2034,
+};
+
+describe("carbon-value", () => {
+  const capture: KpiInputs = {
+    assetType: "capture",
+    throughput: 1_000,
+    timeline,
+  };
+
+  it("values captured tonnes at each year's carbon price", () => {
+    const value = carbonValue(
+      capture,
+      { prices: { "2030": 50, "2032": 80 } },
+      100_000,
+      "GBP",
+    )!;
+
+    expect(value.priceUnit).toBe("GBP/tCO2");
+    expect(value.years).toEqual([2030, 2031, 2032]);
+    expect(value.revenue).toEqual([50_000, 50_000, 80_000]);
+    expect(value.penalty).toEqual([0, 0, 0]);
+    expect(value.lifetimeNet).toBe(180_000);
+    expect(value.discountedNet).toBe(180_000);
+    expect(value.netPresentValue).toBe(80_000);
+  });
+
+  it("charges residual emissions and follows the ramp-up", () => {
+    const value = carbonValue(
+      { ...capture, discountRate: 0.1 },
+      { prices: { "2030": 100 }, captureRate: 0.8 },
+      0,
+      "GBP",
+      [0.5],
+    )!;
+
+    expect(value.capturedTonnes).toEqual([500, 1_000, 1_000]);
+    expect(value.penalty[0]).toBeCloseTo(12_500);
+    expect(value.net[1]).toBeCloseTo(75_000);
+    expect(value.discountedNet).toBeCloseTo(
+      37_500 / 1.1 ** 2 + 75_000 / 1.1 ** 3 + 75_000 / 1.1 ** 4,
+    );
+  });
+
+  it("only values capture assets with a throughput", () => {
+    const prices = { prices: { "2030": 50 } };
+    expect(
+      carbonValue({ ...capture, assetType: "storage" }, prices, 0, "GBP"),
+    ).toBeUndefined();
+    expect(
+      carbonValue({ ...capture, throughput: undefined }, prices, 0, "GBP"),
+    ).toBeUndefined();
+    expect(carbonValue(capture, { prices: {} }, 0, "GBP")).toBeUndefined();
+  });
+
+  it("sums the network's capture assets", () => {
+    const value = carbonValue(capture, { prices: { "2030": 50 } }, 0, "GBP")!;
+    expect(networkCarbonValue([value, value])).toEqual({
+      lifetimeRevenue: 300_000,
+      lifetimePenalty: 0,
+      lifetimeNet: 300_000,
+      discountedNet: 300_000,
+      netPresentValue: 300_000,
+    });
+    expect(networkCarbonValue([])).toBeUndefined();
+  });
+});
@@ -0,0 +1,151 @@
+/**
+ * Carbon value of capture assets.
+ *
+ * Capture business cases hinge on the carbon (ETS) price: every tonne
+ * captured is a tonne of allowances not bought, and any CO2 the plant lets
+ * through at less than full capture is still charged. Given a carbon price
+ * trajectory, each capture asset's operating years are valued as avoided-
+ * emissions revenue less the penalty on residual emissions, discounted like
+ * the costing server's yearly costs, and set against the asset's net
+ * present cost for a net present value.
+ */
+
+import type { KpiInputs } from "./kpis";
+import { throughputFraction } from "./throughput-profile";
+import { valueInYear } from "./utility-prices";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CarbonPriceTrajectory = {
+  /** Carbon price per tonne of CO2 by year, in the result currency */
+  prices: Record<string, number>;
+  /**
+   * Fraction (0-1] of a capture plant's CO2 that is captured. The rest is
+   * charged at the carbon price; without it, no penalty is applied.
+   */
+  captureRate?: number;
+};
+
+/**
+ * Year-aligned carbon value of a capture asset. All arrays have the same
+ * length as `years`.
+ */
+export type CarbonValue = {
+  /** e.g. "GBP/tCO2" */
+  priceUnit: string;
+  years: number[];
+  price: number[];
+  capturedTonnes: number[];
+  /** Avoided-emissions revenue: captured tonnes at the carbon price */
+  revenue: number[];
+  /** Charge on residual (uncaptured) emissions */
+  penalty: number[];
+  /** Revenue less penalty */
+  net: number[];
+  lifetimeNet: number;
+  /** Net value discounted to the start of construction */
+  discountedNet: number;
+  /** Discounted net value less the asset's net present cost */
+  netPresentValue: number;
+};
+
+/** Carbon value summed over the network's capture assets */
+export type NetworkCarbonValue = {
+  lifetimeRevenue: number;
+  lifetimePenalty: number;
+  lifetimeNet: number;
+  discountedNet: number;
+  netPresentValue: number;
+};
+
+// ============================================================================
+// Calculation
+// ============================================================================
+
+/**
+ * Carbon value of an asset over its operating years. Undefined for assets
+ * other than capture, without throughput, or when the trajectory lists no
+ * prices. `escalation` scales each year's price, for nominal cash flows.
+ */
+export function carbonValue(
+  inputs: KpiInputs,
+  trajectory: CarbonPriceTrajectory,
+  lifetimeNpc: number,
+  currency: string,
+  throughputProfile?: number[],
+  escalation: (year: number) => number = () => 1,
+): CarbonValue | undefined {
+  const { throughput, timeline } = inputs;
+  if (inputs.assetType !== "capture" || throughput === undefined) {
+    return undefined;
+  }
+  if (Object.keys(trajectory.prices).length === 0) return undefined;
+
+  const rate = inputs.discountRate ?? 0;
+  // Residual tonnes emitted per tonne captured
+  const residual =
+    trajectory.captureRate !== undefined
+      ? (1 - trajectory.captureRate) / trajectory.captureRate
+      : 0;
+
+  const value: CarbonValue = {
+    priceUnit: `${currency}/tCO2`,
+    years: [],
+    price: [],
+    capturedTonnes: [],
+    revenue: [],
+    penalty: [],
+    net: [],
+    lifetimeNet: 0,
+    discountedNet: 0,
+    netPresentValue: 0,
+  };
+  for (
+    let year = timeline.operation_start;
+    year <= timeline.operation_finish;
+    year++
+  ) {
+    const price = valueInYear(trajectory.prices, year)! * escalation(year);
+    const tonnes =
+      throughput *
+      (throughputProfile
+        ? throughputFraction(throughputProfile, timeline.operation_start, year)
+        : 1);
+    const revenue = tonnes * price;
+    const penalty = tonnes * residual * price;
+    const net = revenue - penalty;
+
+    value.years.push(year);
+    value.price.push(price);
+    value.capturedTonnes.push(tonnes);
+    value.revenue.push(revenue);
+    value.penalty.push(penalty);
+    value.net.push(net);
+    value.lifetimeNet += net;
+    value.discountedNet +=
+      net / Math.pow(1 + rate, year - timeline.construction_start);
+  }
+  value.netPresentValue = value.discountedNet - lifetimeNpc;
+  return value;
+}
+
+/**
+ * Sum assets' carbon values. Undefined when no asset has one.
+ */
+export function networkCarbonValue(
+  values: CarbonValue[],
+): NetworkCarbonValue | undefined {
+  if (values.length === 0) return undefined;
+  const sum = (f: (v: CarbonValue) => number) =>
+    values.reduce((total, v) => total + f(v), 0);
+  const sumOf = (xs: number[]) => xs.reduce((a, b) => a + b, 0);
+  return {
+    lifetimeRevenue: sum((v) => sumOf(v.revenue)),
+    lifetimePenalty: sum((v) => sumOf(v.penalty)),
+    lifetimeNet: sum((v) => v.lifetimeNet),
+    discountedNet: sum((v) => v.discountedNet),
+    netPresentValue: sum((v) => v.netPresentValue),
+  };
+}
@@ -0,0 +1,145 @@
+import { describe, it, expect } from "vitest";
+import { buildCashflow, downsample } from "./cashflow";
+import {
+  mockAssetEstimate,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { CostEstimateResponse } from "./types";
+
+describe("cashflow", () => {
+  const response: CostEstimateResponse = {
+    assets: [
+      mockAssetEstimate("asset-1", [
+        mockYearCosts(
+          2025,
+          { total_installed_cost: 1000 },
+          { total_installed_cost: 900 },
+        ),
+        mockYearCosts(
+          2026,
+          {
+            fixed_opex_cost: { maintenance: 50 },
+            variable_opex_cost: { electrical_power: 25 },
+          },
+          {
+            fixed_opex_cost: { maintenance: 40 },
+            variable_opex_cost: { electrical_power: 20 },
+          },
+        ),
+        mockYearCosts(
+          2027,
+          { decommissioning_cost: 200 },
+          { decommissioning_cost: 150 },
+        ),
+      ]),
+      mockAssetEstimate("asset-2", [
+        mockYearCosts(2026, { total_installed_cost: 500 }),
+        mockYearCosts(2028, { fixed_opex_cost: { maintenance: 10 } }),
+      ]),
+    ],
+  };
+
+  const metadata = [
+    {
+      assetId: "asset-1",
+      name: "Capture",
+      isGroup: true,
+      branchIds: [],
+      blockCount: 0,
+      costableBlockCount: 0,
+      usingDefaults: [],
+      blocks: [],
+    },
+  ];
+
+  describe("buildCashflow", () => {
+    it("flattens per-year costs into parallel arrays", () => {
+      const result = buildCashflow(response, metadata, "USD");
+      const asset = result.assets[0];
+
+      expect(result.currency).toBe("USD");
+      expect(asset.id).toBe("asset-1");
+      expect(asset.name).toBe("Capture");
+      expect(asset.years).toEqual([2025, 2026, 2027]);
+      expect(asset.capex).toEqual([1000, 0, 0]);
+      expect(asset.opex).toEqual([0, 75, 0]);
+      expect(asset.decommissioning).toEqual([0, 0, 200]);
+      expect(asset.total).toEqual([1000, 75, 200]);
+      expect(asset.dcfTotal).toEqual([900, 60, 150]);
+    });
+
+    it("adds contingency to capex only when requested", () => {
+      const withContingency: CostEstimateResponse = {
+        assets: [
+          mockAssetEstimate("asset-1", [
+            mockYearCosts(2025, {
+              total_installed_cost: 1000,
+              lang_factored_capital_cost: { contingency: 200 },
+            }),
+          ]),
+        ],
+      };
+
+      expect(
+        buildCashflow(withContingency, [], "USD").portfolio.capex,
+      ).toEqual([1000]);
+      const included = buildCashflow(withContingency, [], "USD", {
+        includeContingency: true,
+      }).portfolio;
+      expect(included.capex).toEqual([1200]);
+      expect(included.dcfTotal).toEqual([1200]);
+    });
+
+    it("sums assets onto the union of their years for the portfolio", () => {
+      const result = buildCashflow(response, metadata, "USD");
+
+      expect(result.portfolio.years).toEqual([2025, 2026, 2027, 2028]);
+      expect(result.portfolio.capex).toEqual([1000, 500, 0, 0]);
+      expect(result.portfolio.opex).toEqual([0, 75, 0, 10]);
+      expect(result.portfolio.total).toEqual([1000, 575, 200, 10]);
+    });
+
+    it("downsamples when maxPoints is smaller than the series", () => {
+      const result = buildCashflow(response, metadata, "USD", {
+        maxPoints: 2,
+      });
+
+      expect(result.portfolio.years).toEqual([2025, 2027]);
+      expect(result.portfolio.total).toEqual([1575, 210]);
+    });
+  });
+
+  describe("downsample", () => {
+    it("returns the series unchanged when it already fits", () => {
+      const series = {
+        years: [2025, 2026],
+        capex: [1, 2],
+        opex: [0, 0],
+        decommissioning: [0, 0],
+        total: [1, 2],
+        dcfTotal: [1, 2],
+      };
+
+      expect(downsample(series, 5)).toBe(series);
+      expect(downsample(series)).toBe(series);
+    });
+
+    it("preserves totals across buckets", () => {
+      const years = Array.from({ length: 40 }, (_, i) => 2025 + i);
+      const ones = years.map(() => 1);
+      const series = {
+        years,
+        capex: ones,
+        opex: ones,
+        decommissioning: ones,
+        total: ones,
+        dcfTotal: ones,
+      };
+
+      const result = downsample(series, 10);
+
+      expect(result.years).toHaveLength(10);
+      expect(result.total.reduce((a, b) => a + b, 0)).toBe(40);
+    });
+  });
+});
@@ -0,0 +1,247 @@
+/**
+ * Cash flow view of a cost estimate.
+ *
+ * Flattens the costing server's per-year breakdown into parallel arrays that
+ * chart libraries can plot directly, per asset and for the whole portfolio.
+ */
+
+import type {
+  CostEstimateResponse,
+  AssetPeriodCosts,
+  FixedOpexCostEstimate,
+  VariableOpexCostEstimate,
+  Timeline,
+} from "./types";
+import type { AssetMetadata } from "./adapter";
+import {
+  checkFundingCap,
+  type CapexBudget,
+  type FundingAdvisory,
+} from "./funding-cap";
+import type { PriceBasis } from "./price-basis";
+import {
+  buildFinancing,
+  type Financing,
+  type FinancingCashflow,
+} from "./financing";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/**
+ * Year-aligned cash flow arrays. All arrays have the same length as `years`.
+ */
+export type CashflowSeries = {
+  years: number[];
+  capex: number[];
+  opex: number[];
+  decommissioning: number[];
+  /** Capex, opex and decommissioning, summed like `LifetimeCosts.total` */
+  total: number[];
+  dcfTotal: number[];
+};
+
+export type AssetCashflow = CashflowSeries & {
+  id: string;
+  name?: string;
+};
+
+export type CostingCashflowResponse = {
+  currency: string;
+  portfolio: CashflowSeries;
+  assets: AssetCashflow[];
+  /** Capex budget check, when the request sets `capexBudget` */
+  funding?: FundingAdvisory;
+  /** Debt and equity split and debt schedules, when the request sets `financing` */
+  financing?: FinancingCashflow;
+  /** Whether the series are real or nominal */
+  priceBasis?: PriceBasis;
+};
+
+export type CashflowOptions = {
+  /**
+   * Maximum number of points per series. Longer series are downsampled by
+   * summing consecutive years into buckets, so totals are preserved.
+   */
+  maxPoints?: number;
+  /** Add Lang-factored contingency to capex (default: excluded) */
+  includeContingency?: boolean;
+  /** Annual capex cap to check the portfolio against */
+  capexBudget?: CapexBudget;
+  /** Assets' timelines, used to report suggested construction starts */
+  timelines?: Map<string, Timeline>;
+  /** Financing to build debt schedules with (needs `timelines`) */
+  financing?: Financing;
+};
+
+// ============================================================================
+// Builders
+// ============================================================================
+
+/**
+ * Build the cash flow view from a costing server response.
+ */
+export function buildCashflow(
+  response: CostEstimateResponse,
+  assetMetadata: AssetMetadata[],
+  currency: string,
+  options: CashflowOptions = {},
+): CostingCashflowResponse {
+  const metadataMap = new Map(assetMetadata.map((m) => [m.assetId, m]));
+  const capexOf = (costs: AssetPeriodCosts) =>
+    costs.total_installed_cost +
+    (options.includeContingency
+      ? costs.lang_factored_capital_cost.contingency
+      : 0);
+  const totalOf = (costs: AssetPeriodCosts) =>
+    capexOf(costs) + opexOf(costs) + costs.decommissioning_cost;
+
+  const fullAssets = response.assets.map((asset) => {
+    const years = asset.costs_by_year.map((y) => y.year);
+    const capex = asset.costs_by_year.map((y) => capexOf(y.costs_in_year));
+    const opex = asset.costs_by_year.map((y) => opexOf(y.costs_in_year));
+    const decommissioning = asset.costs_by_year.map(
+      (y) => y.costs_in_year.decommissioning_cost,
+    );
+    const dcfTotal = asset.costs_by_year.map((y) =>
+      totalOf(y.dcf_costs_in_year),
+    );
+
+    return {
+      id: asset.id,
+      name: metadataMap.get(asset.id)?.name,
+      series: {
+        years,
+        capex,
+        opex,
+        decommissioning,
+        total: years.map((_, i) => capex[i] + opex[i] + decommissioning[i]),
+        dcfTotal,
+      },
+    };
+  });
+
+  const portfolio = sumSeries(fullAssets.map((a) => a.series));
+
+  const cashflow: CostingCashflowResponse = {
+    currency,
+    portfolio: downsample(portfolio, options.maxPoints),
+    assets: fullAssets.map((a) => ({
+      id: a.id,
+      name: a.name,
+      ...downsample(a.series, options.maxPoints),
+    })),
+  };
+
+  // Checked on full-resolution series so downsampling can't hide a peak year
+  if (options.capexBudget) {
+    cashflow.funding = checkFundingCap(
+      fullAssets.map((a) => ({
+        id: a.id,
+        years: a.series.years,
+        capex: a.series.capex,
+        timeline: options.timelines?.get(a.id),
+      })),
+      options.capexBudget,
+    );
+  }
+
+  if (options.financing) {
+    cashflow.financing = buildFinancing(
+      fullAssets.flatMap((a) => {
+        const timeline = options.timelines?.get(a.id);
+        return timeline
+          ? [
+              {
+                id: a.id,
+                name: a.name,
+                years: a.series.years,
+                capex: a.series.capex,
+                timeline,
+              },
+            ]
+          : [];
+      }),
+      options.financing,
+    );
+  }
+
+  return cashflow;
+}
+
+function opexOf(costs: AssetPeriodCosts): number {
+  return (
+    sumValues(costs.fixed_opex_cost) + sumValues(costs.variable_opex_cost)
+  );
+}
+
+function sumValues(
+  costs: FixedOpexCostEstimate | VariableOpexCostEstimate,
+): number {
+  return Object.values(costs).reduce((a, b) => a + b, 0);
+}
+
+/**
+ * Sum several series onto the union of their years.
+ */
+function sumSeries(series: CashflowSeries[]): CashflowSeries {
+  const years = Array.from(new Set(series.flatMap((s) => s.years))).sort(
+    (a, b) => a - b,
+  );
+  const index = new Map(years.map((year, i) => [year, i]));
+  const zeros = () => years.map(() => 0);
+
+  const result: CashflowSeries = {
+    years,
+    capex: zeros(),
+    opex: zeros(),
+    decommissioning: zeros(),
+    total: zeros(),
+    dcfTotal: zeros(),
+  };
+
+  for (const s of series) {
+    s.years.forEach((year, i) => {
+      const j = index.get(year)!;
+      result.capex[j] += s.capex[i];
+      result.opex[j] += s.opex[i];
+      result.decommissioning[j] += s.decommissioning[i];
+      result.total[j] += s.total[i];
+      result.dcfTotal[j] += s.dcfTotal[i];
+    });
+  }
+
+  return result;
+}
+
+/**
+ * Reduce a series to at most `maxPoints` points by summing consecutive years.
+ * Each bucket is labelled with its first year.
+ */
+export function downsample(
+  series: CashflowSeries,
+  maxPoints?: number,
+): CashflowSeries {
+  if (!maxPoints || maxPoints < 1 || series.years.length <= maxPoints) {
+    return series;
+  }
+
+  const bucketSize = Math.ceil(series.years.length / maxPoints);
+  const bucket = (values: number[]) => {
+    const out: number[] = [];
+    for (let i = 0; i < values.length; i += bucketSize) {
+      out.push(values.slice(i, i + bucketSize).reduce((a, b) => a + b, 0));
+    }
+    return out;
+  };
+
+  return {
+    years: series.years.filter((_, i) => i % bucketSize === 0),
+    capex: bucket(series.capex),
+    opex: bucket(series.opex),
+    decommissioning: bucket(series.decommissioning),
+    total: bucket(series.total),
+    dcfTotal: bucket(series.dcfTotal),
+  };
+}
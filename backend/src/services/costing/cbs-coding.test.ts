@@ -0,0 +1,129 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import { cbsCodeFor, groupByCbsCode, mergeCbsMappings } from "./cbs-coding";
+import { validateCostLibrary } from "./library-validation";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { AssetMetadata } from "./adapter";
+
+describe("cbs-coding", () => {
+  const mapping = mergeCbsMappings(
+    {
+      items: { "Item 023": "C.10.01" },
+      modules: { M0201: "C.10" },
+      default: "Z.99",
+    },
+    { items: { "Item 024": "C.10.02" }, default: "Z.00" },
+  )!;
+
+  it("merges request codes over the library's", () => {
+    expect(mapping).toEqual({
+      items: { "Item 023": "C.10.01", "Item 024": "C.10.02" },
+      modules: { M0201: "C.10" },
+      default: "Z.00",
+    });
+    expect(mergeCbsMappings(undefined, undefined)).toBeUndefined();
+  });
+
+  it("prefers the item's code, then the module's, then the default", () => {
+    expect(cbsCodeFor(mapping, { ref: "Item 023", moduleId: "M0201" })).toBe(
+      "C.10.01",
+    );
+    expect(cbsCodeFor(mapping, { ref: "Item 025", moduleId: "M0201" })).toBe(
+      "C.10",
+    );
+    expect(cbsCodeFor(mapping, { ref: "Item 025" })).toBe("Z.00");
+    expect(cbsCodeFor({}, { ref: "Item 025" })).toBeUndefined();
+  });
+
+  it("tags response lines and groups them by code", () => {
+    const assetMetadata = [
+      {
+        assetId: "capture",
+        usingDefaults: [],
+        blocks: [
+          {
+            moduleId: "M0201",
+            costItems: [
+              { id: "b/blocks/0/Item 023", ref: "Item 023" },
+              { id: "b/blocks/0/Item 025", ref: "Item 025" },
+            ],
+          },
+          {
+            moduleId: "M0301",
+            costItems: [{ id: "b/blocks/1/Item 026", ref: "Item 026" }],
+          },
+        ],
+      },
+    ] as unknown as AssetMetadata[];
+    const line = (id: string, cost: number) =>
+      mockCostItem(id, {
+        direct_equipment_cost: cost,
+        total_installed_cost: cost * 2,
+      });
+
+    const result = transformCostingResponse(
+      {
+        assets: [
+          mockAssetEstimate(
+            "capture",
+            [mockYearCosts(2025, {})],
+            [
+              line("b/blocks/0/Item 023", 100),
+              line("b/blocks/0/Item 025", 50),
+              line("b/blocks/1/Item 026", 10),
+            ],
+          ),
+        ],
+      },
+      assetMetadata,
+      "USD",
+      {
+        cbsMapping: {
+          items: { "Item 023": "C.10" },
+          modules: { M0201: "C.10" },
+        },
+      },
+    );
+
+    expect(result.assets[0].blocks.map((b) => b.cbsCode)).toEqual([
+      "C.10",
+      "C.10",
+      undefined,
+    ]);
+    expect(groupByCbsCode(result)).toEqual([
+      {
+        code: "C.10",
+        lineCount: 2,
+        directEquipmentCost: 150,
+        totalInstalledCost: 300,
+      },
+      { lineCount: 1, directEquipmentCost: 10, totalInstalledCost: 20 },
+    ]);
+  });
+
+  it("validates library codes against its modules and items", () => {
+    const issues = validateCostLibrary({
+      modules: [
+        {
+          id: "M0001",
+          definition: { type: "Test" },
+          subtype: null,
+          cost_items: [],
+        },
+      ],
+      cbs_mapping: { items: { "Item 001": "A" }, modules: { M0001: " " } },
+    });
+
+    expect(issues).toEqual([
+      {
+        path: "cbs_mapping.items.Item 001",
+        message: "cost item Item 001 does not exist",
+      },
+      { path: "cbs_mapping.modules.M0001", message: "must be a non-empty code" },
+    ]);
+  });
+});
@@ -0,0 +1,119 @@
+/**
+ * Cost breakdown structure (CBS) coding.
+ *
+ * Client reporting templates organise costs by their own CBS/WBS codes
+ * rather than by our modules and cost items. A mapping, shipped in the
+ * library or supplied with the request, assigns a code to each cost line;
+ * the grouped view then sums the lines per code.
+ */
+
+import type { CbsMapping } from "./types";
+import type { CostingEstimateResponse } from "./request-types";
+import type { AssetMetadata } from "./adapter";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CbsGroup = {
+  /** CBS code, or undefined for lines the mapping doesn't cover */
+  code?: string;
+  /** Number of cost lines with this code */
+  lineCount: number;
+  directEquipmentCost: number;
+  totalInstalledCost: number;
+};
+
+/** What identifies a cost line to the mapping */
+export type CbsLine = {
+  ref: string;
+  moduleId?: string;
+};
+
+// ============================================================================
+// Mapping
+// ============================================================================
+
+/**
+ * Combine mappings; entries in later mappings win.
+ */
+export function mergeCbsMappings(
+  ...mappings: Array<CbsMapping | undefined>
+): CbsMapping | undefined {
+  const present = mappings.filter((m): m is CbsMapping => m !== undefined);
+  if (present.length === 0) return undefined;
+
+  return present.reduce<CbsMapping>(
+    (merged, mapping) => ({
+      items: { ...merged.items, ...mapping.items },
+      modules: { ...merged.modules, ...mapping.modules },
+      default: mapping.default ?? merged.default,
+    }),
+    {},
+  );
+}
+
+/**
+ * The CBS code of a cost line, if the mapping covers it.
+ */
+export function cbsCodeFor(
+  mapping: CbsMapping,
+  line: CbsLine,
+): string | undefined {
+  const moduleCode =
+    line.moduleId !== undefined ? mapping.modules?.[line.moduleId] : undefined;
+  return mapping.items?.[line.ref] ?? moduleCode ?? mapping.default;
+}
+
+/**
+ * Index the cost lines sent to the costing server by their ID, so response
+ * lines can be traced back to their cost item and module.
+ */
+export function cbsLines(assetMetadata: AssetMetadata[]): Map<string, CbsLine> {
+  const lines = new Map<string, CbsLine>();
+  for (const metadata of assetMetadata) {
+    for (const block of metadata.blocks) {
+      for (const item of block.costItems ?? []) {
+        lines.set(item.id, { ref: item.ref, moduleId: block.moduleId });
+      }
+    }
+  }
+  return lines;
+}
+
+// ============================================================================
+// Grouped view
+// ============================================================================
+
+/**
+ * Sum an estimate's cost lines by CBS code. Groups are sorted by code, with
+ * uncoded lines last.
+ */
+export function groupByCbsCode(result: CostingEstimateResponse): CbsGroup[] {
+  const groups = new Map<string | undefined, CbsGroup>();
+
+  for (const asset of result.assets) {
+    for (const block of asset.blocks) {
+      const group = groups.get(block.cbsCode) ?? {
+        ...(block.cbsCode !== undefined && { code: block.cbsCode }),
+        lineCount: 0,
+        directEquipmentCost: 0,
+        totalInstalledCost: 0,
+      };
+      group.lineCount++;
+      group.directEquipmentCost += block.directEquipmentCost;
+      group.totalInstalledCost += block.totalInstalledCost;
+      groups.set(block.cbsCode, group);
+    }
+  }
+
+  return Array.from(groups.values()).sort((a, b) =>
+    a.code === b.code
+      ? 0
+      : a.code === undefined
+        ? 1
+        : b.code === undefined || a.code < b.code
+          ? -1
+          : 1,
+  );
+}
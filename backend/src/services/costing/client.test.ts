@@ -0,0 +1,73 @@
+import { describe, it, expect, vi, afterEach } from "vitest";
+import {
+  costingTimeoutFromEnv,
+  requestCostEstimate,
+  CostingRequestCancelledError,
+  CostingServerError,
+  CostingServerTimeoutError,
+} from "./client";
+
+/** A fetch that only settles when its signal aborts */
+function hangingFetch() {
+  return vi.fn(
+    (_url: string, init: RequestInit) =>
+      new Promise<Response>((_resolve, reject) => {
+        init.signal?.addEventListener("abort", () =>
+          reject(init.signal?.reason),
+        );
+      }),
+  );
+}
+
+describe("client", () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it("times out slow estimates", async () => {
+    vi.stubGlobal("fetch", hangingFetch());
+
+    const error = await requestCostEstimate(
+      { assets: [] },
+      "V1.1_working",
+      "USD",
+      { timeoutMs: 10 },
+    ).catch((e) => e);
+
+    expect(error).toBeInstanceOf(CostingServerTimeoutError);
+    expect(error.timeoutMs).toBe(10);
+  });
+
+  it("reports cancellation by the caller", async () => {
+    vi.stubGlobal("fetch", hangingFetch());
+    const controller = new AbortController();
+
+    const pending = requestCostEstimate({ assets: [] }, "V1.1_working", "USD", {
+      signal: controller.signal,
+    });
+    controller.abort();
+
+    await expect(pending).rejects.toBeInstanceOf(CostingRequestCancelledError);
+  });
+
+  it("passes through server errors", async () => {
+    vi.stubGlobal(
+      "fetch",
+      vi.fn(async () => new Response("unknown currency XYZ", { status: 400 })),
+    );
+
+    await expect(
+      requestCostEstimate({ assets: [] }, "V1.1_working", "XYZ"),
+    ).rejects.toBeInstanceOf(CostingServerError);
+  });
+
+  it("reads the timeout from COSTING_TIMEOUT_MS", () => {
+    expect(costingTimeoutFromEnv({})).toBe(120_000);
+    expect(costingTimeoutFromEnv({ COSTING_TIMEOUT_MS: "5000" })).toBe(5000);
+    for (const value of ["0", "5s", "-1", "1e3"]) {
+      expect(() =>
+        costingTimeoutFromEnv({ COSTING_TIMEOUT_MS: value }),
+      ).toThrow(/positive number of milliseconds/);
+    }
+  });
+});
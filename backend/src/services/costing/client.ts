@@ -0,0 +1,152 @@
+/**
+ * Costing server client.
+ *
+ * Thin wrapper around the costing server's HTTP API so routes that need an
+ * estimate share the same request building and error classification.
+ */
+
+import type { CostEstimateRequest, CostEstimateResponse } from "./types";
+
+// Default costing server URL (can be overridden via environment variable)
+export const COSTING_SERVER_URL =
+  process.env.COSTING_SERVER_URL || "http://localhost:8080";
+
+// Longest an estimate may take before the request is abandoned
+export const DEFAULT_COSTING_TIMEOUT_MS = 120_000;
+
+/**
+ * Read the estimate timeout from COSTING_TIMEOUT_MS. Throws unless it is a
+ * positive whole number of milliseconds.
+ */
+export function costingTimeoutFromEnv(
+  env: Record<string, string | undefined> = process.env,
+): number {
+  const value = env.COSTING_TIMEOUT_MS;
+  if (!value) return DEFAULT_COSTING_TIMEOUT_MS;
+  if (!/^\d+$/.test(value) || Number(value) === 0) {
+    throw new Error(
+      "COSTING_TIMEOUT_MS must be a positive number of milliseconds",
+    );
+  }
+  return Number(value);
+}
+
+/**
+ * The costing server responded with a non-2xx status.
+ */
+export class CostingServerError extends Error {
+  constructor(
+    public readonly status: number,
+    public readonly body: string,
+  ) {
+    super(`Costing server responded with status ${status}`);
+    this.name = "CostingServerError";
+  }
+}
+
+/**
+ * The costing server could not be reached.
+ */
+export class CostingServerUnavailableError extends Error {
+  constructor(cause: unknown) {
+    super(
+      `Failed to connect to costing server at ${COSTING_SERVER_URL}. ` +
+        "Ensure the costing server is running.",
+      { cause },
+    );
+    this.name = "CostingServerUnavailableError";
+  }
+}
+
+/**
+ * The estimate took longer than the allowed duration.
+ */
+export class CostingServerTimeoutError extends Error {
+  constructor(
+    public readonly timeoutMs: number,
+    public readonly elapsedMs: number,
+  ) {
+    super(`Costing server did not respond within ${timeoutMs} ms`);
+    this.name = "CostingServerTimeoutError";
+  }
+}
+
+/**
+ * The caller cancelled the estimate, e.g. because the client disconnected.
+ */
+export class CostingRequestCancelledError extends Error {
+  constructor() {
+    super("Costing request was cancelled");
+    this.name = "CostingRequestCancelledError";
+  }
+}
+
+export type CostEstimateCallOptions = {
+  /** Aborts the call to the costing server when signalled */
+  signal?: AbortSignal;
+  /** Maximum duration in milliseconds (default: COSTING_TIMEOUT_MS) */
+  timeoutMs?: number;
+};
+
+/**
+ * Request a cost estimate from the costing server.
+ *
+ * @throws CostingServerError if the server rejects the request
+ * @throws CostingServerUnavailableError if the server cannot be reached
+ * @throws CostingServerTimeoutError if the estimate exceeds the timeout
+ * @throws CostingRequestCancelledError if `signal` aborts the call
+ */
+export async function requestCostEstimate(
+  request: CostEstimateRequest,
+  libraryId: string,
+  currency: string,
+  options: CostEstimateCallOptions = {},
+): Promise<CostEstimateResponse> {
+  const timeoutMs = options.timeoutMs ?? costingTimeoutFromEnv();
+  const timeout = AbortSignal.timeout(timeoutMs);
+  const signal = options.signal
+    ? AbortSignal.any([options.signal, timeout])
+    : timeout;
+  const started = performance.now();
+
+  // The error to report if the call failed because it was aborted
+  const abortError = (): Error | undefined => {
+    if (options.signal?.aborted) {
+      return new CostingRequestCancelledError();
+    }
+    if (timeout.aborted) {
+      return new CostingServerTimeoutError(
+        timeoutMs,
+        Math.round(performance.now() - started),
+      );
+    }
+    return undefined;
+  };
+
+  let response: Response;
+  try {
+    response = await fetch(
+      `${COSTING_SERVER_URL}/api/cost/estimate?library_id=${libraryId}&target_currency_code=${currency}`,
+      {
+        method: "POST",
+        headers: {
+          "Content-Type": "application/json",
+        },
+        body: JSON.stringify(request),
+        signal,
+      },
+    );
+  } catch (fetchError) {
+    throw abortError() ?? new CostingServerUnavailableError(fetchError);
+  }
+
+  try {
+    if (!response.ok) {
+      throw new CostingServerError(response.status, await response.text());
+    }
+
+    return (await response.json()) as CostEstimateResponse;
+  } catch (error) {
+    throw abortError() ?? error;
+  }
+}
@@ -0,0 +1,99 @@
+import { describe, it, expect } from "vitest";
+import {
+  migrateCostItemIds,
+  previousCostItemIds,
+  resolveCostItemAlias,
+} from "./cost-item-aliases";
+import { validateCostLibrary } from "./library-validation";
+import type { CostLibrary } from "./types";
+
+describe("cost-item-aliases", () => {
+  const aliases = { "Item 007": "Item 107", "Item 107": "Item 207" };
+
+  it("follows alias chains to the current ID", () => {
+    expect(resolveCostItemAlias(aliases, "Item 007")).toBe("Item 207");
+    expect(resolveCostItemAlias(aliases, "Item 207")).toBeUndefined();
+    expect(previousCostItemIds(aliases, "Item 207")).toEqual([
+      "Item 007",
+      "Item 107",
+    ]);
+  });
+
+  it("stops at cycles", () => {
+    expect(resolveCostItemAlias({ a: "b", b: "a" }, "a")).toBe("a");
+  });
+
+  it("renames item-specific block properties in inline networks", () => {
+    const library: CostLibrary = { modules: [], cost_item_aliases: aliases };
+    const request = {
+      libraryId: "V2.0",
+      source: {
+        type: "data",
+        network: {
+          groups: [],
+          branches: [
+            {
+              id: "branch-1",
+              blocks: [
+                {
+                  type: "Compressor",
+                  electrical_power_item_007: "5 MW",
+                  compressor_duty_item_107: 2,
+                  compressor_duty_item_207: 3,
+                },
+              ],
+            },
+          ],
+        },
+      },
+    };
+
+    const { request: migrated, warnings } = migrateCostItemIds(
+      request,
+      library,
+    );
+
+    expect(migrated.source).toMatchObject({
+      network: {
+        branches: [
+          {
+            blocks: [
+              {
+                type: "Compressor",
+                electrical_power_item_207: "5 MW",
+                compressor_duty_item_207: 3,
+              },
+            ],
+          },
+        ],
+      },
+    });
+    expect(warnings).toEqual([
+      "branch-1/blocks/0: electrical_power_item_007 renamed to " +
+        "electrical_power_item_207 (cost item Item 007 is now Item 207)",
+    ]);
+  });
+
+  it("leaves preset network requests unchanged", () => {
+    const request = { source: { type: "networkId", networkId: "preset1" } };
+    const result = migrateCostItemIds(request, {
+      modules: [],
+      cost_item_aliases: aliases,
+    });
+
+    expect(result).toEqual({ request, warnings: [] });
+  });
+
+  it("validates alias targets", () => {
+    const issues = validateCostLibrary({
+      modules: [],
+      cost_item_aliases: { "Item 001": "Item 002", a: "b", b: "a" },
+    });
+
+    expect(issues.map((i) => i.message)).toEqual([
+      "cost item Item 002 does not exist",
+      "aliases form a cycle",
+      "aliases form a cycle",
+    ]);
+  });
+});
@@ -0,0 +1,128 @@
+/**
+ * Cost item ID aliases.
+ *
+ * When a library version renames cost items, it lists the old IDs in
+ * `cost_item_aliases` (old ID → new ID). Stored projects refer to cost items
+ * through item-specific block properties, suffixed with the item ID (e.g.
+ * `electrical_power_item_007`), so a rename would silently drop those
+ * values. Aliases let the adapter keep reading them, with a warning, and let
+ * saved requests be rewritten to the new IDs.
+ */
+
+import type { CostLibrary } from "./types";
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/**
+ * Follow a cost item ID through the alias table. Returns undefined if the ID
+ * isn't an alias. Chains (V1.1 → V1.3 → V2.0) are followed to the end.
+ */
+export function resolveCostItemAlias(
+  aliases: Record<string, string> | undefined,
+  id: string,
+): string | undefined {
+  if (!aliases || !Object.hasOwn(aliases, id)) return undefined;
+  const seen = new Set([id]);
+  let current = aliases[id];
+  while (Object.hasOwn(aliases, current) && !seen.has(current)) {
+    seen.add(current);
+    current = aliases[current];
+  }
+  return current;
+}
+
+/**
+ * Old IDs that resolve to a cost item ID.
+ */
+export function previousCostItemIds(
+  aliases: Record<string, string> | undefined,
+  id: string,
+): string[] {
+  return Object.keys(aliases ?? {}).filter(
+    (old) => resolveCostItemAlias(aliases, old) === id,
+  );
+}
+
+/**
+ * Block property suffix for item-specific properties named by item ID,
+ * e.g. "Item 007" → "item_007".
+ */
+export function costItemPropertySuffix(id: string): string {
+  return id.toLowerCase().replace(/\s+/g, "_");
+}
+
+// ============================================================================
+// Request migration
+// ============================================================================
+
+export type CostItemIdMigration = {
+  request: Record<string, unknown>;
+  warnings: string[];
+};
+
+/**
+ * Rename item-specific block properties in an inline network from old cost
+ * item IDs to new ones. Properties already set under the new ID are kept.
+ * Requests that reference a preset network are returned unchanged.
+ */
+export function migrateCostItemIds(
+  request: Record<string, unknown>,
+  library: CostLibrary,
+): CostItemIdMigration {
+  const aliases = library.cost_item_aliases ?? {};
+  const renames = Object.keys(aliases).map((old) => {
+    const id = resolveCostItemAlias(aliases, old)!;
+    return {
+      old,
+      id,
+      from: `_${costItemPropertySuffix(old)}`,
+      to: `_${costItemPropertySuffix(id)}`,
+    };
+  });
+  const source = request.source as
+    | { type?: string; network?: { branches?: unknown[] } }
+    | undefined;
+  if (
+    renames.length === 0 ||
+    source?.type !== "data" ||
+    !Array.isArray(source.network?.branches)
+  ) {
+    return { request, warnings: [] };
+  }
+
+  const warnings: string[] = [];
+  const branches = source.network.branches.map((branch) => {
+    const { id, blocks } = branch as { id?: string; blocks?: unknown[] };
+    if (!Array.isArray(blocks)) return branch;
+    return {
+      ...(branch as object),
+      blocks: blocks.map((block, i) => {
+        const renamed = { ...(block as Record<string, unknown>) };
+        for (const key of Object.keys(renamed)) {
+          const rename = renames.find((r) => key.endsWith(r.from));
+          if (!rename) continue;
+          const target = key.slice(0, -rename.from.length) + rename.to;
+          if (renamed[target] === undefined) {
+            renamed[target] = renamed[key];
+            warnings.push(
+              `${id}/blocks/${i}: ${key} renamed to ${target} ` +
+                `(cost item ${rename.old} is now ${rename.id})`,
+            );
+          }
+          delete renamed[key];
+        }
+        return renamed;
+      }),
+    };
+  });
+
+  return {
+    request: {
+      ...request,
+      source: { ...source, network: { ...source.network, branches } },
+    },
+    warnings,
+  };
+}
@@ -0,0 +1,67 @@
+import { describe, it, expect } from "vitest";
+import {
+  convertCostParameter,
+  convertRequestCostParameters,
+  CostParameterError,
+} from "./cost-parameters";
+import type { AssetParameters, CurrencyConversion } from "./types";
+
+describe("cost-parameters", () => {
+  const conversion: CurrencyConversion = {
+    base_currency: "USD",
+    rates: { USD: 1, GBP: 1.25, EUR: 1.1 },
+  };
+
+  it("converts into the estimate currency with the library's rates", () => {
+    expect(
+      convertCostParameter(
+        { currency_code: "GBP", amount: 80_000 },
+        "USD",
+        conversion,
+      ),
+    ).toEqual({ currency_code: "USD", amount: 100_000 });
+    expect(
+      convertCostParameter(
+        { currency_code: "GBP", amount: 88_000 },
+        "EUR",
+        conversion,
+      ).amount,
+    ).toBeCloseTo(100_000);
+  });
+
+  it("leaves parameters already in the estimate currency alone", () => {
+    const salary = { currency_code: "XYZ", amount: 1 };
+    expect(convertCostParameter(salary, "XYZ", undefined)).toBe(salary);
+  });
+
+  it("rejects currencies the library has no rate for", () => {
+    const convert = (from: string, to: string) => () =>
+      convertCostParameter({ currency_code: from, amount: 1 }, to, conversion);
+
+    expect(convert("JPY", "USD")).toThrow(CostParameterError);
+    expect(convert("JPY", "USD")).toThrow(/no exchange rate for JPY/);
+    expect(convert("GBP", "NOK")).toThrow(/no exchange rate for NOK/);
+    expect(() =>
+      convertCostParameter(
+        { currency_code: "GBP", amount: 1 },
+        "USD",
+        undefined,
+      ),
+    ).toThrow(CostParameterError);
+  });
+
+  it("names the asset whose salary can't be converted", () => {
+    const request = {
+      assets: [
+        {
+          id: "branch-1",
+          labour_average_salary: { currency_code: "JPY", amount: 1 },
+        } as AssetParameters,
+      ],
+    };
+
+    expect(() =>
+      convertRequestCostParameters(request, "USD", conversion),
+    ).toThrow(/branch-1 labour_average_salary/);
+  });
+});
@@ -0,0 +1,84 @@
+/**
+ * Currency of cost parameters.
+ *
+ * Request inputs priced in money (the labour average salary) carry their
+ * own currency code, which the costing server takes at face value: a
+ * salary in GBP would be added to costs in USD unconverted. Before costing,
+ * each cost parameter is checked against the library's currency table and
+ * converted into the estimate's currency.
+ */
+
+import type {
+  CostEstimateRequest,
+  CostParameter,
+  CurrencyConversion,
+} from "./types";
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+export class CostParameterError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "CostParameterError";
+  }
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+/**
+ * A cost parameter in `currency`, converted with the library's rates.
+ *
+ * @throws CostParameterError if the library has no rate for the parameter's
+ *   currency or the target currency
+ */
+export function convertCostParameter(
+  parameter: CostParameter,
+  currency: string,
+  conversion: CurrencyConversion | undefined,
+  name = "cost parameter",
+): CostParameter {
+  if (parameter.currency_code === currency) return parameter;
+
+  const rates = conversion?.rates ?? {};
+  for (const code of [parameter.currency_code, currency]) {
+    if (rates[code] === undefined) {
+      throw new CostParameterError(
+        `Can't convert ${name} from ${parameter.currency_code} to ` +
+          `${currency}: the library has no exchange rate for ${code}`,
+      );
+    }
+  }
+  return {
+    currency_code: currency,
+    amount:
+      (parameter.amount * rates[parameter.currency_code]) / rates[currency],
+  };
+}
+
+/**
+ * The request with every asset's cost parameters in `currency`.
+ *
+ * @throws CostParameterError for a currency the library can't convert
+ */
+export function convertRequestCostParameters(
+  request: CostEstimateRequest,
+  currency: string,
+  conversion: CurrencyConversion | undefined,
+): CostEstimateRequest {
+  return {
+    ...request,
+    assets: request.assets.map((asset) => ({
+      ...asset,
+      labour_average_salary: convertCostParameter(
+        asset.labour_average_salary,
+        currency,
+        conversion,
+        `${asset.id} labour_average_salary`,
+      ),
+    })),
+  };
+}
@@ -0,0 +1,63 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import { discountedLifetimeCosts } from "./discount-rates";
+import {
+  mockAssetEstimate,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { Timeline } from "./types";
+
+const timeline: Timeline = {
+  construction_start: 2030,
+  construction_finish: 2031,
+  operation_start: 2031,
+  operation_finish: 2033,
+  decommissioning_start: 2033,
+  decommissioning_finish: 2033,
+};
+
+describe("discount-rates", () => {
+  const asset = (id: string) =>
+    mockAssetEstimate(id, [
+      mockYearCosts(2030, { total_installed_cost: 1_000 }),
+      mockYearCosts(2031, { variable_opex_cost: { tariff: 110 } }),
+      mockYearCosts(2032, { decommissioning_cost: 121 }),
+    ]);
+
+  it("discounts each year to the construction start at every rate", () => {
+    const [undiscounted, atTen] = discountedLifetimeCosts(
+      asset("a"),
+      timeline.construction_start,
+      [0, 0.1],
+    );
+
+    expect(undiscounted.total_installed_cost).toBe(1_000);
+    expect(undiscounted.variable_opex_cost.tariff).toBe(110);
+    expect(atTen.total_installed_cost).toBe(1_000);
+    expect(atTen.variable_opex_cost.tariff).toBeCloseTo(100);
+    expect(atTen.decommissioning_cost).toBeCloseTo(100);
+  });
+
+  it("reports net present cost per rate for assets and the network", () => {
+    const result = transformCostingResponse(
+      { assets: [asset("a"), asset("b")] },
+      [],
+      "GBP",
+      {
+        discountRates: [0.1],
+        timelines: new Map([
+          ["a", timeline],
+          ["b", timeline],
+        ]),
+      },
+    );
+
+    expect(result.assets[0].npcByDiscountRate?.[0].discountRate).toBe(0.1);
+    expect(
+      result.assets[0].npcByDiscountRate?.[0].lifetimeNpcCosts.total,
+    ).toBeCloseTo(1_200);
+    expect(result.npcByDiscountRate?.[0].lifetimeNpcCosts.total).toBeCloseTo(
+      2_400,
+    );
+  });
+});
@@ -0,0 +1,40 @@
+/**
+ * Net present cost at several discount rates.
+ *
+ * Social and commercial discounting are usually compared side by side
+ * (e.g. 3.5% against 8% and 10%). The costing server discounts at one rate
+ * per asset, but its undiscounted yearly costs are enough to discount at
+ * any other: each year's costs are divided by (1 + rate)^(year -
+ * construction start), the server's convention, and summed. One pass over
+ * the years serves every requested rate.
+ */
+
+import type { AssetCostEstimate, AssetPeriodCosts } from "./types";
+import { addCostValues, mapCostValues } from "./totals";
+
+// ============================================================================
+// Calculation
+// ============================================================================
+
+/**
+ * An asset's lifetime costs discounted to its construction start at each
+ * rate, in the order of `rates`.
+ */
+export function discountedLifetimeCosts(
+  asset: AssetCostEstimate,
+  constructionStart: number,
+  rates: number[],
+): AssetPeriodCosts[] {
+  const zero = mapCostValues(asset.lifetime_costs, () => 0);
+  const totals = rates.map(() => zero);
+  for (const y of asset.costs_by_year) {
+    rates.forEach((rate, i) => {
+      const factor = 1 / Math.pow(1 + rate, y.year - constructionStart);
+      totals[i] = addCostValues(
+        totals[i],
+        mapCostValues(y.costs_in_year, (value) => value * factor),
+      );
+    });
+  }
+  return totals;
+}
@@ -0,0 +1,74 @@
+import { describe, it, expect } from "vitest";
+import { buildDryRunReport } from "./dry-run";
+import type { AssetMetadata } from "./adapter";
+
+describe("dry-run", () => {
+  const metadata: AssetMetadata = {
+    assetId: "group-1",
+    name: "Capture plant",
+    isGroup: true,
+    branchIds: ["branch-1"],
+    blockCount: 2,
+    costableBlockCount: 1,
+    usingDefaults: ["timeline", "discount_rate"],
+    blocks: [
+      {
+        id: "branch-1/blocks/0",
+        type: "CaptureUnit",
+        status: "costable",
+        definedProperties: { mass_flow: 100000 },
+        missingProperties: [],
+        costItems: [
+          {
+            id: "branch-1/blocks/0/Item 023",
+            ref: "Item 023",
+            included: true,
+            parameters: [
+              { name: "Mass flow", units: "kg/h", satisfied: true, value: 100000 },
+            ],
+          },
+          {
+            id: "branch-1/blocks/0/Item 024",
+            ref: "Item 024",
+            included: false,
+            parameters: [
+              { name: "Mass flow", units: "kg/h", satisfied: true, value: 100000 },
+              { name: "Electrical power", units: "kW", satisfied: false },
+            ],
+          },
+        ],
+      },
+      {
+        id: "branch-1/blocks/1",
+        type: "Unknown",
+        status: "unknown",
+        definedProperties: {},
+        missingProperties: [],
+      },
+    ],
+  };
+
+  it("lists every cost item with the block it came from", () => {
+    const report = buildDryRunReport([metadata], "V1.1_working");
+
+    expect(report.assets[0].costItems.map((i) => i.blockId)).toEqual([
+      "branch-1/blocks/0",
+      "branch-1/blocks/0",
+    ]);
+    expect(report.assets[0].propertiesUsingDefaults).toEqual([
+      "timeline",
+      "discount_rate",
+    ]);
+  });
+
+  it("summarises included items and missing parameters", () => {
+    const report = buildDryRunReport([metadata], "V1.1_working");
+
+    expect(report.summary).toEqual({
+      costItemCount: 2,
+      includedCount: 1,
+      missingParameterCount: 1,
+      constraintViolationCount: 0,
+    });
+  });
+});
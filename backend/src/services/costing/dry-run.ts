@@ -0,0 +1,92 @@
+/**
+ * Dry-run report.
+ *
+ * Resolves every cost item an estimate would use and reports its required
+ * parameters - which are satisfied and which are missing - together with the
+ * asset-level defaults that would apply, without calling the costing server.
+ * Lets clients drive parameter forms from the cost library.
+ */
+
+import type { AssetMetadata, CostItemReport } from "./adapter";
+import type { BlockConstraintViolation } from "./item-constraints";
+import type { AssetProvenance } from "./request-types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type DryRunCostItem = CostItemReport & {
+  /** Block the cost item was derived from */
+  blockId: string;
+};
+
+export type DryRunAsset = {
+  id: string;
+  name?: string;
+  /** Asset-level properties that would use defaults */
+  propertiesUsingDefaults: string[];
+  /** Factors the asset would be costed with */
+  provenance?: AssetProvenance;
+  costItems: DryRunCostItem[];
+  /** Module selection rules the asset's blocks break */
+  constraintViolations: BlockConstraintViolation[];
+};
+
+export type DryRunResponse = {
+  dryRun: true;
+  libraryId: string;
+  summary: {
+    costItemCount: number;
+    /** Cost items with all required parameters satisfied */
+    includedCount: number;
+    /** Required parameters not satisfied, across all cost items */
+    missingParameterCount: number;
+    /** Cost item constraints broken, across all blocks */
+    constraintViolationCount: number;
+  };
+  assets: DryRunAsset[];
+};
+
+// ============================================================================
+// Builder
+// ============================================================================
+
+/**
+ * Build the dry-run report from the asset metadata of a transformed network.
+ */
+export function buildDryRunReport(
+  assetMetadata: AssetMetadata[],
+  libraryId: string,
+): DryRunResponse {
+  const assets = assetMetadata.map(
+    (m): DryRunAsset => ({
+      id: m.assetId,
+      name: m.name,
+      propertiesUsingDefaults: m.usingDefaults,
+      provenance: m.provenance,
+      costItems: m.blocks.flatMap((block) =>
+        (block.costItems ?? []).map((item) => ({ ...item, blockId: block.id })),
+      ),
+      constraintViolations: m.blocks.flatMap(
+        (block) => block.constraintViolations ?? [],
+      ),
+    }),
+  );
+
+  const costItems = assets.flatMap((a) => a.costItems);
+
+  return {
+    dryRun: true,
+    libraryId,
+    summary: {
+      costItemCount: costItems.length,
+      includedCount: costItems.filter((item) => item.included).length,
+      missingParameterCount: costItems
+        .flatMap((item) => item.parameters)
+        .filter((p) => !p.satisfied).length,
+      constraintViolationCount: assets.flatMap((a) => a.constraintViolations)
+        .length,
+    },
+    assets,
+  };
+}
@@ -0,0 +1,128 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import {
+  summarizeEstimateChanges,
+  EstimateChangesError,
+} from "./estimate-changes";
+import type { StoredEstimate } from "./estimate-store";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { CostingEstimateRequestInput } from "./schemas";
+import type {
+  AssetParameters,
+  CostEstimateRequest,
+  CostEstimateResponse,
+} from "./types";
+
+const ITEM_ID = "capture/blocks/0/Item 074";
+
+/** A cost item whose installed cost is £100k per tonne of captured CO2 */
+function itemCost(parameters: Record<string, number>) {
+  return parameters["Captured CO2"] * 100_000;
+}
+
+function costed(request: CostEstimateRequest): CostEstimateResponse {
+  return {
+    assets: request.assets.map((asset) => {
+      const items = asset.cost_items.map((item) => {
+        const cost = itemCost(item.parameters) * item.quantity;
+        return mockCostItem(item.id, {
+          direct_equipment_cost: cost,
+          total_installed_cost: cost,
+        });
+      });
+      const total = items.reduce(
+        (sum, i) => sum + (i.lifetime_costs.total_installed_cost ?? 0),
+        0,
+      );
+      return mockAssetEstimate(
+        asset.id,
+        [mockYearCosts(2025, { total_installed_cost: total })],
+        items,
+      );
+    }),
+  };
+}
+
+function estimate(
+  id: string,
+  parameters: Record<string, number>,
+): StoredEstimate {
+  const costingRequest = {
+    assets: [
+      {
+        id: "capture",
+        cost_items: [
+          { id: ITEM_ID, ref: "Item 074", quantity: 1, parameters },
+        ],
+      } as unknown as AssetParameters,
+    ],
+  };
+  const costingResponse = costed(costingRequest);
+  return {
+    id,
+    createdAt: "2026-01-01T00:00:00.000Z",
+    libraryId: "V1.3",
+    libraryHash: "hash",
+    request: { libraryId: "V1.3" } as CostingEstimateRequestInput,
+    result: transformCostingResponse(costingResponse, [], "GBP"),
+    costItemIds: { capture: ["Item 074"] },
+    intermediate: { costingRequest, costingResponse, assetMetadata: [] },
+  };
+}
+
+describe("estimate-changes", () => {
+  const baseline = estimate("before", { "Captured CO2": 20, Pressure: 100 });
+  const current = estimate("after", { "Captured CO2": 28, Pressure: 120 });
+
+  it("attributes a cost item's change to the inputs that changed", async () => {
+    const runs: CostEstimateRequest[] = [];
+    const summary = await summarizeEstimateChanges(baseline, current, {
+      rerun: async (request) => {
+        runs.push(request);
+        return costed(request);
+      },
+    });
+
+    expect(summary.totalInstalledCost.percent).toBe(40);
+    expect(summary.contributors).toHaveLength(1);
+    const [contributor] = summary.contributors;
+    expect(contributor.ref).toBe("Item 074");
+    expect(contributor.inputChanges.map((c) => c.parameter)).toEqual([
+      "Captured CO2",
+      "Pressure",
+    ]);
+    // Pressure doesn't move the cost, so Captured CO2 comes first
+    expect(contributor.attribution).toEqual([
+      { parameter: "Captured CO2", delta: 800_000 },
+      { parameter: "Pressure", delta: 0 },
+    ]);
+    expect(runs).toHaveLength(3);
+    expect(summary.narrative[0]).toBe(
+      "Total installed cost up 40% driven by Item 074 (+£800k) due to " +
+        "Captured CO2 20→28 (+£800k), Pressure 100→120 (£0)",
+    );
+  });
+
+  it("lists changed inputs without attribution when not re-costing", async () => {
+    const summary = await summarizeEstimateChanges(baseline, current);
+
+    expect(summary.contributors[0].attribution).toBeUndefined();
+    expect(summary.narrative[0]).toBe(
+      "Total installed cost up 40% driven by Item 074 (+£800k) due to " +
+        "Captured CO2 20→28, Pressure 100→120",
+    );
+    expect(summary.libraryChanged).toBe(false);
+  });
+
+  it("needs stored cost item results", async () => {
+    const older = { ...baseline, intermediate: undefined };
+
+    await expect(
+      summarizeEstimateChanges(older, current),
+    ).rejects.toBeInstanceOf(EstimateChangesError);
+  });
+});
@@ -0,0 +1,398 @@
+/**
+ * Human-readable summaries of the change between two saved estimates.
+ *
+ * The change in total installed cost is broken down by library cost item,
+ * and each of the largest contributors is matched with the inputs that
+ * changed on it (parameters and quantity). For those contributors the
+ * baseline cost item is re-costed with one changed input at a time, which
+ * attributes the item's change to each input, e.g. "Total installed cost up
+ * 12% driven by Item 074 (+£2.1M) due to Captured CO2 20→28".
+ *
+ * Re-runs cost the baseline's items as the baseline was costed: with its
+ * library and the same scenario, price base year and price basis, so
+ * attributions are in the same money as the deltas they explain. The
+ * library is used as currently published, so a contributor's attribution
+ * may not add up to its change when the library moved between the
+ * estimates.
+ */
+
+import { costDelta, type CostDelta } from "./estimate-compare";
+import type { StoredEstimate } from "./estimate-store";
+import type {
+  AssetParameters,
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostItemParameters,
+} from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type InputChange = {
+  assetId: string;
+  costItemId: string;
+  /** Parameter name, or "quantity" */
+  parameter: string;
+  /** null when the cost item or parameter wasn't in the estimate */
+  baseline: number | null;
+  current: number | null;
+};
+
+export type InputAttribution = {
+  parameter: string;
+  /** Change in the item's total installed cost from this input alone */
+  delta: number;
+};
+
+export type CostItemContributor = {
+  /** Library cost item ID, e.g. "Item 074" */
+  ref: string;
+  /** Total installed cost of the item across all assets */
+  totalInstalledCost: CostDelta;
+  inputChanges: InputChange[];
+  /** Only when the item's inputs were re-costed one at a time */
+  attribution?: InputAttribution[];
+};
+
+export type EstimateChangeSummary = {
+  baselineId: string;
+  currentId: string;
+  currency: string;
+  totalInstalledCost: CostDelta;
+  total: CostDelta;
+  /** The estimates were computed with different library snapshots */
+  libraryChanged: boolean;
+  /** Largest contributors to the change in total installed cost */
+  contributors: CostItemContributor[];
+  /** One sentence per finding, headline first */
+  narrative: string[];
+};
+
+export type ChangeSummaryOptions = {
+  /** Number of contributors to report (default: 3) */
+  top?: number;
+  /** Cost a request as the baseline was costed, for attribution */
+  rerun?: (request: CostEstimateRequest) => Promise<CostEstimateResponse>;
+  /** Locale for amounts in the narrative (default: "en-GB") */
+  locale?: string;
+};
+
+export class EstimateChangesError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "EstimateChangesError";
+  }
+}
+
+export const DEFAULT_TOP_CONTRIBUTORS = 3;
+
+/** Re-runs per summary, so a large diff can't flood the costing server */
+export const MAX_ATTRIBUTION_RUNS = 20;
+
+// ============================================================================
+// Cost items
+// ============================================================================
+
+type CostItemInstance = {
+  asset: AssetParameters;
+  item: CostItemParameters;
+  totalInstalledCost: number;
+};
+
+/**
+ * Every cost item in an estimate with its lifetime total installed cost,
+ * keyed by cost item ID.
+ */
+function costItemInstances(
+  estimate: StoredEstimate,
+): Map<string, CostItemInstance> {
+  const intermediate = estimate.intermediate;
+  if (!intermediate) {
+    throw new EstimateChangesError(
+      `Estimate ${estimate.id} was saved without its cost item results; ` +
+        "save it again to summarize changes",
+    );
+  }
+  const costs = new Map(
+    intermediate.costingResponse.assets.flatMap((a) =>
+      a.cost_items.map((i) => [
+        i.id,
+        i.lifetime_costs.total_installed_cost ?? 0,
+      ]),
+    ),
+  );
+  return new Map(
+    intermediate.costingRequest.assets.flatMap((asset) =>
+      asset.cost_items.map((item) => [
+        item.id,
+        { asset, item, totalInstalledCost: costs.get(item.id) ?? 0 },
+      ]),
+    ),
+  );
+}
+
+/**
+ * Inputs that differ between two instances of a cost item.
+ */
+function inputChanges(
+  costItemId: string,
+  before: CostItemInstance | undefined,
+  after: CostItemInstance | undefined,
+): InputChange[] {
+  const assetId = (after ?? before)!.asset.id;
+  const value = (instance: CostItemInstance | undefined, name: string) =>
+    instance === undefined
+      ? null
+      : name === "quantity"
+        ? instance.item.quantity
+        : (instance.item.parameters[name] ?? null);
+
+  const names = [
+    "quantity",
+    ...new Set([
+      ...Object.keys(before?.item.parameters ?? {}),
+      ...Object.keys(after?.item.parameters ?? {}),
+    ]),
+  ];
+  return names
+    .map((parameter) => ({
+      assetId,
+      costItemId,
+      parameter,
+      baseline: value(before, parameter),
+      current: value(after, parameter),
+    }))
+    .filter((change) => change.baseline !== change.current);
+}
+
+// ============================================================================
+// Attribution
+// ============================================================================
+
+function itemTotalInstalledCost(response: CostEstimateResponse): number {
+  return response.assets
+    .flatMap((a) => a.cost_items)
+    .reduce((sum, i) => sum + (i.lifetime_costs.total_installed_cost ?? 0), 0);
+}
+
+/**
+ * Re-cost the baseline item alone, then with each changed input in turn,
+ * summing each input's effect over the item's instances. Inputs of items
+ * added or removed between the estimates can't be varied on their own and
+ * are left out.
+ */
+async function attribute(
+  changes: InputChange[],
+  baseline: Map<string, CostItemInstance>,
+  rerun: NonNullable<ChangeSummaryOptions["rerun"]>,
+  budget: { runs: number },
+): Promise<InputAttribution[] | undefined> {
+  const deltas = new Map<string, number>();
+  const byItem = new Map<string, InputChange[]>();
+  for (const change of changes) {
+    byItem.set(change.costItemId, [
+      ...(byItem.get(change.costItemId) ?? []),
+      change,
+    ]);
+  }
+
+  for (const [costItemId, itemChanges] of byItem) {
+    const instance = baseline.get(costItemId);
+    const varied = itemChanges.filter((c) => c.current !== null);
+    if (!instance || varied.length === 0) continue;
+    if (budget.runs < varied.length + 1) return undefined;
+    budget.runs -= varied.length + 1;
+
+    const cost = async (item: CostItemParameters) =>
+      itemTotalInstalledCost(
+        await rerun({ assets: [{ ...instance.asset, cost_items: [item] }] }),
+      );
+    const base = await cost(instance.item);
+    for (const change of varied) {
+      const item =
+        change.parameter === "quantity"
+          ? { ...instance.item, quantity: change.current! }
+          : {
+              ...instance.item,
+              parameters: {
+                ...instance.item.parameters,
+                [change.parameter]: change.current!,
+              },
+            };
+      deltas.set(
+        change.parameter,
+        (deltas.get(change.parameter) ?? 0) + (await cost(item)) - base,
+      );
+    }
+  }
+
+  return [...deltas]
+    .map(([parameter, delta]) => ({ parameter, delta }))
+    .sort((a, b) => Math.abs(b.delta) - Math.abs(a.delta));
+}
+
+// ============================================================================
+// Narrative
+// ============================================================================
+
+function describeChange(label: string, delta: CostDelta): string {
+  if (delta.delta === 0) return `${label} unchanged`;
+  const direction = delta.delta > 0 ? "up" : "down";
+  return delta.percent === null
+    ? `${label} ${direction} from nothing`
+    : `${label} ${direction} ${Math.abs(Math.round(delta.percent))}%`;
+}
+
+function describeInputs(
+  contributor: CostItemContributor,
+  amount: (value: number) => string,
+): string | undefined {
+  const format = (value: number | null) =>
+    value === null ? "none" : String(value);
+  // One description per parameter; several instances show the first
+  const changes = new Map<string, InputChange>();
+  for (const change of contributor.inputChanges) {
+    if (!changes.has(change.parameter)) changes.set(change.parameter, change);
+  }
+  // Attributed inputs first, largest effect first
+  const deltas = new Map(
+    (contributor.attribution ?? []).map((a) => [a.parameter, a.delta]),
+  );
+  const ordered = [
+    ...[...deltas.keys()].flatMap((p) => changes.get(p) ?? []),
+    ...[...changes.values()].filter((c) => !deltas.has(c.parameter)),
+  ].map((c) => ({ ...c, delta: deltas.get(c.parameter) }));
+  if (ordered.length === 0) return undefined;
+
+  return ordered
+    .map(
+      (c) =>
+        `${c.parameter} ${format(c.baseline)}→${format(c.current)}` +
+        (c.delta !== undefined ? ` (${amount(c.delta)})` : ""),
+    )
+    .join(", ");
+}
+
+function buildNarrative(
+  summary: Omit<EstimateChangeSummary, "narrative">,
+  locale: string,
+): string[] {
+  const formatter = new Intl.NumberFormat(locale, {
+    style: "currency",
+    currency: summary.currency,
+    notation: "compact",
+    maximumSignificantDigits: 2,
+    signDisplay: "exceptZero",
+  });
+  const amount = (value: number) => formatter.format(value);
+  const contributorText = (c: CostItemContributor) => {
+    const inputs = describeInputs(c, amount);
+    return (
+      `${c.ref} (${amount(c.totalInstalledCost.delta)})` +
+      (inputs ? ` due to ${inputs}` : "")
+    );
+  };
+
+  const [first, ...rest] = summary.contributors;
+  const headline =
+    describeChange("Total installed cost", summary.totalInstalledCost) +
+    (first && summary.totalInstalledCost.delta !== 0
+      ? ` driven by ${contributorText(first)}`
+      : "");
+  return [
+    headline,
+    ...rest.map((c) => `Also ${contributorText(c)}`),
+    `${describeChange("Lifetime cost", summary.total)} ` +
+      `(${amount(summary.total.delta)})`,
+    ...(summary.libraryChanged
+      ? ["The cost library changed between the estimates"]
+      : []),
+  ];
+}
+
+// ============================================================================
+// Summary
+// ============================================================================
+
+/**
+ * Summarize the change from a baseline estimate to a current one.
+ *
+ * @throws EstimateChangesError if either estimate has no stored cost item
+ *   results, or they are in different currencies
+ */
+export async function summarizeEstimateChanges(
+  baseline: StoredEstimate,
+  current: StoredEstimate,
+  options: ChangeSummaryOptions = {},
+): Promise<EstimateChangeSummary> {
+  if (baseline.result.currency !== current.result.currency) {
+    throw new EstimateChangesError(
+      `Estimates are in different currencies (${baseline.result.currency}, ` +
+        `${current.result.currency})`,
+    );
+  }
+  const before = costItemInstances(baseline);
+  const after = costItemInstances(current);
+
+  // Change per library cost item, with the inputs that changed on it
+  const byRef = new Map<
+    string,
+    { baseline: number; current: number; changes: InputChange[] }
+  >();
+  for (const id of new Set([...before.keys(), ...after.keys()])) {
+    const b = before.get(id);
+    const a = after.get(id);
+    const ref = (a ?? b)!.item.ref;
+    const entry = byRef.get(ref) ?? { baseline: 0, current: 0, changes: [] };
+    entry.baseline += b?.totalInstalledCost ?? 0;
+    entry.current += a?.totalInstalledCost ?? 0;
+    entry.changes.push(...inputChanges(id, b, a));
+    byRef.set(ref, entry);
+  }
+
+  const ranked = [...byRef]
+    .map(([ref, e]) => ({
+      ref,
+      changes: e.changes,
+      delta: costDelta(e.baseline, e.current),
+    }))
+    .filter((e) => e.delta.delta !== 0)
+    .sort((x, y) => Math.abs(y.delta.delta) - Math.abs(x.delta.delta))
+    .slice(0, options.top ?? DEFAULT_TOP_CONTRIBUTORS);
+
+  const budget = { runs: MAX_ATTRIBUTION_RUNS };
+  const contributors: CostItemContributor[] = [];
+  for (const entry of ranked) {
+    const attribution =
+      options.rerun && entry.changes.length > 0
+        ? await attribute(entry.changes, before, options.rerun, budget)
+        : undefined;
+    contributors.push({
+      ref: entry.ref,
+      totalInstalledCost: entry.delta,
+      inputChanges: entry.changes,
+      ...(attribution && { attribution }),
+    });
+  }
+
+  const summary = {
+    baselineId: baseline.id,
+    currentId: current.id,
+    currency: current.result.currency,
+    totalInstalledCost: costDelta(
+      baseline.result.lifetimeCosts.totalInstalledCost,
+      current.result.lifetimeCosts.totalInstalledCost,
+    ),
+    total: costDelta(
+      baseline.result.lifetimeCosts.total,
+      current.result.lifetimeCosts.total,
+    ),
+    libraryChanged: baseline.libraryHash !== current.libraryHash,
+    contributors,
+  };
+  return {
+    ...summary,
+    narrative: buildNarrative(summary, options.locale ?? "en-GB"),
+  };
+}
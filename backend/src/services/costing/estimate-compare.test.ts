@@ -0,0 +1,227 @@
+import {
+  describe,
+  it,
+  expect,
+  beforeAll,
+  beforeEach,
+  afterEach,
+  vi,
+} from "vitest";
+import { mkdtemp, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { transformCostingResponse } from "./adapter";
+import { compareEstimates } from "./estimate-compare";
+import {
+  getEstimate,
+  getEstimateLibrary,
+  listEstimates,
+  saveEstimate,
+  EstimateNotFoundError,
+  type EstimateLabels,
+} from "./estimate-store";
+import { hashCostLibrary, loadCostLibrary } from "./module-lookup";
+import { getStorage } from "../storage";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { CostingEstimateRequestInput } from "./schemas";
+import type { CostEstimateRequest, CostLibrary } from "./types";
+
+const LIBRARY_ID = "V1.1_working";
+
+function installed(cost: number) {
+  return { direct_equipment_cost: cost, total_installed_cost: cost };
+}
+
+function result(capture: number, pipe: number) {
+  return transformCostingResponse(
+    {
+      assets: [
+        mockAssetEstimate(
+          "capture",
+          [mockYearCosts(2025, installed(capture))],
+          [mockCostItem("b/blocks/0/Item 023", installed(capture))],
+        ),
+        mockAssetEstimate("pipe", [mockYearCosts(2025, installed(pipe))], []),
+      ],
+    },
+    [],
+    "USD",
+  );
+}
+
+const costingRequest = {
+  assets: [
+    {
+      id: "capture",
+      cost_items: [
+        {
+          id: "b/blocks/0/Item 023",
+          ref: "Item 023",
+          quantity: 1,
+          parameters: {},
+        },
+      ],
+    },
+    { id: "pipe", cost_items: [] },
+  ],
+} as unknown as CostEstimateRequest;
+
+const request = {
+  source: { type: "networkId", networkId: "preset1" },
+  libraryId: LIBRARY_ID,
+} as unknown as CostingEstimateRequestInput;
+
+function withCaptureCostChanged(library: CostLibrary): CostLibrary {
+  const changed: CostLibrary = JSON.parse(JSON.stringify(library));
+  for (const module of changed.modules) {
+    for (const item of module.cost_items ?? []) {
+      if (item.id === "Item 023") item.info.note = "Re-quoted";
+    }
+  }
+  return changed;
+}
+
+describe("estimate-compare", () => {
+  let dataRoot: string;
+  let library: CostLibrary;
+
+  beforeAll(async () => {
+    library = await loadCostLibrary(LIBRARY_ID);
+  });
+
+  beforeEach(async () => {
+    dataRoot = await mkdtemp(join(tmpdir(), "estimates-"));
+    vi.stubEnv("DATA_DIR", dataRoot);
+  });
+
+  afterEach(async () => {
+    vi.unstubAllEnvs();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("saves estimates with a snapshot of their library", async () => {
+    const stored = await saveEstimate({
+      request,
+      result: result(100, 50),
+      library,
+      costingRequest,
+      createdBy: "alice",
+      label: "FEED baseline",
+    });
+
+    const loaded = await getEstimate(stored.id);
+    expect(loaded).toMatchObject({
+      createdBy: "alice",
+      libraryHash: hashCostLibrary(library),
+      costItemIds: { capture: ["Item 023"], pipe: [] },
+    });
+    expect(await getEstimateLibrary(loaded)).toEqual(library);
+    expect(await listEstimates({ createdBy: "alice" })).toMatchObject([
+      { id: stored.id, label: "FEED baseline", total: 150 },
+    ]);
+    // Estimates saved before summaries were kept are summarized on listing
+    await getStorage().delete("estimates/summaries", stored.id);
+    expect(await listEstimates()).toMatchObject([{ id: stored.id }]);
+    expect(
+      await getStorage().get("estimates/summaries", stored.id),
+    ).toMatchObject({ id: stored.id, total: 150 });
+    await expect(getEstimate("../secrets")).rejects.toBeInstanceOf(
+      EstimateNotFoundError,
+    );
+  });
+
+  it("searches saved estimates by labels and dates, sorted by total", async () => {
+    const save = (total: number, label: string, labels: EstimateLabels) =>
+      saveEstimate({
+        request,
+        result: result(total, 0),
+        library,
+        costingRequest,
+        label,
+        labels,
+      });
+    const feed = await save(300, "FEED", {
+      project: "Northern Lights",
+      scenario: "High flow",
+      tags: ["feed", "2030s"],
+    });
+    const concept = await save(100, "Concept", {
+      project: "Northern Lights",
+      author: "Sam",
+      tags: ["concept"],
+    });
+    const other = await save(200, "Other", { project: "Porthos" });
+    const ids = (estimates: { id: string }[]) => estimates.map((e) => e.id);
+
+    expect(
+      ids(await listEstimates({ project: "Northern Lights", sort: "-total" })),
+    ).toEqual([feed.id, concept.id]);
+    expect(ids(await listEstimates({ sort: "total" }))).toEqual([
+      concept.id,
+      other.id,
+      feed.id,
+    ]);
+    expect(ids(await listEstimates({ label: "high" }))).toEqual([feed.id]);
+    expect(ids(await listEstimates({ label: "sam" }))).toEqual([concept.id]);
+    expect(ids(await listEstimates({ tags: ["feed", "2030s"] }))).toEqual([
+      feed.id,
+    ]);
+    expect(await listEstimates({ to: feed.createdAt })).toEqual([]);
+    expect(await listEstimates({ from: "2000-01-01" })).toHaveLength(3);
+    // Compared as instants, whatever the offset
+    const withOffset = new Date(Date.parse(feed.createdAt) + 2 * 3_600_000)
+      .toISOString()
+      .replace("Z", "+02:00");
+    expect(
+      ids(await listEstimates({ from: withOffset, label: "FEED" })),
+    ).toEqual([feed.id]);
+    expect((await listEstimates({ scenario: "High flow" }))[0]).toMatchObject({
+      label: "FEED",
+      labels: { project: "Northern Lights", scenario: "High flow" },
+    });
+  });
+
+  it("attributes category and asset deltas to library changes", async () => {
+    const baseline = await saveEstimate({
+      request,
+      result: result(100, 50),
+      library,
+      costingRequest,
+    });
+    const current = withCaptureCostChanged(library);
+
+    const comparison = compareEstimates(baseline, library, {
+      result: result(120, 50),
+      library: current,
+      libraryHash: hashCostLibrary(current),
+      costItemIds: baseline.costItemIds,
+    });
+
+    expect(comparison.total).toEqual({
+      baseline: 150,
+      current: 170,
+      delta: 20,
+      percent: (20 / 150) * 100,
+    });
+    expect(
+      comparison.categories.find((c) => c.category === "directEquipmentCost"),
+    ).toMatchObject({ delta: 20 });
+    expect(comparison.assets).toEqual([
+      expect.objectContaining({
+        id: "capture",
+        status: "changed",
+        drivers: ["Item 023"],
+      }),
+      expect.objectContaining({ id: "pipe", status: "unchanged", drivers: [] }),
+    ]);
+    expect(comparison.libraryChanges).toMatchObject({
+      identical: false,
+      costItems: [{ id: "Item 023", fields: ["info.note"] }],
+      otherChangedCostItems: 0,
+    });
+  });
+});
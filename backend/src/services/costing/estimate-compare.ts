@@ -0,0 +1,190 @@
+/**
+ * Comparison of an estimate against a saved baseline.
+ *
+ * Reports the change in every lifetime cost category and per asset, and
+ * attributes it to the library changes (from the library diff) that touch
+ * cost items the estimate uses. Rates and inflation factors are reported
+ * when they changed, since they affect every cost item.
+ */
+
+import { diffCostLibraries, type CostItemChange } from "./library-diff";
+import type { StoredEstimate } from "./estimate-store";
+import type { CostingEstimateResponse, LifetimeCosts } from "./request-types";
+import type { CostLibrary } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CostDelta = {
+  baseline: number;
+  current: number;
+  delta: number;
+  /** Delta as a percentage of the baseline, or null if the baseline is 0 */
+  percent: number | null;
+};
+
+export type CategoryDelta = CostDelta & {
+  /** Lifetime cost category, e.g. "langFactoredCapitalCost.piping" */
+  category: string;
+};
+
+export type AssetDelta = {
+  id: string;
+  name?: string;
+  status: "changed" | "unchanged" | "added" | "removed";
+  total: CostDelta;
+  /** Cost items used by the asset that changed in the library */
+  drivers: string[];
+};
+
+export type EstimateComparison = {
+  baselineId: string;
+  baselineCreatedAt: string;
+  libraryId: string;
+  baselineLibraryHash: string;
+  currentLibraryHash: string;
+  currency: string;
+  total: CostDelta;
+  categories: CategoryDelta[];
+  assets: AssetDelta[];
+  libraryChanges: {
+    identical: boolean;
+    /** Changed cost items the estimate uses, with the fields that changed */
+    costItems: CostItemChange[];
+    /** Cost items used now that the baseline library didn't have */
+    addedCostItems: string[];
+    /** Cost items the baseline used that the current library doesn't have */
+    removedCostItems: string[];
+    currencyRates: string[];
+    inflationFactors: string[];
+    /** Changed cost items the estimate doesn't use */
+    otherChangedCostItems: number;
+  };
+};
+
+// ============================================================================
+// Comparison
+// ============================================================================
+
+export function costDelta(baseline: number, current: number): CostDelta {
+  return {
+    baseline,
+    current,
+    delta: current - baseline,
+    percent: baseline === 0 ? null : ((current - baseline) / baseline) * 100,
+  };
+}
+
+/**
+ * Flatten lifetime costs into dotted category paths.
+ */
+function categoryValues(costs: LifetimeCosts): Map<string, number> {
+  const values = new Map<string, number>();
+  const visit = (value: unknown, path: string) => {
+    if (typeof value === "number") {
+      values.set(path, value);
+    } else if (typeof value === "object" && value !== null) {
+      for (const [key, child] of Object.entries(value)) {
+        visit(child, path ? `${path}.${key}` : key);
+      }
+    }
+  };
+  visit(costs, "");
+  return values;
+}
+
+/**
+ * Compare a re-run estimate with its saved baseline.
+ */
+export function compareEstimates(
+  baseline: StoredEstimate,
+  baselineLibrary: CostLibrary,
+  current: {
+    result: CostingEstimateResponse;
+    library: CostLibrary;
+    libraryHash: string;
+    costItemIds: Record<string, string[]>;
+  },
+): EstimateComparison {
+  const diff = diffCostLibraries(baselineLibrary, current.library);
+  const changedItems = new Map(diff.costItems.changed.map((c) => [c.id, c]));
+  const added = new Set(diff.costItems.added);
+  const removed = new Set(diff.costItems.removed);
+
+  const baselineCosts = categoryValues(baseline.result.lifetimeCosts);
+  const currentCosts = categoryValues(current.result.lifetimeCosts);
+  const categories = [...currentCosts].map(([category, value]) => ({
+    category,
+    ...costDelta(baselineCosts.get(category) ?? 0, value),
+  }));
+
+  const baselineAssets = new Map(baseline.result.assets.map((a) => [a.id, a]));
+  const currentAssets = new Map(current.result.assets.map((a) => [a.id, a]));
+  const assetIds = [
+    ...current.result.assets.map((a) => a.id),
+    ...baseline.result.assets
+      .map((a) => a.id)
+      .filter((id) => !currentAssets.has(id)),
+  ];
+
+  const assets = assetIds.map((id): AssetDelta => {
+    const before = baselineAssets.get(id);
+    const after = currentAssets.get(id);
+    const total = costDelta(
+      before?.lifetimeCosts.total ?? 0,
+      after?.lifetimeCosts.total ?? 0,
+    );
+    const itemIds = new Set([
+      ...(baseline.costItemIds[id] ?? []),
+      ...(current.costItemIds[id] ?? []),
+    ]);
+    return {
+      id,
+      ...((after ?? before)?.name !== undefined && {
+        name: (after ?? before)!.name,
+      }),
+      status: !before
+        ? "added"
+        : !after
+          ? "removed"
+          : total.delta === 0
+            ? "unchanged"
+            : "changed",
+      total,
+      drivers: [...itemIds].filter(
+        (item) =>
+          changedItems.has(item) || added.has(item) || removed.has(item),
+      ),
+    };
+  });
+
+  const usedNow = new Set(Object.values(current.costItemIds).flat());
+  const usedBefore = new Set(Object.values(baseline.costItemIds).flat());
+  const used = new Set([...usedNow, ...usedBefore]);
+  const usedChanges = diff.costItems.changed.filter((c) => used.has(c.id));
+
+  return {
+    baselineId: baseline.id,
+    baselineCreatedAt: baseline.createdAt,
+    libraryId: baseline.libraryId,
+    baselineLibraryHash: baseline.libraryHash,
+    currentLibraryHash: current.libraryHash,
+    currency: current.result.currency,
+    total: costDelta(
+      baseline.result.lifetimeCosts.total,
+      current.result.lifetimeCosts.total,
+    ),
+    categories,
+    assets,
+    libraryChanges: {
+      identical: diff.identical,
+      costItems: usedChanges,
+      addedCostItems: [...usedNow].filter((id) => added.has(id)),
+      removedCostItems: [...usedBefore].filter((id) => removed.has(id)),
+      currencyRates: diff.currencyRates,
+      inflationFactors: diff.inflationFactors,
+      otherChangedCostItems: diff.costItems.changed.length - usedChanges.length,
+    },
+  };
+}
@@ -0,0 +1,130 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import { mkdtemp, readdir, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { transformCostingResponse } from "./adapter";
+import {
+  archiveEstimate,
+  getEstimate,
+  listEstimates,
+  saveEstimate,
+  unarchiveEstimate,
+  EstimateNotFoundError,
+} from "./estimate-store";
+import {
+  pruneEstimates,
+  pruneIntervalFromEnv,
+  retentionPolicyFromEnv,
+} from "./estimate-retention";
+import type { CostingEstimateRequestInput } from "./schemas";
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+function save(label: string) {
+  return saveEstimate({
+    request: { libraryId: "V1.3" } as CostingEstimateRequestInput,
+    result: transformCostingResponse({ assets: [] }, [], "USD"),
+    library: { modules: [] },
+    costingRequest: { assets: [] },
+    label,
+  });
+}
+
+describe("estimate-retention", () => {
+  let dataRoot: string;
+
+  beforeEach(async () => {
+    dataRoot = await mkdtemp(join(tmpdir(), "estimate-retention-"));
+    vi.spyOn(process, "cwd").mockReturnValue(dataRoot);
+  });
+
+  afterEach(async () => {
+    vi.restoreAllMocks();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("hides archived estimates from listings until unarchived", async () => {
+    const kept = await save("kept");
+    const archived = await save("archived");
+
+    const result = await archiveEstimate(archived.id);
+    expect(result.archivedAt).toBeDefined();
+    expect((await archiveEstimate(archived.id)).archivedAt).toBe(
+      result.archivedAt,
+    );
+
+    expect((await listEstimates()).map((e) => e.id)).toEqual([kept.id]);
+    expect(
+      (await listEstimates({ archived: "only" })).map((e) => e.id),
+    ).toEqual([archived.id]);
+    expect(await listEstimates({ archived: "include" })).toHaveLength(2);
+    expect((await getEstimate(archived.id)).archivedAt).toBe(
+      result.archivedAt,
+    );
+
+    expect((await unarchiveEstimate(archived.id)).archivedAt).toBeUndefined();
+    expect(await listEstimates()).toHaveLength(2);
+  });
+
+  it("archives all but the newest estimates", async () => {
+    const first = await save("first");
+    await new Promise((resolve) => setTimeout(resolve, 5));
+    const second = await save("second");
+
+    const pruned = await pruneEstimates({ maxCount: 1 });
+
+    expect(pruned).toEqual({ archived: [first.id], deleted: [] });
+    expect((await listEstimates()).map((e) => e.id)).toEqual([second.id]);
+  });
+
+  it("archives old estimates and purges them after the grace period", async () => {
+    const estimate = await save("old");
+    const later = (days: number) => new Date(Date.now() + days * DAY_MS);
+
+    expect(await pruneEstimates({ maxAgeDays: 30 }, later(10))).toEqual({
+      archived: [],
+      deleted: [],
+    });
+
+    const policy = { maxAgeDays: 30, purgeAfterDays: 7 };
+    expect(await pruneEstimates(policy, later(31))).toEqual({
+      archived: [estimate.id],
+      deleted: [],
+    });
+    expect(await pruneEstimates(policy, later(39))).toEqual({
+      archived: [],
+      deleted: [estimate.id],
+    });
+
+    await expect(getEstimate(estimate.id)).rejects.toBeInstanceOf(
+      EstimateNotFoundError,
+    );
+    // The library snapshot went with the last estimate that used it
+    expect(
+      await readdir(join(dataRoot, "data/estimates/libraries")),
+    ).toEqual([]);
+  });
+
+  it("reads the policy from the environment", () => {
+    expect(retentionPolicyFromEnv({})).toEqual({});
+    expect(
+      retentionPolicyFromEnv({
+        ESTIMATE_RETENTION_DAYS: "90",
+        ESTIMATE_PURGE_DAYS: "30",
+      }),
+    ).toEqual({ maxAgeDays: 90, purgeAfterDays: 30 });
+    expect(() =>
+      retentionPolicyFromEnv({ ESTIMATE_RETENTION_COUNT: "-1" }),
+    ).toThrow("ESTIMATE_RETENTION_COUNT must be a non-negative integer");
+  });
+
+  it("reads a positive prune interval from the environment", () => {
+    expect(pruneIntervalFromEnv({})).toBe(3_600_000);
+    expect(
+      pruneIntervalFromEnv({ ESTIMATE_PRUNE_INTERVAL_MINUTES: "5" }),
+    ).toBe(300_000);
+    expect(() =>
+      pruneIntervalFromEnv({ ESTIMATE_PRUNE_INTERVAL_MINUTES: "0" }),
+    ).toThrow(/positive number of minutes/);
+  });
+});
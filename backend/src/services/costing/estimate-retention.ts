@@ -0,0 +1,180 @@
+/**
+ * Retention of saved estimates.
+ *
+ * Exploratory runs pile up on a shared server. A retention policy archives
+ * estimates once they are older than a maximum age or fall outside the most
+ * recent N, and purges archived estimates after a grace period, whether
+ * they were archived by the policy or by hand. Pruning runs in the
+ * background at a fixed interval.
+ */
+
+import {
+  archiveEstimate,
+  deleteEstimates,
+  readEstimateSummaries,
+} from "./estimate-store";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type RetentionPolicy = {
+  /** Archive estimates created more than this many days ago */
+  maxAgeDays?: number;
+  /** Archive all but this many of the most recent unarchived estimates */
+  maxCount?: number;
+  /** Delete estimates archived more than this many days ago */
+  purgeAfterDays?: number;
+};
+
+export type PruneResult = {
+  /** IDs of estimates archived by this run */
+  archived: string[];
+  /** IDs of estimates deleted by this run */
+  deleted: string[];
+};
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+// ============================================================================
+// Policy
+// ============================================================================
+
+/**
+ * Read the policy from ESTIMATE_RETENTION_DAYS, ESTIMATE_RETENTION_COUNT and
+ * ESTIMATE_PURGE_DAYS. Unset variables leave that rule off.
+ *
+ * @throws Error for values that aren't non-negative integers
+ */
+export function retentionPolicyFromEnv(
+  env: Record<string, string | undefined>,
+): RetentionPolicy {
+  const read = (name: string): number | undefined => {
+    const value = env[name];
+    if (value === undefined || value === "") return undefined;
+    if (!/^\d+$/.test(value)) {
+      throw new Error(`${name} must be a non-negative integer`);
+    }
+    return parseInt(value);
+  };
+
+  const policy: RetentionPolicy = {};
+  const maxAgeDays = read("ESTIMATE_RETENTION_DAYS");
+  const maxCount = read("ESTIMATE_RETENTION_COUNT");
+  const purgeAfterDays = read("ESTIMATE_PURGE_DAYS");
+  if (maxAgeDays !== undefined) policy.maxAgeDays = maxAgeDays;
+  if (maxCount !== undefined) policy.maxCount = maxCount;
+  if (purgeAfterDays !== undefined) policy.purgeAfterDays = purgeAfterDays;
+  return policy;
+}
+
+/**
+ * Read how often to prune, in milliseconds, from
+ * ESTIMATE_PRUNE_INTERVAL_MINUTES (default 60).
+ *
+ * @throws Error unless it is a positive whole number of minutes
+ */
+export function pruneIntervalFromEnv(
+  env: Record<string, string | undefined>,
+): number {
+  const value = env.ESTIMATE_PRUNE_INTERVAL_MINUTES;
+  if (value === undefined || value === "") return 60 * 60_000;
+  if (!/^\d+$/.test(value) || parseInt(value) === 0) {
+    throw new Error(
+      "ESTIMATE_PRUNE_INTERVAL_MINUTES must be a positive number of minutes",
+    );
+  }
+  return parseInt(value) * 60_000;
+}
+
+export function isRetentionEnabled(policy: RetentionPolicy): boolean {
+  return (
+    policy.maxAgeDays !== undefined ||
+    policy.maxCount !== undefined ||
+    policy.purgeAfterDays !== undefined
+  );
+}
+
+// ============================================================================
+// Pruning
+// ============================================================================
+
+/**
+ * Apply the policy once: archive estimates outside it, then delete archived
+ * estimates past the purge period.
+ */
+export async function pruneEstimates(
+  policy: RetentionPolicy,
+  now: Date = new Date(),
+): Promise<PruneResult> {
+  const daysSince = (iso: string) => (now.getTime() - Date.parse(iso)) / DAY_MS;
+  const estimates = await readEstimateSummaries();
+  const active = estimates
+    .filter((e) => e.archivedAt === undefined)
+    .sort((a, b) => (a.createdAt < b.createdAt ? 1 : -1));
+
+  const toArchive = active.filter(
+    (e, index) =>
+      (policy.maxAgeDays !== undefined &&
+        daysSince(e.createdAt) > policy.maxAgeDays) ||
+      (policy.maxCount !== undefined && index >= policy.maxCount),
+  );
+  for (const estimate of toArchive) {
+    await archiveEstimate(estimate.id, now);
+  }
+
+  const purgeAfterDays = policy.purgeAfterDays;
+  const toDelete =
+    purgeAfterDays === undefined
+      ? []
+      : estimates.filter(
+          (e) =>
+            e.archivedAt !== undefined &&
+            daysSince(e.archivedAt) > purgeAfterDays,
+        );
+  if (toDelete.length > 0) {
+    await deleteEstimates(toDelete.map((e) => e.id));
+  }
+
+  return {
+    archived: toArchive.map((e) => e.id),
+    deleted: toDelete.map((e) => e.id),
+  };
+}
+
+/**
+ * Prune every `intervalMs` while the process runs. Returns undefined when the
+ * policy has no rules.
+ */
+export function startEstimatePruning(
+  policy: RetentionPolicy,
+  intervalMs: number,
+): NodeJS.Timeout | undefined {
+  if (!isRetentionEnabled(policy)) return undefined;
+
+  // A prune reads every estimate, so skip a tick while one is running
+  let running = false;
+  const prune = () => {
+    if (running) return;
+    running = true;
+    pruneEstimates(policy)
+      .then(({ archived, deleted }) => {
+        if (archived.length > 0 || deleted.length > 0) {
+          console.log(
+            `Estimate retention: archived ${archived.length}, ` +
+              `deleted ${deleted.length}`,
+          );
+        }
+      })
+      .catch((err) => console.error("Estimate pruning failed:", err))
+      .finally(() => {
+        running = false;
+      });
+  };
+
+  prune();
+  const timer = setInterval(prune, intervalMs);
+  // Don't keep the process alive just to prune
+  timer.unref();
+  return timer;
+}
@@ -0,0 +1,390 @@
+/**
+ * Saved estimates.
+ *
+ * An estimate saved with `save: true` keeps its request, its result, who ran
+ * it, and a snapshot of the cost library it was computed with, so it can be
+ * re-run later as a baseline and library changes since then can be
+ * identified. Library snapshots are stored once per content hash.
+ *
+ * The costing server request and raw response are kept too, so what-if
+ * overlays can re-aggregate the stored cost item results without re-running
+ * curve evaluation.
+ *
+ * Archiving is a soft delete: an archived estimate is left out of listings
+ * but can still be fetched and unarchived until retention purges it.
+ *
+ * A full record carries the costing server request and response, so each
+ * estimate also has a small summary record. Listings and retention read
+ * only the summaries; full records are read one at a time, by ID.
+ */
+
+import { randomUUID } from "crypto";
+import { getStorage, StorageConflictError } from "../storage";
+import { hashCostLibrary } from "./module-lookup";
+import type { AssetMetadata } from "./adapter";
+import type { CostingEstimateResponse } from "./request-types";
+import type { CostingEstimateRequestInput } from "./schemas";
+import type {
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostLibrary,
+} from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type StoredEstimate = {
+  id: string;
+  /** ISO timestamp */
+  createdAt: string;
+  /** Principal ID of the user who ran the estimate */
+  createdBy?: string;
+  label?: string;
+  libraryId: string;
+  /** Content hash of the library snapshot the estimate was computed with */
+  libraryHash: string;
+  request: CostingEstimateRequestInput;
+  result: CostingEstimateResponse;
+  /** Library cost item IDs used by each asset */
+  costItemIds: Record<string, string[]>;
+  /** Costing server inputs and outputs (absent for older saves) */
+  intermediate?: EstimateIntermediate;
+  /** Project, scenario, author and tags the estimate was saved with */
+  labels?: EstimateLabels;
+  /** ISO timestamp the estimate was archived at (if archived) */
+  archivedAt?: string;
+};
+
+export type EstimateLabels = {
+  project?: string;
+  scenario?: string;
+  author?: string;
+  tags?: string[];
+};
+
+export type EstimateIntermediate = {
+  costingRequest: CostEstimateRequest;
+  costingResponse: CostEstimateResponse;
+  assetMetadata: AssetMetadata[];
+};
+
+export type StoredEstimateSummary = Pick<
+  StoredEstimate,
+  | "id"
+  | "createdAt"
+  | "createdBy"
+  | "label"
+  | "libraryId"
+  | "libraryHash"
+  | "labels"
+  | "archivedAt"
+> & {
+  currency: string;
+  total: number;
+};
+
+export const ESTIMATE_SORTS = [
+  "-createdAt",
+  "createdAt",
+  "-total",
+  "total",
+] as const;
+
+/** Sort field, descending when prefixed with "-" */
+export type EstimateSort = (typeof ESTIMATE_SORTS)[number];
+
+export type EstimateSearch = {
+  libraryId?: string;
+  createdBy?: string;
+  archived?: "exclude" | "include" | "only";
+  /**
+   * Text matched, ignoring case, against the label, project, scenario,
+   * author and tags
+   */
+  label?: string;
+  project?: string;
+  scenario?: string;
+  /** Only estimates carrying every one of these tags */
+  tags?: string[];
+  /** ISO date or timestamp; estimates created at or after it */
+  from?: string;
+  /** ISO date or timestamp; estimates created before it */
+  to?: string;
+  /** Default: newest first */
+  sort?: EstimateSort;
+};
+
+export class EstimateNotFoundError extends Error {
+  constructor(readonly estimateId: string) {
+    super(`Estimate ${estimateId} not found`);
+    this.name = "EstimateNotFoundError";
+  }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+const ESTIMATES = "estimates";
+/** Listing summaries of the estimates, keyed by estimate ID */
+const SUMMARIES = "estimates/summaries";
+/** Library snapshots, keyed by content hash */
+const LIBRARY_SNAPSHOTS = "estimates/libraries";
+
+// Saves and snapshot clean-up are serialized, so a clean-up can't remove the
+// snapshot of an estimate that is being saved
+let snapshotQueue: Promise<unknown> = Promise.resolve();
+
+function withSnapshots<T>(task: () => Promise<T>): Promise<T> {
+  const result = snapshotQueue.then(task);
+  snapshotQueue = result.catch(() => undefined);
+  return result;
+}
+
+function estimateKey(id: string): string {
+  // IDs are UUIDs; anything else can't name a stored estimate
+  if (!/^[\w-]+$/.test(id)) throw new EstimateNotFoundError(id);
+  return id;
+}
+
+/**
+ * Library cost item IDs used by each asset in a costing server request.
+ */
+export function costItemIdsByAsset(
+  request: CostEstimateRequest,
+): Record<string, string[]> {
+  return Object.fromEntries(
+    request.assets.map((asset) => [
+      asset.id,
+      [...new Set(asset.cost_items.map((item) => item.ref))],
+    ]),
+  );
+}
+
+/**
+ * Save an estimate and a snapshot of the library it was computed with.
+ */
+export async function saveEstimate(estimate: {
+  request: CostingEstimateRequestInput;
+  result: CostingEstimateResponse;
+  library: CostLibrary;
+  costingRequest: CostEstimateRequest;
+  costingResponse?: CostEstimateResponse;
+  assetMetadata?: AssetMetadata[];
+  createdBy?: string;
+  label?: string;
+  labels?: EstimateLabels;
+}): Promise<StoredEstimate> {
+  const libraryHash = hashCostLibrary(estimate.library);
+  const storage = getStorage();
+
+  const stored: StoredEstimate = {
+    id: randomUUID(),
+    createdAt: new Date().toISOString(),
+    ...(estimate.createdBy !== undefined && { createdBy: estimate.createdBy }),
+    ...(estimate.label !== undefined && { label: estimate.label }),
+    ...(estimate.labels !== undefined && { labels: estimate.labels }),
+    libraryId: estimate.request.libraryId,
+    libraryHash,
+    request: estimate.request,
+    result: estimate.result,
+    costItemIds: costItemIdsByAsset(estimate.costingRequest),
+    ...(estimate.costingResponse && {
+      intermediate: {
+        costingRequest: estimate.costingRequest,
+        costingResponse: estimate.costingResponse,
+        assetMetadata: estimate.assetMetadata ?? [],
+      },
+    }),
+  };
+  await withSnapshots(async () => {
+    try {
+      await storage.put(LIBRARY_SNAPSHOTS, libraryHash, estimate.library, {
+        create: true,
+      });
+    } catch (error) {
+      // Already snapshotted by an earlier estimate
+      if (!(error instanceof StorageConflictError)) throw error;
+    }
+    await storage.put(ESTIMATES, stored.id, stored, { create: true });
+    await storage.put(SUMMARIES, stored.id, summarize(stored));
+  });
+  return stored;
+}
+
+function summarize(e: StoredEstimate): StoredEstimateSummary {
+  return {
+    id: e.id,
+    createdAt: e.createdAt,
+    ...(e.createdBy !== undefined && { createdBy: e.createdBy }),
+    ...(e.label !== undefined && { label: e.label }),
+    ...(e.labels !== undefined && { labels: e.labels }),
+    libraryId: e.libraryId,
+    libraryHash: e.libraryHash,
+    ...(e.archivedAt !== undefined && { archivedAt: e.archivedAt }),
+    currency: e.result.currency,
+    total: e.result.lifetimeCosts.total,
+  };
+}
+
+/** Replace an estimate's record and its summary */
+async function updateEstimate(estimate: StoredEstimate): Promise<void> {
+  const storage = getStorage();
+  await storage.put(ESTIMATES, estimate.id, estimate);
+  await storage.put(SUMMARIES, estimate.id, summarize(estimate));
+}
+
+export async function getEstimate(id: string): Promise<StoredEstimate> {
+  const estimate = await getStorage().get<StoredEstimate>(
+    ESTIMATES,
+    estimateKey(id),
+  );
+  if (!estimate) throw new EstimateNotFoundError(id);
+  return estimate;
+}
+
+/**
+ * The library snapshot a stored estimate was computed with.
+ */
+export async function getEstimateLibrary(
+  estimate: StoredEstimate,
+): Promise<CostLibrary> {
+  const library = await getStorage().get<CostLibrary>(
+    LIBRARY_SNAPSHOTS,
+    estimate.libraryHash,
+  );
+  if (!library) {
+    throw new Error(`Library snapshot ${estimate.libraryHash} is missing`);
+  }
+  return library;
+}
+
+/**
+ * Summaries of all saved estimates, archived or not, in no particular
+ * order. An estimate without a summary (saved before summaries were kept,
+ * or interrupted while saving) is read in full once and given one.
+ */
+export async function readEstimateSummaries(): Promise<
+  StoredEstimateSummary[]
+> {
+  const storage = getStorage();
+  const ids = await storage.keys(ESTIMATES);
+  return Promise.all(
+    ids.map(async (id) => {
+      const summary = await storage.get<StoredEstimateSummary>(SUMMARIES, id);
+      if (summary) return summary;
+      const backfilled = summarize(await getEstimate(id));
+      await storage.put(SUMMARIES, id, backfilled);
+      return backfilled;
+    }),
+  );
+}
+
+/**
+ * Summaries of the saved estimates matching a search, newest first unless
+ * sorted otherwise. Archived estimates are left out unless `archived` is
+ * "include" or "only".
+ */
+export async function listEstimates(
+  search: EstimateSearch = {},
+): Promise<StoredEstimateSummary[]> {
+  const summaries = (await readEstimateSummaries()).filter((e) =>
+    matchesSearch(e, search),
+  );
+
+  const sort = search.sort ?? "-createdAt";
+  const direction = sort.startsWith("-") ? -1 : 1;
+  const compare = (a: StoredEstimateSummary, b: StoredEstimateSummary) =>
+    sort.endsWith("total")
+      ? a.total - b.total
+      : a.createdAt < b.createdAt
+        ? -1
+        : a.createdAt > b.createdAt
+          ? 1
+          : 0;
+  return summaries.sort((a, b) => direction * compare(a, b));
+}
+
+function matchesSearch(
+  e: StoredEstimateSummary,
+  search: EstimateSearch,
+): boolean {
+  const archived = search.archived ?? "exclude";
+  const labels = e.labels ?? {};
+  const text = search.label?.toLowerCase();
+  const searchable = [
+    e.label,
+    labels.project,
+    labels.scenario,
+    labels.author,
+    ...(labels.tags ?? []),
+  ];
+
+  return (
+    (search.libraryId === undefined || e.libraryId === search.libraryId) &&
+    (search.createdBy === undefined || e.createdBy === search.createdBy) &&
+    (archived === "include" ||
+      (archived === "only") === (e.archivedAt !== undefined)) &&
+    (text === undefined ||
+      searchable.some((value) => value?.toLowerCase().includes(text))) &&
+    (search.project === undefined || labels.project === search.project) &&
+    (search.scenario === undefined || labels.scenario === search.scenario) &&
+    (search.tags ?? []).every((tag) => labels.tags?.includes(tag)) &&
+    // Compared as instants: dates and offsets don't sort as strings
+    (search.from === undefined ||
+      Date.parse(e.createdAt) >= Date.parse(search.from)) &&
+    (search.to === undefined ||
+      Date.parse(e.createdAt) < Date.parse(search.to))
+  );
+}
+
+// ============================================================================
+// Archiving and deletion
+// ============================================================================
+
+/**
+ * Archive an estimate. Archiving an archived estimate keeps its original
+ * archive time.
+ */
+export async function archiveEstimate(
+  id: string,
+  now: Date = new Date(),
+): Promise<StoredEstimate> {
+  const estimate = await getEstimate(id);
+  if (estimate.archivedAt !== undefined) return estimate;
+
+  const archived = { ...estimate, archivedAt: now.toISOString() };
+  await updateEstimate(archived);
+  return archived;
+}
+
+export async function unarchiveEstimate(id: string): Promise<StoredEstimate> {
+  const { archivedAt, ...estimate } = await getEstimate(id);
+  if (archivedAt !== undefined) {
+    await updateEstimate(estimate);
+  }
+  return estimate;
+}
+
+/**
+ * Permanently delete estimates, then the library snapshots no remaining
+ * estimate refers to.
+ */
+export async function deleteEstimates(ids: string[]): Promise<void> {
+  const storage = getStorage();
+  for (const id of ids) {
+    await storage.delete(ESTIMATES, estimateKey(id));
+    await storage.delete(SUMMARIES, id);
+  }
+
+  await withSnapshots(async () => {
+    const estimates = await readEstimateSummaries();
+    const referenced = new Set(estimates.map((e) => e.libraryHash));
+    for (const hash of await storage.keys(LIBRARY_SNAPSHOTS)) {
+      if (!referenced.has(hash)) {
+        await storage.delete(LIBRARY_SNAPSHOTS, hash);
+      }
+    }
+  });
+}
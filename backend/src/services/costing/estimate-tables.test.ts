@@ -0,0 +1,144 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import {
+  estimateTable,
+  tableToHtml,
+  tableToParquet,
+  tableToTsv,
+  EstimateTableError,
+} from "./estimate-tables";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockCostItemPeriodCosts,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { AssetMetadata } from "./adapter";
+import type { StoredEstimate } from "./estimate-store";
+import type { CostEstimateRequest, CostEstimateResponse } from "./types";
+
+const costingResponse: CostEstimateResponse = {
+  assets: [
+    mockAssetEstimate(
+      "capture",
+      [
+        mockYearCosts(2025, { total_installed_cost: 1000.456 }),
+        mockYearCosts(
+          2026,
+          { fixed_opex_cost: { maintenance: 110 } },
+          { fixed_opex_cost: { maintenance: 100 } },
+        ),
+      ],
+      [
+        {
+          ...mockCostItem("b/blocks/0/Item 023", {
+            direct_equipment_cost: 400.25,
+            total_installed_cost: 1000.456,
+          }),
+          costs_by_year: [
+            {
+              year: 2025,
+              costs_in_year: mockCostItemPeriodCosts({
+                direct_equipment_cost: 400.25,
+                total_installed_cost: 1000.456,
+              }),
+              dcf_costs_in_year: mockCostItemPeriodCosts(),
+            },
+            {
+              year: 2026,
+              costs_in_year: mockCostItemPeriodCosts({
+                variable_opex_cost: { electrical_power: 12.6 },
+              }),
+              dcf_costs_in_year: mockCostItemPeriodCosts(),
+            },
+          ],
+        },
+      ],
+    ),
+  ],
+};
+
+const assetMetadata = [
+  { assetId: "capture", name: "Capture <A&B>", usingDefaults: [] },
+] as unknown as AssetMetadata[];
+
+function stored(withIntermediate = true): StoredEstimate {
+  return {
+    id: "e1",
+    createdAt: "2026-01-01T00:00:00.000Z",
+    libraryId: "V1.1_working",
+    libraryHash: "hash",
+    request: {
+      rounding: { mode: "decimals", digits: 0 },
+    } as StoredEstimate["request"],
+    result: transformCostingResponse(costingResponse, assetMetadata, "USD"),
+    costItemIds: {},
+    ...(withIntermediate && {
+      intermediate: {
+        costingRequest: { assets: [] } as CostEstimateRequest,
+        costingResponse,
+        assetMetadata,
+      },
+    }),
+  };
+}
+
+describe("estimate-tables", () => {
+  it("summarises assets and the network with rounded amounts", () => {
+    const table = estimateTable(stored(), "summary");
+    expect(table.columns[1]).toBe("Total installed cost (USD)");
+    expect(table.rows).toEqual([
+      ["Capture <A&B>", 1000, 1110, 1100],
+      ["Network", 1000, 1110, 1100],
+    ]);
+  });
+
+  it("lists yearly cash flows and cost items", () => {
+    expect(estimateTable(stored(), "by-year").rows).toEqual([
+      [2025, 1000, 0, 0, 1000, 1000],
+      [2026, 0, 110, 0, 110, 100],
+    ]);
+    expect(estimateTable(stored(), "by-item").rows).toEqual([
+      ["Capture <A&B>", "b/blocks/0/Item 023", 1, 400, 1000],
+    ]);
+    expect(() => estimateTable(stored(false), "by-year")).toThrow(
+      EstimateTableError,
+    );
+  });
+
+  it("flattens cost items to one row per year and category", () => {
+    const row = (year: number, category: string, value: number) => [
+      "Capture <A&B>",
+      "b/blocks/0/Item 023",
+      year,
+      category,
+      value,
+    ];
+    expect(estimateTable(stored(), "by-item-year").rows).toEqual([
+      row(2025, "direct_equipment_cost", 400),
+      row(2025, "total_installed_cost", 1000),
+      row(2026, "variable_opex.electrical_power", 13),
+    ]);
+    expect(() => estimateTable(stored(false), "by-item-year")).toThrow(
+      EstimateTableError,
+    );
+  });
+
+  it("renders TSV and escaped HTML", () => {
+    const table = { columns: ["Asset", "Cost"], rows: [["A\tB", 1.5]] };
+    expect(tableToTsv(table)).toBe("Asset\tCost\r\nA B\t1.5\r\n");
+
+    const html = tableToHtml(estimateTable(stored(), "summary"));
+    expect(html).toContain("<td>Capture &lt;A&amp;B&gt;</td>");
+    expect(html).toContain('<td style="text-align:right">1110</td>');
+  });
+
+  it("renders Parquet with the table's columns", () => {
+    const parquet = tableToParquet(
+      estimateTable(stored(), "by-item-year"),
+      "by-item-year",
+    );
+    expect(parquet.subarray(0, 4).toString()).toBe("PAR1");
+    expect(parquet.includes(Buffer.from("Value (USD)"))).toBe(true);
+  });
+});
@@ -0,0 +1,297 @@
+/**
+ * Saved estimates as tables for pasting into spreadsheets and documents.
+ *
+ * Tables are rendered as TSV (which Excel splits into cells on paste), as
+ * an HTML table fragment (which Word and Excel both keep as a table), or as
+ * Parquet for loading into pandas or DuckDB with typed columns.
+ * Amounts are plain numbers rounded with the estimate's rounding policy, so
+ * spreadsheets read them as numbers rather than text.
+ */
+
+import { buildCashflow } from "./cashflow";
+import { applyCashflowRounding, roundValue } from "./rounding";
+import { groupByCbsCode } from "./cbs-coding";
+import { writeParquet, type ParquetType } from "../../utils/parquet";
+import type { StoredEstimate } from "./estimate-store";
+import type { CostItemPeriodCosts } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const ESTIMATE_TABLES = [
+  "summary",
+  "by-year",
+  "by-item",
+  "by-cbs",
+  "by-item-year",
+] as const;
+
+export type EstimateTableName = (typeof ESTIMATE_TABLES)[number];
+
+/** Column types of each table, for formats that keep them */
+const COLUMN_TYPES: Record<EstimateTableName, ParquetType[]> = {
+  summary: ["string", "double", "double", "double"],
+  "by-year": ["int32", "double", "double", "double", "double", "double"],
+  "by-item": ["string", "string", "double", "double", "double"],
+  "by-cbs": ["string", "int32", "double", "double"],
+  "by-item-year": ["string", "string", "int32", "string", "double"],
+};
+
+export type EstimateTable = {
+  columns: string[];
+  rows: Array<Array<string | number>>;
+};
+
+export class EstimateTableError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "EstimateTableError";
+  }
+}
+
+export function isEstimateTableName(name: string): name is EstimateTableName {
+  return (ESTIMATE_TABLES as readonly string[]).includes(name);
+}
+
+// ============================================================================
+// Tables
+// ============================================================================
+
+/**
+ * Build one of a saved estimate's tables.
+ *
+ * @throws EstimateTableError for the by-year and by-item-year tables of an
+ *   estimate saved without its yearly results
+ */
+export function estimateTable(
+  estimate: StoredEstimate,
+  name: EstimateTableName,
+): EstimateTable {
+  const { result } = estimate;
+  const rounding = estimate.request.rounding;
+  const amount = (value: number) =>
+    rounding ? roundValue(value, rounding) : value;
+  // Per-item values stay at full precision under a totals-only policy
+  const itemAmount = (value: number) =>
+    rounding?.totalsOnly ? value : amount(value);
+  const currency = result.currency;
+
+  switch (name) {
+    case "summary":
+      return {
+        columns: [
+          "Asset",
+          `Total installed cost (${currency})`,
+          `Lifetime cost (${currency})`,
+          `Lifetime NPC (${currency})`,
+        ],
+        rows: [
+          ...result.assets.map((asset) => [
+            asset.name ?? asset.id,
+            amount(asset.lifetimeCosts.totalInstalledCost),
+            amount(asset.lifetimeCosts.total),
+            amount(asset.lifetimeNpcCosts.total),
+          ]),
+          [
+            "Network",
+            amount(result.lifetimeCosts.totalInstalledCost),
+            amount(result.lifetimeCosts.total),
+            amount(result.lifetimeNpcCosts.total),
+          ],
+        ],
+      };
+
+    case "by-year": {
+      const intermediate = yearlyResults(estimate);
+      const cashflow = buildCashflow(
+        intermediate.costingResponse,
+        intermediate.assetMetadata,
+        currency,
+        { includeContingency: estimate.request.includeContingencyInTotals },
+      );
+      const { portfolio } = rounding
+        ? applyCashflowRounding(cashflow, rounding)
+        : cashflow;
+      return {
+        columns: [
+          "Year",
+          `CAPEX (${currency})`,
+          `OPEX (${currency})`,
+          `Decommissioning (${currency})`,
+          `Total (${currency})`,
+          `Discounted total (${currency})`,
+        ],
+        rows: portfolio.years.map((year, i) => [
+          year,
+          portfolio.capex[i],
+          portfolio.opex[i],
+          portfolio.decommissioning[i],
+          portfolio.total[i],
+          portfolio.dcfTotal[i],
+        ]),
+      };
+    }
+
+    case "by-item":
+      return {
+        columns: [
+          "Asset",
+          "Cost item",
+          "Quantity",
+          `Direct equipment cost (${currency})`,
+          `Total installed cost (${currency})`,
+        ],
+        rows: result.assets.flatMap((asset) =>
+          asset.blocks.map((block) => [
+            asset.name ?? asset.id,
+            block.id,
+            block.quantity,
+            itemAmount(block.directEquipmentCost),
+            itemAmount(block.totalInstalledCost),
+          ]),
+        ),
+      };
+
+    case "by-item-year": {
+      const { costingResponse } = yearlyResults(estimate);
+      const assetNames = new Map(
+        result.assets.map((asset) => [asset.id, asset.name ?? asset.id]),
+      );
+      return {
+        columns: [
+          "Asset",
+          "Cost item",
+          "Year",
+          "Category",
+          `Value (${currency})`,
+        ],
+        rows: costingResponse.assets.flatMap((asset) =>
+          asset.cost_items.flatMap((item) =>
+            item.costs_by_year.flatMap(({ year, costs_in_year }) =>
+              itemYearValues(costs_in_year).map(([category, value]) => [
+                assetNames.get(asset.id) ?? asset.id,
+                item.id,
+                year,
+                category,
+                itemAmount(value),
+              ]),
+            ),
+          ),
+        ),
+      };
+    }
+
+    case "by-cbs":
+      return {
+        columns: [
+          "CBS code",
+          "Cost lines",
+          `Direct equipment cost (${currency})`,
+          `Total installed cost (${currency})`,
+        ],
+        rows: groupByCbsCode(result).map((group) => [
+          group.code ?? "Uncoded",
+          group.lineCount,
+          amount(group.directEquipmentCost),
+          amount(group.totalInstalledCost),
+        ]),
+      };
+  }
+}
+
+function yearlyResults(
+  estimate: StoredEstimate,
+): NonNullable<StoredEstimate["intermediate"]> {
+  if (!estimate.intermediate) {
+    throw new EstimateTableError(
+      `Estimate ${estimate.id} was saved without its yearly results`,
+    );
+  }
+  return estimate.intermediate;
+}
+
+/**
+ * A cost item's non-zero costs in one year as (category, value) pairs.
+ * Variable OPEX categories are prefixed with "variable_opex.".
+ */
+function itemYearValues(costs: CostItemPeriodCosts): Array<[string, number]> {
+  return [
+    ["direct_equipment_cost", costs.direct_equipment_cost ?? 0],
+    ["total_installed_cost", costs.total_installed_cost ?? 0],
+    ...Object.entries(costs.variable_opex_cost).map(
+      ([key, value]): [string, number] => [`variable_opex.${key}`, value],
+    ),
+  ].filter((entry): entry is [string, number] => entry[1] !== 0);
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/**
+ * Render a table as tab-separated values with a header row. Tabs and line
+ * breaks inside cells are replaced with spaces.
+ */
+export function tableToTsv(table: EstimateTable): string {
+  const cell = (value: string | number) =>
+    String(value).replace(/[\t\r\n]+/g, " ");
+  return (
+    [table.columns, ...table.rows]
+      .map((row) => row.map(cell).join("\t"))
+      .join("\r\n") + "\r\n"
+  );
+}
+
+function escapeHtml(text: string): string {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;");
+}
+
+/**
+ * Render a table as an HTML `<table>` fragment.
+ */
+export function tableToHtml(table: EstimateTable): string {
+  const header = table.columns
+    .map((column) => `<th>${escapeHtml(column)}</th>`)
+    .join("");
+  const rows = table.rows.map(
+    (row) =>
+      "<tr>" +
+      row
+        .map((value) =>
+          typeof value === "number"
+            ? `<td style="text-align:right">${value}</td>`
+            : `<td>${escapeHtml(value)}</td>`,
+        )
+        .join("") +
+      "</tr>",
+  );
+  return [
+    "<table>",
+    `<thead><tr>${header}</tr></thead>`,
+    `<tbody>${rows.join("")}</tbody>`,
+    "</table>",
+  ].join("\n");
+}
+
+/**
+ * Render one of a saved estimate's tables as a Parquet file, with integer
+ * columns (years and counts) as int32, amounts as doubles and the rest as
+ * UTF-8 strings.
+ */
+export function tableToParquet(
+  table: EstimateTable,
+  name: EstimateTableName,
+): Buffer {
+  return writeParquet(
+    table.columns.map((column, i) => ({
+      name: column,
+      type: COLUMN_TYPES[name][i],
+    })),
+    table.rows,
+  );
+}
@@ -0,0 +1,61 @@
+import { describe, it, expect } from "vitest";
+import { explainTotalInstalledCost } from "./explain";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+describe("explain", () => {
+  // DEC 100, Lang components 150 of which contingency 40, TIC item 30:
+  // TIC = 100 + 150 - 40 + 30 = 240
+  const asset = mockAssetEstimate(
+    "asset-1",
+    [
+      mockYearCosts(2030, {
+        direct_equipment_cost: 100,
+        total_installed_cost: 240,
+        lang_factored_capital_cost: {
+          equipment_erection: 40,
+          piping: 70,
+          contingency: 40,
+        },
+      }),
+    ],
+    [
+      mockCostItem("Item 001", { direct_equipment_cost: 100 }),
+      mockCostItem("Item 002", { total_installed_cost: 30 }),
+    ],
+  );
+
+  it("breaks total installed cost into its terms", () => {
+    const explanation = explainTotalInstalledCost(asset);
+
+    expect(explanation).toMatchObject({
+      directEquipmentCost: 100,
+      langFactoredTotal: 150,
+      contingency: 40,
+      ticItemsTotal: 30,
+      ticItemIds: ["Item 002"],
+      totalInstalledCost: 240,
+      residual: 0,
+    });
+    expect(explanation.terms).toContainEqual({
+      label: "Piping (Lang factor)",
+      amount: 70,
+    });
+    expect(explanation.terms).toContainEqual({
+      label: "Contingency excluded from TIC",
+      amount: -40,
+    });
+  });
+
+  it("reports a residual when the terms don't add up", () => {
+    const explanation = explainTotalInstalledCost({
+      ...asset,
+      lifetime_costs: { ...asset.lifetime_costs, total_installed_cost: 250 },
+    });
+
+    expect(explanation.residual).toBe(10);
+  });
+});
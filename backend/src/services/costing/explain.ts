@@ -0,0 +1,98 @@
+/**
+ * Explanation of how an asset's total installed cost was derived.
+ *
+ * The costing server computes total installed cost as the direct equipment
+ * cost plus every Lang-factored component, minus the Lang contingency (which
+ * is reported separately), plus the cost items that are already priced as
+ * installed (TIC items, which are not Lang-factored). This spells out each
+ * term so totals can be reconciled against spreadsheets.
+ */
+
+import type { AssetCostEstimate, LangFactoredCostEstimate } from "./types";
+
+export type TicExplanationTerm = {
+  /** What the term is, e.g. "Piping (Lang factor)" */
+  label: string;
+  /** Signed contribution to total installed cost */
+  amount: number;
+};
+
+export type TicExplanation = {
+  directEquipmentCost: number;
+  /** Sum of all Lang-factored components, including contingency */
+  langFactoredTotal: number;
+  /** Subtracted: contingency is excluded from total installed cost */
+  contingency: number;
+  /** Sum of cost items priced as installed (not Lang-factored) */
+  ticItemsTotal: number;
+  /** IDs of the cost items priced as installed */
+  ticItemIds: string[];
+  /** The derivation as ordered, signed terms, one per Lang component */
+  terms: TicExplanationTerm[];
+  /** Total installed cost as reported by the costing server */
+  totalInstalledCost: number;
+  /** Reported minus explained; zero unless the server used another formula */
+  residual: number;
+};
+
+const COMPONENT_LABELS: Record<keyof LangFactoredCostEstimate, string> = {
+  equipment_erection: "Equipment erection",
+  piping: "Piping",
+  instrumentation: "Instrumentation",
+  electrical: "Electrical",
+  buildings_and_process: "Buildings and process",
+  utilities: "Utilities",
+  storages: "Storages",
+  site_development: "Site development",
+  ancillary_buildings: "Ancillary buildings",
+  design_and_engineering: "Design and engineering",
+  contractors_fee: "Contractors fee",
+  contingency: "Contingency",
+};
+
+/**
+ * Explain an asset's lifetime (undiscounted) total installed cost from the
+ * raw costing server response. Values are unrounded so the terms add up
+ * exactly.
+ */
+export function explainTotalInstalledCost(
+  asset: AssetCostEstimate,
+): TicExplanation {
+  const costs = asset.lifetime_costs;
+  const lang = costs.lang_factored_capital_cost;
+  const componentKeys = Object.keys(COMPONENT_LABELS) as Array<
+    keyof LangFactoredCostEstimate
+  >;
+
+  const ticItems = asset.cost_items.filter(
+    (item) =>
+      item.lifetime_costs.direct_equipment_cost === null &&
+      item.lifetime_costs.total_installed_cost !== null,
+  );
+  const ticItemsTotal = ticItems.reduce(
+    (sum, item) => sum + (item.lifetime_costs.total_installed_cost ?? 0),
+    0,
+  );
+
+  const terms: TicExplanationTerm[] = [
+    { label: "Direct equipment cost", amount: costs.direct_equipment_cost },
+    ...componentKeys.map((key) => ({
+      label: `${COMPONENT_LABELS[key]} (Lang factor)`,
+      amount: lang[key],
+    })),
+    { label: "Contingency excluded from TIC", amount: -lang.contingency },
+    { label: "Cost items priced as installed", amount: ticItemsTotal },
+  ];
+  const explained = terms.reduce((sum, term) => sum + term.amount, 0);
+
+  return {
+    directEquipmentCost: costs.direct_equipment_cost,
+    langFactoredTotal: componentKeys.reduce((sum, key) => sum + lang[key], 0),
+    contingency: lang.contingency,
+    ticItemsTotal,
+    ticItemIds: ticItems.map((item) => item.id),
+    terms,
+    totalInstalledCost: costs.total_installed_cost,
+    residual: costs.total_installed_cost - explained,
+  };
+}
@@ -0,0 +1,87 @@
+import { describe, it, expect } from "vitest";
+import {
+  annuityPayment,
+  buildFinancing,
+  debtSchedule,
+  realWacc,
+  wacc,
+  type Financing,
+} from "./financing";
+
+const timeline = {
+  construction_start: 2028,
+  construction_finish: 2030,
+  operation_start: 2030,
+  operation_finish: 2040,
+  decommissioning_start: 2040,
+  decommissioning_finish: 2041,
+};
+
+describe("financing", () => {
+  const financing: Financing = {
+    gearing: 0.6,
+    costOfDebt: 0.05,
+    costOfEquity: 0.1,
+    tenorYears: 2,
+  };
+
+  it("weights the cost of debt and equity by gearing", () => {
+    expect(wacc(financing)).toBeCloseTo(0.07);
+  });
+
+  it("takes escalation out of the WACC for the real discount rate", () => {
+    expect(realWacc(financing, 0)).toBeCloseTo(0.07);
+    expect(realWacc(financing, 0.02)).toBeCloseTo(1.07 / 1.02 - 1);
+  });
+
+  it("levels repayments over the tenor", () => {
+    expect(annuityPayment(1_000, 0, 4)).toBe(250);
+    expect(annuityPayment(1_000, 0.05, 2)).toBeCloseTo(537.8, 1);
+  });
+
+  it("capitalizes interest during construction and repays over the tenor", () => {
+    const schedule = debtSchedule(
+      {
+        id: "asset-1",
+        years: [2028, 2029, 2030],
+        capex: [1_000, 1_000, 0],
+        timeline,
+      },
+      financing,
+    );
+
+    expect(schedule.years).toEqual([2028, 2029, 2030, 2031]);
+    expect(schedule.drawdown).toEqual([600, 600, 0, 0]);
+    expect(schedule.interest[0]).toBeCloseTo(15);
+    expect(schedule.interest[1]).toBeCloseTo(0.05 * (615 + 300));
+    expect(schedule.interestDuringConstruction).toBeCloseTo(60.75);
+    expect(schedule.debt).toBe(1_200);
+    expect(schedule.equity).toBe(800);
+    expect(schedule.debtService[0]).toBe(0);
+    expect(schedule.debtService[2]).toBeCloseTo(
+      annuityPayment(1_260.75, 0.05, 2),
+    );
+    expect(schedule.balance[3]).toBeCloseTo(0);
+  });
+
+  it("sums the assets' schedules", () => {
+    const asset = {
+      years: [2029],
+      capex: [1_000],
+      timeline,
+    };
+    const result = buildFinancing(
+      [
+        { id: "a", ...asset },
+        { id: "b", ...asset },
+      ],
+      financing,
+    );
+
+    expect(result.wacc).toBeCloseTo(0.07);
+    expect(result.debt).toBe(1_200);
+    expect(result.equity).toBe(800);
+    expect(result.interestDuringConstruction).toBeCloseTo(30);
+    expect(result.assets.map((a) => a.id)).toEqual(["a", "b"]);
+  });
+});
@@ -0,0 +1,186 @@
+/**
+ * Project financing.
+ *
+ * The costing server gives overnight costs. A financing block splits each
+ * asset's capex between debt and equity: debt is drawn as capex is spent
+ * (mid-year), accrues interest during construction that is capitalized
+ * into the loan, and is repaid as an annuity over the tenor from operation
+ * start. The weighted average cost of capital (pre-tax) of the split is
+ * used as the estimate's discount rate.
+ *
+ * Costs of debt and equity are market rates, with inflation in them, while
+ * asset discount rates are real. On a nominal price basis the WACC is
+ * deflated by the escalation rate before it becomes the discount rate, so
+ * the nominal rate derived from it again doesn't count inflation twice. A
+ * real basis has no inflation assumption to remove, so its financing rates
+ * should be given in real terms.
+ */
+
+import type { Timeline } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type Financing = {
+  /** Share of capex funded by debt (0-1) */
+  gearing: number;
+  /** Annual interest rate on debt (e.g. 0.06 = 6%), a market rate */
+  costOfDebt: number;
+  /** Annual required return on equity, a market rate */
+  costOfEquity: number;
+  /** Years over which debt is repaid from operation start */
+  tenorYears: number;
+};
+
+/** Year-aligned debt schedule. All arrays have the same length as `years`. */
+export type DebtSchedule = {
+  years: number[];
+  /** Debt drawn to fund the year's capex */
+  drawdown: number[];
+  /** Interest accrued (capitalized during construction) */
+  interest: number[];
+  principal: number[];
+  /** Interest and principal paid */
+  debtService: number[];
+  /** Debt outstanding at the end of the year */
+  balance: number[];
+};
+
+export type AssetFinancing = DebtSchedule & {
+  id: string;
+  name?: string;
+  debt: number;
+  equity: number;
+  /** Interest capitalized before operation start */
+  interestDuringConstruction: number;
+};
+
+export type FinancingCashflow = Financing & {
+  wacc: number;
+  debt: number;
+  equity: number;
+  interestDuringConstruction: number;
+  assets: AssetFinancing[];
+};
+
+/** Per-asset capex profile the schedule is built from */
+export type AssetCapexSchedule = {
+  id: string;
+  name?: string;
+  years: number[];
+  capex: number[];
+  timeline: Timeline;
+};
+
+// ============================================================================
+// Calculation
+// ============================================================================
+
+/** Pre-tax weighted average cost of capital */
+export function wacc(financing: Financing): number {
+  return (
+    financing.gearing * financing.costOfDebt +
+    (1 - financing.gearing) * financing.costOfEquity
+  );
+}
+
+/**
+ * The real discount rate for a financing: its WACC with `escalationRate`
+ * taken out (Fisher).
+ */
+export function realWacc(financing: Financing, escalationRate: number): number {
+  return (1 + wacc(financing)) / (1 + escalationRate) - 1;
+}
+
+/** Level annual payment repaying `amount` over `years` at `rate` */
+export function annuityPayment(
+  amount: number,
+  rate: number,
+  years: number,
+): number {
+  if (years <= 0) return amount;
+  if (rate === 0) return amount / years;
+  return (amount * rate) / (1 - Math.pow(1 + rate, -years));
+}
+
+/**
+ * Debt schedule for an asset: draws with its capex until operation start,
+ * then repayment over the tenor. Capex from operation start is funded by
+ * equity. Years extend past the asset's own series when the tenor outlasts
+ * it.
+ */
+export function debtSchedule(
+  asset: AssetCapexSchedule,
+  financing: Financing,
+): AssetFinancing {
+  const { costOfDebt: rate, gearing, tenorYears } = financing;
+  const start = asset.timeline.operation_start;
+  const capexByYear = new Map(asset.years.map((y, i) => [y, asset.capex[i]]));
+  const firstYear = Math.min(...asset.years, start);
+  const lastYear = Math.max(...asset.years, start + tenorYears - 1);
+
+  const schedule: AssetFinancing = {
+    id: asset.id,
+    ...(asset.name !== undefined && { name: asset.name }),
+    debt: 0,
+    equity: 0,
+    interestDuringConstruction: 0,
+    years: [],
+    drawdown: [],
+    interest: [],
+    principal: [],
+    debtService: [],
+    balance: [],
+  };
+
+  let balance = 0;
+  let payment = 0;
+  for (let year = firstYear; year <= lastYear; year++) {
+    const capex = capexByYear.get(year) ?? 0;
+    const drawdown = year < start ? capex * gearing : 0;
+    let interest: number;
+    let principal = 0;
+    if (year < start) {
+      // Capitalized; the year's draw is outstanding for half of it
+      interest = rate * (balance + drawdown / 2);
+      balance += drawdown + interest;
+      schedule.interestDuringConstruction += interest;
+    } else {
+      if (year === start) payment = annuityPayment(balance, rate, tenorYears);
+      interest = rate * balance;
+      principal = year < start + tenorYears ? payment - interest : 0;
+      balance -= principal;
+    }
+
+    schedule.debt += drawdown;
+    schedule.equity += capex - drawdown;
+    schedule.years.push(year);
+    schedule.drawdown.push(drawdown);
+    schedule.interest.push(interest);
+    schedule.principal.push(principal);
+    schedule.debtService.push(year < start ? 0 : interest + principal);
+    schedule.balance.push(balance);
+  }
+  return schedule;
+}
+
+/**
+ * Financing section for a set of assets.
+ */
+export function buildFinancing(
+  assets: AssetCapexSchedule[],
+  financing: Financing,
+): FinancingCashflow {
+  const schedules = assets.map((a) => debtSchedule(a, financing));
+  const sum = (f: (s: AssetFinancing) => number) =>
+    schedules.reduce((total, s) => total + f(s), 0);
+  return {
+    ...financing,
+    wacc: wacc(financing),
+    debt: sum((s) => s.debt),
+    equity: sum((s) => s.equity),
+    interestDuringConstruction: sum((s) => s.interestDuringConstruction),
+    assets: schedules,
+  };
+}
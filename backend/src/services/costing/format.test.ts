@@ -0,0 +1,51 @@
+import { describe, it, expect } from "vitest";
+import { applyFormatting, currencyFormatter, isSupportedLocale } from "./format";
+import { transformCostingResponse } from "./adapter";
+import { mockAssetEstimate, mockYearCosts } from "./mocks/cost-estimate-response";
+
+describe("format", () => {
+  describe("currencyFormatter", () => {
+    it("uses the locale's separators and currency symbol", () => {
+      expect(currencyFormatter("de-DE", "EUR")(1234567.891)).toBe(
+        "1.234.567,89\u00a0€",
+      );
+      expect(currencyFormatter("en-GB", "GBP")(1500)).toBe("£1,500.00");
+    });
+
+    it("follows the rounding policy", () => {
+      const format = currencyFormatter("en-GB", "GBP", {
+        mode: "decimals",
+        digits: 0,
+      });
+      expect(format(1234.4)).toBe("£1,234");
+    });
+
+    it("falls back to a plain number for unrecognised currency codes", () => {
+      expect(currencyFormatter("en-GB", "EURO")(1500)).toBe("1,500 EURO");
+    });
+  });
+
+  it("rejects unsupported locales", () => {
+    expect(isSupportedLocale("de-DE")).toBe(true);
+    expect(isSupportedLocale("not a locale")).toBe(false);
+  });
+
+  it("formats network and asset totals", () => {
+    const response = transformCostingResponse(
+      {
+        assets: [
+          mockAssetEstimate("asset-1", [
+            mockYearCosts(2025, { total_installed_cost: 2500 }),
+          ]),
+        ],
+      },
+      [],
+      "EUR",
+    );
+
+    const { formatted } = applyFormatting(response, "en-GB");
+
+    expect(formatted?.network.totalInstalledCost).toBe("€2,500.00");
+    expect(formatted?.assets["asset-1"].lifetimeNpcCost).toBe("€2,500.00");
+  });
+});
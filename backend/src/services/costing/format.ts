@@ -0,0 +1,116 @@
+/**
+ * Locale-aware formatting of headline totals.
+ *
+ * Gives every client the same formatted strings (currency symbol, grouping,
+ * decimal separator) for a locale, instead of each UI and report formatting
+ * numbers its own way.
+ */
+
+import type {
+  CostingEstimateResponse,
+  LifetimeCosts,
+} from "./request-types";
+import type { RoundingPolicy } from "./rounding";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type FormattedTotals = {
+  totalInstalledCost: string;
+  lifetimeCost: string;
+  lifetimeNpcCost: string;
+};
+
+export type ResponseFormatting = {
+  locale: string;
+  currency: string;
+  network: FormattedTotals;
+  /** Formatted totals keyed by asset ID */
+  assets: Record<string, FormattedTotals>;
+};
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+/**
+ * Check that a locale tag is well-formed and supported by the runtime.
+ */
+export function isSupportedLocale(locale: string): boolean {
+  try {
+    return Intl.NumberFormat.supportedLocalesOf([locale]).length > 0;
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Build a currency formatter for a locale. Fraction digits follow the
+ * rounding policy when there is one, otherwise the currency's convention.
+ * Currency codes Intl doesn't accept are appended to a plain number.
+ */
+export function currencyFormatter(
+  locale: string,
+  currency: string,
+  rounding?: RoundingPolicy,
+): (value: number) => string {
+  const digits: Intl.NumberFormatOptions =
+    rounding?.mode === "decimals"
+      ? {
+          minimumFractionDigits: rounding.digits,
+          maximumFractionDigits: rounding.digits,
+        }
+      : rounding?.mode === "significantFigures"
+        ? { maximumSignificantDigits: rounding.digits }
+        : {};
+
+  try {
+    const format = new Intl.NumberFormat(locale, {
+      style: "currency",
+      currency,
+      ...digits,
+    });
+    return (value) => format.format(value);
+  } catch {
+    const format = new Intl.NumberFormat(locale, digits);
+    return (value) => `${format.format(value)} ${currency}`;
+  }
+}
+
+/**
+ * Attach formatted headline totals for the network and each asset.
+ */
+export function applyFormatting(
+  response: CostingEstimateResponse,
+  locale: string,
+): CostingEstimateResponse {
+  const format = currencyFormatter(
+    locale,
+    response.currency,
+    response.rounding,
+  );
+  const totals = (
+    lifetime: LifetimeCosts,
+    npc: LifetimeCosts,
+  ): FormattedTotals => ({
+    totalInstalledCost: format(lifetime.totalInstalledCost),
+    lifetimeCost: format(lifetime.total),
+    lifetimeNpcCost: format(npc.total),
+  });
+
+  return {
+    ...response,
+    formatted: {
+      locale,
+      currency: response.currency,
+      network: totals(response.lifetimeCosts, response.lifetimeNpcCosts),
+      assets: Object.fromEntries(
+        response.assets.map((asset) => [
+          asset.id,
+          totals(asset.lifetimeCosts, asset.lifetimeNpcCosts),
+        ]),
+      ),
+    },
+  };
+}
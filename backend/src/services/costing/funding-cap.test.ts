@@ -0,0 +1,57 @@
+import { describe, it, expect } from "vitest";
+import { checkFundingCap, type AssetCapexProfile } from "./funding-cap";
+
+describe("funding-cap", () => {
+  const assets: AssetCapexProfile[] = [
+    { id: "store", years: [2030, 2031, 2032], capex: [60, 60, 0] },
+    { id: "pipeline", years: [2030, 2031, 2032], capex: [50, 50, 0] },
+  ];
+
+  it("flags years where portfolio capex exceeds the cap", () => {
+    expect(checkFundingCap(assets, { annualCap: 100 })).toEqual({
+      annualCap: 100,
+      exceedances: [
+        { year: 2030, capex: 110, excess: 10 },
+        { year: 2031, capex: 110, excess: 10 },
+      ],
+    });
+  });
+
+  it("omits the suggestion when the portfolio fits", () => {
+    const advisory = checkFundingCap(assets, {
+      annualCap: 120,
+      suggestShifts: true,
+    });
+    expect(advisory.exceedances).toEqual([]);
+    expect(advisory.suggestion).toBeUndefined();
+  });
+
+  it("delays later assets until every year fits", () => {
+    const advisory = checkFundingCap(assets, {
+      annualCap: 100,
+      suggestShifts: true,
+    });
+
+    expect(advisory.suggestion).toEqual({
+      shifts: [
+        {
+          asset: "store",
+          shiftYears: 2,
+          constructionStart: 2030,
+          suggestedConstructionStart: 2032,
+        },
+      ],
+      unresolved: [],
+    });
+  });
+
+  it("reports assets that can't fit within the shift limit", () => {
+    const advisory = checkFundingCap(assets, {
+      annualCap: 100,
+      suggestShifts: true,
+      maxShiftYears: 1,
+    });
+
+    expect(advisory.suggestion).toEqual({ shifts: [], unresolved: ["store"] });
+  });
+});
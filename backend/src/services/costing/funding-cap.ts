@@ -0,0 +1,170 @@
+/**
+ * Portfolio funding constraint check.
+ *
+ * Given an annual capex budget, flags the years where the portfolio's
+ * aggregated capex exceeds it. Optionally suggests later construction start
+ * years that fit, using a simple greedy scheduler: assets are placed in order
+ * of their construction start, each at the smallest delay that keeps every
+ * year within the budget given the assets already placed. The result is
+ * advisory; the estimate itself is not re-run with the shifted timelines.
+ */
+
+import type { Timeline } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CapexBudget = {
+  /** Maximum portfolio capex per year, in the response currency */
+  annualCap: number;
+  /** Suggest shifted construction start years that fit the cap */
+  suggestShifts?: boolean;
+  /** Largest delay the scheduler may apply to one asset (default: 10) */
+  maxShiftYears?: number;
+};
+
+export type FundingExceedance = {
+  year: number;
+  capex: number;
+  excess: number;
+};
+
+export type ScheduleShift = {
+  asset: string;
+  shiftYears: number;
+  constructionStart: number;
+  suggestedConstructionStart: number;
+};
+
+export type FundingAdvisory = {
+  annualCap: number;
+  exceedances: FundingExceedance[];
+  /** Present when shifts were requested and the portfolio exceeds the cap */
+  suggestion?: {
+    shifts: ScheduleShift[];
+    /** Assets that could not be placed within `maxShiftYears` */
+    unresolved: string[];
+  };
+};
+
+/** Per-asset capex profile the check works on */
+export type AssetCapexProfile = {
+  id: string;
+  years: number[];
+  capex: number[];
+  timeline?: Timeline;
+};
+
+export const DEFAULT_MAX_SHIFT_YEARS = 10;
+
+// ============================================================================
+// Checking
+// ============================================================================
+
+/**
+ * Compare portfolio capex against an annual budget.
+ */
+export function checkFundingCap(
+  assets: AssetCapexProfile[],
+  budget: CapexBudget,
+): FundingAdvisory {
+  const totals = new Map<number, number>();
+  for (const asset of assets) {
+    addCapex(totals, asset, 0);
+  }
+
+  const exceedances = overCap(totals, budget.annualCap);
+  const advisory: FundingAdvisory = {
+    annualCap: budget.annualCap,
+    exceedances,
+  };
+
+  if (budget.suggestShifts && exceedances.length > 0) {
+    advisory.suggestion = suggestShifts(assets, budget);
+  }
+
+  return advisory;
+}
+
+/**
+ * Greedily delay assets until every year fits the cap.
+ */
+function suggestShifts(
+  assets: AssetCapexProfile[],
+  budget: CapexBudget,
+): NonNullable<FundingAdvisory["suggestion"]> {
+  const maxShift = budget.maxShiftYears ?? DEFAULT_MAX_SHIFT_YEARS;
+  const start = (asset: AssetCapexProfile) =>
+    asset.timeline?.construction_start ?? firstCapexYear(asset);
+  const ordered = [...assets].sort(
+    (a, b) => start(a) - start(b) || (a.id < b.id ? -1 : a.id > b.id ? 1 : 0),
+  );
+
+  const placed = new Map<number, number>();
+  const shifts: ScheduleShift[] = [];
+  const unresolved: string[] = [];
+
+  for (const asset of ordered) {
+    let shift = 0;
+    while (shift <= maxShift && !fits(placed, asset, shift, budget.annualCap)) {
+      shift++;
+    }
+
+    if (shift > maxShift) {
+      unresolved.push(asset.id);
+      addCapex(placed, asset, 0);
+      continue;
+    }
+
+    addCapex(placed, asset, shift);
+    if (shift > 0) {
+      shifts.push({
+        asset: asset.id,
+        shiftYears: shift,
+        constructionStart: start(asset),
+        suggestedConstructionStart: start(asset) + shift,
+      });
+    }
+  }
+
+  return { shifts, unresolved };
+}
+
+function fits(
+  placed: Map<number, number>,
+  asset: AssetCapexProfile,
+  shift: number,
+  cap: number,
+): boolean {
+  return asset.years.every(
+    (year, i) =>
+      asset.capex[i] === 0 ||
+      (placed.get(year + shift) ?? 0) + asset.capex[i] <= cap,
+  );
+}
+
+function addCapex(
+  totals: Map<number, number>,
+  asset: AssetCapexProfile,
+  shift: number,
+): void {
+  asset.years.forEach((year, i) => {
+    totals.set(year + shift, (totals.get(year + shift) ?? 0) + asset.capex[i]);
+  });
+}
+
+function overCap(
+  totals: Map<number, number>,
+  cap: number,
+): FundingExceedance[] {
+  return Array.from(totals)
+    .filter(([, capex]) => capex > cap)
+    .sort(([a], [b]) => a - b)
+    .map(([year, capex]) => ({ year, capex, excess: capex - cap }));
+}
+
+function firstCapexYear(asset: AssetCapexProfile): number {
+  const index = asset.capex.findIndex((v) => v > 0);
+  return asset.years[index === -1 ? 0 : index] ?? 0;
+}
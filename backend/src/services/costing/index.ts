@@ -17,15 +17,39 @@ export {
   loadCostLibrary,
   listCostLibraries,
   buildModuleIndex,
+  hashCostLibrary,
   ModuleLookupService,
   getModuleLookupService,
   clearModuleLookupCache,
+  prewarmLibraries,
+  getLibraryLoadStatus,
+  type LibraryLoadStatus,
   type ModuleInfo,
   type ParameterInfo,
   type ModuleLookupResult,
   type ModuleIndex,
 } from "./module-lookup";
 
+// Module listings
+export {
+  listModules,
+  parseModuleListingQuery,
+  isModuleListingQuery,
+  MODULE_FIELDS,
+  DEFAULT_MODULE_FIELDS,
+  type ModuleField,
+  type ModuleListing,
+  type ModuleListingEntry,
+  type ModuleListingQuery,
+} from "./module-listing";
+
+// Localization
+export {
+  libraryLanguages,
+  negotiateLanguage,
+  localizeCostItem,
+} from "./localization";
+
 // Defaults
 export {
   DEFAULT_TIMELINE,
@@ -40,15 +64,367 @@ export {
   isUsingDefaultOpexFactors,
 } from "./defaults";
 
+// Estimate class presets
+export {
+  ESTIMATE_PRESETS,
+  ESTIMATE_PRESET_NAMES,
+  listEstimatePresets,
+  type EstimatePreset,
+  type EstimatePresetName,
+  type AccuracyRange,
+} from "./presets";
+
+// Accuracy bands
+export {
+  applyAccuracyBands,
+  type AccuracyBand,
+  type NetworkAccuracyBand,
+  type CostRange,
+} from "./accuracy";
+
+// Formatting
+export {
+  applyFormatting,
+  currencyFormatter,
+  isSupportedLocale,
+  type FormattedTotals,
+  type ResponseFormatting,
+} from "./format";
+
+// Library validation and drafts
+export { validateCostLibrary, type LibraryIssue } from "./library-validation";
+export {
+  createDraft,
+  getDraft,
+  discardDraft,
+  putModule,
+  deleteModule,
+  addCostItem,
+  patchCostItem,
+  deleteCostItem,
+  mergePatch,
+  publishDraft,
+  getDraftPackage,
+  listLibraryVersions,
+  getLibraryVersion,
+  LibraryDraftError,
+  type LibraryVersion,
+  type LibraryVersionRecord,
+} from "./library-drafts";
+
+// Library import
+export {
+  importCostLibraryCsv,
+  type LibraryImportResult,
+  type LibraryImportOptions,
+  type ImportRowError,
+  type ImportIssue,
+} from "./library-import";
+
+// Library export, comparison, statistics and anonymization
+export { exportCostLibraryCsv } from "./library-export";
+export {
+  diffCostLibraries,
+  type CostLibraryDiff,
+  type CostItemChange,
+  type IdChanges,
+} from "./library-diff";
+export { costLibraryStats, type CostLibraryStats } from "./library-stats";
+export {
+  anonymizeCostLibrary,
+  VENDOR_PLACEHOLDER,
+  type AnonymizeOptions,
+} from "./library-anonymize";
+
+// Parameter registry
+export {
+  ParameterRegistry,
+  DEFAULT_PARAMETERS,
+  normalizeParameterName,
+} from "./parameter-registry";
+
+// Cost item aliases
+export {
+  resolveCostItemAlias,
+  previousCostItemIds,
+  migrateCostItemIds,
+  type CostItemIdMigration,
+} from "./cost-item-aliases";
+
+// Cost item selection constraints
+export {
+  checkItemConstraints,
+  type ConstraintViolation,
+  type BlockConstraintViolation,
+} from "./item-constraints";
+
+// Asset dependencies
+export {
+  checkAssetDependencies,
+  dependencyWarnings,
+  type AssetDependency,
+  type DependencyIssue,
+} from "./asset-dependencies";
+
+// Threshold alerts
+export {
+  checkThresholds,
+  thresholdWarnings,
+  THRESHOLD_METRICS,
+  type CostThreshold,
+  type ThresholdAlert,
+  type ThresholdMetric,
+} from "./thresholds";
+
+// Portfolio funding cap
+export {
+  checkFundingCap,
+  DEFAULT_MAX_SHIFT_YEARS,
+  type CapexBudget,
+  type FundingAdvisory,
+  type FundingExceedance,
+  type ScheduleShift,
+  type AssetCapexProfile,
+} from "./funding-cap";
+
+// Asset type KPIs
+export {
+  computeAssetKpis,
+  missingKpiInputs,
+  lifetimeTonnes,
+  discountedLifetimeTonnes,
+  unitTransportCost,
+  ASSET_TYPES,
+  type AssetType,
+  type AssetKpi,
+  type KpiInputs,
+  type UnitTransportCost,
+} from "./kpis";
+
+// Throughput ramp-up
+export {
+  applyThroughputProfiles,
+  throughputFraction,
+} from "./throughput-profile";
+
+// Cost parameter currencies
+export {
+  convertCostParameter,
+  convertRequestCostParameters,
+  CostParameterError,
+} from "./cost-parameters";
+
+// Price base year
+export {
+  resolvePriceBase,
+  applyPriceBase,
+  PriceBaseError,
+  type PriceBase,
+} from "./price-base";
+
+// Real or nominal cash flows
+export {
+  applyNominalBasis,
+  nominalRate,
+  PRICE_BASIS_MODES,
+  REAL_PRICE_BASIS,
+  type PriceBasis,
+  type PriceBasisMode,
+} from "./price-basis";
+
+// Block quantities derived from the network
+export { evaluateQuantity, QuantityExpressionError } from "./quantity-expressions";
+
+// Financing
+export {
+  wacc,
+  realWacc,
+  annuityPayment,
+  debtSchedule,
+  buildFinancing,
+  type Financing,
+  type FinancingCashflow,
+  type AssetFinancing,
+  type DebtSchedule,
+  type AssetCapexSchedule,
+} from "./financing";
+
+// Net present cost at several discount rates
+export { discountedLifetimeCosts } from "./discount-rates";
+
+// Carbon value
+export {
+  carbonValue,
+  networkCarbonValue,
+  type CarbonPriceTrajectory,
+  type CarbonValue,
+  type NetworkCarbonValue,
+} from "./carbon-value";
+
+// Utility price indexation
+export {
+  applyUtilityPrices,
+  utilityPrice,
+  valueInYear,
+  utilityPriceFactors,
+  UTILITY_PRICE_KINDS,
+  type UtilityPriceKind,
+  type UtilityPriceSeries,
+  type UtilityPrices,
+} from "./utility-prices";
+
+// Exchange rate and inflation scenarios
+export {
+  resolveScenario,
+  applyScenario,
+  capexScaleFor,
+  ScenarioError,
+  type EconomicScenario,
+  type ResolvedScenario,
+  type ScenarioProvenance,
+} from "./scenario";
+
+// Top cost drivers
+export {
+  assetTopDrivers,
+  portfolioTopDrivers,
+  type CostDriver,
+  type TopDrivers,
+} from "./top-drivers";
+
+// CBS coding
+export {
+  mergeCbsMappings,
+  cbsCodeFor,
+  cbsLines,
+  groupByCbsCode,
+  type CbsGroup,
+  type CbsLine,
+} from "./cbs-coding";
+
+// Library signatures
+export {
+  signLibrary,
+  verifyLibrary,
+  parseSigningKeys,
+  publicKeyBase64,
+  signaturePolicyFromEnv,
+  signaturesRequired,
+  LibrarySignatureError,
+  SIGNATURE_FILE,
+  SIGNATURE_MODES,
+  type LibrarySignature,
+  type SignatureMode,
+  type SignaturePolicy,
+} from "./library-signing";
+
+// Saved estimates and baseline comparison
+export {
+  saveEstimate,
+  getEstimate,
+  getEstimateLibrary,
+  listEstimates,
+  readEstimateSummaries,
+  archiveEstimate,
+  unarchiveEstimate,
+  deleteEstimates,
+  costItemIdsByAsset,
+  EstimateNotFoundError,
+  ESTIMATE_SORTS,
+  type EstimateLabels,
+  type EstimateSearch,
+  type EstimateSort,
+  type StoredEstimate,
+  type StoredEstimateSummary,
+  type EstimateIntermediate,
+} from "./estimate-store";
+export {
+  pruneEstimates,
+  startEstimatePruning,
+  retentionPolicyFromEnv,
+  pruneIntervalFromEnv,
+  isRetentionEnabled,
+  type RetentionPolicy,
+  type PruneResult,
+} from "./estimate-retention";
+export {
+  compareEstimates,
+  costDelta,
+  type EstimateComparison,
+  type CostDelta,
+  type CategoryDelta,
+  type AssetDelta,
+} from "./estimate-compare";
+export {
+  summarizeEstimateChanges,
+  EstimateChangesError,
+  DEFAULT_TOP_CONTRIBUTORS,
+  MAX_ATTRIBUTION_RUNS,
+  type EstimateChangeSummary,
+  type CostItemContributor,
+  type InputChange,
+  type InputAttribution,
+  type ChangeSummaryOptions,
+} from "./estimate-changes";
+
+// Clipboard tables for saved estimates
+export {
+  estimateTable,
+  tableToTsv,
+  tableToHtml,
+  tableToParquet,
+  isEstimateTableName,
+  EstimateTableError,
+  ESTIMATE_TABLES,
+  type EstimateTable,
+  type EstimateTableName,
+} from "./estimate-tables";
+
+// Legacy request upgrades
+export {
+  migrateEstimateRequest,
+  REQUEST_MIGRATIONS,
+  RequestMigrationError,
+  type RequestMigration,
+  type RequestMigrationResult,
+} from "./migrations";
+
+// What-if overlays on saved estimates
+export {
+  applyWhatIf,
+  WhatIfError,
+  type WhatIfOverrides,
+  type WhatIfRun,
+} from "./what-if";
+
+// Parameter sweeps
+export {
+  runSweep,
+  axisValues,
+  applySweepValues,
+  SweepError,
+  MAX_SWEEP_POINTS,
+  DEFAULT_SWEEP_CONCURRENCY,
+  sweepConcurrencyFromEnv,
+  type SweepAxis,
+  type SweepPoint,
+  type SweepPointTotals,
+  type SweepResult,
+} from "./sweep";
+
 // Request/Response types
 export {
   type CostingEstimateRequest,
   type CostingEstimateResponse,
   type AssetPropertyOverrides,
+  type ParameterValues,
+  type ParameterLevel,
   type ResolvedAssetProperties,
+  type AssetProvenance,
   type AssetCostResult,
   type BlockCostResult,
   type LifetimeCosts,
+  type DiscountRateNpc,
   type LangFactoredCosts,
   type FixedOpexCosts,
   type VariableOpexCosts,
@@ -67,11 +443,82 @@ export { mapBlockToModule, type ModuleMapping } from "./block-to-module-mapper";
 export {
   transformNetworkToCostingRequest,
   transformCostingResponse,
+  defaultedParameters,
+  inheritedParameters,
   type CostingTransformOptions as TransformOptions,
   type CostingTransformResult as TransformResult,
+  type CostingResponseOptions,
   type AssetMetadata,
+  type BlockValidation,
+  type CostItemReport,
+  type ParameterReport,
 } from "./adapter";
 
+// Dry run
+export {
+  buildDryRunReport,
+  type DryRunResponse,
+  type DryRunAsset,
+  type DryRunCostItem,
+} from "./dry-run";
+
+// Total installed cost explanation
+export {
+  explainTotalInstalledCost,
+  type TicExplanation,
+  type TicExplanationTerm,
+} from "./explain";
+
+// Audit trail
+export {
+  buildAuditTrail,
+  type AuditOptions,
+  type CostItemAudit,
+  type ScalingFactorAudit,
+} from "./audit";
+
+// Costing server client
+export {
+  requestCostEstimate,
+  CostingServerError,
+  CostingServerUnavailableError,
+  CostingServerTimeoutError,
+  CostingRequestCancelledError,
+  COSTING_SERVER_URL,
+  DEFAULT_COSTING_TIMEOUT_MS,
+  costingTimeoutFromEnv,
+  type CostEstimateCallOptions,
+} from "./client";
+
+// Cash flow view
+export {
+  buildCashflow,
+  downsample,
+  type CashflowSeries,
+  type AssetCashflow,
+  type CostingCashflowResponse,
+  type CashflowOptions,
+} from "./cashflow";
+
+// Totals
+export {
+  lifetimeTotal,
+  withTotal,
+  aggregateLifetimeCosts,
+  enforceTotals,
+  mapCostValues,
+  type LifetimeCostComponents,
+} from "./totals";
+
+// Rounding
+export {
+  roundValue,
+  roundCosts,
+  applyRounding,
+  applyCashflowRounding,
+  type RoundingPolicy,
+} from "./rounding";
+
 // Effect Schemas
 export {
   CostingEstimateRequestSchema,
@@ -81,6 +528,16 @@ export {
   NetworkBranchSchema,
   NetworkGroupSchema,
   AssetPropertyOverridesSchema,
+  ParameterValuesSchema,
+  YearSchema,
+  YearKeySchema,
+  MIN_YEAR,
+  MAX_YEAR,
+  UtilityPricesSchema,
+  CarbonPriceTrajectorySchema,
+  FinancingSchema,
+  RoundingPolicySchema,
+  AccuracyRangeSchema,
   validateRequest,
   formatValidationErrors,
   type CostingEstimateRequestInput,
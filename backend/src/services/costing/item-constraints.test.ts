@@ -0,0 +1,78 @@
+import { describe, it, expect } from "vitest";
+import { checkItemConstraints } from "./item-constraints";
+import { validateCostLibrary } from "./library-validation";
+import type { ItemConstraint } from "./types";
+
+describe("item-constraints", () => {
+  const constraints: ItemConstraint[] = [
+    {
+      type: "exactlyOne",
+      items: ["Item 010", "Item 011"],
+      description: "Choose one drive type",
+    },
+    { type: "requires", item: "Item 008", requires: ["Item 007"] },
+  ];
+
+  it("accepts a valid selection", () => {
+    expect(
+      checkItemConstraints(constraints, ["Item 007", "Item 008", "Item 010"]),
+    ).toEqual([]);
+  });
+
+  it("reports each broken rule", () => {
+    expect(
+      checkItemConstraints(constraints, ["Item 008", "Item 010", "Item 011"]),
+    ).toEqual([
+      {
+        type: "exactlyOne",
+        constraint: 0,
+        items: ["Item 010", "Item 011"],
+        selected: ["Item 010", "Item 011"],
+        message:
+          "Choose one drive type: exactly one of Item 010, Item 011 must " +
+          "be selected; 2 are",
+      },
+      {
+        type: "requires",
+        constraint: 1,
+        items: ["Item 008", "Item 007"],
+        selected: ["Item 008"],
+        message: "Item 008 requires Item 007",
+      },
+    ]);
+  });
+
+  it("only checks requirements of selected items", () => {
+    expect(
+      checkItemConstraints(
+        [{ type: "atMostOne", items: ["A", "B"] }, ...constraints.slice(1)],
+        ["B"],
+      ),
+    ).toEqual([]);
+  });
+
+  it("validates constraints against the module's items", () => {
+    const issues = validateCostLibrary({
+      modules: [
+        {
+          id: "M0001",
+          definition: { type: "Test" },
+          subtype: null,
+          cost_items: [],
+          constraints: [{ type: "atMostOne", items: ["Item 001"] }],
+        },
+      ],
+    });
+
+    expect(issues).toEqual([
+      {
+        path: "modules[0].constraints[0].items",
+        message: "must list at least two items",
+      },
+      {
+        path: "modules[0].constraints[0]",
+        message: "Item 001 is not a cost item or bundle of the module",
+      },
+    ]);
+  });
+});
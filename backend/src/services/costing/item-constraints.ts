@@ -0,0 +1,94 @@
+/**
+ * Cost item selection constraints.
+ *
+ * A module can declare rules on which of its items an asset may use
+ * together: "choose exactly one drive type", "the after-cooler requires the
+ * compressor". Items are selected by supplying their parameters, so the
+ * rules are checked against the items each block ends up including.
+ */
+
+import type { ItemConstraint } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type ConstraintViolation = {
+  type: ItemConstraint["type"];
+  /** Index of the constraint in the module's `constraints` */
+  constraint: number;
+  /** Items the constraint covers */
+  items: string[];
+  /** Items of the constraint the block selected */
+  selected: string[];
+  message: string;
+};
+
+export type BlockConstraintViolation = ConstraintViolation & {
+  blockId: string;
+  moduleId: string;
+};
+
+// ============================================================================
+// Checking
+// ============================================================================
+
+/**
+ * Check a block's selected items against its module's constraints.
+ *
+ * @param selected - IDs of the cost items and bundles the block includes
+ */
+export function checkItemConstraints(
+  constraints: ItemConstraint[],
+  selected: string[],
+): ConstraintViolation[] {
+  const chosen = new Set(selected);
+  const violations: ConstraintViolation[] = [];
+
+  constraints.forEach((constraint, index) => {
+    const items =
+      constraint.type === "requires"
+        ? [constraint.item, ...constraint.requires]
+        : constraint.items;
+    const inConstraint = items.filter((id) => chosen.has(id));
+    const violation = (message: string) =>
+      violations.push({
+        type: constraint.type,
+        constraint: index,
+        items,
+        selected: inConstraint,
+        message: constraint.description
+          ? `${constraint.description}: ${message}`
+          : message,
+      });
+
+    switch (constraint.type) {
+      case "exactlyOne":
+        if (inConstraint.length !== 1) {
+          violation(
+            `exactly one of ${items.join(", ")} must be selected; ` +
+              `${inConstraint.length} are`,
+          );
+        }
+        break;
+      case "atMostOne":
+        if (inConstraint.length > 1) {
+          violation(
+            `at most one of ${items.join(", ")} may be selected; ` +
+              `${inConstraint.join(", ")} are`,
+          );
+        }
+        break;
+      case "requires": {
+        if (!chosen.has(constraint.item)) break;
+        const missing = constraint.requires.filter((id) => !chosen.has(id));
+        if (missing.length > 0) {
+          violation(`${constraint.item} requires ${missing.join(", ")}`);
+        }
+        break;
+      }
+    }
+  });
+
+  return violations;
+}
@@ -0,0 +1,175 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse, type AssetMetadata } from "./adapter";
+import {
+  computeAssetKpis,
+  missingKpiInputs,
+  unitTransportCost,
+  type KpiInputs,
+} from "./kpis";
+import { DEFAULT_TIMELINE } from "./defaults";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+const timeline = {
+  construction_start: 2025,
+  construction_finish: 2027,
+  operation_start: 2028,
+  operation_finish: 2047,
+  decommissioning_start: 2048,
+  decommissioning_finish: 2050,
+};
+
+describe("kpis", () => {
+  it("divides lifetime cost by the CO2 handled while operating", () => {
+    const inputs: KpiInputs = {
+      assetType: "capture",
+      throughput: 1_000_000,
+      timeline,
+    };
+
+    expect(computeAssetKpis(inputs, 400_000_000, "GBP")).toEqual([
+      { name: "costPerTonneCaptured", unit: "GBP/tCO2", value: 20 },
+    ]);
+  });
+
+  it("costs transport per tonne-kilometre", () => {
+    const inputs: KpiInputs = {
+      assetType: "transport",
+      throughput: 1_000_000,
+      lengthKm: 50,
+      timeline,
+    };
+
+    expect(computeAssetKpis(inputs, 100_000_000, "GBP")).toEqual([
+      {
+        name: "costPerTonneKmTransported",
+        unit: "GBP/(tCO2·km)",
+        value: 0.1,
+      },
+    ]);
+  });
+
+  it("levelizes transport cost over discounted tonne-kilometres", () => {
+    const inputs: KpiInputs = {
+      assetType: "transport",
+      throughput: 1000,
+      lengthKm: 10,
+      timeline: { ...timeline, operation_start: 2026, operation_finish: 2027 },
+      discountRate: 0.1,
+    };
+    // 1000 t/yr in 2026 and 2027, discounted one and two years from 2025
+    const discountedTonneKm = (1000 / 1.1 + 1000 / 1.21) * 10;
+
+    const cost = unitTransportCost(inputs, 40_000, 30_000, "GBP");
+
+    expect(cost?.unit).toBe("GBP/(tCO2·km)");
+    expect(cost?.undiscounted).toBe(2);
+    expect(cost?.levelized).toBeCloseTo(30_000 / discountedTonneKm);
+    expect(
+      unitTransportCost({ ...inputs, assetType: "storage" }, 1, 1, "GBP"),
+    ).toBeUndefined();
+    expect(
+      unitTransportCost({ ...inputs, lengthKm: undefined }, 1, 1, "GBP"),
+    ).toBeUndefined();
+  });
+
+  it("counts every operating year of the default timeline", () => {
+    // Operation 2027-2046 inclusive: 20 years of 1000 t over 1 km
+    const inputs: KpiInputs = {
+      assetType: "transport",
+      throughput: 1000,
+      lengthKm: 1,
+      timeline: DEFAULT_TIMELINE,
+      discountRate: 0,
+    };
+
+    const cost = unitTransportCost(inputs, 20_000, 20_000, "GBP");
+
+    expect(cost?.undiscounted).toBe(1);
+    expect(cost?.levelized).toBe(1);
+  });
+
+  it("counts only the profile's fraction of throughput while ramping up", () => {
+    const inputs: KpiInputs = {
+      assetType: "transport",
+      throughput: 1000,
+      lengthKm: 1,
+      timeline: DEFAULT_TIMELINE,
+      discountRate: 0,
+    };
+    // 19.25 full years: the first two run at half and three quarters
+    const profile = [0.5, 0.75];
+
+    const cost = unitTransportCost(inputs, 19_250, 19_250, "GBP", profile);
+
+    expect(cost?.undiscounted).toBe(1);
+    expect(cost?.levelized).toBe(1);
+    expect(
+      computeAssetKpis(
+        { ...inputs, assetType: "storage" },
+        19_250,
+        "GBP",
+        profile,
+      )[0].value,
+    ).toBe(1);
+  });
+
+  it("reports missing inputs and computes nothing without them", () => {
+    const inputs: KpiInputs = { assetType: "transport", timeline };
+
+    expect(missingKpiInputs(inputs)).toEqual(["throughput", "length_km"]);
+    expect(computeAssetKpis(inputs, 100, "GBP")).toEqual([]);
+    expect(
+      missingKpiInputs({ assetType: "storage", throughput: 10, timeline }),
+    ).toEqual([]);
+  });
+
+  it("adds KPIs to typed assets and warns about missing inputs", () => {
+    const estimate = {
+      assets: ["store", "pipe"].map((id) =>
+        mockAssetEstimate(
+          id,
+          [mockYearCosts(2025, { total_installed_cost: 1000 })],
+          [mockCostItem(`${id}/blocks/0/Item 001`, {})],
+        ),
+      ),
+    };
+    const metadata = (id: string, kpiInputs: KpiInputs): AssetMetadata => ({
+      assetId: id,
+      isGroup: false,
+      branchIds: [id],
+      blockCount: 1,
+      costableBlockCount: 1,
+      usingDefaults: [],
+      blocks: [],
+      kpiInputs,
+    });
+
+    const result = transformCostingResponse(
+      estimate,
+      [
+        metadata("store", { assetType: "storage", throughput: 10, timeline }),
+        metadata("pipe", { assetType: "transport", throughput: 10, timeline }),
+      ],
+      "GBP",
+    );
+
+    const [store, pipe] = result.assets;
+    expect(store.kpis).toEqual([
+      {
+        name: "costPerTonneStored",
+        unit: "GBP/tCO2",
+        value: store.lifetimeCosts.total / 200,
+      },
+    ]);
+    expect(pipe.kpis).toEqual([]);
+    expect(store.unitTransportCost).toBeUndefined();
+    expect(pipe.unitTransportCost).toBeUndefined();
+    expect(result.warnings).toContain(
+      "pipe: no KPIs for transport asset without length_km",
+    );
+  });
+});
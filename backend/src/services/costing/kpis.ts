@@ -0,0 +1,200 @@
+/**
+ * Type-specific KPIs for assets.
+ *
+ * An asset's role in the chain decides which unit cost matters: cost per
+ * tonne captured for a capture plant, per tonne-kilometre for a pipeline,
+ * per tonne stored for a store. The request gives each asset its type and
+ * annual throughput (and length, for transport) through the asset
+ * overrides; the KPIs divide the asset's lifetime cost by the CO2 it
+ * handles over its operating years. An asset ramping up on a throughput
+ * profile handles that year's fraction of its throughput.
+ */
+
+import type { Timeline } from "./types";
+import { throughputFraction } from "./throughput-profile";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const ASSET_TYPES = [
+  "capture",
+  "compression",
+  "transport",
+  "storage",
+  "utilization",
+] as const;
+
+export type AssetType = (typeof ASSET_TYPES)[number];
+
+export type KpiInputs = {
+  assetType: AssetType;
+  /** Tonnes of CO2 per operating year */
+  throughput?: number;
+  /** Route length in km (transport) */
+  lengthKm?: number;
+  timeline: Timeline;
+  /** Discount rate the asset was costed at, for levelized KPIs */
+  discountRate?: number;
+};
+
+export type AssetKpi = {
+  /** e.g. "costPerTonneCaptured" */
+  name: string;
+  /** e.g. "GBP/tCO2" */
+  unit: string;
+  value: number;
+};
+
+/**
+ * Cost of moving a tonne of CO2 one kilometre through a transport asset.
+ */
+export type UnitTransportCost = {
+  /** e.g. "GBP/(tCO2·km)" */
+  unit: string;
+  /** Lifetime cost over lifetime tonne-kilometres */
+  undiscounted: number;
+  /** Net present cost over discounted tonne-kilometres */
+  levelized: number;
+};
+
+type KpiFormula = {
+  name: string;
+  /** Per tonne-kilometre rather than per tonne */
+  perKm?: boolean;
+};
+
+const KPI_FORMULAS: Record<AssetType, KpiFormula> = {
+  capture: { name: "costPerTonneCaptured" },
+  compression: { name: "costPerTonneCompressed" },
+  transport: { name: "costPerTonneKmTransported", perKm: true },
+  storage: { name: "costPerTonneStored" },
+  utilization: { name: "costPerTonneUtilized" },
+};
+
+// ============================================================================
+// Calculation
+// ============================================================================
+
+/**
+ * Tonnes of CO2 an asset handles over its operating years, each year
+ * divided by `discount(year)`. Timelines are inclusive: an asset operating
+ * 2027-2046 runs for 20 years.
+ */
+function sumTonnes(
+  inputs: KpiInputs,
+  throughputProfile: number[] | undefined,
+  discount: (year: number) => number,
+): number | undefined {
+  const { throughput, timeline } = inputs;
+  if (throughput === undefined) return undefined;
+  let tonnes = 0;
+  for (
+    let year = timeline.operation_start;
+    year <= timeline.operation_finish;
+    year++
+  ) {
+    const fraction = throughputProfile
+      ? throughputFraction(throughputProfile, timeline.operation_start, year)
+      : 1;
+    tonnes += (throughput * fraction) / discount(year);
+  }
+  return tonnes;
+}
+
+/**
+ * Tonnes of CO2 an asset handles over its operating years, following its
+ * throughput profile if it has one.
+ */
+export function lifetimeTonnes(
+  inputs: KpiInputs,
+  throughputProfile?: number[],
+): number | undefined {
+  return sumTonnes(inputs, throughputProfile, () => 1);
+}
+
+/**
+ * Tonnes of CO2 an asset handles over its operating years, each year
+ * discounted back to the start of construction like the costing server's
+ * yearly costs.
+ */
+export function discountedLifetimeTonnes(
+  inputs: KpiInputs,
+  throughputProfile?: number[],
+): number | undefined {
+  const rate = inputs.discountRate ?? 0;
+  return sumTonnes(inputs, throughputProfile, (year) =>
+    Math.pow(1 + rate, year - inputs.timeline.construction_start),
+  );
+}
+
+/**
+ * Inputs the asset's type needs that the request didn't give.
+ */
+export function missingKpiInputs(inputs: KpiInputs): string[] {
+  return [
+    ...(inputs.throughput === undefined ? ["throughput"] : []),
+    ...(KPI_FORMULAS[inputs.assetType].perKm && inputs.lengthKm === undefined
+      ? ["length_km"]
+      : []),
+  ];
+}
+
+/**
+ * The asset type's KPIs for a lifetime cost. Empty when inputs are missing
+ * or the asset handles no CO2.
+ */
+export function computeAssetKpis(
+  inputs: KpiInputs,
+  lifetimeCost: number,
+  currency: string,
+  throughputProfile?: number[],
+): AssetKpi[] {
+  const tonnes = lifetimeTonnes(inputs, throughputProfile);
+  const formula = KPI_FORMULAS[inputs.assetType];
+  if (!tonnes || missingKpiInputs(inputs).length > 0) return [];
+
+  if (formula.perKm) {
+    const tonneKm = tonnes * inputs.lengthKm!;
+    if (!tonneKm) return [];
+    return [
+      {
+        name: formula.name,
+        unit: `${currency}/(tCO2·km)`,
+        value: lifetimeCost / tonneKm,
+      },
+    ];
+  }
+  return [
+    {
+      name: formula.name,
+      unit: `${currency}/tCO2`,
+      value: lifetimeCost / tonnes,
+    },
+  ];
+}
+
+/**
+ * Levelized cost per tonne-kilometre of a transport asset, undiscounted and
+ * from net present cost. Undefined for other asset types, without throughput
+ * or length, or when nothing is transported.
+ */
+export function unitTransportCost(
+  inputs: KpiInputs,
+  lifetimeCost: number,
+  lifetimeNpc: number,
+  currency: string,
+  throughputProfile?: number[],
+): UnitTransportCost | undefined {
+  const { lengthKm } = inputs;
+  if (inputs.assetType !== "transport" || !lengthKm) return undefined;
+  const tonnes = lifetimeTonnes(inputs, throughputProfile);
+  const discountedTonnes = discountedLifetimeTonnes(inputs, throughputProfile);
+  if (!tonnes || !discountedTonnes) return undefined;
+
+  return {
+    unit: `${currency}/(tCO2·km)`,
+    undiscounted: lifetimeCost / (tonnes * lengthKm),
+    levelized: lifetimeNpc / (discountedTonnes * lengthKm),
+  };
+}
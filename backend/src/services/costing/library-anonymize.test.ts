@@ -0,0 +1,62 @@
+import { describe, it, expect } from "vitest";
+import { anonymizeCostLibrary } from "./library-anonymize";
+import type { CostLibrary, CostLibraryCostItem } from "./types";
+
+describe("library-anonymize", () => {
+  const item = {
+    id: "Item 074",
+    info: {
+      reference_quality: "3",
+      item_type: "Capture",
+      short_name: "CAP Process (Baker Hughes)",
+      description: "baker hughes cryogenic capture",
+      source_reference: "Ref 025",
+      source_reference_detail: "Quote BH-1234",
+      confidentiality: "Confidential",
+      cost_type: "TIC",
+      cost_location: null,
+      note: "Budget offer from Baker Hughes",
+      sources: [
+        {
+          report: "Baker Hughes budget offer",
+          vendor_quote_id: "BH-1234",
+          confidence: "medium",
+        },
+      ],
+    },
+    scaling_factors: [],
+    capex_contribution: {
+      year: 2022,
+      currency: "USD",
+      cost: { type: "linear", base_cost: 1000 },
+    },
+    variable_opex_contributions: [],
+  } satisfies CostLibraryCostItem;
+
+  const library: CostLibrary = {
+    modules: [
+      {
+        id: "M0201",
+        definition: { type: "CaptureUnit" },
+        subtype: "Cryogenic",
+        cost_items: [item],
+      },
+    ],
+  };
+
+  it("replaces vendor names and drops source details", () => {
+    const [anonymized] = anonymizeCostLibrary(library, {
+      vendors: ["Baker Hughes"],
+    }).modules[0].cost_items;
+
+    expect(anonymized.info.short_name).toBe("CAP Process ([vendor])");
+    expect(anonymized.info.description).toBe("[vendor] cryogenic capture");
+    expect(anonymized.info.source_reference_detail).toBeNull();
+    expect(anonymized.info.note).toBeNull();
+    expect(anonymized.info.source_reference).toBe("Ref 025");
+    expect(anonymized.info.sources).toEqual([
+      { report: "[vendor] budget offer", confidence: "medium" },
+    ]);
+    expect(anonymized.capex_contribution).toEqual(item.capex_contribution);
+  });
+});
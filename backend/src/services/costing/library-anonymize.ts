@@ -0,0 +1,77 @@
+/**
+ * Cost library anonymization.
+ *
+ * Produces a copy of a library that can be shared outside the team: vendor
+ * names are replaced in item names, descriptions, translations and source
+ * report names, and the free-text source details, notes and vendor quote IDs
+ * (which identify vendors and offers) are dropped. Cost data is left
+ * untouched.
+ */
+
+import type { CostLibrary } from "./types";
+
+export const VENDOR_PLACEHOLDER = "[vendor]";
+
+export type AnonymizeOptions = {
+  /** Vendor names to replace (matched case-insensitively as whole words) */
+  vendors: string[];
+};
+
+/**
+ * Return an anonymized copy of a cost library.
+ */
+export function anonymizeCostLibrary(
+  library: CostLibrary,
+  options: AnonymizeOptions,
+): CostLibrary {
+  const patterns = options.vendors
+    .map((v) => v.trim())
+    .filter((v) => v !== "")
+    .map((v) => new RegExp(`(?<!\\w)${escapeRegExp(v)}(?!\\w)`, "gi"));
+  const scrub = (text: string) =>
+    patterns.reduce((t, p) => t.replace(p, VENDOR_PLACEHOLDER), text);
+
+  return {
+    ...library,
+    modules: library.modules.map((module) => ({
+      ...module,
+      cost_items: (module.cost_items ?? []).map((item) => ({
+        ...item,
+        info: {
+          ...item.info,
+          short_name: scrub(item.info.short_name),
+          description: scrub(item.info.description),
+          source_reference_detail: null,
+          note: null,
+          ...(item.info.translations && {
+            translations: Object.fromEntries(
+              Object.entries(item.info.translations).map(([tag, text]) => [
+                tag,
+                {
+                  ...(text.short_name !== undefined && {
+                    short_name: scrub(text.short_name),
+                  }),
+                  ...(text.description !== undefined && {
+                    description: scrub(text.description),
+                  }),
+                },
+              ]),
+            ),
+          }),
+          ...(item.info.sources && {
+            sources: item.info.sources.map(
+              ({ vendor_quote_id: _, ...source }) => ({
+                ...source,
+                report: scrub(source.report),
+              }),
+            ),
+          }),
+        },
+      })),
+    })),
+  };
+}
+
+function escapeRegExp(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
@@ -0,0 +1,38 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { diffCostLibraries } from "./library-diff";
+import { loadCostLibrary } from "./module-lookup";
+import type { CostLibrary } from "./types";
+
+describe("library-diff", () => {
+  let library: CostLibrary;
+
+  beforeAll(async () => {
+    library = await loadCostLibrary("V1.1_working");
+  });
+
+  it("reports identical libraries", () => {
+    const diff = diffCostLibraries(library, structuredClone(library));
+
+    expect(diff.identical).toBe(true);
+  });
+
+  it("reports added, removed and changed entries", () => {
+    const changed = structuredClone(library);
+    changed.modules = changed.modules.filter((m) => m.id !== "M0101");
+    for (const item of changed.modules.flatMap((m) => m.cost_items)) {
+      if (item.id === "Item 023") item.info.note = "Reviewed";
+    }
+    changed.currency_conversion!.rates.USD = 0.9;
+
+    const diff = diffCostLibraries(library, changed);
+
+    expect(diff.identical).toBe(false);
+    expect(diff.modules.removed).toEqual(["M0101"]);
+    expect(diff.modules.changed).toContain("M0201");
+    expect(diff.costItems.changed).toEqual([
+      { id: "Item 023", fields: ["info.note"] },
+    ]);
+    expect(diff.currencyRates).toEqual(["USD"]);
+    expect(diff.inflationFactors).toEqual([]);
+  });
+});
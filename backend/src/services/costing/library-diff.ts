@@ -0,0 +1,152 @@
+/**
+ * Cost library comparison.
+ *
+ * Summarises what changed between two libraries (typically two versions of
+ * the same library): modules and cost items added, removed or changed, and
+ * currency rates and inflation factors that moved.
+ */
+
+import type { CostLibrary, CostLibraryCostItem } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type IdChanges = {
+  added: string[];
+  removed: string[];
+  changed: string[];
+};
+
+export type CostItemChange = {
+  id: string;
+  /** Changed fields, e.g. "capex_contribution.cost", "info.note" */
+  fields: string[];
+};
+
+export type CostLibraryDiff = {
+  modules: IdChanges;
+  costItems: Omit<IdChanges, "changed"> & { changed: CostItemChange[] };
+  /** Currency codes whose rate was added, removed or changed */
+  currencyRates: string[];
+  /** Years whose inflation factor was added, removed or changed */
+  inflationFactors: string[];
+  /** True when the libraries are equivalent */
+  identical: boolean;
+};
+
+// ============================================================================
+// Diff
+// ============================================================================
+
+/**
+ * Compare two cost libraries.
+ */
+export function diffCostLibraries(
+  from: CostLibrary,
+  to: CostLibrary,
+): CostLibraryDiff {
+  const modules = diffById(
+    new Map(from.modules.map((m) => [m.id, m])),
+    new Map(to.modules.map((m) => [m.id, m])),
+  );
+
+  const itemsOf = (library: CostLibrary) =>
+    new Map(
+      library.modules
+        .flatMap((m) => m.cost_items ?? [])
+        .map((item) => [item.id, item]),
+    );
+  const fromItems = itemsOf(from);
+  const toItems = itemsOf(to);
+  const items = diffById(fromItems, toItems);
+
+  const costItems = {
+    added: items.added,
+    removed: items.removed,
+    changed: items.changed.map((id) => ({
+      id,
+      fields: changedFields(fromItems.get(id)!, toItems.get(id)!),
+    })),
+  };
+
+  const currencyRates = changedKeys(
+    from.currency_conversion?.rates ?? {},
+    to.currency_conversion?.rates ?? {},
+  );
+  const inflationFactors = changedKeys(
+    from.inflation?.factors ?? {},
+    to.inflation?.factors ?? {},
+  );
+
+  return {
+    modules,
+    costItems,
+    currencyRates,
+    inflationFactors,
+    identical:
+      [modules, items].every(
+        (d) => d.added.length + d.removed.length + d.changed.length === 0,
+      ) &&
+      currencyRates.length === 0 &&
+      inflationFactors.length === 0,
+  };
+}
+
+function diffById<T>(from: Map<string, T>, to: Map<string, T>): IdChanges {
+  return {
+    added: [...to.keys()].filter((id) => !from.has(id)),
+    removed: [...from.keys()].filter((id) => !to.has(id)),
+    changed: [...to.keys()].filter(
+      (id) => from.has(id) && !isEqual(from.get(id), to.get(id)),
+    ),
+  };
+}
+
+/**
+ * Fields of a cost item that differ. Info and capex fields are compared one
+ * level down so a note edit isn't reported as a change to all of "info".
+ */
+function changedFields(
+  from: CostLibraryCostItem,
+  to: CostLibraryCostItem,
+): string[] {
+  const fields: string[] = [];
+  for (const key of ["info", "capex_contribution"] as const) {
+    for (const field of changedKeys(
+      (from[key] ?? {}) as Record<string, unknown>,
+      (to[key] ?? {}) as Record<string, unknown>,
+    )) {
+      fields.push(`${key}.${field}`);
+    }
+  }
+  const listKeys = ["scaling_factors", "variable_opex_contributions"] as const;
+  for (const key of listKeys) {
+    if (!isEqual(from[key], to[key])) fields.push(key);
+  }
+  return fields;
+}
+
+function changedKeys(
+  from: Record<string, unknown>,
+  to: Record<string, unknown>,
+): string[] {
+  const keys = new Set([...Object.keys(from), ...Object.keys(to)]);
+  return [...keys].filter((key) => !isEqual(from[key], to[key]));
+}
+
+function isEqual(a: unknown, b: unknown): boolean {
+  return JSON.stringify(sortKeys(a)) === JSON.stringify(sortKeys(b));
+}
+
+function sortKeys(value: unknown): unknown {
+  if (Array.isArray(value)) return value.map(sortKeys);
+  if (value && typeof value === "object") {
+    return Object.fromEntries(
+      Object.entries(value)
+        .sort(([a], [b]) => a.localeCompare(b))
+        .map(([k, v]) => [k, sortKeys(v)]),
+    );
+  }
+  return value;
+}
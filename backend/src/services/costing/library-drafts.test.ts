@@ -0,0 +1,176 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import { cp, mkdtemp, readFile, rm, writeFile } from "fs/promises";
+import { generateKeyPairSync } from "crypto";
+import { tmpdir } from "os";
+import { join, resolve } from "path";
+import {
+  createDraft,
+  getDraft,
+  patchCostItem,
+  deleteModule,
+  publishDraft,
+  getDraftPackage,
+  listLibraryVersions,
+  getLibraryVersion,
+  mergePatch,
+  LibraryDraftError,
+} from "./library-drafts";
+import { loadCostLibrary } from "./module-lookup";
+import {
+  publicKeyBase64,
+  SIGNATURE_FILE,
+  signLibrary,
+} from "./library-signing";
+
+const LIBRARY_ID = "V1.1_working";
+
+describe("library-drafts", () => {
+  let dataRoot: string;
+
+  beforeEach(async () => {
+    const source = resolve(process.cwd(), "data/costing", LIBRARY_ID);
+    dataRoot = await mkdtemp(join(tmpdir(), "library-drafts-"));
+    await cp(source, join(dataRoot, "data/costing", LIBRARY_ID), {
+      recursive: true,
+    });
+    vi.spyOn(process, "cwd").mockReturnValue(dataRoot);
+  });
+
+  afterEach(async () => {
+    vi.restoreAllMocks();
+    vi.unstubAllEnvs();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("refuses a second draft of the same library", async () => {
+    await createDraft(LIBRARY_ID);
+
+    await expect(createDraft(LIBRARY_ID)).rejects.toMatchObject({
+      status: 409,
+    });
+  });
+
+  it("patches shared cost items in every module", async () => {
+    await createDraft(LIBRARY_ID);
+    await patchCostItem(LIBRARY_ID, "Item 023", {
+      info: { note: "Reviewed" },
+    });
+
+    const items = (await getDraft(LIBRARY_ID)).modules
+      .flatMap((m) => m.cost_items ?? [])
+      .filter((i) => i.id === "Item 023");
+    expect(items.length).toBeGreaterThan(0);
+    expect(items.every((i) => i.info.note === "Reviewed")).toBe(true);
+    expect(items[0].info.short_name).toBeTruthy();
+  });
+
+  it("keeps every one of concurrent edits", async () => {
+    await createDraft(LIBRARY_ID);
+
+    await Promise.all([
+      patchCostItem(LIBRARY_ID, "Item 023", { info: { note: "Reviewed" } }),
+      deleteModule(LIBRARY_ID, "M0101"),
+    ]);
+
+    const draft = await getDraft(LIBRARY_ID);
+    expect(draft.modules.some((m) => m.id === "M0101")).toBe(false);
+    expect(
+      draft.modules
+        .flatMap((m) => m.cost_items ?? [])
+        .find((i) => i.id === "Item 023")?.info.note,
+    ).toBe("Reviewed");
+  });
+
+  it("publishes a valid draft and records the version history", async () => {
+    await createDraft(LIBRARY_ID);
+    await deleteModule(LIBRARY_ID, "M0101");
+
+    const version = await publishDraft(LIBRARY_ID, "jsmith");
+
+    expect(version.version).toBe(2);
+    expect(version.publishedBy).toBe("jsmith");
+    const published = await loadCostLibrary(LIBRARY_ID);
+    expect(published.modules.some((m) => m.id === "M0101")).toBe(false);
+    expect(await listLibraryVersions(LIBRARY_ID)).toEqual([
+      { version: 1, publishedAt: null },
+      { version: 2, publishedAt: version.publishedAt, publishedBy: "jsmith" },
+    ]);
+    const original = await getLibraryVersion(LIBRARY_ID, 1);
+    expect(original.library.modules.some((m) => m.id === "M0101")).toBe(true);
+    await expect(getDraft(LIBRARY_ID)).rejects.toBeInstanceOf(
+      LibraryDraftError,
+    );
+  });
+
+  it("only publishes signed drafts when signatures are required", async () => {
+    const { privateKey } = generateKeyPairSync("ed25519");
+    const pem = privateKey.export({ type: "pkcs8", format: "pem" }).toString();
+    const libraryDir = join(dataRoot, "data/costing", LIBRARY_ID);
+    const sign = (content: Uint8Array) =>
+      signLibrary(content, pem, "release", LIBRARY_ID);
+    await writeFile(
+      join(libraryDir, SIGNATURE_FILE),
+      JSON.stringify(
+        sign(await readFile(join(libraryDir, "cost-library.json"))),
+      ),
+    );
+    vi.stubEnv("LIBRARY_SIGNATURES", "require");
+    vi.stubEnv(
+      "LIBRARY_SIGNING_KEYS",
+      `release:${publicKeyBase64(privateKey)}`,
+    );
+    await createDraft(LIBRARY_ID);
+    await deleteModule(LIBRARY_ID, "M0101");
+
+    await expect(publishDraft(LIBRARY_ID)).rejects.toMatchObject({
+      status: 409,
+    });
+    await expect(
+      publishDraft(LIBRARY_ID, undefined, sign(Buffer.from("{}"))),
+    ).rejects.toMatchObject({ status: 409 });
+    // The signed library is still served
+    expect(
+      (await loadCostLibrary(LIBRARY_ID)).modules.some((m) => m.id === "M0101"),
+    ).toBe(true);
+
+    await publishDraft(
+      LIBRARY_ID,
+      undefined,
+      sign(await getDraftPackage(LIBRARY_ID)),
+    );
+
+    const published = await loadCostLibrary(LIBRARY_ID);
+    expect(published.modules.some((m) => m.id === "M0101")).toBe(false);
+  });
+
+  it("rejects an invalid draft with its validation issues", async () => {
+    await createDraft(LIBRARY_ID);
+    await patchCostItem(LIBRARY_ID, "Item 023", {
+      capex_contribution: { currency: "XYZ" },
+    });
+
+    await expect(publishDraft(LIBRARY_ID)).rejects.toMatchObject({
+      status: 422,
+      issues: expect.arrayContaining([
+        expect.objectContaining({
+          message: "no conversion rate for XYZ",
+        }),
+      ]),
+    });
+  });
+
+  it("rejects library IDs that leave the data directory", async () => {
+    await expect(createDraft("..")).rejects.toMatchObject({ status: 404 });
+  });
+
+  describe("mergePatch", () => {
+    it("merges objects, replaces arrays and removes nulls", () => {
+      expect(
+        mergePatch(
+          { a: 1, b: { c: 2, d: 3 }, e: [1, 2] },
+          { b: { c: 4, d: null }, e: [3] },
+        ),
+      ).toEqual({ a: 1, b: { c: 4 }, e: [3] });
+    });
+  });
+});
@@ -0,0 +1,496 @@
+/**
+ * Cost library drafts and publishing.
+ *
+ * Libraries are edited through a draft: a working copy of a published library
+ * that can be changed module by module and cost item by cost item. Publishing
+ * validates the draft, replaces the published library and appends it to the
+ * library's version history. History entries are written once and never
+ * modified.
+ *
+ * Publishing replaces the library file, so its old signature no longer
+ * matches. A publish may carry a signature over the draft's package (the
+ * exact bytes the library file will have), which is verified and written
+ * alongside; when signatures are required, publishing without one is
+ * refused so the live library never becomes unloadable.
+ *
+ * Draft operations on a library run one at a time, so concurrent edits
+ * can't overwrite each other and a publish sees a settled draft.
+ */
+
+import { mkdir, rename, rm, writeFile } from "fs/promises";
+import { dirname, join } from "path";
+import type {
+  CostLibrary,
+  CostLibraryModule,
+  CostLibraryCostItem,
+} from "./types";
+import { clearModuleLookupCache, loadCostLibrary } from "./module-lookup";
+import { validateCostLibrary, type LibraryIssue } from "./library-validation";
+import { getDataDir } from "../../utils/data-dir";
+import { getStorage, StorageConflictError } from "../storage";
+import {
+  LibrarySignatureError,
+  SIGNATURE_FILE,
+  signaturePolicyFromEnv,
+  signaturesRequired,
+  verifyLibrary,
+  type LibrarySignature,
+} from "./library-signing";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type LibraryVersion = {
+  version: number;
+  /** ISO timestamp, or null for the library as it was before its first publish */
+  publishedAt: string | null;
+  /** ID of the user who published the version, when known */
+  publishedBy?: string;
+};
+
+export type LibraryVersionRecord = LibraryVersion & {
+  library: CostLibrary;
+};
+
+/**
+ * Error raised by draft operations, carrying the HTTP status to report.
+ */
+export class LibraryDraftError extends Error {
+  constructor(
+    message: string,
+    readonly status: 404 | 409 | 422,
+    readonly issues: LibraryIssue[] = [],
+  ) {
+    super(message);
+    this.name = "LibraryDraftError";
+  }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/**
+ * Library IDs become file and directory names, so reject anything that could
+ * step outside the data directory.
+ */
+function assertLibraryId(libraryId: string): void {
+  if (!/^[\w.-]+$/.test(libraryId) || /^\.+$/.test(libraryId)) {
+    throw new LibraryDraftError(`Invalid library ID ${libraryId}`, 404);
+  }
+}
+
+const DRAFTS = "costing-drafts";
+
+function historyCollection(libraryId: string): string {
+  assertLibraryId(libraryId);
+  return `costing-history/${libraryId}`;
+}
+
+function publishedPath(libraryId: string): string {
+  assertLibraryId(libraryId);
+  return join(getDataDir(), "costing", libraryId, "cost-library.json");
+}
+
+function toJsonBytes(data: unknown): Uint8Array {
+  return new TextEncoder().encode(JSON.stringify(data, null, 2) + "\n");
+}
+
+/**
+ * Replace the published library and its signature file. Both are written
+ * in full beside their targets first and then renamed into place, so a
+ * crash can't leave either half-written.
+ */
+async function writePublished(
+  libraryId: string,
+  library: CostLibrary,
+  signature: LibrarySignature | undefined,
+): Promise<void> {
+  const path = publishedPath(libraryId);
+  const signaturePath = join(dirname(path), SIGNATURE_FILE);
+  await mkdir(dirname(path), { recursive: true });
+  await writeFile(`${path}.publishing`, toJsonBytes(library));
+  if (signature) {
+    await writeFile(
+      `${signaturePath}.publishing`,
+      JSON.stringify(signature, null, 2) + "\n",
+    );
+  }
+
+  await rename(`${path}.publishing`, path);
+  if (signature) {
+    await rename(`${signaturePath}.publishing`, signaturePath);
+  } else {
+    // The old signature no longer matches; the new version is signed offline
+    await rm(signaturePath, { force: true });
+  }
+}
+
+// Draft operations are chained per library, like the storage log appends
+const draftQueues = new Map<string, Promise<unknown>>();
+
+function withDraftLock<T>(
+  libraryId: string,
+  task: () => Promise<T>,
+): Promise<T> {
+  const result = (draftQueues.get(libraryId) ?? Promise.resolve()).then(task);
+  const settled = result.catch(() => undefined);
+  draftQueues.set(libraryId, settled);
+  void settled.then(() => {
+    if (draftQueues.get(libraryId) === settled) draftQueues.delete(libraryId);
+  });
+  return result;
+}
+
+function isMissing(error: unknown): boolean {
+  return (error as NodeJS.ErrnoException)?.code === "ENOENT";
+}
+
+// ============================================================================
+// Drafts
+// ============================================================================
+
+/**
+ * Start a draft from the published library.
+ */
+export async function createDraft(libraryId: string): Promise<CostLibrary> {
+  assertLibraryId(libraryId);
+  return withDraftLock(libraryId, async () => {
+    let library: CostLibrary;
+    try {
+      library = await loadCostLibrary(libraryId);
+    } catch (error) {
+      if (isMissing(error)) {
+        throw new LibraryDraftError(`Library ${libraryId} not found`, 404);
+      }
+      throw error;
+    }
+
+    try {
+      await getStorage().put(DRAFTS, libraryId, library, { create: true });
+    } catch (error) {
+      if (error instanceof StorageConflictError) {
+        throw new LibraryDraftError(
+          `Library ${libraryId} already has a draft`,
+          409,
+        );
+      }
+      throw error;
+    }
+    return library;
+  });
+}
+
+export async function getDraft(libraryId: string): Promise<CostLibrary> {
+  assertLibraryId(libraryId);
+  const draft = await getStorage().get<CostLibrary>(DRAFTS, libraryId);
+  if (!draft) {
+    throw new LibraryDraftError(`Library ${libraryId} has no draft`, 404);
+  }
+  return draft;
+}
+
+export function discardDraft(libraryId: string): Promise<void> {
+  return withDraftLock(libraryId, async () => {
+    await getDraft(libraryId);
+    await getStorage().delete(DRAFTS, libraryId);
+  });
+}
+
+/**
+ * Load the draft, apply an edit and save it.
+ */
+function editDraft(
+  libraryId: string,
+  edit: (draft: CostLibrary) => void,
+): Promise<CostLibrary> {
+  return withDraftLock(libraryId, async () => {
+    const draft = await getDraft(libraryId);
+    edit(draft);
+    await getStorage().put(DRAFTS, libraryId, draft);
+    return draft;
+  });
+}
+
+function findModule(draft: CostLibrary, moduleId: string): CostLibraryModule {
+  const module = draft.modules.find((m) => m.id === moduleId);
+  if (!module) {
+    throw new LibraryDraftError(`Module ${moduleId} not found in draft`, 404);
+  }
+  return module;
+}
+
+/**
+ * Create or replace a module in the draft.
+ */
+export function putModule(
+  libraryId: string,
+  module: CostLibraryModule,
+): Promise<CostLibrary> {
+  return editDraft(libraryId, (draft) => {
+    const index = draft.modules.findIndex((m) => m.id === module.id);
+    if (index === -1) {
+      draft.modules.push(module);
+    } else {
+      draft.modules[index] = module;
+    }
+  });
+}
+
+export function deleteModule(
+  libraryId: string,
+  moduleId: string,
+): Promise<CostLibrary> {
+  return editDraft(libraryId, (draft) => {
+    findModule(draft, moduleId);
+    draft.modules = draft.modules.filter((m) => m.id !== moduleId);
+  });
+}
+
+/**
+ * Add a cost item to a module in the draft.
+ */
+export function addCostItem(
+  libraryId: string,
+  moduleId: string,
+  item: CostLibraryCostItem,
+): Promise<CostLibrary> {
+  return editDraft(libraryId, (draft) => {
+    const module = findModule(draft, moduleId);
+    module.cost_items ??= [];
+    if (module.cost_items.some((i) => i.id === item.id)) {
+      throw new LibraryDraftError(
+        `Module ${moduleId} already has cost item ${item.id}`,
+        409,
+      );
+    }
+    module.cost_items.push(item);
+  });
+}
+
+/**
+ * Apply a JSON merge patch (RFC 7396) to a cost item. Cost items shared
+ * between modules are patched everywhere so they stay identical.
+ */
+export function patchCostItem(
+  libraryId: string,
+  itemId: string,
+  patch: Record<string, unknown>,
+): Promise<CostLibrary> {
+  if (patch.id !== undefined && patch.id !== itemId) {
+    throw new LibraryDraftError("Cost item IDs cannot be changed", 409);
+  }
+
+  return editDraft(libraryId, (draft) => {
+    let found = false;
+    for (const module of draft.modules) {
+      module.cost_items = (module.cost_items ?? []).map((item) => {
+        if (item.id !== itemId) return item;
+        found = true;
+        return mergePatch(item, patch) as CostLibraryCostItem;
+      });
+    }
+    if (!found) {
+      throw new LibraryDraftError(`Cost item ${itemId} not found in draft`, 404);
+    }
+  });
+}
+
+export function deleteCostItem(
+  libraryId: string,
+  moduleId: string,
+  itemId: string,
+): Promise<CostLibrary> {
+  return editDraft(libraryId, (draft) => {
+    const module = findModule(draft, moduleId);
+    const items = module.cost_items ?? [];
+    if (!items.some((i) => i.id === itemId)) {
+      throw new LibraryDraftError(
+        `Module ${moduleId} has no cost item ${itemId}`,
+        404,
+      );
+    }
+    module.cost_items = items.filter((i) => i.id !== itemId);
+  });
+}
+
+/**
+ * RFC 7396 merge patch: objects merge recursively, null removes a key, and
+ * anything else (including arrays) replaces the target value.
+ */
+export function mergePatch(target: unknown, patch: unknown): unknown {
+  if (!isPlainObject(patch)) {
+    return patch;
+  }
+  const result: Record<string, unknown> = isPlainObject(target)
+    ? { ...target }
+    : {};
+  for (const [key, value] of Object.entries(patch)) {
+    if (value === null) {
+      delete result[key];
+    } else {
+      result[key] = mergePatch(result[key], value);
+    }
+  }
+  return result;
+}
+
+function isPlainObject(value: unknown): value is Record<string, unknown> {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+// ============================================================================
+// Publishing
+// ============================================================================
+
+/**
+ * List the published versions of a library, oldest first.
+ */
+export async function listLibraryVersions(
+  libraryId: string,
+): Promise<LibraryVersion[]> {
+  const records = await readHistory(libraryId);
+  return records.map(({ version, publishedAt, publishedBy }) => ({
+    version,
+    publishedAt,
+    ...(publishedBy !== undefined && { publishedBy }),
+  }));
+}
+
+export async function getLibraryVersion(
+  libraryId: string,
+  version: number,
+): Promise<LibraryVersionRecord> {
+  const record = (await readHistory(libraryId)).find(
+    (r) => r.version === version,
+  );
+  if (!record) {
+    throw new LibraryDraftError(
+      `Library ${libraryId} has no version ${version}`,
+      404,
+    );
+  }
+  return record;
+}
+
+async function readHistory(libraryId: string): Promise<LibraryVersionRecord[]> {
+  const storage = getStorage();
+  const collection = historyCollection(libraryId);
+  const records = await Promise.all(
+    (await storage.keys(collection)).map((key) =>
+      storage.get<LibraryVersionRecord>(collection, key),
+    ),
+  );
+  return records
+    .filter((r): r is LibraryVersionRecord => r !== undefined)
+    .sort((a, b) => a.version - b.version);
+}
+
+/**
+ * The bytes the published library file will have if the draft is
+ * published: what a signature for the publish must be made over.
+ */
+export async function getDraftPackage(libraryId: string): Promise<Uint8Array> {
+  return toJsonBytes(await getDraft(libraryId));
+}
+
+/**
+ * Validate the draft and publish it as the library's next version.
+ *
+ * The first publish also records the library as it was before, as version 1,
+ * so every published state stays retrievable. `publishedBy` is the ID of the
+ * user publishing, recorded in the version history. `signature` signs the
+ * draft's package and is published with it.
+ *
+ * @throws LibraryDraftError (409) if the signature doesn't verify, none is
+ *   given when signatures are required, or the version was published
+ *   elsewhere at the same time
+ */
+export function publishDraft(
+  libraryId: string,
+  publishedBy?: string,
+  signature?: LibrarySignature,
+): Promise<LibraryVersion> {
+  return withDraftLock(libraryId, async () => {
+    const draft = await getDraft(libraryId);
+
+    const issues = validateCostLibrary(draft);
+    if (issues.length > 0) {
+      throw new LibraryDraftError(
+        `Draft of ${libraryId} has ${issues.length} validation issue(s)`,
+        422,
+        issues,
+      );
+    }
+
+    const policy = signaturePolicyFromEnv();
+    if (!signature && signaturesRequired(policy)) {
+      throw new LibraryDraftError(
+        `Cost library signatures are required; sign the draft package of ` +
+          `${libraryId} and publish with its signature`,
+        409,
+      );
+    }
+    if (signature) {
+      try {
+        verifyLibrary(libraryId, toJsonBytes(draft), signature, policy);
+      } catch (error) {
+        if (error instanceof LibrarySignatureError) {
+          throw new LibraryDraftError(error.message, 409);
+        }
+        throw error;
+      }
+    }
+
+    const history = await readHistory(libraryId);
+    // Kept as version 1 on the first publish
+    const original =
+      history.length === 0 ? await loadCostLibrary(libraryId) : undefined;
+    const version: LibraryVersion = {
+      version: Math.max(1, ...history.map((r) => r.version)) + 1,
+      publishedAt: new Date().toISOString(),
+      ...(publishedBy !== undefined && { publishedBy }),
+    };
+
+    // The library and its signature go first, so history only ever records
+    // versions that were published
+    await writePublished(libraryId, draft, signature);
+    clearModuleLookupCache();
+    try {
+      if (original) {
+        await appendVersion(libraryId, {
+          version: 1,
+          publishedAt: null,
+          library: original,
+        });
+      }
+      await appendVersion(libraryId, { ...version, library: draft });
+    } catch (error) {
+      if (error instanceof StorageConflictError) {
+        throw new LibraryDraftError(
+          `Library ${libraryId} was published at the same time; ` +
+            "check its versions and publish again",
+          409,
+        );
+      }
+      throw error;
+    }
+    await getStorage().delete(DRAFTS, libraryId);
+
+    return version;
+  });
+}
+
+function appendVersion(
+  libraryId: string,
+  record: LibraryVersionRecord,
+): Promise<void> {
+  // Fails rather than overwrite an existing version
+  return getStorage().put(
+    historyCollection(libraryId),
+    String(record.version),
+    record,
+    { create: true },
+  );
+}
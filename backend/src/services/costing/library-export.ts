@@ -0,0 +1,130 @@
+/**
+ * Cost library export to the standard spreadsheet layout.
+ *
+ * The inverse of library-import: writes one row per cost item per module,
+ * using the same columns, so libraries can round-trip through a spreadsheet.
+ * Currency conversion, inflation and defaults are not part of the layout.
+ */
+
+import { formatCsv } from "../../utils/csv";
+import type { CostLibrary, CostLibraryCostItem } from "./types";
+
+const INFO_COLUMNS = [
+  "short_name",
+  "description",
+  "item_type",
+  "reference_quality",
+  "source_reference",
+  "source_reference_detail",
+  "confidentiality",
+  "cost_type",
+  "cost_location",
+  "note",
+] as const;
+
+/**
+ * Export a cost library as CSV in the standard layout.
+ */
+export function exportCostLibraryCsv(
+  library: CostLibrary,
+  delimiter = ",",
+): string {
+  const items = library.modules.flatMap((m) => m.cost_items ?? []);
+  const maxOf = (count: (item: CostLibraryCostItem) => number) =>
+    Math.max(0, ...items.map(count));
+  const sfCount = maxOf((i) => i.scaling_factors?.length ?? 0);
+  const termCount = maxOf(
+    (i) => i.capex_contribution.cost.parameters?.length ?? 0,
+  );
+  const opexCount = maxOf((i) => i.variable_opex_contributions?.length ?? 0);
+
+  const definitionFields = Array.from(
+    new Set(
+      library.modules.flatMap((m) =>
+        Object.keys(m.definition).filter((k) => k !== "type"),
+      ),
+    ),
+  );
+
+  const header = [
+    "module_id",
+    "module_type",
+    "module_subtype",
+    ...definitionFields.map((f) => `definition.${f}`),
+    "item_id",
+    ...INFO_COLUMNS,
+    "cost_year",
+    "currency",
+    "curve_type",
+    "base_cost",
+    ...range(sfCount).flatMap((n) => [
+      `sf${n}_name`,
+      `sf${n}_units`,
+      `sf${n}_source_value`,
+    ]),
+    ...range(termCount).flatMap((n) => [
+      `term${n}_dimension`,
+      `term${n}_coefficient`,
+      `term${n}_exponent`,
+      `term${n}_value`,
+    ]),
+    ...range(opexCount).flatMap((n) => [
+      `opex${n}_name`,
+      `opex${n}_units`,
+      `opex${n}_scaled_by`,
+    ]),
+  ];
+
+  const rows: unknown[][] = [header];
+  for (const module of library.modules) {
+    const moduleCells = [
+      module.id,
+      module.definition.type,
+      module.subtype,
+      ...definitionFields.map((f) => module.definition[f]),
+    ];
+    const moduleItems = module.cost_items ?? [];
+
+    if (moduleItems.length === 0) {
+      rows.push(moduleCells);
+      continue;
+    }
+
+    for (const item of moduleItems) {
+      const { year, currency, cost } = item.capex_contribution;
+      rows.push([
+        ...moduleCells,
+        item.id,
+        ...INFO_COLUMNS.map((c) => item.info?.[c]),
+        year,
+        currency,
+        cost.type,
+        cost.base_cost,
+        ...range(sfCount).flatMap((n) => {
+          const sf = item.scaling_factors?.[n - 1];
+          return [sf?.name, sf?.units, sf?.source_value];
+        }),
+        ...range(termCount).flatMap((n) => {
+          const term = cost.parameters?.[n - 1];
+          return [
+            term?.dimension_name,
+            term?.coefficient,
+            term?.exponent,
+            term?.value,
+          ];
+        }),
+        ...range(opexCount).flatMap((n) => {
+          const opex = item.variable_opex_contributions?.[n - 1];
+          return [opex?.name, opex?.units, opex?.scaled_by];
+        }),
+      ]);
+    }
+  }
+
+  return formatCsv(rows, delimiter);
+}
+
+/** 1-based column group numbers */
+function range(count: number): number[] {
+  return Array.from({ length: count }, (_, i) => i + 1);
+}
@@ -0,0 +1,121 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { importCostLibraryCsv } from "./library-import";
+import { exportCostLibraryCsv } from "./library-export";
+import { loadCostLibrary } from "./module-lookup";
+import { parseCsv } from "../../utils/csv";
+import type { CostLibrary } from "./types";
+
+const HEADER =
+  "module_id,module_type,module_subtype,item_id,short_name,description," +
+  "cost_year,currency,curve_type,base_cost,sf1_name,sf1_units,sf1_source_value," +
+  "term1_dimension,term1_coefficient,term1_exponent,term2_value";
+
+describe("library-import", () => {
+  let base: CostLibrary;
+
+  beforeAll(async () => {
+    base = await loadCostLibrary("V1.1_working");
+  });
+
+  it("builds modules and cost items from rows", () => {
+    const csv = [
+      HEADER,
+      'M0201,CaptureUnit,Amine,Item 023,Capture,"Amine (MEA), with ""notes""",2011,usd,linear,1000000,Mass flow,kg/h,342465.75,,,,',
+      "M0202,CaptureUnit,Other,Item 023,Capture,\"Amine (MEA), with \"\"notes\"\"\",2011,usd,linear,1000000,Mass flow,kg/h,342465.75,,,,",
+      "M0901,Well,,Item 034,Well,Drilling,2014,USD,polynomial,,Well depth,m,0,Well depth,1.65e-05,1.607,5000",
+    ].join("\n");
+
+    const result = importCostLibraryCsv(csv, { base });
+
+    expect(result.rowErrors).toEqual([]);
+    expect(result.issues).toEqual([]);
+    expect(result.isValid).toBe(true);
+    expect(result.library.modules.map((m) => m.id)).toEqual([
+      "M0201",
+      "M0202",
+      "M0901",
+    ]);
+
+    const [amine] = result.library.modules[0].cost_items;
+    expect(amine.info.description).toBe('Amine (MEA), with "notes"');
+    expect(amine.capex_contribution).toEqual({
+      year: 2011,
+      currency: "USD",
+      cost: { type: "linear", base_cost: 1000000 },
+    });
+    expect(amine.scaling_factors).toEqual([
+      { name: "Mass flow", units: "kg/h", source_value: 342465.75 },
+    ]);
+
+    expect(
+      result.library.modules[2].cost_items[0].capex_contribution.cost,
+    ).toEqual({
+      type: "polynomial",
+      parameters: [
+        { dimension_name: "Well depth", coefficient: 1.65e-5, exponent: 1.607 },
+        { value: 5000 },
+      ],
+    });
+    expect(result.library.inflation).toBe(base.inflation);
+  });
+
+  it("reports unreadable cells against their row and column", () => {
+    const csv = [
+      HEADER,
+      "M0201,CaptureUnit,Amine,Item 023,,,20x1,USD,cubic,,,,,,,,",
+    ].join("\n");
+
+    const { rowErrors, isValid } = importCostLibraryCsv(csv, { base });
+
+    expect(isValid).toBe(false);
+    expect(rowErrors).toEqual([
+      { row: 2, column: "cost_year", message: '"20x1" is not a number' },
+      {
+        row: 2,
+        column: "curve_type",
+        message: 'must be "linear" or "polynomial"',
+      },
+    ]);
+  });
+
+  it("maps validation issues back to spreadsheet rows", () => {
+    const csv = [
+      HEADER,
+      "M0201,CaptureUnit,Amine,Item 023,,,2011,XYZ,linear,100,,,,,,,",
+    ].join("\n");
+
+    const { issues } = importCostLibraryCsv(csv, { base });
+
+    expect(issues).toEqual([
+      {
+        path: "modules[0].cost_items[0].capex_contribution.currency",
+        message: "no conversion rate for XYZ",
+        row: 2,
+      },
+    ]);
+  });
+
+  it("requires the identifying columns", () => {
+    const { rowErrors } = importCostLibraryCsv("item_id,cost_year\n");
+
+    expect(rowErrors).toEqual([
+      { row: 1, message: "missing required column(s): module_id, module_type" },
+    ]);
+  });
+
+  it("round-trips a library through the exported layout", () => {
+    const result = importCostLibraryCsv(exportCostLibraryCsv(base), { base });
+
+    expect(result.rowErrors).toEqual([]);
+    expect(result.library.modules).toEqual(base.modules);
+  });
+
+  describe("parseCsv", () => {
+    it("handles quoted delimiters, quotes and line breaks", () => {
+      expect(parseCsv('a,"b,c","d ""e""","f\r\ng"\r\n\r\nh,i\n')).toEqual([
+        ["a", "b,c", 'd "e"', "f\r\ng"],
+        ["h", "i"],
+      ]);
+    });
+  });
+});
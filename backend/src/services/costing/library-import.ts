@@ -0,0 +1,323 @@
+/**
+ * Cost library import from spreadsheets.
+ *
+ * Converts the standard cost-data spreadsheet layout, exported as CSV, into a
+ * CostLibrary. The layout has one row per cost item (repeated for each module
+ * that shares the item) with these columns:
+ *
+ * - module_id, module_type, module_subtype, definition.<field>
+ * - item_id, short_name, description, item_type, reference_quality,
+ *   source_reference, source_reference_detail, confidentiality, cost_type,
+ *   cost_location, note
+ * - cost_year, currency, curve_type ("linear" or "polynomial"), base_cost
+ * - sf1_name, sf1_units, sf1_source_value, sf2_... (scaling factors)
+ * - term1_dimension, term1_coefficient, term1_exponent or term1_value, ...
+ *   (polynomial terms)
+ * - opex1_name, opex1_units, opex1_scaled_by, ... (variable OPEX)
+ *
+ * A row with an empty item_id declares a module without cost items. Problems
+ * are reported against spreadsheet row numbers (the header is row 1).
+ */
+
+import { parseCsv } from "../../utils/csv";
+import type {
+  CostLibrary,
+  CostLibraryModule,
+  CostLibraryCostItem,
+  CostItemInfo,
+  PolynomialTerm,
+} from "./types";
+import { validateCostLibrary, type LibraryIssue } from "./library-validation";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type ImportRowError = {
+  /** Spreadsheet row number (header is row 1) */
+  row: number;
+  column?: string;
+  message: string;
+};
+
+export type ImportIssue = LibraryIssue & {
+  /** Spreadsheet row the issue was found in (if known) */
+  row?: number;
+};
+
+export type LibraryImportResult = {
+  library: CostLibrary;
+  /** Rows that couldn't be read */
+  rowErrors: ImportRowError[];
+  /** Validation issues in the resulting library */
+  issues: ImportIssue[];
+  isValid: boolean;
+};
+
+export type LibraryImportOptions = {
+  /**
+   * Library to take currency conversion, inflation and defaults from, since
+   * the spreadsheet only holds modules and cost items.
+   */
+  base?: Pick<CostLibrary, "currency_conversion" | "inflation" | "defaults">;
+  delimiter?: string;
+};
+
+const REQUIRED_COLUMNS = ["module_id", "module_type", "item_id"];
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/**
+ * Import a cost library from CSV text in the standard layout.
+ */
+export function importCostLibraryCsv(
+  text: string,
+  options: LibraryImportOptions = {},
+): LibraryImportResult {
+  const [header = [], ...rows] = parseCsv(text, options.delimiter);
+  const columns = header.map((h) => h.trim().toLowerCase());
+  const rowErrors: ImportRowError[] = [];
+
+  const missing = REQUIRED_COLUMNS.filter((c) => !columns.includes(c));
+  if (missing.length > 0) {
+    rowErrors.push({
+      row: 1,
+      message: `missing required column(s): ${missing.join(", ")}`,
+    });
+  }
+
+  const modules: CostLibraryModule[] = [];
+  const moduleById = new Map<string, CostLibraryModule>();
+  /** Spreadsheet row of each cost item, by module index and item index */
+  const itemRows: number[][] = [];
+
+  if (missing.length === 0) {
+    rows.forEach((fields, r) => {
+      const rowNumber = r + 2;
+      const cell = (name: string) =>
+        fields[columns.indexOf(name)]?.trim() ?? "";
+      const reader = new RowReader(cell, rowNumber, rowErrors);
+
+      const moduleId = cell("module_id");
+      if (!moduleId) {
+        reader.error("module_id", "is required");
+        return;
+      }
+
+      let module = moduleById.get(moduleId);
+      if (!module) {
+        module = {
+          id: moduleId,
+          definition: {
+            type: cell("module_type"),
+            ...definitionOf(columns, fields),
+          },
+          subtype: cell("module_subtype") || null,
+          cost_items: [],
+        };
+        moduleById.set(moduleId, module);
+        modules.push(module);
+        itemRows.push([]);
+      }
+
+      if (!cell("item_id")) return;
+
+      const errorCount = rowErrors.length;
+      const item = readCostItem(reader, cell, columns);
+      if (rowErrors.length === errorCount) {
+        module.cost_items.push(item);
+        itemRows[modules.indexOf(module)].push(rowNumber);
+      }
+    });
+  }
+
+  const library: CostLibrary = {
+    modules,
+    ...(options.base?.currency_conversion && {
+      currency_conversion: options.base.currency_conversion,
+    }),
+    ...(options.base?.inflation && { inflation: options.base.inflation }),
+    ...(options.base?.defaults && { defaults: options.base.defaults }),
+  };
+
+  const issues = validateCostLibrary(library).map((issue): ImportIssue => {
+    const match = issue.path.match(
+      /^modules\[(\d+)\](?:\.cost_items\[(\d+)\])?/,
+    );
+    if (!match) return issue;
+    const moduleRows = itemRows[Number(match[1])] ?? [];
+    const row =
+      match[2] !== undefined ? moduleRows[Number(match[2])] : moduleRows[0];
+    return row !== undefined ? { ...issue, row } : issue;
+  });
+
+  return {
+    library,
+    rowErrors,
+    issues,
+    isValid: rowErrors.length === 0 && issues.length === 0,
+  };
+}
+
+/**
+ * Reads typed values from a row, recording errors against the row.
+ */
+class RowReader {
+  constructor(
+    private readonly cell: (name: string) => string,
+    private readonly row: number,
+    private readonly errors: ImportRowError[],
+  ) {}
+
+  error(column: string, message: string): void {
+    this.errors.push({ row: this.row, column, message });
+  }
+
+  number(column: string, required = true): number | undefined {
+    const text = this.cell(column);
+    if (text === "") {
+      if (required) this.error(column, "is required");
+      return undefined;
+    }
+    const value = Number(text);
+    if (!Number.isFinite(value)) {
+      this.error(column, `"${text}" is not a number`);
+      return undefined;
+    }
+    return value;
+  }
+}
+
+function readCostItem(
+  reader: RowReader,
+  cell: (name: string) => string,
+  columns: string[],
+): CostLibraryCostItem {
+  const nullable = (name: string) => cell(name) || null;
+
+  const info: CostItemInfo = {
+    reference_quality: cell("reference_quality"),
+    item_type: cell("item_type"),
+    short_name: cell("short_name"),
+    description: cell("description"),
+    source_reference: cell("source_reference"),
+    source_reference_detail: nullable("source_reference_detail"),
+    confidentiality: cell("confidentiality"),
+    cost_type: nullable("cost_type"),
+    cost_location: nullable("cost_location"),
+    note: nullable("note"),
+  };
+
+  const scalingFactors = groupIndices(columns, "sf").flatMap((n) => {
+    const name = cell(`sf${n}_name`);
+    if (!name) return [];
+    return [
+      {
+        name,
+        units: cell(`sf${n}_units`),
+        source_value: reader.number(`sf${n}_source_value`) ?? 0,
+      },
+    ];
+  });
+
+  const variableOpex = groupIndices(columns, "opex").flatMap((n) => {
+    const name = cell(`opex${n}_name`);
+    if (!name) return [];
+    return [
+      {
+        name,
+        units: cell(`opex${n}_units`),
+        scaled_by: reader.number(`opex${n}_scaled_by`) ?? 0,
+      },
+    ];
+  });
+
+  const curveType = cell("curve_type").toLowerCase();
+  const year = reader.number("cost_year");
+  if (year !== undefined && !Number.isInteger(year)) {
+    reader.error("cost_year", "must be a whole year");
+  }
+  if (!cell("currency")) {
+    reader.error("currency", "is required");
+  }
+
+  let cost: CostLibraryCostItem["capex_contribution"]["cost"];
+  if (curveType === "linear") {
+    cost = { type: "linear", base_cost: reader.number("base_cost") };
+  } else if (curveType === "polynomial") {
+    cost = { type: "polynomial", parameters: readTerms(reader, cell, columns) };
+  } else {
+    reader.error("curve_type", 'must be "linear" or "polynomial"');
+    cost = { type: curveType };
+  }
+
+  return {
+    id: cell("item_id"),
+    info,
+    scaling_factors: scalingFactors,
+    capex_contribution: {
+      year: year ?? 0,
+      currency: cell("currency").toUpperCase(),
+      cost,
+    },
+    variable_opex_contributions: variableOpex,
+  };
+}
+
+function readTerms(
+  reader: RowReader,
+  cell: (name: string) => string,
+  columns: string[],
+): PolynomialTerm[] {
+  return groupIndices(columns, "term").flatMap((n): PolynomialTerm[] => {
+    if (cell(`term${n}_value`) !== "") {
+      return [{ value: reader.number(`term${n}_value`) }];
+    }
+    const dimension = cell(`term${n}_dimension`);
+    if (!dimension) return [];
+    return [
+      {
+        dimension_name: dimension,
+        coefficient: reader.number(`term${n}_coefficient`),
+        exponent: reader.number(`term${n}_exponent`),
+      },
+    ];
+  });
+}
+
+/**
+ * Indices of numbered column groups present in the header, e.g. [1, 2] for
+ * sf1_name and sf2_name.
+ */
+function groupIndices(columns: string[], prefix: string): number[] {
+  const pattern = new RegExp(`^${prefix}(\\d+)_`);
+  const indices = new Set<number>();
+  for (const column of columns) {
+    const match = column.match(pattern);
+    if (match) indices.add(Number(match[1]));
+  }
+  return Array.from(indices).sort((a, b) => a - b);
+}
+
+/**
+ * Extra module definition fields from definition.<field> columns. Numeric
+ * values are stored as numbers.
+ */
+function definitionOf(
+  columns: string[],
+  fields: string[],
+): Record<string, unknown> {
+  const definition: Record<string, unknown> = {};
+  columns.forEach((column, i) => {
+    if (!column.startsWith("definition.")) return;
+    const value = fields[i]?.trim() ?? "";
+    if (value === "") return;
+    const number = Number(value);
+    definition[column.slice("definition.".length)] = Number.isFinite(number)
+      ? number
+      : value;
+  });
+  return definition;
+}
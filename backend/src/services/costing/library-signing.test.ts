@@ -0,0 +1,100 @@
+import { describe, it, expect } from "vitest";
+import { generateKeyPairSync } from "crypto";
+import {
+  LibrarySignatureError,
+  parseSigningKeys,
+  publicKeyBase64,
+  signLibrary,
+  signaturePolicyFromEnv,
+  verifyLibrary,
+  type SignaturePolicy,
+} from "./library-signing";
+
+describe("library-signing", () => {
+  const { privateKey } = generateKeyPairSync("ed25519");
+  const pem = privateKey.export({ type: "pkcs8", format: "pem" }).toString();
+  const keys = parseSigningKeys(`release:${publicKeyBase64(privateKey)}`);
+  const content = Buffer.from('{"modules":[]}\n');
+  const signature = signLibrary(content, pem, "release", "V1.1_working");
+
+  const policy = (mode: SignaturePolicy["mode"]): SignaturePolicy => ({
+    mode,
+    keys,
+  });
+
+  it("verifies a signed library", () => {
+    expect(
+      verifyLibrary("V1.1_working", content, signature, policy("verify")),
+    ).toBe("release");
+  });
+
+  it("rejects a tampered library", () => {
+    const tampered = Buffer.from('{"modules":[{}]}\n');
+    expect(() =>
+      verifyLibrary("V1.1_working", tampered, signature, policy("verify")),
+    ).toThrow(LibrarySignatureError);
+  });
+
+  it("rejects a signature for another library", () => {
+    expect(() =>
+      verifyLibrary("other", content, signature, policy("verify")),
+    ).toThrow(/does not match/);
+  });
+
+  it("rejects a signature whose fields were edited", () => {
+    // A signed library copied under another ID, with the ID edited to match
+    expect(() =>
+      verifyLibrary(
+        "copy",
+        content,
+        { ...signature, libraryId: "copy" },
+        policy("verify"),
+      ),
+    ).toThrow(/does not match/);
+    expect(() =>
+      verifyLibrary(
+        "V1.1_working",
+        content,
+        { ...signature, sha256: "0".repeat(64) },
+        policy("verify"),
+      ),
+    ).toThrow(/does not match/);
+  });
+
+  it("rejects untrusted keys", () => {
+    const { privateKey: other } = generateKeyPairSync("ed25519");
+    const otherKeys = parseSigningKeys(`other:${publicKeyBase64(other)}`);
+    expect(() =>
+      verifyLibrary("V1.1_working", content, signature, {
+        mode: "verify",
+        keys: otherKeys,
+      }),
+    ).toThrow(/untrusted key release/);
+  });
+
+  it("rejects unsigned libraries once trusted keys are configured", () => {
+    expect(() =>
+      verifyLibrary("V1.1_working", content, undefined, policy("verify")),
+    ).toThrow(/not signed/);
+    expect(() =>
+      verifyLibrary("V1.1_working", content, undefined, policy("require")),
+    ).toThrow(/not signed/);
+    expect(
+      verifyLibrary("V1.1_working", content, undefined, {
+        mode: "verify",
+        keys: new Map(),
+      }),
+    ).toBeUndefined();
+  });
+
+  it("validates the policy settings", () => {
+    expect(signaturePolicyFromEnv({}).mode).toBe("verify");
+    expect(() =>
+      signaturePolicyFromEnv({ LIBRARY_SIGNATURES: "strict" }),
+    ).toThrow();
+    expect(() =>
+      signaturePolicyFromEnv({ LIBRARY_SIGNATURES: "require" }),
+    ).toThrow(/needs LIBRARY_SIGNING_KEYS/);
+    expect(() => parseSigningKeys("release:c2hvcnQ=")).toThrow(/32 bytes/);
+  });
+});
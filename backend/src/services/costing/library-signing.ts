@@ -0,0 +1,241 @@
+/**
+ * Signed cost library packages.
+ *
+ * A signed library is its `cost-library.json` plus a detached signature
+ * file, `cost-library.sig.json`, holding the library ID, the SHA-256 of the
+ * library file's exact bytes, the signing time, the signing key's ID, and
+ * an ed25519 signature over the first three. Binding the ID and time into
+ * the signature stops a signed library being passed off under another ID.
+ * Libraries are signed offline with `cost-library sign`.
+ *
+ * The server trusts the public keys in LIBRARY_SIGNING_KEYS, a
+ * comma-separated list of `keyId:publicKey` entries where the public key is
+ * the raw 32-byte ed25519 key in base64. LIBRARY_SIGNATURES sets the policy:
+ * - off: signatures are ignored
+ * - verify (default): once trusted keys are configured, every library must
+ *   be signed by one of them, so deleting a signature doesn't let a
+ *   tampered library load. Without keys nothing is verified, and this gives
+ *   no protection against tampering
+ * - require: as verify, but trusted keys must be configured
+ */
+
+import {
+  createHash,
+  createPrivateKey,
+  createPublicKey,
+  sign,
+  verify,
+  type KeyObject,
+} from "crypto";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const SIGNATURE_FILE = "cost-library.sig.json";
+
+export type LibrarySignature = {
+  algorithm: "ed25519";
+  keyId: string;
+  /** Base64 signature over the signed fields (see `signedFields`) */
+  signature: string;
+  /** Hex SHA-256 of the library file's bytes */
+  sha256: string;
+  libraryId: string;
+  /** ISO timestamp */
+  signedAt: string;
+};
+
+export const SIGNATURE_MODES = ["off", "verify", "require"] as const;
+
+export type SignatureMode = (typeof SIGNATURE_MODES)[number];
+
+export type SignaturePolicy = {
+  mode: SignatureMode;
+  /** Trusted public keys by key ID */
+  keys: Map<string, KeyObject>;
+};
+
+export class LibrarySignatureError extends Error {
+  constructor(
+    readonly libraryId: string,
+    message: string,
+  ) {
+    super(`Cost library ${libraryId}: ${message}`);
+    this.name = "LibrarySignatureError";
+  }
+}
+
+// ============================================================================
+// Keys and policy
+// ============================================================================
+
+function publicKeyFromBase64(value: string): KeyObject {
+  const raw = Buffer.from(value, "base64");
+  if (raw.length !== 32) {
+    throw new Error("an ed25519 public key is 32 bytes");
+  }
+  return createPublicKey({
+    key: { kty: "OKP", crv: "Ed25519", x: raw.toString("base64url") },
+    format: "jwk",
+  });
+}
+
+/**
+ * The raw ed25519 public key, in base64, for a key pair's private key.
+ */
+export function publicKeyBase64(privateKey: KeyObject): string {
+  const jwk = createPublicKey(privateKey).export({ format: "jwk" });
+  return Buffer.from(jwk.x!, "base64url").toString("base64");
+}
+
+/**
+ * Parse LIBRARY_SIGNING_KEYS. Throws on malformed entries.
+ */
+export function parseSigningKeys(
+  value: string | undefined,
+): Map<string, KeyObject> {
+  const keys = new Map<string, KeyObject>();
+  for (const entry of (value ?? "").split(",")) {
+    if (!entry.trim()) continue;
+    const [keyId, key] = entry.trim().split(":");
+    if (!keyId || !key) {
+      throw new Error(`Signing key entry "${entry}" must be keyId:publicKey`);
+    }
+    try {
+      keys.set(keyId, publicKeyFromBase64(key));
+    } catch (error) {
+      throw new Error(
+        `Signing key ${keyId} is invalid: ${(error as Error).message}`,
+      );
+    }
+  }
+  return keys;
+}
+
+/**
+ * Read the signature policy from LIBRARY_SIGNATURES and
+ * LIBRARY_SIGNING_KEYS. Throws if the configuration is invalid.
+ */
+export function signaturePolicyFromEnv(
+  env: Record<string, string | undefined> = process.env,
+): SignaturePolicy {
+  const mode = (env.LIBRARY_SIGNATURES || "verify") as SignatureMode;
+  if (!SIGNATURE_MODES.includes(mode)) {
+    throw new Error(
+      `LIBRARY_SIGNATURES must be one of ${SIGNATURE_MODES.join(", ")}`,
+    );
+  }
+  const keys = parseSigningKeys(env.LIBRARY_SIGNING_KEYS);
+  if (mode === "require" && keys.size === 0) {
+    throw new Error("LIBRARY_SIGNATURES=require needs LIBRARY_SIGNING_KEYS");
+  }
+  return { mode, keys };
+}
+
+/**
+ * Whether every library must carry a valid signature under a policy: under
+ * `require`, and under `verify` once trusted keys are configured.
+ */
+export function signaturesRequired(policy: SignaturePolicy): boolean {
+  return (
+    policy.mode === "require" ||
+    (policy.mode === "verify" && policy.keys.size > 0)
+  );
+}
+
+// ============================================================================
+// Signing and verification
+// ============================================================================
+
+/**
+ * The bytes a signature is made over: the library ID, the library file's
+ * SHA-256 and the signing time, as JSON in that key order.
+ */
+function signedFields(
+  signature: Pick<LibrarySignature, "libraryId" | "sha256" | "signedAt">,
+): Buffer {
+  return Buffer.from(
+    JSON.stringify({
+      libraryId: signature.libraryId,
+      sha256: signature.sha256,
+      signedAt: signature.signedAt,
+    }),
+  );
+}
+
+function sha256Of(content: Uint8Array): string {
+  return createHash("sha256").update(content).digest("hex");
+}
+
+/**
+ * Sign a library file with a PEM-encoded ed25519 private key.
+ */
+export function signLibrary(
+  content: Uint8Array,
+  privateKeyPem: string,
+  keyId: string,
+  libraryId: string,
+): LibrarySignature {
+  const key = createPrivateKey(privateKeyPem);
+  if (key.asymmetricKeyType !== "ed25519") {
+    throw new Error("Libraries are signed with ed25519 keys");
+  }
+  const fields = {
+    sha256: sha256Of(content),
+    libraryId,
+    signedAt: new Date().toISOString(),
+  };
+  return {
+    algorithm: "ed25519",
+    keyId,
+    signature: sign(null, signedFields(fields), key).toString("base64"),
+    ...fields,
+  };
+}
+
+/**
+ * Check a library file against the signature policy.
+ *
+ * @param signature - The parsed signature file, if there is one
+ * @returns The ID of the key that signed the library, if it was verified
+ * @throws LibrarySignatureError if the library is tampered with, signed by
+ *   an untrusted key or for another library, or unsigned when signatures
+ *   are required
+ */
+export function verifyLibrary(
+  libraryId: string,
+  content: Uint8Array,
+  signature: LibrarySignature | undefined,
+  policy: SignaturePolicy,
+): string | undefined {
+  if (!signaturesRequired(policy)) return undefined;
+  if (!signature) {
+    throw new LibrarySignatureError(libraryId, "library is not signed");
+  }
+
+  const key = policy.keys.get(signature.keyId);
+  if (!key) {
+    throw new LibrarySignatureError(
+      libraryId,
+      `signed with untrusted key ${signature.keyId}`,
+    );
+  }
+  if (
+    signature.algorithm !== "ed25519" ||
+    signature.libraryId !== libraryId ||
+    signature.sha256 !== sha256Of(content) ||
+    !verify(
+      null,
+      signedFields(signature),
+      key,
+      Buffer.from(signature.signature, "base64"),
+    )
+  ) {
+    throw new LibrarySignatureError(
+      libraryId,
+      "signature does not match the library; it may have been tampered with",
+    );
+  }
+  return signature.keyId;
+}
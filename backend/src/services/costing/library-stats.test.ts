@@ -0,0 +1,40 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { costLibraryStats } from "./library-stats";
+import { loadCostLibrary } from "./module-lookup";
+import type { CostLibrary } from "./types";
+
+describe("library-stats", () => {
+  let library: CostLibrary;
+
+  beforeAll(async () => {
+    library = await loadCostLibrary("V1.1_working");
+  });
+
+  it("counts shared cost items once", () => {
+    const stats = costLibraryStats(library);
+    const ids = new Set(
+      library.modules.flatMap((m) => m.cost_items.map((i) => i.id)),
+    );
+
+    expect(stats.moduleCount).toBe(library.modules.length);
+    expect(stats.costItemCount).toBe(ids.size);
+    expect(
+      Object.values(stats.curveTypes).reduce((a, b) => a + b, 0),
+    ).toBe(ids.size);
+    expect(stats.modulesByType.CaptureUnit).toBeGreaterThan(0);
+  });
+
+  it("reports capex coverage and parameter names", () => {
+    const stats = costLibraryStats(library);
+    const covered = Object.values(stats.capexCoverage)
+      .flatMap((byYear) => Object.values(byYear))
+      .reduce((a, b) => a + b, 0);
+
+    expect(covered).toBe(stats.costItemCount);
+    expect(Object.keys(stats.capexCoverage)).toEqual(
+      Object.keys(stats.currencies),
+    );
+    expect(stats.parameters.length).toBeGreaterThan(0);
+    expect(stats.parameters).toEqual([...new Set(stats.parameters)].sort());
+  });
+});
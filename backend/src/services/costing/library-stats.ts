@@ -0,0 +1,77 @@
+/**
+ * Cost library statistics.
+ */
+
+import type { CostLibrary } from "./types";
+
+export type CostLibraryStats = {
+  moduleCount: number;
+  /** Distinct cost items (items shared between modules count once) */
+  costItemCount: number;
+  modulesByType: Record<string, number>;
+  /** Distinct cost items by cost curve type */
+  curveTypes: Record<string, number>;
+  /** Distinct cost items by source currency */
+  currencies: Record<string, number>;
+  /** Earliest and latest source cost year (null if there are no items) */
+  costYears: { min: number; max: number } | null;
+  /** Distinct cost items by capex currency, then by source cost year */
+  capexCoverage: Record<string, Record<string, number>>;
+  /**
+   * Distinct parameter names used by scaling factors, polynomial cost
+   * curves and variable OPEX contributions, sorted
+   */
+  parameters: string[];
+};
+
+/**
+ * Summarise the contents of a cost library.
+ */
+export function costLibraryStats(library: CostLibrary): CostLibraryStats {
+  const items = new Map(
+    library.modules
+      .flatMap((m) => m.cost_items ?? [])
+      .map((item) => [item.id, item]),
+  );
+  const capex = [...items.values()].map((i) => i.capex_contribution);
+  const years = capex.map((c) => c.year);
+
+  const capexCoverage: Record<string, Record<string, number>> = {};
+  for (const c of capex) {
+    const byYear = (capexCoverage[c.currency] ??= {});
+    byYear[c.year] = (byYear[c.year] ?? 0) + 1;
+  }
+
+  const parameters = new Set<string>();
+  for (const item of items.values()) {
+    for (const factor of item.scaling_factors) parameters.add(factor.name);
+    for (const term of item.capex_contribution.cost.parameters ?? []) {
+      if (term.dimension_name) parameters.add(term.dimension_name);
+    }
+    for (const opex of item.variable_opex_contributions) {
+      parameters.add(opex.name);
+    }
+  }
+
+  return {
+    moduleCount: library.modules.length,
+    costItemCount: items.size,
+    modulesByType: countBy(library.modules.map((m) => m.definition.type)),
+    curveTypes: countBy(capex.map((c) => c.cost.type)),
+    currencies: countBy(capex.map((c) => c.currency)),
+    costYears:
+      years.length > 0
+        ? { min: Math.min(...years), max: Math.max(...years) }
+        : null,
+    capexCoverage,
+    parameters: [...parameters].sort(),
+  };
+}
+
+function countBy(values: string[]): Record<string, number> {
+  const counts: Record<string, number> = {};
+  for (const value of values) {
+    counts[value] = (counts[value] ?? 0) + 1;
+  }
+  return counts;
+}
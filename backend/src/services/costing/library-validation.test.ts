@@ -0,0 +1,147 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { validateCostLibrary } from "./library-validation";
+import { listCostLibraries, loadCostLibrary } from "./module-lookup";
+import type { CostLibrary, CostLibraryCostItem } from "./types";
+
+describe("library-validation", () => {
+  let library: CostLibrary;
+
+  beforeAll(async () => {
+    library = await loadCostLibrary("V1.1_working");
+  });
+
+  function withItem(item: CostLibraryCostItem): CostLibrary {
+    return {
+      ...library,
+      modules: [
+        {
+          id: "M9901",
+          definition: { type: "Test" },
+          subtype: "",
+          cost_items: [item],
+        },
+      ],
+    };
+  }
+
+  const item = (): CostLibraryCostItem =>
+    structuredClone(
+      library.modules.flatMap((m) => m.cost_items).find(
+        (i) => i.id === "Item 023",
+      )!,
+    );
+
+  it("accepts every shipped library", async () => {
+    for (const id of await listCostLibraries()) {
+      expect(validateCostLibrary(await loadCostLibrary(id))).toEqual([]);
+    }
+  });
+
+  it("reports duplicate module IDs", () => {
+    const issues = validateCostLibrary({
+      ...library,
+      modules: [library.modules[0], library.modules[0]],
+    });
+
+    expect(issues).toEqual([
+      { path: "modules[1].id", message: "duplicate module ID M0101" },
+    ]);
+  });
+
+  it("reports cost years and currencies the library can't convert", () => {
+    const bad = item();
+    bad.capex_contribution.year = 1850;
+    bad.capex_contribution.currency = "XYZ";
+
+    expect(validateCostLibrary(withItem(bad)).map((i) => i.path)).toEqual([
+      "modules[0].cost_items[0].capex_contribution.year",
+      "modules[0].cost_items[0].capex_contribution.currency",
+    ]);
+  });
+
+  it("reports polynomial terms on unknown dimensions", () => {
+    const bad = item();
+    bad.capex_contribution.cost = {
+      type: "polynomial",
+      parameters: [{ dimension_name: "Depth", coefficient: 1, exponent: 2 }],
+    };
+
+    expect(validateCostLibrary(withItem(bad))).toEqual([
+      {
+        path: "modules[0].cost_items[0].capex_contribution.cost.parameters[0].dimension_name",
+        message: "Depth is not a scaling factor of this item",
+      },
+    ]);
+  });
+
+  it("reports shared cost items that differ between modules", () => {
+    const changed = item();
+    changed.capex_contribution.year = 2012;
+
+    const issues = validateCostLibrary({
+      ...library,
+      modules: [
+        ...library.modules,
+        {
+          id: "M9901",
+          definition: { type: "Test" },
+          subtype: "",
+          cost_items: [changed],
+        },
+      ],
+    });
+
+    expect(issues).toHaveLength(1);
+    expect(issues[0].message).toMatch(/^cost item Item 023 differs/);
+  });
+
+  it("reports malformed translations and source references", () => {
+    const bad = item();
+    bad.info.translations = { "not a tag": { short_name: "x" } };
+    bad.info.sources = [
+      { report: "", confidence: "certain" as "high" },
+      { report: "IEAGHG 2011/7", page: "42", confidence: "medium" },
+    ];
+
+    expect(validateCostLibrary(withItem(bad))).toEqual([
+      {
+        path: "modules[0].cost_items[0].info.translations.not a tag",
+        message: "is not a BCP 47 language tag",
+      },
+      {
+        path: "modules[0].cost_items[0].info.sources[0].report",
+        message: "is required",
+      },
+      {
+        path: "modules[0].cost_items[0].info.sources[0].confidence",
+        message: "must be one of low, medium, high",
+      },
+    ]);
+  });
+
+  it("reports bundles with unknown components or references", () => {
+    const issues = validateCostLibrary({
+      ...withItem(item()),
+      bundles: [
+        {
+          id: "B001",
+          info: { short_name: "Package" },
+          components: [{ ref: "Item 023" }, { ref: "Item 999", quantity: 0 }],
+        },
+      ],
+      modules: [{ ...withItem(item()).modules[0], bundles: ["B001", "B002"] }],
+    });
+
+    expect(issues).toEqual([
+      {
+        path: "bundles[0].components[1].ref",
+        message: "cost item Item 999 not found",
+      },
+      {
+        path: "bundles[0].components[1].quantity",
+        message: "must be positive",
+      },
+      { path: "modules[0].bundles[1]", message: "bundle B002 not found" },
+    ]);
+  });
+});
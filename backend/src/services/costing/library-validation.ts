@@ -0,0 +1,420 @@
+/**
+ * Cost library validation.
+ *
+ * Checks a cost library for the problems that would make the costing server
+ * reject it or silently produce wrong numbers: missing or duplicate IDs, cost
+ * curves without coefficients, cost years or currencies the library can't
+ * convert, and shared cost items that differ between modules.
+ */
+
+import {
+  SOURCE_CONFIDENCE_LEVELS,
+  type CostLibrary,
+  type CostLibraryCostItem,
+  type CapexCost,
+} from "./types";
+import { resolveCostItemAlias } from "./cost-item-aliases";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type LibraryIssue = {
+  /** Location of the problem, e.g. "modules[3].cost_items[0].capex_contribution" */
+  path: string;
+  message: string;
+};
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/**
+ * Validate a cost library. Returns an empty array when the library is valid.
+ */
+export function validateCostLibrary(library: CostLibrary): LibraryIssue[] {
+  const issues: LibraryIssue[] = [];
+  const issue = (path: string, message: string) =>
+    issues.push({ path, message });
+
+  if (!Array.isArray(library?.modules)) {
+    issue("modules", "must be an array");
+    return issues;
+  }
+
+  const rates = library.currency_conversion?.rates;
+  const inflation = library.inflation?.factors;
+
+  if (library.currency_conversion) {
+    const base = library.currency_conversion.base_currency;
+    if (rates?.[base] !== 1) {
+      issue("currency_conversion", `base currency ${base} must have rate 1`);
+    }
+    for (const [code, rate] of Object.entries(rates ?? {})) {
+      if (!(rate > 0)) {
+        issue(`currency_conversion.rates.${code}`, "must be positive");
+      }
+    }
+  }
+
+  validateParameters(library, issue);
+
+  const moduleIds = new Set<string>();
+  const itemsById = new Map<string, { path: string; json: string }>();
+
+  library.modules.forEach((module, m) => {
+    const modulePath = `modules[${m}]`;
+
+    if (!module.id) {
+      issue(`${modulePath}.id`, "is required");
+    } else if (moduleIds.has(module.id)) {
+      issue(`${modulePath}.id`, `duplicate module ID ${module.id}`);
+    } else {
+      moduleIds.add(module.id);
+    }
+
+    if (typeof module.definition?.type !== "string") {
+      issue(`${modulePath}.definition.type`, "is required");
+    }
+
+    (module.cost_items ?? []).forEach((item, i) => {
+      const itemPath = `${modulePath}.cost_items[${i}]`;
+      validateCostItem(item, itemPath, issue, rates, inflation);
+
+      if (!item.id) return;
+      const json = JSON.stringify(item);
+      const first = itemsById.get(item.id);
+      if (!first) {
+        itemsById.set(item.id, { path: itemPath, json });
+      } else if (first.json !== json) {
+        issue(
+          itemPath,
+          `cost item ${item.id} differs from its definition at ${first.path}`,
+        );
+      }
+    });
+  });
+
+  validateCostItemAliases(library, itemsById, issue);
+  validateBundles(library, itemsById, issue);
+  validateConstraints(library, issue);
+  validateCbsMapping(library, moduleIds, itemsById, issue);
+
+  return issues;
+}
+
+function validateCostItemAliases(
+  library: CostLibrary,
+  itemsById: Map<string, unknown>,
+  issue: (path: string, message: string) => void,
+): void {
+  const aliases = library.cost_item_aliases ?? {};
+  for (const old of Object.keys(aliases)) {
+    const path = `cost_item_aliases.${old}`;
+    const id = resolveCostItemAlias(aliases, old)!;
+    if (itemsById.has(old)) {
+      issue(path, `${old} is still a cost item ID`);
+    } else if (Object.hasOwn(aliases, id)) {
+      issue(path, "aliases form a cycle");
+    } else if (!itemsById.has(id)) {
+      issue(path, `cost item ${id} does not exist`);
+    }
+  }
+}
+
+function validateCbsMapping(
+  library: CostLibrary,
+  moduleIds: Set<string>,
+  itemsById: Map<string, unknown>,
+  issue: (path: string, message: string) => void,
+): void {
+  const mapping = library.cbs_mapping;
+  if (!mapping) return;
+
+  const checkCodes = (
+    field: "items" | "modules",
+    exists: (id: string) => boolean,
+    what: string,
+  ) => {
+    for (const [id, code] of Object.entries(mapping[field] ?? {})) {
+      const path = `cbs_mapping.${field}.${id}`;
+      if (typeof code !== "string" || code.trim() === "") {
+        issue(path, "must be a non-empty code");
+      }
+      if (!exists(id)) {
+        issue(path, `${what} ${id} does not exist`);
+      }
+    }
+  };
+  checkCodes("items", (id) => itemsById.has(id), "cost item");
+  checkCodes("modules", (id) => moduleIds.has(id), "module");
+
+  if (
+    mapping.default !== undefined &&
+    (typeof mapping.default !== "string" || mapping.default.trim() === "")
+  ) {
+    issue("cbs_mapping.default", "must be a non-empty code");
+  }
+}
+
+function validateBundles(
+  library: CostLibrary,
+  itemsById: Map<string, unknown>,
+  issue: (path: string, message: string) => void,
+): void {
+  const bundleIds = new Set<string>();
+  (library.bundles ?? []).forEach((bundle, b) => {
+    const path = `bundles[${b}]`;
+    if (!bundle.id) {
+      issue(`${path}.id`, "is required");
+    } else if (bundleIds.has(bundle.id)) {
+      issue(`${path}.id`, `duplicate bundle ID ${bundle.id}`);
+    } else {
+      bundleIds.add(bundle.id);
+    }
+    if (!Array.isArray(bundle.components) || bundle.components.length === 0) {
+      issue(`${path}.components`, "must have at least one component");
+      return;
+    }
+    bundle.components.forEach((component, c) => {
+      const componentPath = `${path}.components[${c}]`;
+      const ref = resolveCostItemAlias(
+        library.cost_item_aliases,
+        component.ref,
+      );
+      if (!itemsById.has(ref ?? component.ref)) {
+        issue(`${componentPath}.ref`, `cost item ${component.ref} not found`);
+      }
+      if (component.quantity !== undefined && !(component.quantity > 0)) {
+        issue(`${componentPath}.quantity`, "must be positive");
+      }
+    });
+  });
+
+  library.modules.forEach((module, m) => {
+    (module.bundles ?? []).forEach((id, i) => {
+      if (!bundleIds.has(id)) {
+        issue(`modules[${m}].bundles[${i}]`, `bundle ${id} not found`);
+      }
+    });
+  });
+}
+
+function validateConstraints(
+  library: CostLibrary,
+  issue: (path: string, message: string) => void,
+): void {
+  library.modules.forEach((module, m) => {
+    const selectable = new Set([
+      ...(module.cost_items ?? []).map((item) => item.id),
+      ...(module.bundles ?? []),
+    ]);
+    (module.constraints ?? []).forEach((constraint, c) => {
+      const path = `modules[${m}].constraints[${c}]`;
+      let items: string[];
+      switch (constraint.type) {
+        case "exactlyOne":
+        case "atMostOne":
+          items = constraint.items ?? [];
+          if (items.length < 2) {
+            issue(`${path}.items`, "must list at least two items");
+          }
+          break;
+        case "requires":
+          items = [constraint.item, ...(constraint.requires ?? [])];
+          if (!constraint.requires?.length) {
+            issue(`${path}.requires`, "must list at least one item");
+          }
+          break;
+        default:
+          issue(
+            `${path}.type`,
+            "must be one of exactlyOne, atMostOne, requires",
+          );
+          return;
+      }
+      for (const id of items) {
+        if (!selectable.has(id)) {
+          issue(path, `${id} is not a cost item or bundle of the module`);
+        }
+      }
+    });
+  });
+}
+
+function validateParameters(
+  library: CostLibrary,
+  issue: (path: string, message: string) => void,
+): void {
+  const ids = new Set<string>();
+  const owners = new Map<string, string>();
+  (library.parameters ?? []).forEach((parameter, p) => {
+    const path = `parameters[${p}]`;
+    if (!parameter.id) {
+      issue(`${path}.id`, "is required");
+    } else if (ids.has(parameter.id)) {
+      issue(`${path}.id`, `duplicate parameter ID ${parameter.id}`);
+    } else {
+      ids.add(parameter.id);
+    }
+    if (!parameter.name) {
+      issue(`${path}.name`, "is required");
+    }
+    for (const name of [parameter.name, ...(parameter.aliases ?? [])]) {
+      if (!name) continue;
+      const owner = owners.get(name.toLowerCase());
+      if (owner !== undefined && owner !== parameter.id) {
+        issue(path, `name "${name}" is also used by parameter ${owner}`);
+      }
+      owners.set(name.toLowerCase(), parameter.id);
+    }
+  });
+}
+
+function validateCostItem(
+  item: CostLibraryCostItem,
+  path: string,
+  issue: (path: string, message: string) => void,
+  rates: Record<string, number> | undefined,
+  inflation: Record<string, number> | undefined,
+): void {
+  if (!item.id) {
+    issue(`${path}.id`, "is required");
+  }
+
+  for (const tag of Object.keys(item.info?.translations ?? {})) {
+    if (!isLanguageTag(tag)) {
+      issue(`${path}.info.translations.${tag}`, "is not a BCP 47 language tag");
+    }
+  }
+
+  (item.info?.sources ?? []).forEach((source, s) => {
+    const sourcePath = `${path}.info.sources[${s}]`;
+    if (!source.report) {
+      issue(`${sourcePath}.report`, "is required");
+    }
+    if (
+      source.confidence !== undefined &&
+      !SOURCE_CONFIDENCE_LEVELS.includes(source.confidence)
+    ) {
+      issue(
+        `${sourcePath}.confidence`,
+        `must be one of ${SOURCE_CONFIDENCE_LEVELS.join(", ")}`,
+      );
+    }
+  });
+
+  const factorNames = new Set<string>();
+  (item.scaling_factors ?? []).forEach((sf, s) => {
+    const sfPath = `${path}.scaling_factors[${s}]`;
+    if (!sf.name) {
+      issue(`${sfPath}.name`, "is required");
+    } else if (factorNames.has(sf.name)) {
+      issue(`${sfPath}.name`, `duplicate scaling factor ${sf.name}`);
+    }
+    factorNames.add(sf.name);
+    if (!Number.isFinite(sf.source_value)) {
+      issue(`${sfPath}.source_value`, "must be a number");
+    }
+    if (sf.default_value !== undefined && !Number.isFinite(sf.default_value)) {
+      issue(`${sfPath}.default_value`, "must be a number");
+    }
+  });
+
+  (item.variable_opex_contributions ?? []).forEach((opex, o) => {
+    if (
+      opex.default_value !== undefined &&
+      !Number.isFinite(opex.default_value)
+    ) {
+      issue(
+        `${path}.variable_opex_contributions[${o}].default_value`,
+        "must be a number",
+      );
+    }
+  });
+
+  const capex = item.capex_contribution;
+  const capexPath = `${path}.capex_contribution`;
+  if (!capex) {
+    issue(capexPath, "is required");
+    return;
+  }
+
+  if (!Number.isInteger(capex.year)) {
+    issue(`${capexPath}.year`, "must be an integer year");
+  } else if (inflation && inflation[String(capex.year)] === undefined) {
+    issue(`${capexPath}.year`, `no inflation factor for ${capex.year}`);
+  }
+
+  if (!capex.currency) {
+    issue(`${capexPath}.currency`, "is required");
+  } else if (rates && rates[capex.currency] === undefined) {
+    issue(`${capexPath}.currency`, `no conversion rate for ${capex.currency}`);
+  }
+
+  validateCapexCost(capex.cost, path, issue, item, factorNames);
+}
+
+function validateCapexCost(
+  cost: CapexCost | undefined,
+  itemPath: string,
+  issue: (path: string, message: string) => void,
+  item: CostLibraryCostItem,
+  factorNames: Set<string>,
+): void {
+  const path = `${itemPath}.capex_contribution.cost`;
+
+  switch (cost?.type) {
+    case "linear":
+      if (typeof cost.base_cost !== "number" || !(cost.base_cost >= 0)) {
+        issue(`${path}.base_cost`, "must be a non-negative number");
+      }
+      // Linear costs scale by value / source_value
+      (item.scaling_factors ?? []).forEach((sf, s) => {
+        if (sf.source_value === 0) {
+          issue(
+            `${itemPath}.scaling_factors[${s}].source_value`,
+            "must be non-zero for a linear cost",
+          );
+        }
+      });
+      return;
+
+    case "polynomial":
+      if (!Array.isArray(cost.parameters) || cost.parameters.length === 0) {
+        issue(`${path}.parameters`, "must have at least one term");
+        return;
+      }
+      cost.parameters.forEach((term, t) => {
+        const termPath = `${path}.parameters[${t}]`;
+        if (typeof term.value === "number") return;
+        if (
+          typeof term.coefficient !== "number" ||
+          typeof term.exponent !== "number" ||
+          !term.dimension_name
+        ) {
+          issue(
+            termPath,
+            "must be a constant value or dimension_name, coefficient and exponent",
+          );
+        } else if (!factorNames.has(term.dimension_name)) {
+          issue(
+            `${termPath}.dimension_name`,
+            `${term.dimension_name} is not a scaling factor of this item`,
+          );
+        }
+      });
+      return;
+
+    default:
+      issue(`${path}.type`, 'must be "linear" or "polynomial"');
+  }
+}
+
+function isLanguageTag(tag: string): boolean {
+  try {
+    return Intl.getCanonicalLocales(tag).length === 1;
+  } catch {
+    return false;
+  }
+}
@@ -0,0 +1,79 @@
+import { describe, it, expect } from "vitest";
+import {
+  libraryLanguages,
+  localizeCostItem,
+  negotiateLanguage,
+} from "./localization";
+import type { CostLibraryCostItem } from "./types";
+
+describe("localization", () => {
+  const item: CostLibraryCostItem = {
+    id: "Item 023",
+    info: {
+      reference_quality: "3",
+      item_type: "Capture",
+      short_name: "Amine capture plant",
+      description: "Post-combustion amine capture",
+      source_reference: "Ref 001",
+      source_reference_detail: null,
+      confidentiality: "Public",
+      cost_type: "DEC",
+      cost_location: null,
+      note: null,
+      translations: {
+        de: { short_name: "Aminwäscher" },
+        "pt-BR": {
+          short_name: "Planta de captura por aminas",
+          description: "Captura pós-combustão por aminas",
+        },
+      },
+    },
+    scaling_factors: [],
+    capex_contribution: {
+      year: 2022,
+      currency: "EUR",
+      cost: { type: "linear", base_cost: 1 },
+    },
+    variable_opex_contributions: [],
+  };
+
+  describe("negotiateLanguage", () => {
+    const available = ["de", "pt-BR"];
+
+    it("matches exact tags, prefixes and parents", () => {
+      expect(negotiateLanguage("pt-BR", available)).toBe("pt-BR");
+      expect(negotiateLanguage("pt", available)).toBe("pt-BR");
+      expect(negotiateLanguage("de-CH", available)).toBe("de");
+    });
+
+    it("follows q-values and ignores refused languages", () => {
+      expect(negotiateLanguage("de;q=0.5, pt;q=0.9", available)).toBe("pt-BR");
+      expect(negotiateLanguage("de;q=0, fr", available)).toBeUndefined();
+      expect(negotiateLanguage(undefined, available)).toBeUndefined();
+    });
+  });
+
+  it("lists the languages a library has translations for", () => {
+    const library = {
+      modules: [
+        {
+          id: "M0201",
+          definition: { type: "CaptureUnit" },
+          subtype: null,
+          cost_items: [item],
+        },
+      ],
+    };
+
+    expect(libraryLanguages(library)).toEqual(["de", "pt-BR"]);
+  });
+
+  it("substitutes translated text and falls back per field", () => {
+    const german = localizeCostItem(item, "de");
+
+    expect(german.info.short_name).toBe("Aminwäscher");
+    expect(german.info.description).toBe("Post-combustion amine capture");
+    expect(german.info.translations).toBeUndefined();
+    expect(item.info.translations).toBeDefined();
+  });
+});
@@ -0,0 +1,86 @@
+/**
+ * Localized cost item names and descriptions.
+ *
+ * Cost items may carry `info.translations` keyed by language tag. Listings
+ * negotiate a language from Accept-Language against the languages the
+ * library provides and substitute the translated text, falling back to the
+ * library's own text for items without a translation.
+ */
+
+import type { CostLibrary, CostLibraryCostItem } from "./types";
+
+/**
+ * Languages the library has at least one translation for.
+ */
+export function libraryLanguages(library: CostLibrary): string[] {
+  const languages = new Set<string>();
+  for (const module of library.modules) {
+    for (const item of module.cost_items ?? []) {
+      for (const tag of Object.keys(item.info.translations ?? {})) {
+        languages.add(tag);
+      }
+    }
+  }
+  return [...languages].sort();
+}
+
+/**
+ * Pick the best available language for an Accept-Language header. A range
+ * matches a tag exactly or as a prefix ("de" matches "de-CH" and "de-CH"
+ * falls back to "de"). Returns undefined when nothing matches, meaning the
+ * library's own text should be used.
+ */
+export function negotiateLanguage(
+  acceptLanguage: string | undefined,
+  available: string[],
+): string | undefined {
+  if (!acceptLanguage || available.length === 0) return undefined;
+
+  const ranges = acceptLanguage
+    .split(",")
+    .map((part, index) => {
+      const [range, ...params] = part.trim().split(";");
+      const q = params
+        .map((p) => p.trim())
+        .find((p) => p.startsWith("q="));
+      return {
+        range: range.trim().toLowerCase(),
+        q: q ? parseFloat(q.slice(2)) : 1,
+        index,
+      };
+    })
+    .filter((r) => r.range && r.range !== "*" && r.q > 0)
+    .sort((a, b) => b.q - a.q || a.index - b.index);
+
+  const byLower = new Map(available.map((tag) => [tag.toLowerCase(), tag]));
+  for (const { range } of ranges) {
+    if (byLower.has(range)) return byLower.get(range);
+    const prefix = [...byLower.keys()].find((tag) =>
+      tag.startsWith(`${range}-`),
+    );
+    if (prefix) return byLower.get(prefix);
+    const parent = range.split("-")[0];
+    if (byLower.has(parent)) return byLower.get(parent);
+  }
+  return undefined;
+}
+
+/**
+ * A copy of the cost item with its name and description in `language`.
+ * The translations map is dropped from the copy.
+ */
+export function localizeCostItem(
+  item: CostLibraryCostItem,
+  language: string | undefined,
+): CostLibraryCostItem {
+  const { translations, ...info } = item.info;
+  const text = language ? translations?.[language] : undefined;
+  return {
+    ...item,
+    info: {
+      ...info,
+      short_name: text?.short_name ?? info.short_name,
+      description: text?.description ?? info.description,
+    },
+  };
+}
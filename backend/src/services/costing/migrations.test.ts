@@ -0,0 +1,62 @@
+import { describe, it, expect } from "vitest";
+import { Either } from "effect";
+import { migrateEstimateRequest, RequestMigrationError } from "./migrations";
+import { CostingEstimateRequestSchema, validateRequest } from "./schemas";
+
+const network = { groups: [], branches: [] };
+
+describe("migrations", () => {
+  it("moves a top-level networkPath into source", () => {
+    const result = migrateEstimateRequest({
+      networkPath: "preset1",
+      libraryId: "V1.1_working",
+    });
+
+    expect(result.request).toEqual({
+      source: { type: "networkId", networkId: "preset1" },
+      libraryId: "V1.1_working",
+    });
+    expect(result.applied).toEqual(["network-path"]);
+    expect(result.warnings).toHaveLength(1);
+    expect(
+      Either.isRight(
+        validateRequest(CostingEstimateRequestSchema, result.request),
+      ),
+    ).toBe(true);
+  });
+
+  it("upgrades legacy source shapes", () => {
+    expect(
+      migrateEstimateRequest({ network, libraryId: "x" }).request.source,
+    ).toEqual({ type: "data", network });
+    expect(
+      migrateEstimateRequest({ source: "preset2", libraryId: "x" }).request
+        .source,
+    ).toEqual({ type: "networkId", networkId: "preset2" });
+    expect(
+      migrateEstimateRequest({
+        source: { type: "path", path: "/srv/networks/a" },
+        libraryId: "x",
+      }),
+    ).toMatchObject({
+      request: { source: { type: "networkId", networkId: "/srv/networks/a" } },
+      applied: ["path-source"],
+    });
+  });
+
+  it("leaves current requests unchanged", () => {
+    const current = {
+      source: { type: "networkId", networkId: "preset1" },
+      libraryId: "V1.1_working",
+      targetCurrency: "EUR",
+    };
+    expect(migrateEstimateRequest(current)).toEqual({
+      request: current,
+      applied: [],
+      warnings: [],
+    });
+    expect(() => migrateEstimateRequest([current])).toThrow(
+      RequestMigrationError,
+    );
+  });
+});
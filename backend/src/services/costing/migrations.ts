@@ -0,0 +1,128 @@
+/**
+ * Upgrades for estimate requests saved in earlier shapes.
+ *
+ * Project files keep the estimate request they were costed with, so files
+ * written by older frontends and desktop hosts can carry request shapes the
+ * API no longer accepts. Each migration recognises one legacy shape and
+ * rewrites it to the current one, with a warning describing the change.
+ * Migrations run in order and are idempotent: a current request passes
+ * through unchanged.
+ */
+
+// ============================================================================
+// Types
+// ============================================================================
+
+type RequestJson = Record<string, unknown>;
+
+export type RequestMigration = {
+  id: string;
+  description: string;
+  /** Rewrite the request, or return undefined if it doesn't apply */
+  migrate: (request: RequestJson) => RequestJson | undefined;
+};
+
+export type RequestMigrationResult = {
+  request: RequestJson;
+  /** IDs of the migrations that changed the request, in order */
+  applied: string[];
+  warnings: string[];
+};
+
+export class RequestMigrationError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "RequestMigrationError";
+  }
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+function isObject(value: unknown): value is RequestJson {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+function withoutKey(request: RequestJson, key: string): RequestJson {
+  const rest = { ...request };
+  delete rest[key];
+  return rest;
+}
+
+export const REQUEST_MIGRATIONS: RequestMigration[] = [
+  {
+    id: "network-path",
+    description: "Top-level networkPath moved to source (networkId)",
+    migrate: (request) =>
+      typeof request.networkPath === "string" && request.source === undefined
+        ? {
+            ...withoutKey(request, "networkPath"),
+            source: { type: "networkId", networkId: request.networkPath },
+          }
+        : undefined,
+  },
+  {
+    id: "inline-network",
+    description: "Top-level network moved to source (data)",
+    migrate: (request) =>
+      isObject(request.network) && request.source === undefined
+        ? {
+            ...withoutKey(request, "network"),
+            source: { type: "data", network: request.network },
+          }
+        : undefined,
+  },
+  {
+    id: "string-source",
+    description: "source given as a network ID string",
+    migrate: (request) =>
+      typeof request.source === "string"
+        ? {
+            ...request,
+            source: { type: "networkId", networkId: request.source },
+          }
+        : undefined,
+  },
+  {
+    id: "path-source",
+    description: 'source of type "path" is now type "networkId"',
+    migrate: (request) =>
+      isObject(request.source) &&
+      request.source.type === "path" &&
+      typeof request.source.path === "string"
+        ? {
+            ...request,
+            source: { type: "networkId", networkId: request.source.path },
+          }
+        : undefined,
+  },
+];
+
+/**
+ * Upgrade an estimate request to the current shape. The result still needs
+ * validating: migrations only rewrite shapes they recognise.
+ *
+ * @throws RequestMigrationError if the request isn't a JSON object
+ */
+export function migrateEstimateRequest(
+  raw: unknown,
+  migrations: RequestMigration[] = REQUEST_MIGRATIONS,
+): RequestMigrationResult {
+  if (!isObject(raw)) {
+    throw new RequestMigrationError("An estimate request must be an object");
+  }
+
+  let request = raw;
+  const applied: string[] = [];
+  const warnings: string[] = [];
+  for (const migration of migrations) {
+    const migrated = migration.migrate(request);
+    if (migrated) {
+      request = migrated;
+      applied.push(migration.id);
+      warnings.push(migration.description);
+    }
+  }
+  return { request, applied, warnings };
+}
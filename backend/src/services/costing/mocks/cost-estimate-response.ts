@@ -0,0 +1,181 @@
+/**
+ * Builders for costing server responses, used by tests.
+ */
+
+import type {
+  AssetCostEstimate,
+  AssetPeriodCosts,
+  CostItemCostEstimate,
+  CostItemPeriodCosts,
+  FixedOpexCostEstimate,
+  LangFactoredCostEstimate,
+  VariableOpexCostEstimate,
+  YearAssetCosts,
+} from "../types";
+
+export function mockLangFactoredCosts(
+  overrides: Partial<LangFactoredCostEstimate> = {},
+): LangFactoredCostEstimate {
+  return {
+    equipment_erection: 0,
+    piping: 0,
+    instrumentation: 0,
+    electrical: 0,
+    buildings_and_process: 0,
+    utilities: 0,
+    storages: 0,
+    site_development: 0,
+    ancillary_buildings: 0,
+    design_and_engineering: 0,
+    contractors_fee: 0,
+    contingency: 0,
+    ...overrides,
+  };
+}
+
+export function mockFixedOpexCosts(
+  overrides: Partial<FixedOpexCostEstimate> = {},
+): FixedOpexCostEstimate {
+  return {
+    maintenance: 0,
+    control_room_facilities: 0,
+    insurance_liability: 0,
+    insurance_equipment_loss: 0,
+    cost_of_capital: 0,
+    major_turnarounds: 0,
+    ...overrides,
+  };
+}
+
+export function mockVariableOpexCosts(
+  overrides: Partial<VariableOpexCostEstimate> = {},
+): VariableOpexCostEstimate {
+  return {
+    electrical_power: 0,
+    cooling_water: 0,
+    natural_gas: 0,
+    steam_hp_superheated: 0,
+    steam_lp_saturated: 0,
+    catalysts_and_chemicals: 0,
+    equipment_item_rental: 0,
+    cost_per_tonne_of_co2: 0,
+    tariff: 0,
+    ...overrides,
+  };
+}
+
+export type MockPeriodCosts = {
+  direct_equipment_cost?: number;
+  total_installed_cost?: number;
+  decommissioning_cost?: number;
+  lang_factored_capital_cost?: Partial<LangFactoredCostEstimate>;
+  fixed_opex_cost?: Partial<FixedOpexCostEstimate>;
+  variable_opex_cost?: Partial<VariableOpexCostEstimate>;
+};
+
+export function mockPeriodCosts(costs: MockPeriodCosts = {}): AssetPeriodCosts {
+  return {
+    direct_equipment_cost: costs.direct_equipment_cost ?? 0,
+    lang_factored_capital_cost: mockLangFactoredCosts(
+      costs.lang_factored_capital_cost,
+    ),
+    total_installed_cost: costs.total_installed_cost ?? 0,
+    fixed_opex_cost: mockFixedOpexCosts(costs.fixed_opex_cost),
+    variable_opex_cost: mockVariableOpexCosts(costs.variable_opex_cost),
+    decommissioning_cost: costs.decommissioning_cost ?? 0,
+  };
+}
+
+export function mockYearCosts(
+  year: number,
+  costs: MockPeriodCosts,
+  dcfCosts: MockPeriodCosts = costs,
+): YearAssetCosts {
+  return {
+    year,
+    costs_in_year: mockPeriodCosts(costs),
+    dcf_costs_in_year: mockPeriodCosts(dcfCosts),
+  };
+}
+
+export function mockCostItemPeriodCosts(
+  costs: {
+    direct_equipment_cost?: number | null;
+    total_installed_cost?: number | null;
+    variable_opex_cost?: Partial<VariableOpexCostEstimate>;
+  } = {},
+): CostItemPeriodCosts {
+  return {
+    direct_equipment_cost: costs.direct_equipment_cost ?? null,
+    total_installed_cost: costs.total_installed_cost ?? null,
+    variable_opex_cost: mockVariableOpexCosts(costs.variable_opex_cost),
+  };
+}
+
+export function mockCostItem(
+  id: string,
+  lifetime: Parameters<typeof mockCostItemPeriodCosts>[0] = {},
+  lifetimeDcf: Parameters<typeof mockCostItemPeriodCosts>[0] = lifetime,
+): CostItemCostEstimate {
+  const lifetimeCosts = mockCostItemPeriodCosts(lifetime);
+  return {
+    id,
+    quantity: 1,
+    costs: {
+      direct_equipment_cost: lifetimeCosts.direct_equipment_cost,
+      total_installed_cost: lifetimeCosts.total_installed_cost,
+      variable_opex_cost_per_year: mockVariableOpexCosts(),
+    },
+    costs_by_year: [],
+    lifetime_costs: lifetimeCosts,
+    lifetime_dcf_costs: mockCostItemPeriodCosts(lifetimeDcf),
+  };
+}
+
+/**
+ * Build an asset estimate from per-year costs. Lifetime totals are the sums
+ * of the per-year values, matching what the costing server returns.
+ */
+export function mockAssetEstimate(
+  id: string,
+  years: YearAssetCosts[],
+  costItems: CostItemCostEstimate[] = [],
+): AssetCostEstimate {
+  const lifetime = sumPeriodCosts(years.map((y) => y.costs_in_year));
+  const lifetimeDcf = sumPeriodCosts(years.map((y) => y.dcf_costs_in_year));
+
+  return {
+    id,
+    costs: {
+      direct_equipment_cost: lifetime.direct_equipment_cost,
+      lang_factored_capital_cost: lifetime.lang_factored_capital_cost,
+      total_installed_cost: lifetime.total_installed_cost,
+      fixed_opex_cost_per_year: mockFixedOpexCosts(),
+      variable_opex_cost_per_year: mockVariableOpexCosts(),
+      decommissioning_cost: lifetime.decommissioning_cost,
+    },
+    costs_by_year: years,
+    lifetime_costs: lifetime,
+    lifetime_dcf_costs: lifetimeDcf,
+    cost_items: costItems,
+  };
+}
+
+function sumPeriodCosts(periods: AssetPeriodCosts[]): AssetPeriodCosts {
+  const total = mockPeriodCosts();
+  for (const p of periods) {
+    total.direct_equipment_cost += p.direct_equipment_cost;
+    total.total_installed_cost += p.total_installed_cost;
+    total.decommissioning_cost += p.decommissioning_cost;
+    addInto(total.lang_factored_capital_cost, p.lang_factored_capital_cost);
+    addInto(total.fixed_opex_cost, p.fixed_opex_cost);
+    addInto(total.variable_opex_cost, p.variable_opex_cost);
+  }
+  return total;
+}
+
+function addInto<T extends Record<string, number>>(target: T, source: T) {
+  for (const key of Object.keys(source) as Array<keyof T>) {
+    target[key] = (target[key] + source[key]) as T[keyof T];
+  }
+}
@@ -0,0 +1,76 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import {
+  isModuleListingQuery,
+  listModules,
+  parseModuleListingQuery,
+  type ModuleListingQuery,
+} from "./module-listing";
+import { loadCostLibrary, ModuleLookupService } from "./module-lookup";
+
+describe("module-listing", () => {
+  let service: ModuleLookupService;
+
+  beforeAll(async () => {
+    service = new ModuleLookupService(await loadCostLibrary("V1.1_working"));
+  });
+
+  const parse = (query: Record<string, string>): ModuleListingQuery => {
+    const parsed = parseModuleListingQuery(query);
+    if (!parsed.ok) throw new Error(parsed.message);
+    return parsed.query;
+  };
+
+  describe("parseModuleListingQuery", () => {
+    it("only treats listing params as a listing query", () => {
+      expect(isModuleListingQuery({})).toBe(false);
+      expect(isModuleListingQuery({ fields: "id" })).toBe(true);
+    });
+
+    it("defaults to the first page when per_page is given", () => {
+      expect(parse({ per_page: "10" })).toMatchObject({ page: 1, perPage: 10 });
+      expect(parse({ type: "Pipe" }).page).toBeUndefined();
+    });
+
+    it("rejects unknown fields and bad page sizes", () => {
+      expect(parseModuleListingQuery({ fields: "id,curve" })).toMatchObject({
+        ok: false,
+      });
+      expect(parseModuleListingQuery({ per_page: "0" }).ok).toBe(false);
+      expect(parseModuleListingQuery({ per_page: "501" }).ok).toBe(false);
+      expect(parseModuleListingQuery({ page: "-1" }).ok).toBe(false);
+    });
+  });
+
+  describe("listModules", () => {
+    it("paginates in library order", () => {
+      const all = service.listAll();
+      const listing = listModules(
+        service,
+        parse({ page: "2", per_page: "5", fields: "id" }),
+      );
+
+      expect(listing.modules).toEqual(all.slice(5, 10).map((m) => ({ id: m.id })));
+      expect(listing.total).toBe(all.length);
+      expect(listing.totalPages).toBe(Math.ceil(all.length / 5));
+    });
+
+    it("filters by ids and loads cost curves on request", () => {
+      const listing = listModules(
+        service,
+        parse({ ids: "M0201,M9999", fields: "id,costItems" }),
+      );
+
+      expect(listing.total).toBe(1);
+      expect(listing.modules[0].id).toBe("M0201");
+      expect(listing.modules[0].costItems?.[0].capex_contribution).toBeDefined();
+      expect(listing.modules[0].requiredParameters).toBeUndefined();
+    });
+
+    it("keeps the type filter in the response", () => {
+      const listing = listModules(service, parse({ type: "CaptureUnit" }));
+
+      expect(listing.type).toBe("CaptureUnit");
+      expect(listing.modules.every((m) => m.type === "CaptureUnit")).toBe(true);
+    });
+  });
+});
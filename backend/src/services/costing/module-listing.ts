@@ -0,0 +1,220 @@
+/**
+ * Paginated, field-selectable module listings.
+ *
+ * The module picker only needs ids and names; cost curves are large and are
+ * fetched on demand with `fields=costItems` for the modules being shown.
+ */
+
+import type { ModuleInfo, ModuleLookupService } from "./module-lookup";
+import type { CostLibraryCostItem } from "./types";
+import { localizeCostItem } from "./localization";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const MODULE_FIELDS = [
+  "id",
+  "type",
+  "subtype",
+  "costItemIds",
+  "requiredParameters",
+  "costItems",
+] as const;
+
+export type ModuleField = (typeof MODULE_FIELDS)[number];
+
+export const DEFAULT_MODULE_FIELDS: ModuleField[] = [
+  "id",
+  "type",
+  "subtype",
+  "requiredParameters",
+];
+
+export const MAX_PER_PAGE = 500;
+
+export type ModuleListingQuery = {
+  type?: string;
+  ids?: string[];
+  fields: ModuleField[];
+  /** 1-based page, or undefined for everything */
+  page?: number;
+  perPage: number;
+  /** Language to present cost item names and descriptions in */
+  language?: string;
+};
+
+export type ModuleListingEntry = Partial<
+  ModuleInfo & {
+    /** Full cost item definitions, including cost curves */
+    costItems: CostLibraryCostItem[];
+  }
+>;
+
+export type ModuleListing = {
+  type?: string;
+  /** Language cost item text is presented in, if translated */
+  language?: string;
+  modules: ModuleListingEntry[];
+  /** Number of modules matching the filters, across all pages */
+  total: number;
+  page?: number;
+  perPage?: number;
+  totalPages?: number;
+};
+
+// ============================================================================
+// Query Parsing
+// ============================================================================
+
+const LISTING_PARAMS = ["type", "ids", "fields", "page", "per_page"];
+
+/**
+ * Whether the query asks for a module listing rather than the type summary.
+ */
+export function isModuleListingQuery(
+  query: Record<string, string | undefined>,
+): boolean {
+  return LISTING_PARAMS.some((param) => query[param] !== undefined);
+}
+
+function splitList(value: string | undefined): string[] | undefined {
+  if (value === undefined) return undefined;
+  return value
+    .split(",")
+    .map((part) => part.trim())
+    .filter((part) => part.length > 0);
+}
+
+function parsePositiveInt(value: string): number | undefined {
+  return /^\d+$/.test(value) && parseInt(value) > 0
+    ? parseInt(value)
+    : undefined;
+}
+
+/**
+ * Parse `type`, `ids`, `fields`, `page` and `per_page` query parameters.
+ * Returns an error message for invalid values.
+ */
+export function parseModuleListingQuery(
+  query: Record<string, string | undefined>,
+): { ok: true; query: ModuleListingQuery } | { ok: false; message: string } {
+  const fields = splitList(query.fields) ?? DEFAULT_MODULE_FIELDS;
+  const unknown = fields.filter(
+    (f) => !(MODULE_FIELDS as readonly string[]).includes(f),
+  );
+  if (unknown.length > 0) {
+    return {
+      ok: false,
+      message: `Unknown fields: ${unknown.join(", ")}. Valid fields: ${MODULE_FIELDS.join(", ")}`,
+    };
+  }
+
+  let page: number | undefined;
+  if (query.page !== undefined) {
+    page = parsePositiveInt(query.page);
+    if (page === undefined) {
+      return { ok: false, message: "page must be a positive integer" };
+    }
+  }
+
+  let perPage = 50;
+  if (query.per_page !== undefined) {
+    const parsed = parsePositiveInt(query.per_page);
+    if (parsed === undefined || parsed > MAX_PER_PAGE) {
+      return {
+        ok: false,
+        message: `per_page must be an integer between 1 and ${MAX_PER_PAGE}`,
+      };
+    }
+    perPage = parsed;
+    page ??= 1;
+  }
+
+  return {
+    ok: true,
+    query: {
+      type: query.type,
+      ids: splitList(query.ids),
+      fields: fields as ModuleField[],
+      page,
+      perPage,
+    },
+  };
+}
+
+// ============================================================================
+// Listing
+// ============================================================================
+
+const FIELD_VALUES: {
+  [F in ModuleField]-?: (
+    module: ModuleInfo,
+    service: ModuleLookupService,
+    language: string | undefined,
+  ) => ModuleListingEntry[F];
+} = {
+  id: (m) => m.id,
+  type: (m) => m.type,
+  subtype: (m) => m.subtype,
+  costItemIds: (m) => m.costItemIds,
+  requiredParameters: (m) => m.requiredParameters,
+  costItems: (m, service, language) =>
+    m.costItemIds
+      .map((id) => service.findCostItem(id))
+      .filter((item): item is CostLibraryCostItem => item !== undefined)
+      .map((item) => (language ? localizeCostItem(item, language) : item)),
+};
+
+function selectFields(
+  module: ModuleInfo,
+  fields: ModuleField[],
+  service: ModuleLookupService,
+  language: string | undefined,
+): ModuleListingEntry {
+  return Object.fromEntries(
+    fields.map((field) => [
+      field,
+      FIELD_VALUES[field](module, service, language),
+    ]),
+  );
+}
+
+/**
+ * List modules matching the query, in library order.
+ */
+export function listModules(
+  service: ModuleLookupService,
+  query: ModuleListingQuery,
+): ModuleListing {
+  let modules = query.type
+    ? service.findByType(query.type)
+    : service.listAll();
+  if (query.ids) {
+    const ids = new Set(query.ids);
+    modules = modules.filter((m) => ids.has(m.id));
+  }
+
+  const total = modules.length;
+  const pageModules =
+    query.page === undefined
+      ? modules
+      : modules.slice(
+          (query.page - 1) * query.perPage,
+          query.page * query.perPage,
+        );
+
+  return {
+    ...(query.type !== undefined && { type: query.type }),
+    ...(query.language !== undefined && { language: query.language }),
+    modules: pageModules.map((m) =>
+      selectFields(m, query.fields, service, query.language),
+    ),
+    total,
+    ...(query.page !== undefined && {
+      page: query.page,
+      perPage: query.perPage,
+      totalPages: Math.ceil(total / query.perPage),
+    }),
+  };
+}
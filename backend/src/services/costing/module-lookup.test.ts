@@ -2,12 +2,17 @@
  * Tests for the module lookup service.
  */
 
-import { describe, it, expect, beforeAll } from "vitest";
+import { describe, it, expect, beforeAll, beforeEach } from "vitest";
 import {
   loadCostLibrary,
   listCostLibraries,
   ModuleLookupService,
   buildModuleIndex,
+  getModuleLookupService,
+  prewarmLibraries,
+  getLibraryLoadStatus,
+  clearModuleLookupCache,
+  hashCostLibrary,
 } from "./module-lookup";
 import {
   normalizeBlockType,
@@ -75,6 +80,57 @@ describe("listCostLibraries", () => {
   });
 });
 
+describe("library cache", () => {
+  beforeEach(() => {
+    clearModuleLookupCache();
+  });
+
+  it("shares one load between concurrent callers", async () => {
+    const [a, b] = await Promise.all([
+      getModuleLookupService("V1.1_working"),
+      getModuleLookupService("V1.1_working"),
+    ]);
+
+    expect(a).toBe(b);
+    expect(getLibraryLoadStatus()).toEqual([
+      expect.objectContaining({ libraryId: "V1.1_working", status: "ready" }),
+    ]);
+  });
+
+  it("pre-warms every library and records timings", async () => {
+    const status = await prewarmLibraries(2);
+
+    expect(status.map((s) => s.libraryId)).toEqual(
+      (await listCostLibraries()).sort(),
+    );
+    for (const entry of status) {
+      expect(entry.status).toBe("ready");
+      expect(entry.durationMs).toBeGreaterThanOrEqual(0);
+    }
+  });
+
+  it("hashes library content at load", async () => {
+    const service = await getModuleLookupService("V1.1_working");
+    const library = await loadCostLibrary("V1.1_working");
+
+    expect(service.contentHash).toBe(hashCostLibrary(library));
+    expect(service.contentHash).not.toBe(
+      hashCostLibrary({ ...library, modules: library.modules.slice(1) }),
+    );
+    expect(getLibraryLoadStatus()[0].contentHash).toBe(service.contentHash);
+  });
+
+  it("records failed loads without caching them", async () => {
+    await expect(getModuleLookupService("nonexistent")).rejects.toThrow();
+
+    expect(getLibraryLoadStatus()[0]).toMatchObject({
+      libraryId: "nonexistent",
+      status: "failed",
+    });
+    await expect(getModuleLookupService("nonexistent")).rejects.toThrow();
+  });
+});
+
 describe("ModuleLookupService", () => {
   let library: CostLibrary;
   let service: ModuleLookupService;
@@ -186,4 +242,64 @@ describe("ModuleLookupService", () => {
       expect(massFlowParam?.units).toBe("kg/h");
     });
   });
+
+  describe("bundles", () => {
+    it("requires component parameters under the bundle's names", () => {
+      const bundled = new ModuleLookupService({
+        ...library,
+        modules: library.modules.map((m) =>
+          m.id === "M0201" ? { ...m, bundles: ["B001"] } : m,
+        ),
+        bundles: [
+          {
+            id: "B001",
+            info: { short_name: "Compression package" },
+            components: [
+              { ref: "Item 007" },
+              {
+                ref: "Item 008",
+                quantity: 2,
+                parameters: { "Electrical power": "Cooler electrical power" },
+              },
+            ],
+          },
+        ],
+      });
+
+      const module = bundled.lookup("CaptureUnit", "Amine");
+      expect(module?.bundleIds).toEqual(["B001"]);
+      expect(module?.requiredParameters).toContainEqual({
+        name: "Cooler electrical power",
+        units: expect.any(String),
+        costItemId: "Item 008",
+      });
+      expect(module?.requiredParameters).toContainEqual(
+        expect.objectContaining({
+          name: "Compressor Duty",
+          costItemId: "Item 007",
+        }),
+      );
+      expect(bundled.getBundle("B001")?.components).toHaveLength(2);
+    });
+  });
+
+  describe("getFactorDefaults", () => {
+    it("returns the factor defaults shipped with the library", () => {
+      const defaults = service.getFactorDefaults();
+      expect(defaults.capex_lang_factors.piping).toBe(0.7);
+      expect(defaults.opex_factors.maintenance).toBe(0.08);
+    });
+
+    it("falls back to built-in defaults for unspecified factors", () => {
+      const library: CostLibrary = {
+        modules: [],
+        defaults: { capex_lang_factors: { piping: 0.5 } },
+      };
+      const defaults = new ModuleLookupService(library).getFactorDefaults();
+
+      expect(defaults.capex_lang_factors.piping).toBe(0.5);
+      expect(defaults.capex_lang_factors.contingency).toBe(1.0);
+      expect(defaults.opex_factors.maintenance).toBe(0.08);
+    });
+  });
 });
@@ -5,10 +5,36 @@
  * Dagger block types to cost library module IDs.
  */
 
+import { createHash } from "crypto";
 import { readFile } from "fs/promises";
-import { join, resolve } from "path";
-import type { CostLibrary, CostLibraryModule } from "./types";
+import { join } from "path";
+import type {
+  CostLibrary,
+  CostLibraryModule,
+  CostLibraryCostItem,
+  CapexLangFactors,
+  FixedOpexFactors,
+  ParameterDefinition,
+  CostItemBundle,
+  ItemConstraint,
+  CbsMapping,
+} from "./types";
+import { DEFAULT_CAPEX_LANG_FACTORS, DEFAULT_OPEX_FACTORS } from "./defaults";
 import { normalizeBlockTypeWithOverrides } from "./type-normalization";
+import { libraryLanguages } from "./localization";
+import { costLibraryStats, type CostLibraryStats } from "./library-stats";
+import { ParameterRegistry } from "./parameter-registry";
+import {
+  previousCostItemIds,
+  resolveCostItemAlias,
+} from "./cost-item-aliases";
+import { getDataDir } from "../../utils/data-dir";
+import {
+  SIGNATURE_FILE,
+  signaturePolicyFromEnv,
+  verifyLibrary,
+  type LibrarySignature,
+} from "./library-signing";
 
 // ============================================================================
 // Types
@@ -19,6 +45,7 @@ export type ModuleInfo = {
   type: string;         // e.g., "CaptureUnit"
   subtype: string | null; // e.g., "Amine" or null if no subtype
   costItemIds: string[]; // e.g., ["Item 023"]
+  bundleIds: string[];   // e.g., ["B001"]
   requiredParameters: ParameterInfo[];
 };
 
@@ -40,20 +67,37 @@ export type ModuleLookupResult = {
 
 /**
  * Get the path to the cost library data directory.
- * Resolves relative to process.cwd() which should be the backend directory.
  */
 function getLibraryDataPath(): string {
-  return resolve(process.cwd(), "data/costing");
+  return join(getDataDir(), "costing");
+}
+
+async function readSignature(
+  libraryDir: string,
+): Promise<LibrarySignature | undefined> {
+  try {
+    const content = await readFile(join(libraryDir, SIGNATURE_FILE), "utf-8");
+    return JSON.parse(content) as LibrarySignature;
+  } catch (error) {
+    if ((error as NodeJS.ErrnoException).code === "ENOENT") return undefined;
+    throw error;
+  }
 }
 
 /**
- * Load a cost library by ID.
+ * Load a cost library by ID, verifying its signature under the
+ * LIBRARY_SIGNATURES policy.
+ *
+ * @throws LibrarySignatureError if the library fails verification
  */
 export async function loadCostLibrary(libraryId: string): Promise<CostLibrary> {
-  const libraryDataPath = getLibraryDataPath();
-  const filePath = join(libraryDataPath, libraryId, "cost-library.json");
-  const content = await readFile(filePath, "utf-8");
-  return JSON.parse(content) as CostLibrary;
+  const libraryDir = join(getLibraryDataPath(), libraryId);
+  const bytes = await readFile(join(libraryDir, "cost-library.json"));
+  const policy = signaturePolicyFromEnv();
+  if (policy.mode !== "off") {
+    verifyLibrary(libraryId, bytes, await readSignature(libraryDir), policy);
+  }
+  return JSON.parse(bytes.toString("utf-8")) as CostLibrary;
 }
 
 /**
@@ -90,7 +134,7 @@ export function buildModuleIndex(library: CostLibrary): ModuleIndex {
   const all: ModuleInfo[] = [];
 
   for (const module of library.modules) {
-    const info = extractModuleInfo(module);
+    const info = extractModuleInfo(module, library);
     all.push(info);
     byId.set(info.id, info);
 
@@ -109,7 +153,10 @@ export function buildModuleIndex(library: CostLibrary): ModuleIndex {
 /**
  * Extract module info from a cost library module.
  */
-function extractModuleInfo(module: CostLibraryModule): ModuleInfo {
+function extractModuleInfo(
+  module: CostLibraryModule,
+  library: CostLibrary,
+): ModuleInfo {
   const costItemIds = module.cost_items?.map(item => item.id) ?? [];
   
   // Collect all required parameters from cost items
@@ -132,11 +179,33 @@ function extractModuleInfo(module: CostLibraryModule): ModuleInfo {
     }
   }
 
+  // Bundle components need their parameters under the bundle's names
+  const bundleIds = module.bundles ?? [];
+  for (const bundleId of bundleIds) {
+    const bundle = library.bundles?.find((b) => b.id === bundleId);
+    for (const component of bundle?.components ?? []) {
+      const costItem = library.modules
+        .flatMap((m) => m.cost_items ?? [])
+        .find((item) => item.id === component.ref);
+      for (const p of [
+        ...(costItem?.scaling_factors ?? []),
+        ...(costItem?.variable_opex_contributions ?? []),
+      ]) {
+        requiredParameters.push({
+          name: component.parameters?.[p.name] ?? p.name,
+          units: p.units,
+          costItemId: component.ref,
+        });
+      }
+    }
+  }
+
   return {
     id: module.id,
     type: module.definition.type,
     subtype: module.subtype ?? null,
     costItemIds,
+    bundleIds,
     requiredParameters,
   };
 }
@@ -152,17 +221,40 @@ export type CostItemInfo = {
     short_name?: string;
     description?: string;
   };
-  scaling_factors: Array<{ name: string; units: string; source_value: number }>;
-  variable_opex_contributions: Array<{ name: string; units: string; scaled_by?: number }>;
+  scaling_factors: Array<{
+    name: string;
+    units: string;
+    source_value: number;
+    default_value?: number;
+  }>;
+  variable_opex_contributions: Array<{
+    name: string;
+    units: string;
+    scaled_by?: number;
+    default_value?: number;
+  }>;
 };
 
+/**
+ * SHA-256 of a library's content, used as its ETag and integrity hash.
+ */
+export function hashCostLibrary(library: CostLibrary): string {
+  return createHash("sha256").update(JSON.stringify(library)).digest("hex");
+}
+
 export class ModuleLookupService {
   private index: ModuleIndex;
   private library: CostLibrary;
+  /** Content hash of the library, computed once at load */
+  readonly contentHash: string;
+  /** Canonical names for the library's parameters */
+  readonly parameters: ParameterRegistry;
 
   constructor(library: CostLibrary) {
     this.index = buildModuleIndex(library);
     this.library = library;
+    this.contentHash = hashCostLibrary(library);
+    this.parameters = ParameterRegistry.forLibrary(library);
   }
 
   /**
@@ -267,23 +359,222 @@ export class ModuleLookupService {
       variable_opex_contributions: costItem.variable_opex_contributions ?? [],
     };
   }
+
+  /**
+   * Find a cost item by ID in any module, following the library's cost item
+   * aliases for IDs renamed since an earlier version.
+   * Cost items shared between modules are identical, so the first match is used.
+   */
+  findCostItem(costItemId: string): CostLibraryCostItem | undefined {
+    costItemId = this.resolveCostItemId(costItemId);
+    for (const module of this.library.modules) {
+      const costItem = module.cost_items?.find(item => item.id === costItemId);
+      if (costItem) return costItem;
+    }
+    return undefined;
+  }
+
+  /**
+   * Selection constraints declared on a module.
+   */
+  getConstraints(moduleId: string): ItemConstraint[] {
+    return (
+      this.library.modules.find((m) => m.id === moduleId)?.constraints ?? []
+    );
+  }
+
+  /**
+   * CBS codes shipped with the library (if any).
+   */
+  getCbsMapping(): CbsMapping | undefined {
+    return this.library.cbs_mapping;
+  }
+
+  /**
+   * Get a bundle by ID.
+   */
+  getBundle(bundleId: string): CostItemBundle | undefined {
+    return this.library.bundles?.find((b) => b.id === bundleId);
+  }
+
+  /**
+   * The current ID of a cost item, following the library's aliases for IDs
+   * renamed since an earlier version.
+   */
+  resolveCostItemId(costItemId: string): string {
+    return (
+      resolveCostItemAlias(this.library.cost_item_aliases, costItemId) ??
+      costItemId
+    );
+  }
+
+  /**
+   * Earlier IDs of a cost item, from the library's aliases.
+   */
+  previousCostItemIds(costItemId: string): string[] {
+    return previousCostItemIds(this.library.cost_item_aliases, costItemId);
+  }
+
+  /**
+   * Get the library's inflation factor taking a cost from `year` to the
+   * library's current year, or undefined if the year is not in the table.
+   */
+  getInflationFactor(year: number): number | undefined {
+    return this.library.inflation?.factors[String(year)];
+  }
+
+  /**
+   * The library's price base year (the year all costs are inflated to).
+   */
+  getPriceBaseYear(): number | undefined {
+    const year = this.library.inflation?.current_year;
+    return year !== undefined ? parseInt(year) : undefined;
+  }
+
+  /**
+   * Get the rate converting an amount in `from` currency to `to` currency,
+   * or undefined if either currency is unknown to the library.
+   */
+  getCurrencyRate(from: string, to: string): number | undefined {
+    const rates = this.library.currency_conversion?.rates;
+    const fromRate = rates?.[from];
+    const toRate = rates?.[to];
+    if (fromRate === undefined || toRate === undefined) return undefined;
+    return fromRate / toRate;
+  }
+
+  /**
+   * Languages the library has cost item translations for.
+   */
+  getLanguages(): string[] {
+    return libraryLanguages(this.library);
+  }
+
+  /**
+   * Summary statistics for this library.
+   */
+  getStats(): CostLibraryStats {
+    return costLibraryStats(this.library);
+  }
+
+  /**
+   * Canonical parameters this library's cost items use.
+   */
+  listParameters(): ParameterDefinition[] {
+    return this.parameters.usedBy(this.library);
+  }
+
+  /**
+   * Default Lang and fixed OPEX factors for this library. Factors the library
+   * doesn't specify fall back to the built-in defaults.
+   */
+  getFactorDefaults(): {
+    capex_lang_factors: CapexLangFactors;
+    opex_factors: FixedOpexFactors;
+  } {
+    return {
+      capex_lang_factors: {
+        ...DEFAULT_CAPEX_LANG_FACTORS,
+        ...this.library.defaults?.capex_lang_factors,
+      },
+      opex_factors: {
+        ...DEFAULT_OPEX_FACTORS,
+        ...this.library.defaults?.opex_factors,
+      },
+    };
+  }
 }
 
 // ============================================================================
 // Singleton Cache
 // ============================================================================
 
-const serviceCache = new Map<string, ModuleLookupService>();
+/**
+ * Load state of a library in the service cache, reported by /health.
+ */
+export type LibraryLoadStatus = {
+  libraryId: string;
+  status: "loading" | "ready" | "failed";
+  /** Time spent reading, parsing and indexing the library */
+  durationMs?: number;
+  loadedAt?: string;
+  contentHash?: string;
+  error?: string;
+};
+
+const serviceCache = new Map<string, Promise<ModuleLookupService>>();
+const loadStatus = new Map<string, LibraryLoadStatus>();
 
 /**
  * Get or create a module lookup service for a given library.
+ *
+ * Libraries are loaded lazily on first use. Concurrent callers share the same
+ * load; a failed load is not cached so the next call retries.
  */
 export async function getModuleLookupService(libraryId: string): Promise<ModuleLookupService> {
-  if (!serviceCache.has(libraryId)) {
-    const library = await loadCostLibrary(libraryId);
-    serviceCache.set(libraryId, new ModuleLookupService(library));
+  let service = serviceCache.get(libraryId);
+  if (!service) {
+    service = loadModuleLookupService(libraryId);
+    serviceCache.set(libraryId, service);
+    service.catch(() => {
+      if (serviceCache.get(libraryId) === service) {
+        serviceCache.delete(libraryId);
+      }
+    });
+  }
+  return service;
+}
+
+async function loadModuleLookupService(libraryId: string): Promise<ModuleLookupService> {
+  const started = performance.now();
+  loadStatus.set(libraryId, { libraryId, status: "loading" });
+  try {
+    const service = new ModuleLookupService(await loadCostLibrary(libraryId));
+    loadStatus.set(libraryId, {
+      libraryId,
+      status: "ready",
+      durationMs: Math.round(performance.now() - started),
+      loadedAt: new Date().toISOString(),
+      contentHash: service.contentHash,
+    });
+    return service;
+  } catch (error) {
+    loadStatus.set(libraryId, {
+      libraryId,
+      status: "failed",
+      durationMs: Math.round(performance.now() - started),
+      error: error instanceof Error ? error.message : String(error),
+    });
+    throw error;
   }
-  return serviceCache.get(libraryId)!;
+}
+
+/**
+ * Load all available libraries in the background so the first estimate
+ * against each one does not pay the parsing cost. At most `concurrency`
+ * libraries are loaded at once. Failures are recorded in the load status
+ * rather than thrown.
+ */
+export async function prewarmLibraries(concurrency = 2): Promise<LibraryLoadStatus[]> {
+  const queue = await listCostLibraries();
+  const worker = async () => {
+    for (let id = queue.shift(); id !== undefined; id = queue.shift()) {
+      await getModuleLookupService(id).catch(() => undefined);
+    }
+  };
+  await Promise.all(
+    Array.from({ length: Math.max(1, concurrency) }, worker),
+  );
+  return getLibraryLoadStatus();
+}
+
+/**
+ * Load state and timing of every library loaded (or being loaded) so far.
+ */
+export function getLibraryLoadStatus(): LibraryLoadStatus[] {
+  return [...loadStatus.values()].sort((a, b) =>
+    a.libraryId.localeCompare(b.libraryId),
+  );
 }
 
 /**
@@ -291,4 +582,5 @@ export async function getModuleLookupService(libraryId: string): Promise<ModuleL
  */
 export function clearModuleLookupCache(): void {
   serviceCache.clear();
+  loadStatus.clear();
 }
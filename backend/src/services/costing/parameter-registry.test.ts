@@ -0,0 +1,70 @@
+import { describe, it, expect } from "vitest";
+import { ParameterRegistry } from "./parameter-registry";
+import { loadCostLibrary } from "./module-lookup";
+import { validateCostLibrary } from "./library-validation";
+
+describe("parameter-registry", () => {
+  it("resolves names and aliases case-insensitively", () => {
+    const registry = ParameterRegistry.forLibrary({ modules: [] });
+
+    expect(registry.resolve("pump moter rating")?.id).toBe(
+      "pump_motor_rating",
+    );
+    expect(registry.resolve("Frequency of crossings per 10 km")?.id).toBe(
+      registry.resolve("Number of crossings")?.id,
+    );
+    expect(registry.resolve("Mass flow")).toBeUndefined();
+  });
+
+  it("reads values from the canonical ID before other names", () => {
+    const registry = ParameterRegistry.forLibrary({ modules: [] });
+
+    expect(registry.blockProperties("Pump flowrate (volumetric)")).toEqual([
+      "pump_flowrate",
+      "pump_flowrate_volumetric",
+    ]);
+    expect(registry.blockProperties("Mass flow")).toEqual(["mass_flow"]);
+  });
+
+  it("lets a library override built-in parameters", () => {
+    const registry = ParameterRegistry.forLibrary({
+      modules: [],
+      parameters: [
+        {
+          id: "heater_duty",
+          name: "Heater duty",
+          units: "MW",
+          aliases: ["Heater Duty", "Heating duty"],
+        },
+      ],
+    });
+
+    expect(registry.resolve("Heating duty")?.name).toBe("Heater duty");
+  });
+
+  it("lists the parameters a library uses", async () => {
+    const library = await loadCostLibrary("V2.0");
+    const parameters = ParameterRegistry.forLibrary(library).usedBy(library);
+    const ids = parameters.map((p) => p.id);
+
+    expect(ids).toContain("crossings_frequency");
+    expect(ids).toEqual([...new Set(ids)].sort());
+  });
+
+  it("flags names claimed by two parameters", () => {
+    const issues = validateCostLibrary({
+      modules: [],
+      parameters: [
+        { id: "a", name: "Duty", units: "MW" },
+        { id: "b", name: "Other", units: "MW", aliases: ["duty"] },
+      ],
+    });
+
+    expect(issues).toEqual([
+      {
+        path: "parameters[1]",
+        message: 'name "duty" is also used by parameter a',
+      },
+    ]);
+  });
+});
@@ -0,0 +1,141 @@
+/**
+ * Canonical parameter registry.
+ *
+ * Cost items name their scaling factors and variable OPEX contributions by
+ * display name ("Cooling water (10degC temp rise)"), and those names have
+ * drifted between library versions. The registry gives each parameter a
+ * canonical ID, which is also the block property its value is read from,
+ * and maps the display name and any aliases to it. Libraries declare their
+ * parameters in `parameters`; the built-in entries below cover names the
+ * shipped libraries use without declaring them.
+ */
+
+import type { CostLibrary, ParameterDefinition } from "./types";
+
+// ============================================================================
+// Built-in parameters
+// ============================================================================
+
+export const DEFAULT_PARAMETERS: ParameterDefinition[] = [
+  {
+    id: "pump_motor_rating",
+    name: "Pump motor rating",
+    units: "kW",
+    // V1.1 misspells "motor"
+    aliases: ["Pump moter rating"],
+  },
+  {
+    id: "pump_flowrate",
+    name: "Pump flowrate (volumetric)",
+    units: "m3/hr",
+  },
+  {
+    id: "cooling_water",
+    name: "Cooling water (10degC temp rise)",
+    units: "m3/h",
+  },
+  {
+    id: "heater_duty",
+    name: "Heater Duty",
+    units: "MW",
+  },
+  {
+    id: "crossings_frequency",
+    name: "Number of crossings",
+    units: "dimensionless",
+    // V1.1 and V1.3 name; renamed in V2.0
+    aliases: ["Frequency of crossings per 10 km"],
+  },
+];
+
+/**
+ * Normalize a parameter name to block property format.
+ * e.g., "Mass flow" → "mass_flow"
+ */
+export function normalizeParameterName(name: string): string {
+  return name
+    .toLowerCase()
+    .replace(/\s+/g, "_")
+    .replace(/[^a-z0-9_]/g, "");
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+export class ParameterRegistry {
+  private byId = new Map<string, ParameterDefinition>();
+  private byName = new Map<string, ParameterDefinition>();
+
+  /**
+   * @param definitions - Later definitions replace earlier ones with the
+   *   same ID, so library entries override the built-in ones
+   */
+  constructor(definitions: ParameterDefinition[]) {
+    for (const definition of definitions) {
+      this.byId.set(definition.id, definition);
+    }
+    for (const definition of this.byId.values()) {
+      for (const name of [definition.name, ...(definition.aliases ?? [])]) {
+        this.byName.set(name.toLowerCase(), definition);
+      }
+    }
+  }
+
+  /**
+   * The registry for a library: the built-in parameters overlaid with the
+   * library's own.
+   */
+  static forLibrary(library: CostLibrary): ParameterRegistry {
+    return new ParameterRegistry([
+      ...DEFAULT_PARAMETERS,
+      ...(library.parameters ?? []),
+    ]);
+  }
+
+  /**
+   * Find the canonical parameter for a cost item's parameter name or alias
+   * (case-insensitive).
+   */
+  resolve(name: string): ParameterDefinition | undefined {
+    return this.byName.get(name.toLowerCase());
+  }
+
+  /**
+   * Block properties a parameter's value may be read from, in order: the
+   * canonical ID, then the normalized display name and aliases.
+   */
+  blockProperties(name: string): string[] {
+    const definition = this.resolve(name);
+    if (!definition) return [normalizeParameterName(name)];
+    return [
+      ...new Set([
+        definition.id,
+        normalizeParameterName(definition.name),
+        ...(definition.aliases ?? []).map(normalizeParameterName),
+      ]),
+    ];
+  }
+
+  /**
+   * The canonical parameters a library's cost items use, sorted by ID.
+   * Names the registry doesn't know get an entry with a normalized ID.
+   */
+  usedBy(library: CostLibrary): ParameterDefinition[] {
+    const used = new Map<string, ParameterDefinition>();
+    for (const item of library.modules.flatMap((m) => m.cost_items ?? [])) {
+      for (const { name, units } of [
+        ...(item.scaling_factors ?? []),
+        ...(item.variable_opex_contributions ?? []),
+      ]) {
+        const definition = this.resolve(name) ?? {
+          id: normalizeParameterName(name),
+          name,
+          units,
+        };
+        used.set(definition.id, definition);
+      }
+    }
+    return [...used.values()].sort((a, b) => (a.id < b.id ? -1 : 1));
+  }
+}
@@ -0,0 +1,48 @@
+import { describe, it, expect } from "vitest";
+import { ESTIMATE_PRESETS, listEstimatePresets } from "./presets";
+import { resolveAssetProperties } from "./request-types";
+
+describe("presets", () => {
+  it("lists presets from least to most defined", () => {
+    expect(listEstimatePresets().map((p) => p.name)).toEqual([
+      "AACE Class 5",
+      "AACE Class 4",
+      "AACE Class 3",
+    ]);
+  });
+
+  describe("resolveAssetProperties", () => {
+    it("takes Lang factors from the named preset", () => {
+      const resolved = resolveAssetProperties(undefined, {
+        preset: "AACE Class 4",
+      });
+
+      expect(resolved.preset).toBe("AACE Class 4");
+      expect(resolved.capex_lang_factors).toEqual(
+        ESTIMATE_PRESETS["AACE Class 4"].capex_lang_factors,
+      );
+      expect(resolved.usingDefaults.has("capex_lang_factors")).toBe(false);
+    });
+
+    it("lets explicit factors override the preset", () => {
+      const resolved = resolveAssetProperties({
+        preset: "AACE Class 3",
+        capex_lang_factors: { contingency: 0.1 },
+      });
+
+      expect(resolved.capex_lang_factors.contingency).toBe(0.1);
+      expect(resolved.capex_lang_factors.piping).toBe(
+        ESTIMATE_PRESETS["AACE Class 3"].capex_lang_factors.piping,
+      );
+    });
+
+    it("prefers a per-asset preset over the global one", () => {
+      const resolved = resolveAssetProperties(
+        { preset: "AACE Class 3" },
+        { preset: "AACE Class 5" },
+      );
+
+      expect(resolved.preset).toBe("AACE Class 3");
+    });
+  });
+});
@@ -0,0 +1,68 @@
+/**
+ * Named factor presets (estimate classes).
+ *
+ * A preset bundles the Lang factors and accuracy range appropriate to an
+ * estimate class, so a request can name the class instead of spelling out
+ * every factor. Explicit factors in the same request still take precedence.
+ */
+
+import type { CapexLangFactors } from "./types";
+import { DEFAULT_CAPEX_LANG_FACTORS } from "./defaults";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const ESTIMATE_PRESET_NAMES = [
+  "AACE Class 5",
+  "AACE Class 4",
+  "AACE Class 3",
+] as const;
+
+export type EstimatePresetName = (typeof ESTIMATE_PRESET_NAMES)[number];
+
+export type AccuracyRange = {
+  /** Expected low end, as a fraction of the point estimate (e.g., -0.3) */
+  low: number;
+  /** Expected high end, as a fraction of the point estimate (e.g., 0.5) */
+  high: number;
+};
+
+export type EstimatePreset = {
+  name: EstimatePresetName;
+  description: string;
+  capex_lang_factors: CapexLangFactors;
+  accuracy: AccuracyRange;
+};
+
+// ============================================================================
+// Presets
+// ============================================================================
+
+export const ESTIMATE_PRESETS: Record<EstimatePresetName, EstimatePreset> = {
+  "AACE Class 5": {
+    name: "AACE Class 5",
+    description: "Concept screening (0-2% project definition)",
+    capex_lang_factors: { ...DEFAULT_CAPEX_LANG_FACTORS, contingency: 1.0 },
+    accuracy: { low: -0.3, high: 0.5 },
+  },
+  "AACE Class 4": {
+    name: "AACE Class 4",
+    description: "Study or feasibility (1-15% project definition)",
+    capex_lang_factors: { ...DEFAULT_CAPEX_LANG_FACTORS, contingency: 0.5 },
+    accuracy: { low: -0.2, high: 0.3 },
+  },
+  "AACE Class 3": {
+    name: "AACE Class 3",
+    description: "Budget authorization (10-40% project definition)",
+    capex_lang_factors: { ...DEFAULT_CAPEX_LANG_FACTORS, contingency: 0.3 },
+    accuracy: { low: -0.15, high: 0.2 },
+  },
+};
+
+/**
+ * List all presets in order of increasing definition.
+ */
+export function listEstimatePresets(): EstimatePreset[] {
+  return ESTIMATE_PRESET_NAMES.map((name) => ESTIMATE_PRESETS[name]);
+}
@@ -0,0 +1,84 @@
+import { describe, it, expect } from "vitest";
+import type { AssetMetadata } from "./adapter";
+import {
+  applyPriceBase,
+  PriceBaseError,
+  resolvePriceBase,
+} from "./price-base";
+import type { CostLibrary } from "./types";
+import { DEFAULT_OPEX_FACTORS } from "./defaults";
+import { ESTIMATE_PRESETS } from "./presets";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+const library = {
+  modules: [],
+  inflation: { current_year: "2024", factors: { "2022": 1.25, "2024": 1 } },
+} as CostLibrary;
+
+describe("price-base", () => {
+  it("re-bases from the library's price base year", () => {
+    expect(resolvePriceBase(2022, library)).toEqual({
+      year: 2022,
+      libraryYear: 2024,
+      factor: 0.8,
+    });
+    expect(resolvePriceBase(2024, library).factor).toBe(1);
+  });
+
+  it("rejects years the library can't inflate from", () => {
+    expect(() => resolvePriceBase(2010, library)).toThrow(PriceBaseError);
+    expect(() =>
+      resolvePriceBase(2022, { modules: [] } as CostLibrary),
+    ).toThrow(/no price base year/);
+  });
+
+  it("scales every cost but not years or quantities", () => {
+    const response = {
+      assets: [
+        mockAssetEstimate(
+          "asset-1",
+          [
+            mockYearCosts(2030, {
+              total_installed_cost: 100,
+              variable_opex_cost: { tariff: 10 },
+            }),
+          ],
+          [mockCostItem("item-1", { direct_equipment_cost: 50 })],
+        ),
+      ],
+    };
+    const metadata: AssetMetadata = {
+      assetId: "asset-1",
+      isGroup: false,
+      branchIds: ["asset-1"],
+      blockCount: 0,
+      costableBlockCount: 0,
+      usingDefaults: [],
+      provenance: {
+        capexLangFactors: ESTIMATE_PRESETS["AACE Class 5"].capex_lang_factors,
+        opexFactors: DEFAULT_OPEX_FACTORS,
+      },
+      blocks: [],
+    };
+    const priceBase = resolvePriceBase(2022, library);
+
+    const { costingResponse, assetMetadata } = applyPriceBase(
+      response,
+      [metadata],
+      priceBase,
+    );
+
+    const [asset] = costingResponse.assets;
+    expect(asset.costs_by_year[0].year).toBe(2030);
+    expect(asset.costs_by_year[0].costs_in_year.total_installed_cost).toBe(80);
+    expect(asset.lifetime_costs.variable_opex_cost.tariff).toBe(8);
+    expect(asset.cost_items[0].quantity).toBe(1);
+    expect(asset.cost_items[0].lifetime_costs.direct_equipment_cost).toBe(40);
+    expect(asset.cost_items[0].lifetime_costs.total_installed_cost).toBeNull();
+    expect(assetMetadata[0].provenance?.priceBase).toEqual(priceBase);
+  });
+});
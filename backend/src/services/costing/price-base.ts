@@ -0,0 +1,108 @@
+/**
+ * Price base year.
+ *
+ * The costing server inflates every cost to the library's price base year
+ * (`inflation.current_year`). An analysis can ask for another base, e.g.
+ * "2022 real terms": every monetary value in the response is then divided
+ * by the library's inflation factor from that year to its own base, so
+ * costs read in the requested year's prices. Rebased assets record the
+ * base in their provenance.
+ */
+
+import type { AssetMetadata } from "./adapter";
+import type { CostEstimateResponse, CostLibrary } from "./types";
+import { mapCostValues, rescaleYears, type YearlyCosts } from "./totals";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/** A requested price base year checked against the library */
+export type PriceBase = {
+  /** Year outputs are expressed in */
+  year: number;
+  /** The library's price base year */
+  libraryYear: number;
+  /** Multiplier taking a cost from the library's base to `year` */
+  factor: number;
+};
+
+export class PriceBaseError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "PriceBaseError";
+  }
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/**
+ * The factor re-basing costs from the library's price base year to `year`.
+ *
+ * @throws PriceBaseError if the library has no price base year or no
+ *   inflation factor for `year`
+ */
+export function resolvePriceBase(
+  year: number,
+  library: CostLibrary,
+): PriceBase {
+  const current = library.inflation?.current_year;
+  if (current === undefined) {
+    throw new PriceBaseError(
+      "The library has no price base year to re-base costs from",
+    );
+  }
+  const libraryYear = parseInt(current);
+  if (year === libraryYear) return { year, libraryYear, factor: 1 };
+
+  const factor = library.inflation?.factors[String(year)];
+  if (factor === undefined || factor === 0) {
+    throw new PriceBaseError(
+      `The library has no inflation factor for ${year}`,
+    );
+  }
+  return { year, libraryYear, factor: 1 / factor };
+}
+
+// ============================================================================
+// Application
+// ============================================================================
+
+/**
+ * Re-base every cost in a costing response to the price base year, and
+ * record it in each asset's provenance.
+ */
+export function applyPriceBase(
+  response: CostEstimateResponse,
+  assetMetadata: AssetMetadata[],
+  priceBase: PriceBase,
+): { costingResponse: CostEstimateResponse; assetMetadata: AssetMetadata[] } {
+  const scale = <T>(costs: T): T =>
+    mapCostValues(costs, (value) => value * priceBase.factor);
+  // A uniform factor scales the lifetime totals exactly, including those of
+  // cost items with no yearly costs
+  const rebase = <C extends YearlyCosts & { costs: unknown }>(costs: C): C => ({
+    ...rescaleYears(costs, scale),
+    costs: scale(costs.costs),
+    lifetime_costs: scale(costs.lifetime_costs),
+    lifetime_dcf_costs: scale(costs.lifetime_dcf_costs),
+  });
+
+  return {
+    costingResponse: {
+      ...response,
+      assets: response.assets.map((asset) => ({
+        ...rebase(asset),
+        cost_items: asset.cost_items.map(rebase),
+      })),
+    },
+    assetMetadata: assetMetadata.map((m) => ({
+      ...m,
+      ...(m.provenance && {
+        provenance: { ...m.provenance, priceBase },
+      }),
+    })),
+  };
+}
@@ -0,0 +1,198 @@
+import { describe, it, expect } from "vitest";
+import {
+  applyNominalBasis,
+  nominalRate,
+  type PriceBasis,
+} from "./price-basis";
+import { transformCostingResponse, type AssetMetadata } from "./adapter";
+import { discountedLifetimeTonnes, type KpiInputs } from "./kpis";
+import type { AssetParameters } from "./types";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+const timeline = {
+  construction_start: 2030,
+  construction_finish: 2031,
+  operation_start: 2031,
+  operation_finish: 2032,
+  decommissioning_start: 2032,
+  decommissioning_finish: 2032,
+};
+
+describe("price-basis", () => {
+  it("derives the nominal discount rate from the real one", () => {
+    expect(nominalRate(0.05, 0.02)).toBeCloseTo(0.071);
+  });
+
+  it("escalates yearly costs and discounts them at the nominal rate", () => {
+    const realRate = 0.1;
+    const escalation = 0.02;
+    // Real costs of 100 a year, discounted at the real rate
+    const year = (y: number) =>
+      mockYearCosts(
+        y,
+        { total_installed_cost: 100 },
+        { total_installed_cost: 100 / (1 + realRate) ** (y - 2030) },
+      );
+    const response = {
+      assets: [
+        mockAssetEstimate(
+          "asset-1",
+          [year(2030), year(2031)],
+          [mockCostItem("item-1", { total_installed_cost: 200 })],
+        ),
+      ],
+    };
+    const request = {
+      assets: [{ id: "asset-1", timeline } as AssetParameters],
+    };
+
+    const [asset] = applyNominalBasis(
+      response,
+      request,
+      escalation,
+      2024,
+    ).assets;
+
+    const nominal = (y: number) => 100 * (1 + escalation) ** (y - 2024);
+    expect(
+      asset.costs_by_year[0].costs_in_year.total_installed_cost,
+    ).toBeCloseTo(nominal(2030));
+    expect(
+      asset.costs_by_year[1].dcf_costs_in_year.total_installed_cost,
+    ).toBeCloseTo(nominal(2031) / (1 + nominalRate(realRate, escalation)));
+    expect(asset.lifetime_costs.total_installed_cost).toBeCloseTo(
+      nominal(2030) + nominal(2031),
+    );
+    expect(asset.costs.total_installed_cost).toBe(200);
+    // Items without yearly costs keep their lifetime totals
+    expect(asset.cost_items[0].lifetime_costs.total_installed_cost).toBe(200);
+  });
+
+  it("leaves assets without a timeline real", () => {
+    const response = {
+      assets: [mockAssetEstimate("asset-1", [mockYearCosts(2030, {})])],
+    };
+
+    expect(applyNominalBasis(response, { assets: [] }, 0.02, 2024)).toEqual(
+      response,
+    );
+  });
+
+  it("derives metrics from nominal costs on the nominal basis", () => {
+    const realRate = 0.1;
+    const escalation = 0.02;
+    const metricTimeline = {
+      construction_start: 2030,
+      construction_finish: 2030,
+      operation_start: 2031,
+      operation_finish: 2033,
+      decommissioning_start: 2034,
+      decommissioning_finish: 2034,
+    };
+    const years = [2030, 2031, 2032, 2033].map((y) => {
+      const cost = y === 2030 ? 1000 : 100;
+      return mockYearCosts(
+        y,
+        { total_installed_cost: cost },
+        { total_installed_cost: cost / (1 + realRate) ** (y - 2030) },
+      );
+    });
+    const response = {
+      assets: [
+        mockAssetEstimate("pipe", years),
+        mockAssetEstimate("capture", years),
+      ],
+    };
+    const kpiInputs = (assetType: "transport" | "capture"): KpiInputs => ({
+      assetType,
+      throughput: 10,
+      lengthKm: 5,
+      timeline: metricTimeline,
+      discountRate: realRate,
+    });
+    const metadata = (assetId: string, inputs: KpiInputs): AssetMetadata => ({
+      assetId,
+      isGroup: false,
+      branchIds: [assetId],
+      blockCount: 0,
+      costableBlockCount: 0,
+      usingDefaults: [],
+      blocks: [],
+      kpiInputs: inputs,
+    });
+    const assetMetadata = [
+      metadata("pipe", kpiInputs("transport")),
+      metadata("capture", kpiInputs("capture")),
+    ];
+    const options = {
+      carbonPrice: { prices: { "2031": 50 } },
+      discountRates: [0.035],
+      timelines: new Map([
+        ["pipe", metricTimeline],
+        ["capture", metricTimeline],
+      ]),
+    };
+    const nominalBasis: PriceBasis = {
+      mode: "nominal",
+      escalationRate: escalation,
+      priceBaseYear: 2024,
+      discountRates: "real",
+    };
+
+    const real = transformCostingResponse(
+      response,
+      assetMetadata,
+      "GBP",
+      options,
+    );
+    const nominal = transformCostingResponse(
+      applyNominalBasis(
+        response,
+        {
+          assets: [
+            { id: "pipe", timeline: metricTimeline } as AssetParameters,
+            { id: "capture", timeline: metricTimeline } as AssetParameters,
+          ],
+        },
+        escalation,
+        2024,
+      ),
+      assetMetadata,
+      "GBP",
+      { ...options, priceBasis: nominalBasis },
+    );
+
+    // Discounting nominal costs at the nominal rate leaves real present
+    // values in construction start money
+    const toConstructionStart = (1 + escalation) ** (2030 - 2024);
+    const [realPipe, realCapture] = real.assets;
+    const [nominalPipe, nominalCapture] = nominal.assets;
+    expect(nominalPipe.lifetimeNpcCosts.total).toBeCloseTo(
+      realPipe.lifetimeNpcCosts.total * toConstructionStart,
+    );
+    expect(
+      nominalPipe.npcByDiscountRate![0].lifetimeNpcCosts.total,
+    ).toBeCloseTo(
+      realPipe.npcByDiscountRate![0].lifetimeNpcCosts.total *
+        toConstructionStart,
+    );
+    expect(nominalCapture.carbonValue!.netPresentValue).toBeCloseTo(
+      realCapture.carbonValue!.netPresentValue * toConstructionStart,
+    );
+    expect(nominalCapture.carbonValue!.price[0]).toBeCloseTo(
+      50 * (1 + escalation) ** (2031 - 2024),
+    );
+    // Tonnes are discounted at the same nominal rate as the costs
+    const nominalTonnes = discountedLifetimeTonnes({
+      ...kpiInputs("transport"),
+      discountRate: nominalRate(realRate, escalation),
+    })!;
+    expect(nominalPipe.unitTransportCost!.levelized).toBeCloseTo(
+      nominalPipe.lifetimeNpcCosts.total / (nominalTonnes * 5),
+    );
+  });
+});
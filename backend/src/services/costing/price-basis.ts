@@ -0,0 +1,118 @@
+/**
+ * Real or nominal cash flows.
+ *
+ * The costing server gives real costs: every year in the price base year's
+ * money, discounted at the asset's discount rate, which is therefore a real
+ * rate. Nominal cash flows escalate each year's costs by an escalation rate
+ * from the price base year and discount them at the matching nominal rate,
+ * (1 + real)(1 + escalation) - 1, so a real discount rate is never applied
+ * to nominal costs or the other way round. Metrics derived from the yearly
+ * costs (net present cost at other rates, levelized costs, carbon value)
+ * discount and price on the same basis. The chosen basis is stamped on the
+ * response.
+ */
+
+import type { CostEstimateRequest, CostEstimateResponse } from "./types";
+import { mapCostValues, rescaleYears } from "./totals";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const PRICE_BASIS_MODES = ["real", "nominal"] as const;
+
+export type PriceBasisMode = (typeof PRICE_BASIS_MODES)[number];
+
+/** How the response's yearly costs are priced and discounted */
+export type PriceBasis =
+  | {
+      mode: "real";
+      /** Asset discount rates are real rates */
+      discountRates: "real";
+    }
+  | {
+      mode: "nominal";
+      /** Annual escalation from the price base year */
+      escalationRate: number;
+      /** Year escalation starts from */
+      priceBaseYear: number;
+      /** Asset discount rates are real; nominal rates are derived from them */
+      discountRates: "real";
+    };
+
+export const REAL_PRICE_BASIS: PriceBasis = {
+  mode: "real",
+  discountRates: "real",
+};
+
+// ============================================================================
+// Calculation
+// ============================================================================
+
+/** Nominal discount rate for a real rate and an escalation rate (Fisher) */
+export function nominalRate(realRate: number, escalationRate: number): number {
+  return (1 + realRate) * (1 + escalationRate) - 1;
+}
+
+/**
+ * Factor an amount in price base year money is escalated by in `year`: 1
+ * on a real basis.
+ */
+export function escalationFactor(basis: PriceBasis, year: number): number {
+  return basis.mode === "nominal"
+    ? Math.pow(1 + basis.escalationRate, year - basis.priceBaseYear)
+    : 1;
+}
+
+/**
+ * The discount rate matching a basis for a real rate: the rate itself on a
+ * real basis, the nominal rate on a nominal one.
+ */
+export function discountRateFor(basis: PriceBasis, realRate: number): number {
+  return basis.mode === "nominal"
+    ? nominalRate(realRate, basis.escalationRate)
+    : realRate;
+}
+
+/**
+ * Turn the costing server's real cash flows into nominal ones. Annual cost
+ * rates (`costs`) stay in price base year money.
+ */
+export function applyNominalBasis(
+  response: CostEstimateResponse,
+  request: CostEstimateRequest,
+  escalationRate: number,
+  priceBaseYear: number,
+): CostEstimateResponse {
+  const timelines = new Map(request.assets.map((a) => [a.id, a.timeline]));
+  const basis: PriceBasis = {
+    mode: "nominal",
+    escalationRate,
+    priceBaseYear,
+    discountRates: "real",
+  };
+
+  return {
+    ...response,
+    assets: response.assets.map((asset) => {
+      const start = timelines.get(asset.id)?.construction_start;
+      if (start === undefined) return asset;
+      // (1 + nominal)^t is (1 + real)^t (1 + escalation)^t, and the server
+      // has already divided by the real part
+      const escalate = <P>(costs: P, year: number, discounted: boolean) =>
+        mapCostValues(
+          costs,
+          (v) =>
+            (v * escalationFactor(basis, year)) /
+            (discounted ? Math.pow(1 + escalationRate, year - start) : 1),
+        );
+
+      return {
+        ...rescaleYears(asset, escalate),
+        cost_items: asset.cost_items.map((item) =>
+          rescaleYears(item, escalate),
+        ),
+      };
+    }),
+  };
+}
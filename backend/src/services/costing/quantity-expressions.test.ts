@@ -0,0 +1,77 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import {
+  evaluateQuantity,
+  QuantityExpressionError,
+} from "./quantity-expressions";
+import type { NetworkBlock } from "./request-types";
+import dim from "../dim";
+
+const branch: NetworkBlock[] = [
+  { type: "Pipe", length: 1200 },
+  { type: "Valve" },
+  { type: "Pipe", length: "800" },
+  { type: "Valve" },
+  { type: "Compressor", length: 5 },
+  { type: "Valve" },
+];
+
+describe("quantity-expressions", () => {
+  beforeAll(async () => {
+    await dim.init();
+  });
+
+  it("passes numbers through and defaults to 1", () => {
+    expect(evaluateQuantity(3, branch)).toBe(3);
+    expect(evaluateQuantity("4", branch)).toBe(4);
+    expect(evaluateQuantity(undefined, branch)).toBe(1);
+  });
+
+  it("counts blocks of a type on the branch", () => {
+    expect(evaluateQuantity("count(Valve)", branch)).toBe(3);
+    expect(evaluateQuantity("count(Valve) * 2", branch)).toBe(6);
+    expect(evaluateQuantity("count(Shipping)", branch)).toBe(0);
+  });
+
+  it("sums a property over the branch or one block type", () => {
+    expect(evaluateQuantity("sum(length)", branch)).toBe(2005);
+    expect(evaluateQuantity("sum(length, Pipe)", branch)).toBe(2000);
+    expect(evaluateQuantity("sum(length, Pipe) * 0.001", branch)).toBe(2);
+  });
+
+  it("converts unit-bearing values", () => {
+    // As in the preset networks' branch files
+    const pipes: NetworkBlock[] = [
+      { type: "Pipe", length: "1000 m" },
+      { type: "Pipe", length: "3 mi" },
+      { type: "Valve" },
+    ];
+
+    expect(evaluateQuantity("sum(length, Pipe, km)", pipes)).toBeCloseTo(
+      1 + 3 * 1.609344,
+    );
+    expect(evaluateQuantity("sum(length, *, m)", pipes)).toBeCloseTo(
+      1000 + 3 * 1609.344,
+    );
+    expect(evaluateQuantity("sum(length)", pipes)).toBeCloseTo(
+      1000 + 3 * 1609.344,
+    );
+    expect(() => evaluateQuantity("sum(length, Pipe, kg)", pipes)).toThrow(
+      /not a quantity in kg/,
+    );
+  });
+
+  it("rejects expressions it can't evaluate", () => {
+    expect(() => evaluateQuantity("max(length)", branch)).toThrow(
+      QuantityExpressionError,
+    );
+    expect(() => evaluateQuantity("count(Valve, Pipe)", branch)).toThrow(
+      /one block type/,
+    );
+    expect(() => evaluateQuantity("sum(length,, km)", branch)).toThrow(
+      QuantityExpressionError,
+    );
+    expect(() =>
+      evaluateQuantity("sum(phase)", [{ type: "Pipe", phase: "gas" }]),
+    ).toThrow(/not a number/);
+  });
+});
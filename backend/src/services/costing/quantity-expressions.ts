@@ -0,0 +1,121 @@
+/**
+ * Block quantities derived from the network.
+ *
+ * A block's `quantity` is usually a number, but quantities like the number
+ * of valve stations or the metres of pipe a coating covers follow from the
+ * network model. A quantity can instead be an expression over the blocks on
+ * the block's branch, evaluated when the network is transformed:
+ *
+ * - `count(Valve)`: number of blocks of a type
+ * - `sum(length)`: sum of a property over the blocks that have it
+ * - `sum(length, Pipe)`: the same, over blocks of one type only (`*` for any)
+ * - `sum(length, Pipe, km)`: the same, in a unit
+ *
+ * Either may be scaled by a factor, e.g. `count(Pipe) * 2`. Network
+ * properties carry their units (`"1000 m"`, `"3 mi"`), so summed values are
+ * converted with dim: to the given unit, or to base SI units without one.
+ */
+
+import type { NetworkBlock } from "./request-types";
+import dim from "../dim";
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+export class QuantityExpressionError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "QuantityExpressionError";
+  }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+const EXPRESSION = /^\s*(count|sum)\((.*)\)\s*(?:\*\s*([\d.]+)\s*)?$/;
+
+/**
+ * A property value as a number, in `unit` or in base SI units without one.
+ */
+function propertyValue(value: string | number, unit?: string): number {
+  if (typeof value === "number") return value;
+  if (value.trim() !== "" && !isNaN(Number(value))) return Number(value);
+  if (!unit) return parseFloat(dim.eval(value));
+  // "(3 mi) / (1 km)" gives the number of km, but "(3 mi) / (1 kg)" is
+  // also a quantity, so check the units match first
+  if (!dim.checkUnitCompatibility(value, unit)) return NaN;
+  return parseFloat(dim.eval(`(${value}) / (1 ${unit})`));
+}
+
+/**
+ * A block's quantity: the number it gives, or its expression evaluated over
+ * the blocks on its branch. 1 when it gives none. Evaluating a sum needs
+ * dim to be initialized.
+ *
+ * @throws QuantityExpressionError for an expression that can't be parsed or
+ *   sums a property that isn't a quantity in the unit
+ */
+export function evaluateQuantity(
+  quantity: number | string | undefined,
+  branchBlocks: NetworkBlock[],
+): number {
+  if (quantity === undefined) return 1;
+  if (typeof quantity === "number") return quantity;
+  if (quantity.trim() !== "" && !isNaN(Number(quantity))) {
+    return Number(quantity);
+  }
+
+  const match = EXPRESSION.exec(quantity);
+  const args = match?.[2].split(",").map((arg) => arg.trim());
+  if (!match || !args || args.some((arg) => arg === "")) {
+    throw new QuantityExpressionError(
+      `Quantity "${quantity}" must be a number, count(Type) or ` +
+        "sum(property[, Type][, unit])",
+    );
+  }
+  const [, fn, , factorText] = match;
+  const factor = factorText !== undefined ? Number(factorText) : 1;
+  if (isNaN(factor)) {
+    throw new QuantityExpressionError(
+      `Quantity "${quantity}" has an invalid factor ${factorText}`,
+    );
+  }
+
+  if (fn === "count") {
+    if (args.length !== 1) {
+      throw new QuantityExpressionError(
+        `Quantity "${quantity}": count takes one block type`,
+      );
+    }
+    return branchBlocks.filter((b) => b.type === args[0]).length * factor;
+  }
+
+  if (args.length > 3) {
+    throw new QuantityExpressionError(
+      `Quantity "${quantity}": sum takes a property, a block type and a unit`,
+    );
+  }
+  const [property, type = "*", unit] = args;
+  let total = 0;
+  for (const block of branchBlocks) {
+    if (type !== "*" && block.type !== type) continue;
+    const value = block[property];
+    if (value === undefined || value === null) continue;
+    let numeric: number;
+    try {
+      numeric = propertyValue(value, unit);
+    } catch {
+      numeric = NaN;
+    }
+    if (isNaN(numeric)) {
+      throw new QuantityExpressionError(
+        `Quantity "${quantity}": ${block.type} block's ${property} ` +
+          `"${value}" is not ${unit ? `a quantity in ${unit}` : "a number"}`,
+      );
+    }
+    total += numeric;
+  }
+  return total * factor;
+}
@@ -11,7 +11,35 @@ import type {
   CapexLangFactors,
   FixedOpexFactors,
   CostParameter,
+  LibraryFactorDefaults,
+  CbsMapping,
 } from "./types";
+import type { CostItemAudit } from "./audit";
+import type { TicExplanation } from "./explain";
+import type { RoundingPolicy } from "./rounding";
+import type { AccuracyBand, NetworkAccuracyBand } from "./accuracy";
+import type { ResponseFormatting } from "./format";
+import type { AssetDependency } from "./asset-dependencies";
+import type { CapexBudget } from "./funding-cap";
+import type { Financing } from "./financing";
+import type { PriceBase } from "./price-base";
+import type { PriceBasis, PriceBasisMode } from "./price-basis";
+import type { AssetKpi, AssetType, UnitTransportCost } from "./kpis";
+import type { EconomicScenario, ScenarioProvenance } from "./scenario";
+import type { TopDrivers } from "./top-drivers";
+import type { UtilityPrices } from "./utility-prices";
+import type {
+  CarbonPriceTrajectory,
+  CarbonValue,
+  NetworkCarbonValue,
+} from "./carbon-value";
+import type { CostThreshold } from "./thresholds";
+import type { EstimateLabels } from "./estimate-store";
+import {
+  ESTIMATE_PRESETS,
+  type EstimatePresetName,
+  type AccuracyRange,
+} from "./presets";
 import {
   DEFAULT_TIMELINE,
   DEFAULT_LABOUR_AVERAGE_SALARY,
@@ -49,7 +77,11 @@ export type NetworkBranch = {
 
 export type NetworkBlock = {
   type: string;
-  quantity?: number;
+  /**
+   * Number of units, or an expression over the blocks on the branch:
+   * `count(Type)` or `sum(property[, Type][, unit])`, optionally `* factor`.
+   */
+  quantity?: number | string;
 
   [key: string]: string | number | null | undefined;
 };
@@ -86,13 +118,174 @@ export type CostingEstimateRequest = {
    * Takes precedence over assetDefaults.
    */
   assetOverrides?: Record<string, AssetPropertyOverrides>;
+
+  /**
+   * Cost item parameter values (e.g. electricity price) inherited by every
+   * block that doesn't set them, keyed by block property name. Numbers are
+   * in the parameter's library units; strings may carry their own
+   * ("50 GBP/MWh").
+   */
+  globalParameters?: ParameterValues;
+
+  /**
+   * Per-asset parameter values keyed by group ID or branch ID. Take
+   * precedence over globalParameters; a block's own properties take
+   * precedence over both.
+   */
+  assetParameters?: Record<string, ParameterValues>;
+
+  /**
+   * Per-year utility prices (e.g. electricity by year) and the base price
+   * the library costs at. Each year's variable opex for a priced utility is
+   * scaled by that year's price over the base price.
+   */
+  utilityPrices?: UtilityPrices;
+
+  /**
+   * Attach a per-cost-item audit trail (inputs, cost curve, inflation and
+   * currency factors) to each asset. Off by default to keep payloads small.
+   */
+  includeAudit?: boolean;
+
+  /** Echo each cost item's source references in the audit trail */
+  includeSources?: boolean;
+
+  /**
+   * Attach a breakdown of each asset's total installed cost: direct
+   * equipment cost, each Lang component, the contingency subtraction and
+   * cost items priced as installed.
+   */
+  explainTotals?: boolean;
+
+  /**
+   * Rank this many of the largest capex and annual opex contributors per
+   * asset and for the network (default: no ranking)
+   */
+  topDrivers?: number;
+
+  /**
+   * Carbon price by year. Capture assets with a throughput are valued at
+   * it (avoided emissions less any residual-emissions penalty) and given a
+   * net present value against their net present cost.
+   */
+  carbonPrice?: CarbonPriceTrajectory;
+
+  /**
+   * Further discount rates (e.g. [0.035, 0.08, 0.1]) to report net present
+   * cost at, per asset and for the network, alongside the assets' own.
+   * These are real rates; nominal cash flows are discounted at the matching
+   * nominal rates.
+   */
+  discountRates?: number[];
+
+  /** Rounding applied to all monetary outputs (default: full precision) */
+  rounding?: RoundingPolicy;
+
+  /**
+   * Accuracy range applied to every asset. Without it, assets costed with a
+   * preset use the preset's range.
+   */
+  accuracy?: AccuracyRange;
+
+  /**
+   * Add Lang-factored contingency to lifetime totals, NPC totals and cash
+   * flows. Off by default: total installed cost excludes contingency.
+   */
+  includeContingencyInTotals?: boolean;
+
+  /**
+   * Dependencies between assets, e.g. a store that must be operational
+   * before a pipeline starts operating. Timelines that break them are
+   * reported in the response warnings.
+   */
+  dependencies?: AssetDependency[];
+
+  /**
+   * Limits on the estimate, e.g. total installed cost or any one cost line's
+   * share of capex. Each limit crossed is reported in the response warnings.
+   */
+  thresholds?: CostThreshold[];
+
+  /**
+   * Annual capex budget for the portfolio. The cash flow view flags years
+   * that exceed it and can suggest delayed construction starts that fit.
+   */
+  capexBudget?: CapexBudget;
+
+  /**
+   * Debt/equity financing. Its weighted average cost of capital is the
+   * discount rate for assets that don't set their own, and the cash flow
+   * view includes a financing section with debt schedules. Its rates are
+   * market rates: on a nominal basis the escalation rate is taken out of
+   * the WACC to give the real discount rate; on a real basis give real
+   * rates.
+   */
+  financing?: Financing;
+
+  /**
+   * CBS codes for the cost lines, merged over the library's mapping. Each
+   * line in the response is tagged with its code.
+   */
+  cbsMapping?: CbsMapping;
+
+  /**
+   * Scenario exchange rates and inflation used instead of the library's.
+   * Each asset's provenance records the scenario it was costed under.
+   */
+  scenario?: EconomicScenario;
+
+  /**
+   * Year to express all costs in (e.g. 2022 for "2022 real terms"), using
+   * the library's inflation table. Default: the library's price base year.
+   * Each asset's provenance records the base.
+   */
+  priceBaseYear?: number;
+
+  /**
+   * Real (constant price base, default) or nominal (escalated) cash flows.
+   * Discount rates are always real; nominal cash flows are discounted at
+   * the matching nominal rate.
+   */
+  priceBasis?: PriceBasisMode;
+
+  /** Annual cost escalation from the price base year (nominal basis) */
+  escalationRate?: number;
+
+  /**
+   * BCP 47 locale (e.g., "en-GB", "de-DE"). When set, the response includes
+   * formatted strings for the headline totals.
+   */
+  locale?: string;
+
+  /**
+   * Save the request and result so the estimate can be listed and later
+   * compared against as a baseline. Requires the estimator role.
+   */
+  save?: boolean;
+
+  /** Label for a saved estimate */
+  label?: string;
+
+  /** Project, scenario, author and tags to find a saved estimate by */
+  labels?: EstimateLabels;
 };
 
+/** Cost item parameter values keyed by block property name */
+export type ParameterValues = Record<string, number | string>;
+
+/** Request level a cost item parameter was inherited from */
+export type ParameterLevel = "asset" | "global";
+
 /**
  * Overridable asset-level properties.
  * All fields are optional - defaults from defaults.ts are used when not specified.
  */
 export type AssetPropertyOverrides = {
+  /**
+   * Named estimate class supplying the Lang factors. Factors given in
+   * capex_lang_factors override the preset's.
+   */
+  preset?: EstimatePresetName;
   timeline?: Partial<Timeline>;
   labour_average_salary?: CostParameter;
   fte_personnel?: number;
@@ -100,6 +293,18 @@ export type AssetPropertyOverrides = {
   discount_rate?: number;
   capex_lang_factors?: Partial<CapexLangFactors>;
   opex_factors?: Partial<FixedOpexFactors>;
+  /** Role of the asset in the chain, which selects its KPIs */
+  asset_type?: AssetType;
+  /** Tonnes of CO2 the asset handles per operating year (for KPIs) */
+  throughput?: number;
+  /** Route length in km (for transport KPIs) */
+  length_km?: number;
+  /**
+   * Fraction of full throughput (0-1) in each operating year from operation
+   * start, scaling that year's variable opex. Later years run at full
+   * throughput.
+   */
+  throughput_profile?: number[];
 };
 
 /**
@@ -113,6 +318,12 @@ export type ResolvedAssetProperties = {
   discount_rate: number;
   capex_lang_factors: CapexLangFactors;
   opex_factors: FixedOpexFactors;
+  /** Preset the Lang factors were taken from (if any) */
+  preset?: EstimatePresetName;
+  asset_type?: AssetType;
+  throughput?: number;
+  length_km?: number;
+  throughput_profile?: number[];
   /** Which fields are using defaults */
   usingDefaults: Set<string>;
 };
@@ -140,11 +351,41 @@ export type CostingEstimateResponse = {
   /** Network-level totals (discounted - Net Present Cost) */
   lifetimeNpcCosts: LifetimeCosts;
 
+  /** Network-level accuracy band (only when every asset has one) */
+  accuracy?: NetworkAccuracyBand;
+
   /** Per-asset results */
   assets: AssetCostResult[];
 
+  /** Largest contributors across assets (only when topDrivers is set) */
+  topDrivers?: TopDrivers;
+
+  /** Capture assets' carbon value summed (only when carbonPrice is set) */
+  carbonValue?: NetworkCarbonValue;
+
+  /** Whether yearly and discounted costs are real or nominal */
+  priceBasis?: PriceBasis;
+
+  /** Net present cost at each requested discount rate */
+  npcByDiscountRate?: DiscountRateNpc[];
+
   /** IDs of assets that used all defaults */
   assetsUsingDefaults: string[];
+
+  /** Rounding policy applied to monetary values (if any) */
+  rounding?: RoundingPolicy;
+
+  /** Locale-formatted headline totals (only when a locale is requested) */
+  formatted?: ResponseFormatting;
+
+  /** ID of the saved estimate, when the request asked for it to be saved */
+  estimateId?: string;
+
+  /**
+   * Problems worth a look that didn't stop the estimate, e.g. values read
+   * through a renamed cost item's earlier ID
+   */
+  warnings?: string[];
 };
 
 /**
@@ -169,8 +410,52 @@ export type AssetCostResult = {
   /** Lifetime NPC (discounted) */
   lifetimeNpcCosts: LifetimeCosts;
 
+  /** Accuracy band around the point estimates (if a range applies) */
+  accuracy?: AccuracyBand;
+
+  /** Factors the asset was costed with, after presets and overrides */
+  provenance?: AssetProvenance;
+
   /** Per-block costs */
   blocks: BlockCostResult[];
+
+  /** Per-cost-item audit trail (only when includeAudit is set) */
+  audit?: CostItemAudit[];
+
+  /** How totalInstalledCost was derived (only when explainTotals is set) */
+  ticExplanation?: TicExplanation;
+
+  /** Unit costs for the asset's type (when asset_type and inputs are set) */
+  kpis?: AssetKpi[];
+
+  /** Largest capex and opex contributors (only when topDrivers is set) */
+  topDrivers?: TopDrivers;
+
+  /** Cost per tonne-km (transport assets with throughput and length) */
+  unitTransportCost?: UnitTransportCost;
+
+  /** Carbon value by year (capture assets, when carbonPrice is set) */
+  carbonValue?: CarbonValue;
+
+  /** Net present cost at each requested discount rate */
+  npcByDiscountRate?: DiscountRateNpc[];
+};
+
+/**
+ * Resolved factors an asset was costed with.
+ */
+export type AssetProvenance = {
+  preset?: EstimatePresetName;
+  capexLangFactors: CapexLangFactors;
+  opexFactors: FixedOpexFactors;
+  /** Throughput ramp-up the variable opex was scaled by (if any) */
+  throughputProfile?: number[];
+  /** Utility price series the variable opex was indexed by (if any) */
+  utilityPrices?: UtilityPrices;
+  /** Exchange rate and inflation scenario the asset was costed under */
+  scenario?: ScenarioProvenance;
+  /** Price base year the asset's costs were re-based to (if requested) */
+  priceBase?: PriceBase;
 };
 
 /**
@@ -189,6 +474,12 @@ export type BlockCostResult = {
   /** Module reference in cost library */
   moduleRef: string;
 
+  /** Bundle this cost item is a component of (if any) */
+  bundle?: string;
+
+  /** Client CBS code (when the library or request supplies a mapping) */
+  cbsCode?: string;
+
   /** Quantity */
   quantity: number;
 
@@ -199,16 +490,36 @@ export type BlockCostResult = {
   totalInstalledCost: number;
 };
 
+/**
+ * Lifetime costs discounted at one of the request's discount rates.
+ */
+export type DiscountRateNpc = {
+  discountRate: number;
+  lifetimeNpcCosts: LifetimeCosts;
+};
+
 /**
  * Lifetime cost breakdown.
  */
 export type LifetimeCosts = {
   directEquipmentCost: number;
   langFactoredCapitalCost: LangFactoredCosts;
+  /** Total installed cost, excluding contingency */
   totalInstalledCost: number;
+  /** Same as totalInstalledCost, named to make the exclusion explicit */
+  totalInstalledCostExclContingency: number;
+  /** Total installed cost plus Lang-factored contingency */
+  totalInstalledCostInclContingency: number;
+  /** Contingency included in `total` (0 unless includeContingencyInTotals) */
+  contingencyApplied: number;
   fixedOpexCost: FixedOpexCosts;
   variableOpexCost: VariableOpexCosts;
   decommissioningCost: number;
+  /**
+   * Total installed cost + contingency applied + fixed OPEX + variable OPEX +
+   * decommissioning, always recomputed from the reported components.
+   */
+  total: number;
 };
 
 /**
@@ -258,21 +569,36 @@ export type VariableOpexCosts = {
 
 /**
  * Resolve asset properties by applying overrides to defaults.
+ *
+ * `libraryDefaults` replaces the built-in factor defaults with those shipped
+ * in the cost library. Factors taken from it still count as defaults.
  */
 export function resolveAssetProperties(
   overrides?: AssetPropertyOverrides,
   globalOverrides?: AssetPropertyOverrides,
+  libraryDefaults?: LibraryFactorDefaults,
 ): ResolvedAssetProperties {
   const usingDefaults = new Set<string>();
 
   // Start with defaults
   const timeline = { ...DEFAULT_TIMELINE };
-  const capexLangFactors = { ...DEFAULT_CAPEX_LANG_FACTORS };
-  const opexFactors = { ...DEFAULT_OPEX_FACTORS };
+  const capexLangFactors = {
+    ...DEFAULT_CAPEX_LANG_FACTORS,
+    ...libraryDefaults?.capex_lang_factors,
+  };
+  const opexFactors = {
+    ...DEFAULT_OPEX_FACTORS,
+    ...libraryDefaults?.opex_factors,
+  };
   let labourAverageSalary = { ...DEFAULT_LABOUR_AVERAGE_SALARY };
   let ftePersonnel = DEFAULT_FTE_PERSONNEL;
   let assetUptime = DEFAULT_ASSET_UPTIME;
   let discountRate = DEFAULT_DISCOUNT_RATE;
+  let preset: EstimatePresetName | undefined;
+  let assetType: AssetType | undefined;
+  let throughput: number | undefined;
+  let lengthKm: number | undefined;
+  let throughputProfile: number[] | undefined;
 
   // Track what's using defaults
   usingDefaults.add("timeline");
@@ -294,6 +620,14 @@ export function resolveAssetProperties(
   }
 
   function applyOverrides(o: AssetPropertyOverrides) {
+    if (o.preset) {
+      Object.assign(
+        capexLangFactors,
+        ESTIMATE_PRESETS[o.preset].capex_lang_factors,
+      );
+      preset = o.preset;
+      usingDefaults.delete("capex_lang_factors");
+    }
     if (o.timeline) {
       Object.assign(timeline, o.timeline);
       usingDefaults.delete("timeline");
@@ -322,6 +656,11 @@ export function resolveAssetProperties(
       Object.assign(opexFactors, o.opex_factors);
       usingDefaults.delete("opex_factors");
     }
+    // KPI inputs have no defaults, so they aren't tracked in usingDefaults
+    if (o.asset_type !== undefined) assetType = o.asset_type;
+    if (o.throughput !== undefined) throughput = o.throughput;
+    if (o.length_km !== undefined) lengthKm = o.length_km;
+    if (o.throughput_profile) throughputProfile = o.throughput_profile;
   }
 
   return {
@@ -332,6 +671,11 @@ export function resolveAssetProperties(
     discount_rate: discountRate,
     capex_lang_factors: capexLangFactors,
     opex_factors: opexFactors,
+    preset,
+    ...(assetType !== undefined && { asset_type: assetType }),
+    ...(throughput !== undefined && { throughput }),
+    ...(lengthKm !== undefined && { length_km: lengthKm }),
+    ...(throughputProfile && { throughput_profile: throughputProfile }),
     usingDefaults,
   };
 }
@@ -0,0 +1,184 @@
+import { describe, it, expect } from "vitest";
+import { roundValue, applyRounding } from "./rounding";
+import { transformCostingResponse } from "./adapter";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+describe("rounding", () => {
+  describe("roundValue", () => {
+    it("rounds to decimal places", () => {
+      expect(
+        roundValue(23654191.663114145, { mode: "decimals", digits: 2 }),
+      ).toBe(23654191.66);
+      expect(roundValue(1.005, { mode: "decimals", digits: 0 })).toBe(1);
+    });
+
+    it("rounds to significant figures", () => {
+      expect(
+        roundValue(23654191.663114145, {
+          mode: "significantFigures",
+          digits: 3,
+        }),
+      ).toBe(23700000);
+      expect(
+        roundValue(0.0012345, { mode: "significantFigures", digits: 2 }),
+      ).toBe(0.0012);
+    });
+
+    it("leaves zero and non-finite values alone", () => {
+      const policy = { mode: "significantFigures", digits: 3 } as const;
+      expect(roundValue(0, policy)).toBe(0);
+      expect(roundValue(Infinity, policy)).toBe(Infinity);
+    });
+  });
+
+  describe("applyRounding", () => {
+    const response = transformCostingResponse(
+      {
+        assets: [
+          mockAssetEstimate(
+            "asset-1",
+            [
+              mockYearCosts(2025, {
+                direct_equipment_cost: 1234.5678,
+                total_installed_cost: 2345.6789,
+                fixed_opex_cost: { maintenance: 12.3456 },
+              }),
+            ],
+            [
+              mockCostItem("b/blocks/0/Item 001", {
+                direct_equipment_cost: 1234.5678,
+                total_installed_cost: 2345.6789,
+              }),
+            ],
+          ),
+        ],
+      },
+      [],
+      "USD",
+    );
+
+    it("rounds totals, asset costs and block costs", () => {
+      const result = applyRounding(response, { mode: "decimals", digits: 1 });
+
+      expect(result.lifetimeCosts.directEquipmentCost).toBe(1234.6);
+      expect(result.lifetimeCosts.fixedOpexCost.maintenance).toBe(12.3);
+      expect(result.assets[0].lifetimeCosts.totalInstalledCost).toBe(2345.7);
+      expect(result.assets[0].blocks[0].directEquipmentCost).toBe(1234.6);
+      expect(result.rounding).toEqual({ mode: "decimals", digits: 1 });
+    });
+
+    it("leaves block costs at full precision when rounding totals only", () => {
+      const result = applyRounding(response, {
+        mode: "decimals",
+        digits: 0,
+        totalsOnly: true,
+      });
+
+      expect(result.lifetimeCosts.totalInstalledCost).toBe(2346);
+      expect(result.assets[0].blocks[0].totalInstalledCost).toBe(2345.6789);
+    });
+
+    it("rounds unit costs, carbon values and TIC explanations", () => {
+      const [asset] = response.assets;
+      const carbonValue = {
+        priceUnit: "USD/tCO2",
+        years: [2030],
+        price: [50.123],
+        capturedTonnes: [10.25],
+        revenue: [513.76],
+        penalty: [0],
+        net: [513.76],
+        lifetimeNet: 513.76,
+        discountedNet: 467.054,
+        netPresentValue: -1878.6249,
+      };
+      const withMetrics = {
+        ...response,
+        assets: [
+          {
+            ...asset,
+            kpis: [
+              { name: "costPerTonneStored", unit: "USD/tCO2", value: 12.345 },
+            ],
+            unitTransportCost: {
+              unit: "USD/(tCO2·km)",
+              undiscounted: 0.456,
+              levelized: 0.789,
+            },
+            carbonValue,
+            ticExplanation: {
+              directEquipmentCost: 1234.5678,
+              langFactoredTotal: 1111.1111,
+              contingency: 0,
+              ticItemsTotal: 0,
+              ticItemIds: [],
+              terms: [{ label: "Direct equipment cost", amount: 1234.5678 }],
+              totalInstalledCost: 2345.6789,
+              residual: 0,
+            },
+          },
+        ],
+        carbonValue: {
+          lifetimeRevenue: 513.76,
+          lifetimePenalty: 0,
+          lifetimeNet: 513.76,
+          discountedNet: 467.054,
+          netPresentValue: -1878.6249,
+        },
+      };
+
+      const result = applyRounding(withMetrics, {
+        mode: "decimals",
+        digits: 1,
+      });
+
+      const [rounded] = result.assets;
+      expect(rounded.kpis?.[0].value).toBe(12.3);
+      expect(rounded.unitTransportCost).toMatchObject({
+        undiscounted: 0.5,
+        levelized: 0.8,
+      });
+      expect(rounded.carbonValue).toMatchObject({
+        price: [50.1],
+        capturedTonnes: [10.25],
+        revenue: [513.8],
+        netPresentValue: -1878.6,
+      });
+      expect(rounded.ticExplanation?.terms[0].amount).toBe(1234.6);
+      expect(rounded.ticExplanation?.totalInstalledCost).toBe(2345.7);
+      expect(result.carbonValue?.discountedNet).toBe(467.1);
+    });
+
+    it("rounds recomputed totals to significant figures", () => {
+      const result = applyRounding(response, {
+        mode: "significantFigures",
+        digits: 3,
+      });
+
+      // 2350 + 12.3 carries five figures before rounding
+      expect(result.assets[0].lifetimeCosts.total).toBe(2360);
+      expect(result.lifetimeCosts.total).toBe(2360);
+    });
+
+    it("does not round quantities", () => {
+      const [asset] = response.assets;
+      const withFractionalQuantity = {
+        ...response,
+        assets: [
+          { ...asset, blocks: [{ ...asset.blocks[0], quantity: 2.5 }] },
+        ],
+      };
+
+      const result = applyRounding(withFractionalQuantity, {
+        mode: "significantFigures",
+        digits: 1,
+      });
+
+      expect(result.assets[0].blocks[0].quantity).toBe(2.5);
+    });
+  });
+});
@@ -0,0 +1,246 @@
+/**
+ * Rounding policy for monetary outputs.
+ *
+ * Applied once, when the response is built, so every consumer sees the same
+ * numbers instead of rounding client-side in slightly different ways.
+ */
+
+import type {
+  CostingEstimateResponse,
+  AssetCostResult,
+  BlockCostResult,
+  LifetimeCosts,
+  DiscountRateNpc,
+} from "./request-types";
+import type { CostingCashflowResponse, CashflowSeries } from "./cashflow";
+import type { TopDrivers } from "./top-drivers";
+import type { AssetKpi, UnitTransportCost } from "./kpis";
+import type { CarbonValue } from "./carbon-value";
+import type { TicExplanation } from "./explain";
+import { enforceTotals, mapCostValues, withTotal } from "./totals";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type RoundingPolicy = {
+  /** Round to a number of decimal places or significant figures */
+  mode: "decimals" | "significantFigures";
+  digits: number;
+  /**
+   * Only round network and asset totals, leaving per-block values at full
+   * precision.
+   */
+  totalsOnly?: boolean;
+};
+
+// ============================================================================
+// Rounding
+// ============================================================================
+
+/**
+ * Round a single value according to the policy.
+ */
+export function roundValue(value: number, policy: RoundingPolicy): number {
+  if (!Number.isFinite(value) || value === 0) {
+    return value;
+  }
+  if (policy.mode === "significantFigures") {
+    return Number(value.toPrecision(policy.digits));
+  }
+  return Number(value.toFixed(policy.digits));
+}
+
+/**
+ * Round every number in a nested cost breakdown.
+ */
+export function roundCosts<T>(costs: T, policy: RoundingPolicy): T {
+  return mapCostValues(costs, (value) => roundValue(value, policy));
+}
+
+/**
+ * Apply the rounding policy to all monetary values in an estimate response:
+ * costs, unit costs (KPIs, unit transport cost), carbon values and total
+ * installed cost explanations. Non-monetary values (quantities, tonnes,
+ * years, IDs) are left untouched.
+ *
+ * Asset costs are rounded first and every total is then recomputed from the
+ * rounded components and rounded again. With decimal rounding that only
+ * strips floating-point noise, so totals match what they summarise exactly.
+ * With significant figures the total is rounded to the same number of
+ * figures, so it may differ from the sum of its components by less than its
+ * last figure.
+ */
+export function applyRounding(
+  response: CostingEstimateResponse,
+  policy: RoundingPolicy,
+): CostingEstimateResponse {
+  const roundLifetime = (costs: LifetimeCosts) => roundCosts(costs, policy);
+  const roundBlock = (block: BlockCostResult): BlockCostResult =>
+    policy.totalsOnly
+      ? block
+      : {
+          ...block,
+          directEquipmentCost: roundValue(block.directEquipmentCost, policy),
+          totalInstalledCost: roundValue(block.totalInstalledCost, policy),
+        };
+  // Drivers are per-line values, like blocks
+  const roundDrivers = (drivers: TopDrivers): TopDrivers =>
+    policy.totalsOnly
+      ? drivers
+      : {
+          capex: drivers.capex.map((d) => ({
+            ...d,
+            value: roundValue(d.value, policy),
+          })),
+          annualOpex: drivers.annualOpex.map((d) => ({
+            ...d,
+            value: roundValue(d.value, policy),
+          })),
+        };
+
+  // Asset-level figures, rounded like totals
+  const round = (value: number) => roundValue(value, policy);
+  const roundAll = (values: number[]) => values.map(round);
+  const roundKpis = (kpis: AssetKpi[]): AssetKpi[] =>
+    kpis.map((kpi) => ({ ...kpi, value: round(kpi.value) }));
+  const roundTransportCost = (
+    cost: UnitTransportCost,
+  ): UnitTransportCost => ({
+    ...cost,
+    undiscounted: round(cost.undiscounted),
+    levelized: round(cost.levelized),
+  });
+  const roundCarbonValue = (value: CarbonValue): CarbonValue => ({
+    ...value,
+    price: roundAll(value.price),
+    revenue: roundAll(value.revenue),
+    penalty: roundAll(value.penalty),
+    net: roundAll(value.net),
+    lifetimeNet: round(value.lifetimeNet),
+    discountedNet: round(value.discountedNet),
+    netPresentValue: round(value.netPresentValue),
+  });
+  const roundTicExplanation = (
+    explanation: TicExplanation,
+  ): TicExplanation => ({
+    ...explanation,
+    directEquipmentCost: round(explanation.directEquipmentCost),
+    langFactoredTotal: round(explanation.langFactoredTotal),
+    contingency: round(explanation.contingency),
+    ticItemsTotal: round(explanation.ticItemsTotal),
+    terms: explanation.terms.map((term) => ({
+      ...term,
+      amount: round(term.amount),
+    })),
+    totalInstalledCost: round(explanation.totalInstalledCost),
+    residual: round(explanation.residual),
+  });
+
+  const roundNpcs = (npcs: DiscountRateNpc[]): DiscountRateNpc[] =>
+    npcs.map((npc) => ({
+      ...npc,
+      lifetimeNpcCosts: roundLifetime(
+        withTotal(roundLifetime(npc.lifetimeNpcCosts)),
+      ),
+    }));
+
+  const rounded: CostingEstimateResponse = {
+    ...response,
+    assets: response.assets.map(
+      (asset): AssetCostResult => ({
+        ...asset,
+        lifetimeCosts: roundLifetime(asset.lifetimeCosts),
+        lifetimeNpcCosts: roundLifetime(asset.lifetimeNpcCosts),
+        blocks: asset.blocks.map(roundBlock),
+        ...(asset.topDrivers && {
+          topDrivers: roundDrivers(asset.topDrivers),
+        }),
+        ...(asset.npcByDiscountRate && {
+          npcByDiscountRate: roundNpcs(asset.npcByDiscountRate),
+        }),
+        ...(asset.kpis && { kpis: roundKpis(asset.kpis) }),
+        ...(asset.unitTransportCost && {
+          unitTransportCost: roundTransportCost(asset.unitTransportCost),
+        }),
+        ...(asset.carbonValue && {
+          carbonValue: roundCarbonValue(asset.carbonValue),
+        }),
+        ...(asset.ticExplanation && {
+          ticExplanation: roundTicExplanation(asset.ticExplanation),
+        }),
+      }),
+    ),
+    ...(response.carbonValue && {
+      carbonValue: roundCosts(response.carbonValue, policy),
+    }),
+    ...(response.npcByDiscountRate && {
+      npcByDiscountRate: roundNpcs(response.npcByDiscountRate),
+    }),
+    ...(response.topDrivers && {
+      topDrivers: roundDrivers(response.topDrivers),
+    }),
+    rounding: policy,
+  };
+
+  return enforceTotals(rounded, round);
+}
+
+/**
+ * Apply the rounding policy to a cash flow view. Years are left untouched.
+ * Every series is an asset or portfolio total, so totalsOnly has no effect.
+ */
+export function applyCashflowRounding(
+  cashflow: CostingCashflowResponse,
+  policy: RoundingPolicy,
+): CostingCashflowResponse {
+  const roundSeries = <T extends CashflowSeries>(series: T): T => ({
+    ...series,
+    capex: series.capex.map((v) => roundValue(v, policy)),
+    opex: series.opex.map((v) => roundValue(v, policy)),
+    decommissioning: series.decommissioning.map((v) => roundValue(v, policy)),
+    total: series.total.map((v) => roundValue(v, policy)),
+    dcfTotal: series.dcfTotal.map((v) => roundValue(v, policy)),
+  });
+
+  return {
+    ...cashflow,
+    portfolio: roundSeries(cashflow.portfolio),
+    assets: cashflow.assets.map(roundSeries),
+    ...(cashflow.funding && {
+      funding: {
+        ...cashflow.funding,
+        exceedances: cashflow.funding.exceedances.map((e) => ({
+          ...e,
+          capex: roundValue(e.capex, policy),
+          excess: roundValue(e.excess, policy),
+        })),
+      },
+    }),
+    ...(cashflow.financing && {
+      financing: {
+        ...cashflow.financing,
+        debt: roundValue(cashflow.financing.debt, policy),
+        equity: roundValue(cashflow.financing.equity, policy),
+        interestDuringConstruction: roundValue(
+          cashflow.financing.interestDuringConstruction,
+          policy,
+        ),
+        assets: cashflow.financing.assets.map((a) => ({
+          ...a,
+          debt: roundValue(a.debt, policy),
+          equity: roundValue(a.equity, policy),
+          interestDuringConstruction: roundValue(
+            a.interestDuringConstruction,
+            policy,
+          ),
+          drawdown: a.drawdown.map((v) => roundValue(v, policy)),
+          interest: a.interest.map((v) => roundValue(v, policy)),
+          principal: a.principal.map((v) => roundValue(v, policy)),
+          debtService: a.debtService.map((v) => roundValue(v, policy)),
+          balance: a.balance.map((v) => roundValue(v, policy)),
+        })),
+      },
+    }),
+  };
+}
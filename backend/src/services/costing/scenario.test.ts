@@ -0,0 +1,147 @@
+import { describe, it, expect } from "vitest";
+import type { AssetMetadata } from "./adapter";
+import {
+  applyScenario,
+  capexScaleFor,
+  resolveScenario,
+  ScenarioError,
+} from "./scenario";
+import type {
+  AssetParameters,
+  CostLibrary,
+  CostLibraryModule,
+} from "./types";
+import { DEFAULT_OPEX_FACTORS } from "./defaults";
+import { ESTIMATE_PRESETS } from "./presets";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+const library = {
+  modules: [
+    {
+      id: "M0101",
+      cost_items: [
+        { id: "Item 001", capex_contribution: { year: 2020, currency: "GBP" } },
+        { id: "Item 002", capex_contribution: { year: 2024, currency: "EUR" } },
+      ],
+    } as unknown as CostLibraryModule,
+  ],
+  currency_conversion: { base_currency: "EUR", rates: { EUR: 1, GBP: 1.2 } },
+  inflation: { current_year: "2024", factors: { "2020": 1.2, "2024": 1 } },
+} as CostLibrary;
+
+describe("scenario", () => {
+  it("builds scenario rates and inflation over the library's", () => {
+    const resolved = resolveScenario(
+      { exchangeRates: { "GBP/EUR": 1.3 }, inflationRate: 0.05 },
+      library,
+    );
+
+    expect(resolved.rates).toEqual({ EUR: 1, GBP: 1.3 });
+    expect(resolved.inflationFactors["2020"]).toBeCloseTo(1.05 ** 4);
+    expect(resolved.inflationFactors["2024"]).toBe(1);
+    expect(() =>
+      resolveScenario({ exchangeRates: { "USD/EUR": 1 } }, library),
+    ).toThrow(ScenarioError);
+  });
+
+  it("scales capital cost by the change in conversion and inflation", () => {
+    const resolved = resolveScenario(
+      { exchangeRates: { "GBP/EUR": 1.3 }, inflationFactors: { "2020": 1.5 } },
+      library,
+    );
+    const capex = library.modules[0].cost_items;
+
+    // GBP item costed in EUR: 1.3/1.2 for the rate, 1.5/1.2 for inflation
+    expect(
+      capexScaleFor(resolved, capex[0].capex_contribution, "EUR"),
+    ).toBeCloseTo((1.3 / 1.2) * (1.5 / 1.2));
+    expect(capexScaleFor(resolved, capex[1].capex_contribution, "EUR")).toBe(
+      1,
+    );
+  });
+
+  it("rescales capex-derived costs and records the scenario", () => {
+    const response = {
+      assets: [
+        mockAssetEstimate(
+          "asset-1",
+          [
+            mockYearCosts(2025, {
+              direct_equipment_cost: 200,
+              total_installed_cost: 500,
+              fixed_opex_cost: { maintenance: 10 },
+              variable_opex_cost: { tariff: 7 },
+            }),
+          ],
+          [
+            mockCostItem("asset-1/blocks/0/Item 001", {
+              direct_equipment_cost: 100,
+              total_installed_cost: 250,
+            }),
+            mockCostItem("asset-1/blocks/0/Item 002", {
+              direct_equipment_cost: 100,
+              total_installed_cost: 250,
+            }),
+          ],
+        ),
+      ],
+    };
+    const request = {
+      assets: [
+        {
+          id: "asset-1",
+          cost_items: ["Item 001", "Item 002"].map((ref) => ({
+            id: `asset-1/blocks/0/${ref}`,
+            ref,
+            quantity: 1,
+            parameters: {},
+          })),
+        } as unknown as AssetParameters,
+      ],
+    };
+    const metadata: AssetMetadata = {
+      assetId: "asset-1",
+      isGroup: false,
+      branchIds: ["asset-1"],
+      blockCount: 1,
+      costableBlockCount: 1,
+      usingDefaults: [],
+      provenance: {
+        capexLangFactors: ESTIMATE_PRESETS["AACE Class 5"].capex_lang_factors,
+        opexFactors: DEFAULT_OPEX_FACTORS,
+      },
+      blocks: [],
+    };
+
+    const result = applyScenario(
+      response,
+      request,
+      [metadata],
+      resolveScenario(
+        { name: "stress", inflationFactors: { "2020": 2.4 } },
+        library,
+      ),
+      "GBP",
+    );
+
+    // Item 001 doubles, Item 002 is unchanged: the asset's capex scales 1.5x
+    const [asset] = result.costingResponse.assets;
+    expect(
+      asset.cost_items.map((i) => i.lifetime_costs.total_installed_cost),
+    ).toEqual([500, 250]);
+    expect(asset.lifetime_costs.total_installed_cost).toBe(750);
+    expect(asset.lifetime_costs.fixed_opex_cost.maintenance).toBe(15);
+    expect(asset.lifetime_costs.variable_opex_cost.tariff).toBe(7);
+    expect(asset.costs_by_year[0].costs_in_year.direct_equipment_cost).toBe(
+      300,
+    );
+    expect(result.assetMetadata[0].provenance?.scenario).toEqual({
+      name: "stress",
+      capexScale: 1.5,
+    });
+  });
+});
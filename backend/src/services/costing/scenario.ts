@@ -0,0 +1,302 @@
+/**
+ * Exchange rate and inflation scenarios.
+ *
+ * A request can cost the network under scenario values for the library's
+ * currency rates and inflation factors (e.g. "stress: GBP/EUR at 1.3, 5%
+ * inflation") without editing or forking the library. The costing server
+ * still prices every cost item with the library's own tables; each item's
+ * capital cost is then rescaled by the ratio of the scenario's conversion
+ * and inflation from the item's cost year and currency to the library's.
+ * The Lang-factored costs, fixed opex and decommissioning derived from
+ * capital cost follow their asset's capital cost. Every fixed opex line is
+ * an opex factor times capital cost; labour is priced separately from the
+ * average salary and isn't part of it. Variable opex isn't tied to a cost
+ * year or currency in the library and is left as costed.
+ *
+ * Costed assets record the scenario in their provenance.
+ */
+
+import type { AssetMetadata } from "./adapter";
+import type {
+  AssetPeriodCosts,
+  CapexContribution,
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostLibrary,
+  LangFactoredCostEstimate,
+} from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type EconomicScenario = {
+  /** Label for the scenario, e.g. "stress" */
+  name?: string;
+  /**
+   * Exchange rates by currency pair, e.g. { "GBP/EUR": 1.3 } for one pound
+   * buying 1.3 euros. Applied in order over the library's rates, moving the
+   * first currency of each pair.
+   */
+  exchangeRates?: Record<string, number>;
+  /**
+   * Annual inflation rate (e.g. 0.05) replacing the library's factors: a
+   * cost from year Y is inflated by (1 + rate)^(priceBaseYear - Y)
+   */
+  inflationRate?: number;
+  /** Inflation factors by cost year, replacing the library's for those years */
+  inflationFactors?: Record<string, number>;
+};
+
+/** A scenario checked against the library, with its rate tables built */
+export type ResolvedScenario = {
+  scenario: EconomicScenario;
+  library: CostLibrary;
+  /** Value of one unit of each currency in the library's base currency */
+  rates: Record<string, number>;
+  /** Multiplier taking a cost from the keyed year to the price base year */
+  inflationFactors: Record<string, number>;
+};
+
+/** How a scenario changed an asset's costs */
+export type ScenarioProvenance = {
+  name?: string;
+  /** Factor the asset's capital cost was scaled by */
+  capexScale: number;
+};
+
+export class ScenarioError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "ScenarioError";
+  }
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/**
+ * Build the scenario's currency rates and inflation factors over the
+ * library's.
+ *
+ * @throws ScenarioError if a pair names a currency the library can't convert,
+ *   or an inflation rate is given for a library without a price base year
+ */
+export function resolveScenario(
+  scenario: EconomicScenario,
+  library: CostLibrary,
+): ResolvedScenario {
+  const rates = { ...library.currency_conversion?.rates };
+  for (const [pair, rate] of Object.entries(scenario.exchangeRates ?? {})) {
+    const [from, to] = pair.split("/");
+    if (!from || !to) {
+      throw new ScenarioError(
+        `Exchange rate ${pair} must name a currency pair as FROM/TO`,
+      );
+    }
+    for (const currency of [from, to]) {
+      if (rates[currency] === undefined) {
+        throw new ScenarioError(
+          `The library has no exchange rate for ${currency}`,
+        );
+      }
+    }
+    rates[from] = rate * rates[to];
+  }
+
+  const inflationFactors = { ...library.inflation?.factors };
+  if (scenario.inflationRate !== undefined) {
+    const baseYear = library.inflation?.current_year;
+    if (baseYear === undefined) {
+      throw new ScenarioError(
+        "The library has no price base year to inflate costs to",
+      );
+    }
+    for (const year of Object.keys(inflationFactors)) {
+      inflationFactors[year] = Math.pow(
+        1 + scenario.inflationRate,
+        parseInt(baseYear) - parseInt(year),
+      );
+    }
+  }
+  Object.assign(inflationFactors, scenario.inflationFactors);
+
+  return { scenario, library, rates, inflationFactors };
+}
+
+/**
+ * Ratio of the scenario's conversion of a capital cost into `currency` to
+ * the library's. 1 where either can't be worked out.
+ */
+export function capexScaleFor(
+  resolved: ResolvedScenario,
+  capex: CapexContribution,
+  currency: string,
+): number {
+  const libraryRates = resolved.library.currency_conversion?.rates ?? {};
+  const libraryFactors = resolved.library.inflation?.factors ?? {};
+  const ratio = (
+    scenario: number | undefined,
+    library: number | undefined,
+  ) =>
+    scenario !== undefined && library !== undefined && library !== 0
+      ? scenario / library
+      : 1;
+
+  const year = String(capex.year);
+  const inflation = ratio(
+    resolved.inflationFactors[year],
+    libraryFactors[year],
+  );
+  if (capex.currency === currency) return inflation;
+  const rate = (rates: Record<string, number>) =>
+    rates[capex.currency] !== undefined && rates[currency] !== undefined
+      ? rates[capex.currency] / rates[currency]
+      : undefined;
+  return inflation * ratio(rate(resolved.rates), rate(libraryRates));
+}
+
+// ============================================================================
+// Application
+// ============================================================================
+
+type CapitalCosts = {
+  direct_equipment_cost: number | null;
+  total_installed_cost: number | null;
+};
+
+type AssetCapitalCosts = {
+  direct_equipment_cost: number;
+  lang_factored_capital_cost: LangFactoredCostEstimate;
+  total_installed_cost: number;
+  decommissioning_cost: number;
+};
+
+function scaleValues<V extends object>(values: V, factor: number): V {
+  return Object.fromEntries(
+    Object.entries(values).map(([key, value]) => [key, value * factor]),
+  ) as V;
+}
+
+function scaleItemCosts<T extends CapitalCosts>(costs: T, factor: number): T {
+  const scale = (value: number | null) =>
+    value === null ? null : value * factor;
+  return {
+    ...costs,
+    direct_equipment_cost: scale(costs.direct_equipment_cost),
+    total_installed_cost: scale(costs.total_installed_cost),
+  };
+}
+
+/** Scale the costs derived from capital cost; opex is left to the caller */
+function scaleAssetCosts<T extends AssetCapitalCosts>(
+  costs: T,
+  factor: number,
+): T {
+  return {
+    ...costs,
+    direct_equipment_cost: costs.direct_equipment_cost * factor,
+    lang_factored_capital_cost: scaleValues(
+      costs.lang_factored_capital_cost,
+      factor,
+    ),
+    total_installed_cost: costs.total_installed_cost * factor,
+    decommissioning_cost: costs.decommissioning_cost * factor,
+  };
+}
+
+function scalePeriod(
+  costs: AssetPeriodCosts,
+  factor: number,
+): AssetPeriodCosts {
+  return {
+    ...scaleAssetCosts(costs, factor),
+    fixed_opex_cost: scaleValues(costs.fixed_opex_cost, factor),
+  };
+}
+
+/**
+ * Rescale a costing response to the scenario, and record the scenario in
+ * each asset's provenance.
+ */
+export function applyScenario(
+  response: CostEstimateResponse,
+  request: CostEstimateRequest,
+  assetMetadata: AssetMetadata[],
+  resolved: ResolvedScenario,
+  currency: string,
+): { costingResponse: CostEstimateResponse; assetMetadata: AssetMetadata[] } {
+  const capex = new Map(
+    resolved.library.modules.flatMap((m) =>
+      m.cost_items.map((item) => [item.id, item.capex_contribution] as const),
+    ),
+  );
+  const refs = new Map(
+    request.assets.flatMap((a) => a.cost_items.map((i) => [i.id, i.ref])),
+  );
+  const itemScale = (id: string) => {
+    const contribution = capex.get(refs.get(id) ?? "");
+    return contribution ? capexScaleFor(resolved, contribution, currency) : 1;
+  };
+
+  const capexScales = new Map<string, number>();
+  const assets = response.assets.map((asset) => {
+    let saved = 0;
+    let scaled = 0;
+    const costItems = asset.cost_items.map((item) => {
+      const factor = itemScale(item.id);
+      saved += item.lifetime_costs.direct_equipment_cost ?? 0;
+      scaled += (item.lifetime_costs.direct_equipment_cost ?? 0) * factor;
+      return {
+        ...item,
+        costs: scaleItemCosts(item.costs, factor),
+        costs_by_year: item.costs_by_year.map((y) => ({
+          ...y,
+          costs_in_year: scaleItemCosts(y.costs_in_year, factor),
+          dcf_costs_in_year: scaleItemCosts(y.dcf_costs_in_year, factor),
+        })),
+        lifetime_costs: scaleItemCosts(item.lifetime_costs, factor),
+        lifetime_dcf_costs: scaleItemCosts(item.lifetime_dcf_costs, factor),
+      };
+    });
+
+    // Lang factors and capex-based opex are linear in direct equipment cost
+    const factor = saved === 0 ? 1 : scaled / saved;
+    capexScales.set(asset.id, factor);
+    return {
+      ...asset,
+      costs: {
+        ...scaleAssetCosts(asset.costs, factor),
+        fixed_opex_cost_per_year: scaleValues(
+          asset.costs.fixed_opex_cost_per_year,
+          factor,
+        ),
+      },
+      costs_by_year: asset.costs_by_year.map((y) => ({
+        ...y,
+        costs_in_year: scalePeriod(y.costs_in_year, factor),
+        dcf_costs_in_year: scalePeriod(y.dcf_costs_in_year, factor),
+      })),
+      lifetime_costs: scalePeriod(asset.lifetime_costs, factor),
+      lifetime_dcf_costs: scalePeriod(asset.lifetime_dcf_costs, factor),
+      cost_items: costItems,
+    };
+  });
+
+  return {
+    costingResponse: { ...response, assets },
+    assetMetadata: assetMetadata.map((m) => ({
+      ...m,
+      ...(m.provenance && {
+        provenance: {
+          ...m.provenance,
+          scenario: {
+            ...(resolved.scenario.name && { name: resolved.scenario.name }),
+            capexScale: capexScales.get(m.assetId) ?? 1,
+          },
+        },
+      }),
+    })),
+  };
+}
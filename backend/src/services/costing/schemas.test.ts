@@ -117,6 +117,75 @@ describe("schemas", () => {
         expect(result.right.assetDefaults?.discount_rate).toBe(0.08);
       }
     });
+
+    it("validates request with a rounding policy", () => {
+      const request = {
+        source: { type: "networkId", networkId: "preset1" },
+        libraryId: "V1.1_working",
+        rounding: { mode: "significantFigures", digits: 4, totalsOnly: true },
+      };
+
+      const result = validateRequest(CostingEstimateRequestSchema, request);
+
+      expect(Either.isRight(result)).toBe(true);
+    });
+
+    it("rejects significant-figures rounding with zero digits", () => {
+      const request = {
+        source: { type: "networkId", networkId: "preset1" },
+        libraryId: "V1.1_working",
+        rounding: { mode: "significantFigures", digits: 0 },
+      };
+
+      const result = validateRequest(CostingEstimateRequestSchema, request);
+
+      expect(Either.isLeft(result)).toBe(true);
+    });
+
+    it("rejects timeline years outside the supported range", () => {
+      const request = (year: number) => ({
+        source: { type: "networkId", networkId: "preset1" },
+        libraryId: "V1.1_working",
+        assetDefaults: { timeline: { construction_start: year } },
+      });
+
+      for (const year of [0, 1899, 2201, 30000, 2030.5]) {
+        expect(
+          Either.isLeft(
+            validateRequest(CostingEstimateRequestSchema, request(year)),
+          ),
+        ).toBe(true);
+      }
+      expect(
+        Either.isRight(
+          validateRequest(CostingEstimateRequestSchema, request(2030)),
+        ),
+      ).toBe(true);
+    });
+
+    it("rejects price series keyed by years outside the range", () => {
+      const request = {
+        source: { type: "networkId", networkId: "preset1" },
+        libraryId: "V1.1_working",
+        carbonPrice: { prices: { "0030": 50 } },
+      };
+
+      const result = validateRequest(CostingEstimateRequestSchema, request);
+
+      expect(Either.isLeft(result)).toBe(true);
+    });
+
+    it("rejects unknown factor presets", () => {
+      const request = {
+        source: { type: "networkId", networkId: "preset1" },
+        libraryId: "V1.1_working",
+        assetDefaults: { preset: "AACE Class 9" },
+      };
+
+      const result = validateRequest(CostingEstimateRequestSchema, request);
+
+      expect(Either.isLeft(result)).toBe(true);
+    });
   });
 
   describe("formatValidationErrors", () => {
@@ -3,6 +3,12 @@
  */
 
 import * as S from "effect/Schema";
+import { ESTIMATE_PRESET_NAMES } from "./presets";
+import { ASSET_TYPES } from "./kpis";
+import { THRESHOLD_METRICS } from "./thresholds";
+import { isSupportedLocale } from "./format";
+import { UTILITY_PRICE_KINDS } from "./utility-prices";
+import { PRICE_BASIS_MODES } from "./price-basis";
 
 // ============================================================================
 // Network Data Schemas
@@ -11,7 +17,7 @@ import * as S from "effect/Schema";
 export const NetworkBlockSchema = S.mutable(
   S.Struct({
     type: S.String,
-    quantity: S.optional(S.Number),
+    quantity: S.optional(S.Union(S.Number, S.String)),
   }).pipe(
     S.extend(
       S.Record({
@@ -73,17 +79,41 @@ export const NetworkSourceSchema = S.Union(
 // Asset Property Schemas
 // ============================================================================
 
+/** Range of calendar years a request may use */
+export const MIN_YEAR = 1900;
+export const MAX_YEAR = 2200;
+
+const yearMessage = () =>
+  `years must be whole years from ${MIN_YEAR} to ${MAX_YEAR}`;
+
+export const YearSchema = S.Number.pipe(
+  S.int({ message: yearMessage }),
+  S.between(MIN_YEAR, MAX_YEAR, { message: yearMessage }),
+);
+
+/** A year as a record key, e.g. "2030" */
+export const YearKeySchema = S.String.pipe(
+  S.pattern(/^\d{4}$/, { message: yearMessage }),
+  S.filter((key) => Number(key) >= MIN_YEAR && Number(key) <= MAX_YEAR, {
+    message: yearMessage,
+  }),
+);
+
 export const TimelineSchema = S.Struct({
-  construction_start: S.Number,
-  construction_finish: S.Number,
-  operation_start: S.Number,
-  operation_finish: S.Number,
-  decommissioning_start: S.Number,
-  decommissioning_finish: S.Number,
+  construction_start: YearSchema,
+  construction_finish: YearSchema,
+  operation_start: YearSchema,
+  operation_finish: YearSchema,
+  decommissioning_start: YearSchema,
+  decommissioning_finish: YearSchema,
 });
 
 export const CostParameterSchema = S.Struct({
-  currency_code: S.String,
+  currency_code: S.String.pipe(
+    S.pattern(/^[A-Z]{3}$/, {
+      message: () => "currency_code must be an ISO 4217 code, e.g. GBP",
+    }),
+  ),
   amount: S.Number,
 });
 
@@ -112,6 +142,7 @@ export const FixedOpexFactorsSchema = S.Struct({
 });
 
 export const AssetPropertyOverridesSchema = S.Struct({
+  preset: S.optional(S.Literal(...ESTIMATE_PRESET_NAMES)),
   timeline: S.optional(S.partial(TimelineSchema)),
   labour_average_salary: S.optional(CostParameterSchema),
   fte_personnel: S.optional(S.Number),
@@ -119,6 +150,119 @@ export const AssetPropertyOverridesSchema = S.Struct({
   discount_rate: S.optional(S.Number),
   capex_lang_factors: S.optional(S.partial(CapexLangFactorsSchema)),
   opex_factors: S.optional(S.partial(FixedOpexFactorsSchema)),
+  asset_type: S.optional(S.Literal(...ASSET_TYPES)),
+  throughput: S.optional(S.Number.pipe(S.nonNegative())),
+  length_km: S.optional(S.Number.pipe(S.nonNegative())),
+  throughput_profile: S.optional(
+    S.mutable(S.Array(S.Number.pipe(S.between(0, 1)))),
+  ),
+});
+
+export const ParameterValuesSchema = S.Record({
+  key: S.String,
+  value: S.Union(S.Number, S.String),
+});
+
+export const AssetDependencySchema = S.Struct({
+  asset: S.String,
+  dependsOn: S.String,
+  leadYears: S.optional(S.Number.pipe(S.nonNegative())),
+  throughOperation: S.optional(S.Boolean),
+});
+
+export const CostThresholdSchema = S.Struct({
+  metric: S.Literal(...THRESHOLD_METRICS),
+  max: S.Number,
+  scope: S.optional(S.Literal("network", "asset")),
+  label: S.optional(S.String),
+});
+
+export const EstimateLabelsSchema = S.Struct({
+  project: S.optional(S.String),
+  scenario: S.optional(S.String),
+  author: S.optional(S.String),
+  tags: S.optional(S.mutable(S.Array(S.String))),
+});
+
+export const CapexBudgetSchema = S.Struct({
+  annualCap: S.Number.pipe(S.positive()),
+  suggestShifts: S.optional(S.Boolean),
+  maxShiftYears: S.optional(S.Number.pipe(S.int(), S.between(1, 50))),
+});
+
+export const FinancingSchema = S.Struct({
+  gearing: S.Number.pipe(S.between(0, 1)),
+  costOfDebt: S.Number.pipe(S.nonNegative()),
+  costOfEquity: S.Number.pipe(S.nonNegative()),
+  tenorYears: S.Number.pipe(S.int(), S.between(1, 100)),
+});
+
+export const CbsMappingSchema = S.Struct({
+  items: S.optional(S.Record({ key: S.String, value: S.String })),
+  modules: S.optional(S.Record({ key: S.String, value: S.String })),
+  default: S.optional(S.String),
+});
+
+export const EconomicScenarioSchema = S.Struct({
+  name: S.optional(S.String),
+  exchangeRates: S.optional(
+    S.Record({
+      key: S.String.pipe(
+        S.pattern(/^[A-Z]{3}\/[A-Z]{3}$/, {
+          message: () => "exchange rates must be keyed by pair, e.g. GBP/EUR",
+        }),
+      ),
+      value: S.Number.pipe(S.positive()),
+    }),
+  ),
+  inflationRate: S.optional(S.Number.pipe(S.greaterThan(-1))),
+  inflationFactors: S.optional(
+    S.Record({ key: YearKeySchema, value: S.Number.pipe(S.positive()) }),
+  ),
+});
+
+export const CarbonPriceTrajectorySchema = S.Struct({
+  prices: S.Record({
+    key: YearKeySchema,
+    value: S.Number.pipe(S.nonNegative()),
+  }),
+  captureRate: S.optional(
+    S.Number.pipe(S.greaterThan(0), S.lessThanOrEqualTo(1)),
+  ),
+});
+
+export const UtilityPriceSeriesSchema = S.Struct({
+  base_price: S.Number.pipe(S.positive()),
+  prices: S.Record({
+    key: YearKeySchema,
+    value: S.Number.pipe(S.nonNegative()),
+  }),
+});
+
+export const UtilityPricesSchema = S.partial(
+  S.Record({
+    key: S.Literal(...UTILITY_PRICE_KINDS),
+    value: UtilityPriceSeriesSchema,
+  }),
+);
+
+// ============================================================================
+// Output Option Schemas
+// ============================================================================
+
+export const RoundingPolicySchema = S.Struct({
+  mode: S.Literal("decimals", "significantFigures"),
+  digits: S.Number.pipe(S.int(), S.between(0, 15)),
+  totalsOnly: S.optional(S.Boolean),
+}).pipe(
+  S.filter((policy) => policy.mode === "decimals" || policy.digits >= 1, {
+    message: () => "significantFigures rounding needs at least 1 digit",
+  }),
+);
+
+export const AccuracyRangeSchema = S.Struct({
+  low: S.Number.pipe(S.between(-1, 0)),
+  high: S.Number.pipe(S.nonNegative()),
 });
 
 // ============================================================================
@@ -136,9 +280,82 @@ export const CostingEstimateRequestSchema = S.mutable(
         S.Record({ key: S.String, value: AssetPropertyOverridesSchema }),
       ),
     ),
+    globalParameters: S.optional(ParameterValuesSchema),
+    assetParameters: S.optional(
+      S.Record({ key: S.String, value: ParameterValuesSchema }),
+    ),
+    utilityPrices: S.optional(UtilityPricesSchema),
+    includeAudit: S.optional(S.Boolean),
+    includeSources: S.optional(S.Boolean),
+    explainTotals: S.optional(S.Boolean),
+    topDrivers: S.optional(S.Number.pipe(S.int(), S.between(1, 100))),
+    carbonPrice: S.optional(CarbonPriceTrajectorySchema),
+    discountRates: S.optional(
+      S.mutable(
+        S.Array(S.Number.pipe(S.between(0, 1))).pipe(S.maxItems(20)),
+      ),
+    ),
+    rounding: S.optional(RoundingPolicySchema),
+    accuracy: S.optional(AccuracyRangeSchema),
+    includeContingencyInTotals: S.optional(S.Boolean),
+    dependencies: S.optional(S.Array(AssetDependencySchema)),
+    thresholds: S.optional(S.Array(CostThresholdSchema)),
+    capexBudget: S.optional(CapexBudgetSchema),
+    financing: S.optional(FinancingSchema),
+    cbsMapping: S.optional(CbsMappingSchema),
+    scenario: S.optional(EconomicScenarioSchema),
+    priceBaseYear: S.optional(YearSchema),
+    priceBasis: S.optional(S.Literal(...PRICE_BASIS_MODES)),
+    escalationRate: S.optional(S.Number.pipe(S.greaterThan(-1))),
+    locale: S.optional(
+      S.String.pipe(
+        S.filter(isSupportedLocale, {
+          message: () => "locale must be a supported BCP 47 locale tag",
+        }),
+      ),
+    ),
+    save: S.optional(S.Boolean),
+    label: S.optional(S.String),
+    labels: S.optional(EstimateLabelsSchema),
   }),
 );
 
+export const SweepAxisSchema = S.Struct({
+  parameter: S.String,
+  values: S.optional(S.Array(S.Number)),
+  from: S.optional(S.Number),
+  to: S.optional(S.Number),
+  step: S.optional(S.Number),
+  assetIds: S.optional(S.Array(S.String)),
+});
+
+/** The sweep part of a sweep request; the rest is an estimate request */
+export const CostingSweepSchema = S.Struct({
+  sweep: S.Array(SweepAxisSchema).pipe(S.minItems(1), S.maxItems(2)),
+});
+
+/** Factor-level overrides for a what-if on a saved estimate */
+export const WhatIfOverridesSchema = S.Struct({
+  discountRate: S.optional(S.Number.pipe(S.greaterThan(-1))),
+  contingency: S.optional(S.Number.pipe(S.nonNegative())),
+  includeContingencyInTotals: S.optional(S.Boolean),
+  targetCurrency: S.optional(S.String),
+});
+
+/** Options for summarizing the change from a baseline estimate */
+export const EstimateChangesOptionsSchema = S.Struct({
+  baselineId: S.String,
+  top: S.optional(S.Number.pipe(S.int(), S.between(1, 20))),
+  attribute: S.optional(S.Boolean),
+  locale: S.optional(
+    S.String.pipe(
+      S.filter(isSupportedLocale, {
+        message: () => "locale must be a supported BCP 47 locale tag",
+      }),
+    ),
+  ),
+});
+
 // Infer types from schemas
 export type NetworkBlockInput = S.Schema.Type<typeof NetworkBlockSchema>;
 export type NetworkBranchInput = S.Schema.Type<typeof NetworkBranchSchema>;
@@ -0,0 +1,149 @@
+import { describe, it, expect } from "vitest";
+import {
+  axisValues,
+  runSweep,
+  sweepConcurrencyFromEnv,
+  SweepError,
+  MAX_SWEEP_POINTS,
+} from "./sweep";
+import type { CostEstimateRequest } from "./types";
+
+const request = {
+  assets: [
+    {
+      id: "pipeline",
+      cost_items: [
+        {
+          id: "b/blocks/0/Item 040",
+          ref: "Item 040",
+          quantity: 1,
+          parameters: { Diameter: 400, Length: 10 },
+        },
+      ],
+    },
+    {
+      id: "compressor",
+      cost_items: [
+        {
+          id: "b/blocks/1/Item 007",
+          ref: "Item 007",
+          quantity: 1,
+          parameters: { "Electrical power": 5 },
+        },
+      ],
+    },
+  ],
+} as unknown as CostEstimateRequest;
+
+/** Pipe cost rises with diameter; pumping cost falls with it */
+async function evaluate(req: CostEstimateRequest) {
+  const d = req.assets[0].cost_items[0].parameters.Diameter;
+  const total = d * 10 + 4_000_000 / d;
+  return { total, totalInstalledCost: d * 10, netPresentCost: total / 2 };
+}
+
+describe("sweep", () => {
+  it("expands ranges without floating point drift", () => {
+    expect(
+      axisValues({ parameter: "x", from: 0.1, to: 0.5, step: 0.1 }),
+    ).toEqual([0.1, 0.2, 0.3, 0.4, 0.5]);
+    expect(() =>
+      axisValues({ parameter: "x", from: 5, to: 1, step: 1 }),
+    ).toThrow(SweepError);
+    expect(() => axisValues({ parameter: "x", from: 0, to: 1 })).toThrow(
+      /from, to and step/,
+    );
+  });
+
+  it("costs every point and finds the minimum", async () => {
+    const result = await runSweep(
+      request,
+      [{ parameter: "Diameter", from: 200, to: 900, step: 50 }],
+      evaluate,
+      { concurrency: 3 },
+    );
+
+    expect(result.points).toHaveLength(15);
+    expect(result.axes[0]).toMatchObject({
+      parameter: "Diameter",
+      costItemCount: 1,
+    });
+    expect(result.points.map((p) => p.values[0])).toEqual(
+      result.axes[0].values,
+    );
+    expect(result.minimum.values).toEqual([650]);
+  });
+
+  it("varies two parameters over a grid, last axis fastest", async () => {
+    const seen: number[][] = [];
+    const result = await runSweep(
+      request,
+      [
+        { parameter: "Diameter", values: [300, 400] },
+        { parameter: "Length", values: [1, 2, 3] },
+      ],
+      async (req) => {
+        const p = req.assets[0].cost_items[0].parameters;
+        seen.push([p.Diameter, p.Length]);
+        return {
+          total: p.Diameter * p.Length,
+          totalInstalledCost: 0,
+          netPresentCost: 0,
+        };
+      },
+      { concurrency: 1 },
+    );
+
+    expect(result.points.map((p) => p.values)).toEqual(seen);
+    expect(seen[1]).toEqual([300, 2]);
+    expect(result.minimum.values).toEqual([300, 1]);
+  });
+
+  it("rejects unknown parameters, oversized grids and failures", async () => {
+    await expect(
+      runSweep(
+        request,
+        [{ parameter: "Diameter", values: [1], assetIds: ["compressor"] }],
+        evaluate,
+      ),
+    ).rejects.toThrow(/No cost item/);
+    const wide = Array.from(
+      { length: MAX_SWEEP_POINTS / 10 + 1 },
+      (_, i) => i + 1,
+    );
+    await expect(
+      runSweep(
+        request,
+        [
+          { parameter: "Diameter", values: wide },
+          { parameter: "Length", values: wide.slice(0, 10) },
+        ],
+        evaluate,
+      ),
+    ).rejects.toThrow(/limit/);
+
+    let calls = 0;
+    await expect(
+      runSweep(
+        request,
+        [{ parameter: "Diameter", from: 100, to: 1000, step: 10 }],
+        async () => {
+          calls++;
+          throw new Error("server down");
+        },
+        { concurrency: 2 },
+      ),
+    ).rejects.toThrow("server down");
+    expect(calls).toBeLessThanOrEqual(2);
+  });
+
+  it("reads a positive concurrency from the environment", () => {
+    expect(sweepConcurrencyFromEnv({})).toBe(4);
+    expect(sweepConcurrencyFromEnv({ SWEEP_CONCURRENCY: "8" })).toBe(8);
+    for (const value of ["0", "-1", "2.5", "four"]) {
+      expect(() =>
+        sweepConcurrencyFromEnv({ SWEEP_CONCURRENCY: value }),
+      ).toThrow(/positive whole number/);
+    }
+  });
+});
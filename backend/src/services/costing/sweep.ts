@@ -0,0 +1,236 @@
+/**
+ * Cost-vs-parameter sweeps.
+ *
+ * Varies one or two cost item parameters (by scaling factor name, in the
+ * library's units) over a grid and costs the network at every grid point, so
+ * the frontend can plot cost curves and find minima. Each axis applies to
+ * every cost item that takes the parameter, optionally limited to some
+ * assets.
+ */
+
+import type { CostEstimateRequest } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type SweepAxis = {
+  /** Cost item parameter name, e.g. "Diameter" */
+  parameter: string;
+  /** Explicit values, or a range with from/to/step */
+  values?: readonly number[];
+  from?: number;
+  to?: number;
+  step?: number;
+  /** Only vary the parameter on these assets (default: all) */
+  assetIds?: readonly string[];
+};
+
+export type SweepPointTotals = {
+  /** Lifetime total cost */
+  total: number;
+  totalInstalledCost: number;
+  /** Lifetime net present cost */
+  netPresentCost: number;
+};
+
+export type SweepPoint = SweepPointTotals & {
+  /** Parameter value per axis, in axis order */
+  values: number[];
+};
+
+export type SweepResult = {
+  axes: Array<{ parameter: string; values: number[]; costItemCount: number }>;
+  /** Grid points, varying the last axis fastest */
+  points: SweepPoint[];
+  /** The point with the lowest lifetime total */
+  minimum: SweepPoint;
+};
+
+export class SweepError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "SweepError";
+  }
+}
+
+/** Largest number of grid points one sweep may cost */
+export const MAX_SWEEP_POINTS = 400;
+
+/** Costing server calls a sweep makes at once, unless configured */
+export const DEFAULT_SWEEP_CONCURRENCY = 4;
+
+/**
+ * Read the sweep concurrency from SWEEP_CONCURRENCY. Throws unless it is a
+ * positive whole number.
+ */
+export function sweepConcurrencyFromEnv(
+  env: Record<string, string | undefined> = process.env,
+): number {
+  const value = env.SWEEP_CONCURRENCY;
+  if (!value) return DEFAULT_SWEEP_CONCURRENCY;
+  if (!/^\d+$/.test(value) || Number(value) === 0) {
+    throw new Error("SWEEP_CONCURRENCY must be a positive whole number");
+  }
+  return Number(value);
+}
+
+// ============================================================================
+// Grid
+// ============================================================================
+
+/**
+ * The values an axis takes.
+ *
+ * @throws SweepError for an empty or inverted range, or a non-positive step
+ */
+export function axisValues(axis: SweepAxis): number[] {
+  if (axis.values) {
+    if (axis.values.length === 0) {
+      throw new SweepError(`${axis.parameter}: values must not be empty`);
+    }
+    return [...axis.values];
+  }
+
+  const { from, to, step } = axis;
+  if (from === undefined || to === undefined || step === undefined) {
+    throw new SweepError(
+      `${axis.parameter}: give either values or from, to and step`,
+    );
+  }
+  if (!(step > 0) || to < from) {
+    throw new SweepError(
+      `${axis.parameter}: step must be positive and to must not be below from`,
+    );
+  }
+  const count = Math.floor((to - from) / step + 1e-9) + 1;
+  if (count > MAX_SWEEP_POINTS) {
+    throw new SweepError(
+      `${axis.parameter}: range has ${count} values; the limit is ${MAX_SWEEP_POINTS}`,
+    );
+  }
+  // Round away floating point drift from repeated steps
+  return Array.from(
+    { length: count },
+    (_, i) => Math.round((from + i * step) * 1e9) / 1e9,
+  );
+}
+
+/**
+ * Every combination of axis values, varying the last axis fastest.
+ */
+function grid(values: number[][]): number[][] {
+  return values.reduce<number[][]>(
+    (points, axis) => points.flatMap((p) => axis.map((v) => [...p, v])),
+    [[]],
+  );
+}
+
+/**
+ * Copy a costing request with each axis parameter set to its value.
+ */
+export function applySweepValues(
+  request: CostEstimateRequest,
+  axes: readonly SweepAxis[],
+  values: number[],
+): CostEstimateRequest {
+  return {
+    ...request,
+    assets: request.assets.map((asset) => ({
+      ...asset,
+      cost_items: asset.cost_items.map((item) => {
+        const parameters = { ...item.parameters };
+        axes.forEach((axis, i) => {
+          if (
+            axis.parameter in parameters &&
+            (!axis.assetIds || axis.assetIds.includes(asset.id))
+          ) {
+            parameters[axis.parameter] = values[i];
+          }
+        });
+        return { ...item, parameters };
+      }),
+    })),
+  };
+}
+
+function costItemCount(request: CostEstimateRequest, axis: SweepAxis): number {
+  return request.assets
+    .filter((asset) => !axis.assetIds || axis.assetIds.includes(asset.id))
+    .flatMap((asset) => asset.cost_items)
+    .filter((item) => axis.parameter in item.parameters).length;
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+/**
+ * Cost every grid point, at most `concurrency` at a time. The first failure
+ * aborts the sweep.
+ *
+ * @throws SweepError if the sweep is invalid (too many points, or an axis
+ *   that no cost item takes)
+ */
+export async function runSweep(
+  request: CostEstimateRequest,
+  axes: readonly SweepAxis[],
+  evaluate: (request: CostEstimateRequest) => Promise<SweepPointTotals>,
+  options: { concurrency?: number } = {},
+): Promise<SweepResult> {
+  if (axes.length < 1 || axes.length > 2) {
+    throw new SweepError("A sweep varies one or two parameters");
+  }
+  const values = axes.map(axisValues);
+  const counts = axes.map((axis) => costItemCount(request, axis));
+  axes.forEach((axis, i) => {
+    if (counts[i] === 0) {
+      throw new SweepError(
+        `No cost item in the network takes the parameter "${axis.parameter}"`,
+      );
+    }
+  });
+
+  const combinations = grid(values);
+  if (combinations.length > MAX_SWEEP_POINTS) {
+    throw new SweepError(
+      `Sweep has ${combinations.length} points; the limit is ${MAX_SWEEP_POINTS}`,
+    );
+  }
+
+  const points: SweepPoint[] = new Array(combinations.length);
+  let next = 0;
+  let failed = false;
+  const worker = async () => {
+    while (!failed && next < combinations.length) {
+      const index = next++;
+      try {
+        const totals = await evaluate(
+          applySweepValues(request, axes, combinations[index]),
+        );
+        points[index] = { values: combinations[index], ...totals };
+      } catch (error) {
+        // Stop the other workers picking up more points
+        failed = true;
+        throw error;
+      }
+    }
+  };
+  const concurrency = Math.max(
+    1,
+    options.concurrency ?? DEFAULT_SWEEP_CONCURRENCY,
+  );
+  await Promise.all(
+    Array.from({ length: Math.min(concurrency, combinations.length) }, worker),
+  );
+
+  return {
+    axes: axes.map((axis, i) => ({
+      parameter: axis.parameter,
+      values: values[i],
+      costItemCount: counts[i],
+    })),
+    points,
+    minimum: points.reduce((min, p) => (p.total < min.total ? p : min)),
+  };
+}
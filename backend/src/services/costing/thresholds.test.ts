@@ -0,0 +1,69 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import { checkThresholds, thresholdWarnings } from "./thresholds";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+function asset(id: string, items: number[]) {
+  const total = items.reduce((sum, tic) => sum + tic, 0);
+  return mockAssetEstimate(
+    id,
+    [mockYearCosts(2025, { total_installed_cost: total })],
+    items.map((tic, i) =>
+      mockCostItem(`${id}/blocks/${i}/Item 00${i}`, {
+        total_installed_cost: tic,
+      }),
+    ),
+  );
+}
+
+describe("thresholds", () => {
+  const result = transformCostingResponse(
+    { assets: [asset("capture", [600, 200]), asset("pipe", [200])] },
+    [],
+    "GBP",
+  );
+
+  it("checks costs for the network or each asset", () => {
+    const network = { metric: "totalInstalledCost", max: 900 } as const;
+    const perAsset = { ...network, max: 500, scope: "asset" } as const;
+
+    expect(checkThresholds(result, [network, perAsset])).toEqual([
+      { threshold: network, value: 1000 },
+      { threshold: perAsset, subject: "capture", value: 800 },
+    ]);
+    expect(
+      checkThresholds(result, [{ metric: "totalInstalledCost", max: 1000 }]),
+    ).toEqual([]);
+  });
+
+  it("checks each cost line's share of network or asset capex", () => {
+    const network = { metric: "lineShareOfCapex", max: 50 } as const;
+    const perAsset = { ...network, scope: "asset" } as const;
+
+    expect(checkThresholds(result, [network])).toEqual([
+      { threshold: network, subject: "capture/blocks/0/Item 000", value: 60 },
+    ]);
+    expect(checkThresholds(result, [perAsset])).toEqual([
+      { threshold: perAsset, subject: "capture/blocks/0/Item 000", value: 75 },
+      { threshold: perAsset, subject: "pipe/blocks/0/Item 000", value: 100 },
+    ]);
+  });
+
+  it("formats alerts as warnings", () => {
+    const alerts = checkThresholds(result, [
+      { metric: "totalInstalledCost", max: 900, label: "capex gate" },
+      { metric: "lineShareOfCapex", max: 50 },
+    ]);
+
+    expect(thresholdWarnings(alerts, "GBP")).toEqual([
+      "Threshold capex gate: Network total installed cost 1000 GBP is above " +
+        "900 GBP",
+      "Threshold: capture/blocks/0/Item 000 share of capex 60.0% is above " +
+        "50.0%",
+    ]);
+  });
+});
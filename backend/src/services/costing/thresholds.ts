@@ -0,0 +1,142 @@
+/**
+ * Threshold alerts.
+ *
+ * A request can set limits on the estimate, e.g. total capex above £500M or
+ * any single cost line above 30% of capex, and every limit crossed is
+ * reported in the response's warnings. Automated pipelines can gate on
+ * them without re-deriving the figures.
+ */
+
+import type {
+  AssetCostResult,
+  CostingEstimateResponse,
+} from "./request-types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const THRESHOLD_METRICS = [
+  "totalInstalledCost",
+  "lifetimeCost",
+  "netPresentCost",
+  "lineShareOfCapex",
+] as const;
+
+export type ThresholdMetric = (typeof THRESHOLD_METRICS)[number];
+
+export type CostThreshold = {
+  /**
+   * What to check: a lifetime cost in the result currency, or the share of
+   * capex (total installed cost) of any one cost line, as a percentage
+   */
+  metric: ThresholdMetric;
+  /** Alert when the value is above this */
+  max: number;
+  /**
+   * Check the network (default) or each asset. For lineShareOfCapex, the
+   * share of the network's or the line's own asset's capex.
+   */
+  scope?: "network" | "asset";
+  /** Name shown in the alert, e.g. "capex gate" */
+  label?: string;
+};
+
+export type ThresholdAlert = {
+  threshold: CostThreshold;
+  /** Asset or cost line the alert is for; absent for the network */
+  subject?: string;
+  value: number;
+};
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+const METRIC_NAMES: Record<ThresholdMetric, string> = {
+  totalInstalledCost: "total installed cost",
+  lifetimeCost: "lifetime cost",
+  netPresentCost: "net present cost",
+  lineShareOfCapex: "share of capex",
+};
+
+function costValue(
+  costs: Pick<CostingEstimateResponse, "lifetimeCosts" | "lifetimeNpcCosts">,
+  metric: Exclude<ThresholdMetric, "lineShareOfCapex">,
+): number {
+  switch (metric) {
+    case "totalInstalledCost":
+      return costs.lifetimeCosts.totalInstalledCost;
+    case "lifetimeCost":
+      return costs.lifetimeCosts.total;
+    case "netPresentCost":
+      return costs.lifetimeNpcCosts.total;
+  }
+}
+
+function lineShares(
+  assets: AssetCostResult[],
+  capex: (asset: AssetCostResult) => number,
+): { subject: string; value: number }[] {
+  return assets.flatMap((asset) => {
+    const total = capex(asset);
+    return total === 0
+      ? []
+      : asset.blocks.map((line) => ({
+          subject: line.id,
+          value: (line.totalInstalledCost / total) * 100,
+        }));
+  });
+}
+
+/**
+ * The thresholds an estimate crosses.
+ */
+export function checkThresholds(
+  result: CostingEstimateResponse,
+  thresholds: readonly CostThreshold[],
+): ThresholdAlert[] {
+  return thresholds.flatMap((threshold) => {
+    const { metric } = threshold;
+    const perAsset = threshold.scope === "asset";
+    let values: { subject?: string; value: number }[];
+    if (metric === "lineShareOfCapex") {
+      values = lineShares(result.assets, (asset) =>
+        perAsset
+          ? asset.lifetimeCosts.totalInstalledCost
+          : result.lifetimeCosts.totalInstalledCost,
+      );
+    } else if (perAsset) {
+      values = result.assets.map((asset) => ({
+        subject: asset.id,
+        value: costValue(asset, metric),
+      }));
+    } else {
+      values = [{ value: costValue(result, metric) }];
+    }
+
+    return values
+      .filter(({ value }) => value > threshold.max)
+      .map((alert) => ({ threshold, ...alert }));
+  });
+}
+
+/**
+ * Format threshold alerts for the response's warnings.
+ */
+export function thresholdWarnings(
+  alerts: ThresholdAlert[],
+  currency: string,
+): string[] {
+  return alerts.map(({ threshold, subject, value }) => {
+    const share = threshold.metric === "lineShareOfCapex";
+    const format = (n: number) =>
+      share ? `${n.toFixed(1)}%` : `${Math.round(n)} ${currency}`;
+    const what =
+      `${subject ?? "Network"} ${METRIC_NAMES[threshold.metric]} ` +
+      `${format(value)} is above ${format(threshold.max)}`;
+    return threshold.label
+      ? `Threshold ${threshold.label}: ${what}`
+      : `Threshold: ${what}`;
+  });
+}
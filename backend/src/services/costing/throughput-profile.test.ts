@@ -0,0 +1,86 @@
+import { describe, it, expect } from "vitest";
+import type { AssetMetadata } from "./adapter";
+import {
+  applyThroughputProfiles,
+  throughputFraction,
+} from "./throughput-profile";
+import type { AssetParameters } from "./types";
+import {
+  mockAssetEstimate,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import { DEFAULT_OPEX_FACTORS } from "./defaults";
+import { ESTIMATE_PRESETS } from "./presets";
+
+describe("throughput-profile", () => {
+  const timeline = {
+    construction_start: 2025,
+    construction_finish: 2026,
+    operation_start: 2027,
+    operation_finish: 2030,
+    decommissioning_start: 2030,
+    decommissioning_finish: 2031,
+  };
+  const year = (y: number) =>
+    mockYearCosts(
+      y,
+      { variable_opex_cost: { tariff: 100, electrical_power: 10 } },
+      { variable_opex_cost: { tariff: 50, electrical_power: 5 } },
+    );
+  const metadata = (
+    assetId: string,
+    throughputProfile?: number[],
+  ): AssetMetadata => ({
+    assetId,
+    isGroup: false,
+    branchIds: [assetId],
+    blockCount: 0,
+    costableBlockCount: 0,
+    usingDefaults: [],
+    provenance: {
+      capexLangFactors: ESTIMATE_PRESETS["AACE Class 5"].capex_lang_factors,
+      opexFactors: DEFAULT_OPEX_FACTORS,
+      ...(throughputProfile && { throughputProfile }),
+    },
+    blocks: [],
+  });
+
+  it("runs at full throughput outside the profile", () => {
+    expect(throughputFraction([0.5, 0.8], 2027, 2026)).toBe(1);
+    expect(throughputFraction([0.5, 0.8], 2027, 2027)).toBe(0.5);
+    expect(throughputFraction([0.5, 0.8], 2027, 2028)).toBe(0.8);
+    expect(throughputFraction([0.5, 0.8], 2027, 2029)).toBe(1);
+  });
+
+  it("scales each year's variable opex and the lifetime totals", () => {
+    const response = {
+      assets: ["ramped", "flat"].map((id) =>
+        mockAssetEstimate(id, [year(2027), year(2028), year(2029)]),
+      ),
+    };
+    const request = {
+      assets: ["ramped", "flat"].map(
+        (id) => ({ id, timeline }) as AssetParameters,
+      ),
+    };
+
+    const result = applyThroughputProfiles(response, request, [
+      metadata("ramped", [0.5, 0.8]),
+      metadata("flat"),
+    ]);
+
+    const [ramped, flat] = result.assets;
+    expect(
+      ramped.costs_by_year.map((y) => y.costs_in_year.variable_opex_cost.tariff),
+    ).toEqual([50, 80, 100]);
+    expect(ramped.costs_by_year[0].dcf_costs_in_year.variable_opex_cost).toEqual(
+      expect.objectContaining({ tariff: 25, electrical_power: 2.5 }),
+    );
+    expect(ramped.lifetime_costs.variable_opex_cost.tariff).toBeCloseTo(230);
+    expect(ramped.lifetime_costs.variable_opex_cost.electrical_power).toBeCloseTo(
+      23,
+    );
+    expect(ramped.lifetime_dcf_costs.variable_opex_cost.tariff).toBeCloseTo(115);
+    expect(flat).toEqual(response.assets[1]);
+  });
+});
@@ -0,0 +1,112 @@
+/**
+ * Throughput ramp-up profiles.
+ *
+ * The costing server prices variable opex (utilities, consumables and the
+ * per-tonne CO2 charges and tariffs) as if an asset runs at full throughput
+ * from its first operating year. A profile gives the fraction of full
+ * throughput for each operating year, from operation start; years past the
+ * end of the profile run at full throughput. Each year's variable opex in
+ * `costs_by_year` is scaled by its fraction and the lifetime totals are
+ * adjusted to match, so everything built from the response (totals, cash
+ * flows, tables) follows the ramp-up.
+ */
+
+import type { AssetMetadata } from "./adapter";
+import type {
+  CostEstimateRequest,
+  CostEstimateResponse,
+  VariableOpexCostEstimate,
+} from "./types";
+import { rescaleYears, type YearlyCosts } from "./totals";
+
+// ============================================================================
+// Cost arithmetic
+// ============================================================================
+
+/** Multiplier for each kind of variable opex; omitted kinds are unchanged */
+export type VariableOpexFactors = Partial<VariableOpexCostEstimate>;
+
+function scale(
+  costs: VariableOpexCostEstimate,
+  factor: number | VariableOpexFactors,
+): VariableOpexCostEstimate {
+  return Object.fromEntries(
+    Object.entries(costs).map(([key, value]) => [
+      key,
+      value *
+        (typeof factor === "number"
+          ? factor
+          : (factor[key as keyof VariableOpexCostEstimate] ?? 1)),
+    ]),
+  ) as VariableOpexCostEstimate;
+}
+
+/**
+ * Scale each year's variable opex and move the lifetime totals by the same
+ * amounts.
+ */
+export function scaleYears<
+  C extends YearlyCosts<{ variable_opex_cost: VariableOpexCostEstimate }>,
+>(costs: C, factor: (year: number) => number | VariableOpexFactors): C {
+  return rescaleYears(costs, (period, year) => {
+    const f = factor(year);
+    if (f === 1) return period;
+    return {
+      ...period,
+      variable_opex_cost: scale(period.variable_opex_cost, f),
+    };
+  });
+}
+
+// ============================================================================
+// Profiles
+// ============================================================================
+
+/**
+ * Fraction of full throughput in a year, for a profile starting at
+ * `operationStart`.
+ */
+export function throughputFraction(
+  profile: number[],
+  operationStart: number,
+  year: number,
+): number {
+  const index = year - operationStart;
+  return index >= 0 && index < profile.length ? profile[index] : 1;
+}
+
+/**
+ * Apply each asset's throughput profile (from its provenance) to the
+ * costing server's variable opex. Assets without a profile are unchanged.
+ */
+export function applyThroughputProfiles(
+  response: CostEstimateResponse,
+  request: CostEstimateRequest,
+  assetMetadata: AssetMetadata[],
+): CostEstimateResponse {
+  const profiles = new Map(
+    assetMetadata.flatMap((m) =>
+      m.provenance?.throughputProfile
+        ? [[m.assetId, m.provenance.throughputProfile] as const]
+        : [],
+    ),
+  );
+  if (profiles.size === 0) return response;
+  const timelines = new Map(request.assets.map((a) => [a.id, a.timeline]));
+
+  return {
+    ...response,
+    assets: response.assets.map((asset) => {
+      const profile = profiles.get(asset.id);
+      const timeline = timelines.get(asset.id);
+      if (!profile || !timeline) return asset;
+      const factor = (year: number) =>
+        throughputFraction(profile, timeline.operation_start, year);
+
+      return {
+        ...scaleYears(asset, factor),
+        cost_items: asset.cost_items.map((item) => scaleYears(item, factor)),
+      };
+    }),
+  };
+}
@@ -0,0 +1,90 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import { assetTopDrivers, portfolioTopDrivers } from "./top-drivers";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockFixedOpexCosts,
+  mockVariableOpexCosts,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+function asset(id: string, items: [number, number][], maintenance: number) {
+  const estimate = mockAssetEstimate(
+    id,
+    [mockYearCosts(2025, {})],
+    items.map(([tic, power], i) => {
+      const item = mockCostItem(`${id}/blocks/${i}/Item 00${i}`, {
+        total_installed_cost: tic,
+      });
+      item.costs.variable_opex_cost_per_year = mockVariableOpexCosts({
+        electrical_power: power,
+      });
+      return item;
+    }),
+  );
+  estimate.costs.fixed_opex_cost_per_year = mockFixedOpexCosts({
+    maintenance,
+  });
+  return estimate;
+}
+
+describe("top-drivers", () => {
+  const capture = asset(
+    "capture",
+    [
+      [600, 30],
+      [300, 0],
+      [100, 10],
+    ],
+    60,
+  );
+  const pipe = asset("pipe", [[1000, 0]], 40);
+
+  it("ranks an asset's largest capex and annual opex contributors", () => {
+    const drivers = assetTopDrivers(capture, 2);
+
+    expect(drivers.capex).toEqual([
+      { id: "capture/blocks/0/Item 000", value: 600, share: 60 },
+      { id: "capture/blocks/1/Item 001", value: 300, share: 30 },
+    ]);
+    expect(drivers.annualOpex).toEqual([
+      { id: "fixedOpex.maintenance", value: 60, share: 60 },
+      { id: "capture/blocks/0/Item 000", value: 30, share: 30 },
+    ]);
+  });
+
+  it("ranks across assets, summing fixed opex categories", () => {
+    const drivers = portfolioTopDrivers([capture, pipe], 2);
+
+    expect(drivers.capex).toEqual([
+      { id: "pipe/blocks/0/Item 000", assetId: "pipe", value: 1000, share: 50 },
+      {
+        id: "capture/blocks/0/Item 000",
+        assetId: "capture",
+        value: 600,
+        share: 30,
+      },
+    ]);
+    expect(drivers.annualOpex[0]).toEqual({
+      id: "fixedOpex.maintenance",
+      value: 100,
+      share: 100 * (100 / 140),
+    });
+  });
+
+  it("is only added to the response when asked for", () => {
+    const estimate = { assets: [capture, pipe] };
+
+    expect(transformCostingResponse(estimate, [], "GBP").topDrivers).toBe(
+      undefined,
+    );
+    const ranked = transformCostingResponse(estimate, [], "GBP", {
+      topDrivers: 1,
+    });
+    expect(ranked.topDrivers?.capex).toHaveLength(1);
+    expect(ranked.assets[1].topDrivers?.capex[0].id).toBe(
+      "pipe/blocks/0/Item 000",
+    );
+  });
+});
@@ -0,0 +1,109 @@
+/**
+ * Top cost drivers.
+ *
+ * Ranks the largest contributors to capital cost and annual opex, per asset
+ * and across the portfolio, with each one's share of the total. Capital
+ * cost contributors are cost lines (total installed cost). Annual opex
+ * contributors are cost lines (their variable opex per operating year) and
+ * the asset's fixed opex categories.
+ */
+
+import type { AssetCostEstimate } from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type CostDriver = {
+  /** Cost line ID, or "fixedOpex.<category>" for fixed opex */
+  id: string;
+  /** Asset the driver belongs to (portfolio rankings only) */
+  assetId?: string;
+  value: number;
+  /** Percentage of the ranked total */
+  share: number;
+};
+
+export type TopDrivers = {
+  /** Largest contributors to total installed cost */
+  capex: CostDriver[];
+  /** Largest contributors to opex per operating year */
+  annualOpex: CostDriver[];
+};
+
+type Contribution = { id: string; assetId?: string; value: number };
+
+type Contributions = { capex: Contribution[]; annualOpex: Contribution[] };
+
+// ============================================================================
+// Ranking
+// ============================================================================
+
+function sumValues(costs: object): number {
+  return Object.values(costs).reduce<number>((sum, v) => sum + v, 0);
+}
+
+function contributions(asset: AssetCostEstimate): Contributions {
+  return {
+    capex: asset.cost_items.map((item) => ({
+      id: item.id,
+      value: item.lifetime_costs.total_installed_cost ?? 0,
+    })),
+    annualOpex: [
+      ...asset.cost_items.map((item) => ({
+        id: item.id,
+        value: sumValues(item.costs.variable_opex_cost_per_year),
+      })),
+      ...Object.entries(asset.costs.fixed_opex_cost_per_year).map(
+        ([category, value]) => ({ id: `fixedOpex.${category}`, value }),
+      ),
+    ],
+  };
+}
+
+function rank(items: Contribution[], count: number): CostDriver[] {
+  const total = items.reduce((sum, i) => sum + i.value, 0);
+  return items
+    .filter((i) => i.value !== 0)
+    .sort((a, b) => b.value - a.value)
+    .slice(0, count)
+    .map((i) => ({ ...i, share: total === 0 ? 0 : (i.value / total) * 100 }));
+}
+
+/**
+ * The `count` largest capex and annual opex contributors of an asset.
+ */
+export function assetTopDrivers(
+  asset: AssetCostEstimate,
+  count: number,
+): TopDrivers {
+  const { capex, annualOpex } = contributions(asset);
+  return { capex: rank(capex, count), annualOpex: rank(annualOpex, count) };
+}
+
+/**
+ * The `count` largest contributors across all assets. Fixed opex categories
+ * are summed over the assets.
+ */
+export function portfolioTopDrivers(
+  assets: AssetCostEstimate[],
+  count: number,
+): TopDrivers {
+  const capex: Contribution[] = [];
+  const annualOpex: Contribution[] = [];
+  const fixedOpex = new Map<string, number>();
+  for (const asset of assets) {
+    const own = contributions(asset);
+    capex.push(...own.capex.map((c) => ({ ...c, assetId: asset.id })));
+    for (const c of own.annualOpex) {
+      if (c.id.startsWith("fixedOpex.")) {
+        fixedOpex.set(c.id, (fixedOpex.get(c.id) ?? 0) + c.value);
+      } else {
+        annualOpex.push({ ...c, assetId: asset.id });
+      }
+    }
+  }
+  annualOpex.push(...[...fixedOpex].map(([id, value]) => ({ id, value })));
+
+  return { capex: rank(capex, count), annualOpex: rank(annualOpex, count) };
+}
@@ -0,0 +1,105 @@
+import { describe, it, expect } from "vitest";
+import { enforceTotals, lifetimeTotal } from "./totals";
+import { applyRounding } from "./rounding";
+import { transformCostingResponse } from "./adapter";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+
+describe("totals", () => {
+  const response = transformCostingResponse(
+    {
+      assets: [
+        mockAssetEstimate(
+          "asset-1",
+          [
+            mockYearCosts(2025, {
+              total_installed_cost: 1000.1,
+              fixed_opex_cost: { maintenance: 10.2 },
+              decommissioning_cost: 5.3,
+            }),
+          ],
+          [mockCostItem("b/blocks/0/Item 001", { total_installed_cost: 1000.1 })],
+        ),
+        mockAssetEstimate(
+          "asset-2",
+          [mockYearCosts(2025, { total_installed_cost: 2000.2 })],
+          [mockCostItem("b/blocks/0/Item 002", { total_installed_cost: 2000.2 })],
+        ),
+      ],
+    },
+    [],
+    "USD",
+  );
+
+  it("reports total as installed cost plus OPEX and decommissioning", () => {
+    const [asset] = response.assets;
+
+    expect(asset.lifetimeCosts.total).toBeCloseTo(1000.1 + 10.2 + 5.3, 9);
+    expect(lifetimeTotal(asset.lifetimeCosts)).toBe(asset.lifetimeCosts.total);
+  });
+
+  it("derives network costs from asset costs", () => {
+    const result = enforceTotals(response);
+
+    expect(result.lifetimeCosts.totalInstalledCost).toBeCloseTo(3000.3, 9);
+    expect(result.lifetimeCosts.total).toBeCloseTo(
+      result.assets[0].lifetimeCosts.total +
+        result.assets[1].lifetimeCosts.total,
+      9,
+    );
+  });
+
+  it("keeps rounded totals equal to the sum of rounded components", () => {
+    const result = applyRounding(response, { mode: "decimals", digits: 0 });
+
+    expect(result.assets[0].lifetimeCosts.total).toBe(1000 + 10 + 5);
+    expect(result.lifetimeCosts.totalInstalledCost).toBe(1000 + 2000);
+    expect(result.lifetimeCosts.total).toBe(1015 + 2000);
+  });
+
+  describe("contingency", () => {
+    const withContingency = {
+      assets: [
+        mockAssetEstimate(
+          "asset-1",
+          [
+            mockYearCosts(2025, {
+              total_installed_cost: 1000,
+              lang_factored_capital_cost: { contingency: 200 },
+            }),
+          ],
+          [],
+        ),
+      ],
+    };
+
+    it("reports installed cost with and without contingency", () => {
+      const [asset] = transformCostingResponse(withContingency, [], "USD")
+        .assets;
+
+      expect(asset.lifetimeCosts.totalInstalledCostExclContingency).toBe(1000);
+      expect(asset.lifetimeCosts.totalInstalledCostInclContingency).toBe(1200);
+      expect(asset.lifetimeCosts.contingencyApplied).toBe(0);
+      expect(asset.lifetimeCosts.total).toBe(1000);
+    });
+
+    it("adds contingency to totals when requested", () => {
+      const result = transformCostingResponse(withContingency, [], "USD", {
+        includeContingencyInTotals: true,
+      });
+
+      expect(result.assets[0].lifetimeCosts.contingencyApplied).toBe(200);
+      expect(result.assets[0].lifetimeNpcCosts.total).toBe(1200);
+      expect(result.lifetimeCosts.total).toBe(1200);
+    });
+  });
+
+  it("strips floating-point noise from decimal-rounded sums", () => {
+    const result = applyRounding(response, { mode: "decimals", digits: 1 });
+
+    expect(result.lifetimeCosts.totalInstalledCost).toBe(3000.3);
+  });
+});
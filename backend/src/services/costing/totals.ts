@@ -0,0 +1,243 @@
+/**
+ * Totals and aggregation.
+ *
+ * Every total in a response is derived here from its components, so totals
+ * always match the values they summarise - including after rounding - and
+ * clients never need to re-implement the summation.
+ */
+
+import type {
+  CostingEstimateResponse,
+  LifetimeCosts,
+  FixedOpexCosts,
+  VariableOpexCosts,
+} from "./request-types";
+
+/** Fields of LifetimeCosts derived from the others */
+type DerivedField =
+  | "total"
+  | "totalInstalledCostExclContingency"
+  | "totalInstalledCostInclContingency";
+
+export type LifetimeCostComponents = Omit<LifetimeCosts, DerivedField>;
+
+/**
+ * Total lifetime cost: installed capital plus any contingency applied, fixed
+ * and variable OPEX, and decommissioning.
+ */
+export function lifetimeTotal(costs: LifetimeCostComponents): number {
+  return (
+    costs.totalInstalledCost +
+    costs.contingencyApplied +
+    sumOf(costs.fixedOpexCost) +
+    sumOf(costs.variableOpexCost) +
+    costs.decommissioningCost
+  );
+}
+
+function sumOf(costs: FixedOpexCosts | VariableOpexCosts): number {
+  return Object.values(costs).reduce((a, b) => a + b, 0);
+}
+
+/**
+ * Return the costs with `total` and the contingency-inclusive and exclusive
+ * installed costs recomputed from the components.
+ */
+export function withTotal(costs: LifetimeCostComponents): LifetimeCosts {
+  return {
+    ...costs,
+    totalInstalledCostExclContingency: costs.totalInstalledCost,
+    totalInstalledCostInclContingency:
+      costs.totalInstalledCost + costs.langFactoredCapitalCost.contingency,
+    total: lifetimeTotal(costs),
+  };
+}
+
+/**
+ * Sum lifetime costs field by field.
+ */
+export function aggregateLifetimeCosts(costs: LifetimeCosts[]): LifetimeCosts {
+  const sum = (arr: number[]) => arr.reduce((a, b) => a + b, 0);
+
+  return withTotal({
+    directEquipmentCost: sum(costs.map((c) => c.directEquipmentCost)),
+    langFactoredCapitalCost: {
+      equipmentErection: sum(
+        costs.map((c) => c.langFactoredCapitalCost.equipmentErection)
+      ),
+      piping: sum(costs.map((c) => c.langFactoredCapitalCost.piping)),
+      instrumentation: sum(
+        costs.map((c) => c.langFactoredCapitalCost.instrumentation)
+      ),
+      electrical: sum(costs.map((c) => c.langFactoredCapitalCost.electrical)),
+      buildingsAndProcess: sum(
+        costs.map((c) => c.langFactoredCapitalCost.buildingsAndProcess)
+      ),
+      utilities: sum(costs.map((c) => c.langFactoredCapitalCost.utilities)),
+      storages: sum(costs.map((c) => c.langFactoredCapitalCost.storages)),
+      siteDevelopment: sum(
+        costs.map((c) => c.langFactoredCapitalCost.siteDevelopment)
+      ),
+      ancillaryBuildings: sum(
+        costs.map((c) => c.langFactoredCapitalCost.ancillaryBuildings)
+      ),
+      designAndEngineering: sum(
+        costs.map((c) => c.langFactoredCapitalCost.designAndEngineering)
+      ),
+      contractorsFee: sum(
+        costs.map((c) => c.langFactoredCapitalCost.contractorsFee)
+      ),
+      contingency: sum(costs.map((c) => c.langFactoredCapitalCost.contingency)),
+    },
+    totalInstalledCost: sum(costs.map((c) => c.totalInstalledCost)),
+    contingencyApplied: sum(costs.map((c) => c.contingencyApplied)),
+    fixedOpexCost: {
+      maintenance: sum(costs.map((c) => c.fixedOpexCost.maintenance)),
+      controlRoomFacilities: sum(
+        costs.map((c) => c.fixedOpexCost.controlRoomFacilities)
+      ),
+      insuranceLiability: sum(
+        costs.map((c) => c.fixedOpexCost.insuranceLiability)
+      ),
+      insuranceEquipmentLoss: sum(
+        costs.map((c) => c.fixedOpexCost.insuranceEquipmentLoss)
+      ),
+      costOfCapital: sum(costs.map((c) => c.fixedOpexCost.costOfCapital)),
+      majorTurnarounds: sum(costs.map((c) => c.fixedOpexCost.majorTurnarounds)),
+      labourCost: sum(costs.map((c) => c.fixedOpexCost.labourCost)),
+    },
+    variableOpexCost: {
+      electricity: sum(costs.map((c) => c.variableOpexCost.electricity)),
+      naturalGas: sum(costs.map((c) => c.variableOpexCost.naturalGas)),
+      water: sum(costs.map((c) => c.variableOpexCost.water)),
+      other: sum(costs.map((c) => c.variableOpexCost.other)),
+    },
+    decommissioningCost: sum(costs.map((c) => c.decommissioningCost)),
+  });
+}
+
+/**
+ * Apply a function to every number in a nested cost breakdown.
+ */
+export function mapCostValues<T>(costs: T, fn: (value: number) => number): T {
+  if (typeof costs === "number") {
+    return fn(costs) as T;
+  }
+  if (costs && typeof costs === "object") {
+    return Object.fromEntries(
+      Object.entries(costs).map(([key, value]) => [
+        key,
+        mapCostValues(value, fn),
+      ])
+    ) as T;
+  }
+  return costs;
+}
+
+/**
+ * Add two nested cost breakdowns of the same shape number by number. Null
+ * values (costs that don't apply) stay null.
+ */
+export function addCostValues<T>(a: T, b: T): T {
+  if (typeof a === "number") {
+    return (a + ((b as number | null) ?? 0)) as T;
+  }
+  if (a && typeof a === "object") {
+    return Object.fromEntries(
+      Object.entries(a).map(([key, value]) => [
+        key,
+        addCostValues(value, (b as Record<string, unknown>)?.[key]),
+      ])
+    ) as T;
+  }
+  return a;
+}
+
+/** An asset or cost item estimate: yearly costs and their lifetime sums */
+export type YearlyCosts<P = unknown> = {
+  costs_by_year: { year: number; costs_in_year: P; dcf_costs_in_year: P }[];
+  lifetime_costs: P;
+  lifetime_dcf_costs: P;
+};
+
+/**
+ * Rescale each year's costs and move the lifetime totals by the same
+ * amounts, so they stay the sums of the years. `rescale` gives a year's new
+ * costs from its undiscounted or discounted costs; returning them unchanged
+ * leaves the year as it was.
+ */
+export function rescaleYears<C extends YearlyCosts>(
+  costs: C,
+  rescale: (
+    costs: C["lifetime_costs"],
+    year: number,
+    discounted: boolean
+  ) => C["lifetime_costs"]
+): C {
+  type Period = C["lifetime_costs"];
+  let lifetime: unknown = costs.lifetime_costs;
+  let lifetimeDcf: unknown = costs.lifetime_dcf_costs;
+  // Lifetime total plus the change between a year's rescaled and saved costs
+  const adjust = (total: unknown, rescaled: unknown, saved: unknown) =>
+    addCostValues(
+      total,
+      addCostValues(rescaled, mapCostValues(saved, (v) => -v))
+    );
+
+  const costsByYear = costs.costs_by_year.map((y) => {
+    const costsInYear = rescale(y.costs_in_year as Period, y.year, false);
+    const dcfCostsInYear = rescale(y.dcf_costs_in_year as Period, y.year, true);
+    if (
+      costsInYear === y.costs_in_year &&
+      dcfCostsInYear === y.dcf_costs_in_year
+    ) {
+      return y;
+    }
+    lifetime = adjust(lifetime, costsInYear, y.costs_in_year);
+    lifetimeDcf = adjust(lifetimeDcf, dcfCostsInYear, y.dcf_costs_in_year);
+    return {
+      ...y,
+      costs_in_year: costsInYear,
+      dcf_costs_in_year: dcfCostsInYear,
+    };
+  });
+
+  return {
+    ...costs,
+    costs_by_year: costsByYear,
+    lifetime_costs: lifetime,
+    lifetime_dcf_costs: lifetimeDcf,
+  } as C;
+}
+
+/**
+ * Recompute every derived value in a response from its components:
+ * asset totals from asset components, and network costs from asset costs.
+ *
+ * `normalizeValue` is applied to each derived value, e.g. to strip floating-point
+ * noise from sums of already-rounded components.
+ */
+export function enforceTotals(
+  response: CostingEstimateResponse,
+  normalizeValue?: (value: number) => number
+): CostingEstimateResponse {
+  const normalize = (costs: LifetimeCosts) =>
+    normalizeValue ? mapCostValues(costs, normalizeValue) : costs;
+
+  const assets = response.assets.map((asset) => ({
+    ...asset,
+    lifetimeCosts: normalize(withTotal(asset.lifetimeCosts)),
+    lifetimeNpcCosts: normalize(withTotal(asset.lifetimeNpcCosts)),
+  }));
+
+  return {
+    ...response,
+    assets,
+    lifetimeCosts: normalize(
+      aggregateLifetimeCosts(assets.map((a) => a.lifetimeCosts))
+    ),
+    lifetimeNpcCosts: normalize(
+      aggregateLifetimeCosts(assets.map((a) => a.lifetimeNpcCosts))
+    ),
+  };
+}
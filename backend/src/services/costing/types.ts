@@ -9,15 +9,122 @@
 
 export type CostLibrary = {
   modules: CostLibraryModule[];
+  currency_conversion?: CurrencyConversion;
+  inflation?: InflationTable;
+  defaults?: LibraryFactorDefaults;
+  /** Canonical parameters the library's cost items use */
+  parameters?: ParameterDefinition[];
+  /** Cost item IDs renamed from earlier library versions (old ID → new ID) */
+  cost_item_aliases?: Record<string, string>;
+  /** Composite items modules can include as one item */
+  bundles?: CostItemBundle[];
+  /** Client CBS/WBS codes for the library's cost lines */
+  cbs_mapping?: CbsMapping;
+};
+
+/**
+ * Client cost breakdown structure (CBS/WBS) codes for cost lines. A line
+ * takes the code of its cost item, then of its module, then the default.
+ */
+export type CbsMapping = {
+  /** Codes by cost item ID (e.g., "Item 023") */
+  items?: Record<string, string>;
+  /** Codes by module ID (e.g., "M0201") */
+  modules?: Record<string, string>;
+  /** Code for lines neither map covers */
+  default?: string;
+};
+
+/**
+ * A composite item, e.g. an injection wellhead package, that a module
+ * includes as one item and that is costed as its component cost items.
+ */
+export type CostItemBundle = {
+  id: string;
+  info: {
+    short_name: string;
+    description?: string;
+  };
+  components: BundleComponent[];
+};
+
+export type BundleComponent = {
+  /** Cost item ID */
+  ref: string;
+  /** Units of this item per bundle (default: 1) */
+  quantity?: number;
+  /**
+   * Bundle parameter supplying each of the item's parameters, keyed by the
+   * item's parameter name. Unmapped parameters pass through by name.
+   */
+  parameters?: Record<string, string>;
+};
+
+/**
+ * A canonical cost item parameter. Cost items name parameters by display
+ * name, which has varied between library versions; the registry maps every
+ * known name to one ID.
+ */
+export type ParameterDefinition = {
+  /** Canonical ID, also the block property the value is read from */
+  id: string;
+  /** Display name */
+  name: string;
+  units: string;
+  /** Other names cost items use for this parameter */
+  aliases?: string[];
+};
+
+/**
+ * Recommended factor sets shipped with a library version.
+ * Used for any factor a request does not specify.
+ */
+export type LibraryFactorDefaults = {
+  capex_lang_factors?: Partial<CapexLangFactors>;
+  opex_factors?: Partial<FixedOpexFactors>;
+};
+
+export type CurrencyConversion = {
+  base_currency: string;
+  /** Value of one unit of each currency in the base currency */
+  rates: Record<string, number>;
+};
+
+export type InflationTable = {
+  /** Price base year that all costs are inflated to */
+  current_year: string;
+  /** Multiplier taking a cost from the keyed year to current_year */
+  factors: Record<string, number>;
 };
 
 export type CostLibraryModule = {
   id: string;
   definition: ModuleDefinition;
-  subtype: string;
+  subtype: string | null;
   cost_items: CostLibraryCostItem[];
+  /** IDs of bundles the module includes */
+  bundles?: string[];
+  /** Rules on which of the module's items an asset may use together */
+  constraints?: ItemConstraint[];
 };
 
+/**
+ * A rule on a module's cost item selection. Items are cost item or bundle
+ * IDs; an item is selected when all its parameters are supplied.
+ * - exactlyOne: one of the items must be selected (e.g. one drive type)
+ * - atMostOne: the items are mutually exclusive
+ * - requires: selecting `item` requires every item in `requires`
+ */
+export type ItemConstraint =
+  | { type: "exactlyOne"; items: string[]; description?: string }
+  | { type: "atMostOne"; items: string[]; description?: string }
+  | {
+      type: "requires";
+      item: string;
+      requires: string[];
+      description?: string;
+    };
+
 export type ModuleDefinition = {
   type: string;
   [key: string]: unknown; // Additional type-specific fields
@@ -42,27 +149,65 @@ export type CostItemInfo = {
   cost_type: string | null;
   cost_location: string | null;
   note: string | null;
+  /** Translated names and descriptions keyed by BCP 47 language tag */
+  translations?: Record<string, LocalizedCostItemText>;
+  /** Documents the cost was taken from */
+  sources?: SourceReference[];
+};
+
+export const SOURCE_CONFIDENCE_LEVELS = ["low", "medium", "high"] as const;
+
+export type SourceReference = {
+  /** Report or document name */
+  report: string;
+  /** Page or section within the report */
+  page?: string;
+  /** Vendor quote or offer identifier */
+  vendor_quote_id?: string;
+  confidence?: (typeof SOURCE_CONFIDENCE_LEVELS)[number];
+};
+
+export type LocalizedCostItemText = {
+  short_name?: string;
+  description?: string;
 };
 
 export type ScalingFactor = {
   name: string;
   units: string;
   source_value: number;
+  /** Value used when an estimate doesn't supply one, in `units` */
+  default_value?: number;
 };
 
 export type CapexContribution = {
   year: number;
   currency: string;
-  cost: {
-    type: string;
-    base_cost: number;
-  };
+  cost: CapexCost;
+};
+
+export type CapexCost = {
+  type: string; // "linear" | "polynomial"
+  base_cost?: number;
+  parameters?: PolynomialTerm[];
+};
+
+/**
+ * A polynomial term is either coefficient * dimension^exponent or a constant value.
+ */
+export type PolynomialTerm = {
+  dimension_name?: string;
+  coefficient?: number;
+  exponent?: number;
+  value?: number;
 };
 
 export type VariableOpexContribution = {
   name: string;
   units: string;
   scaled_by: number;
+  /** Value used when an estimate doesn't supply one, in `units` */
+  default_value?: number;
 };
 
 // ============================================================================
@@ -162,6 +307,12 @@ export type YearAssetCosts = {
   dcf_costs_in_year: AssetPeriodCosts;
 };
 
+/**
+ * Costs over a period as the costing server reports them. Deliberately has
+ * no `total`: the backend rescales these breakdowns (profiles, prices,
+ * price base) after the fact, and a stored total would go stale. Clients get
+ * each year's total from the cash flow view instead.
+ */
 export type AssetPeriodCosts = {
   direct_equipment_cost: number;
   lang_factored_capital_cost: LangFactoredCostEstimate;
@@ -0,0 +1,81 @@
+import { describe, it, expect } from "vitest";
+import type { AssetMetadata } from "./adapter";
+import {
+  applyUtilityPrices,
+  utilityPrice,
+  type UtilityPrices,
+} from "./utility-prices";
+import {
+  mockAssetEstimate,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import { DEFAULT_OPEX_FACTORS } from "./defaults";
+import { ESTIMATE_PRESETS } from "./presets";
+
+describe("utility-prices", () => {
+  const year = (y: number) =>
+    mockYearCosts(
+      y,
+      { variable_opex_cost: { tariff: 100, electrical_power: 10 } },
+      { variable_opex_cost: { tariff: 50, electrical_power: 5 } },
+    );
+  const metadata = (
+    assetId: string,
+    utilityPrices?: UtilityPrices,
+  ): AssetMetadata => ({
+    assetId,
+    isGroup: false,
+    branchIds: [assetId],
+    blockCount: 0,
+    costableBlockCount: 0,
+    usingDefaults: [],
+    provenance: {
+      capexLangFactors: ESTIMATE_PRESETS["AACE Class 5"].capex_lang_factors,
+      opexFactors: DEFAULT_OPEX_FACTORS,
+      ...(utilityPrices && { utilityPrices }),
+    },
+    blocks: [],
+  });
+
+  it("holds the latest listed price until the next", () => {
+    const series = { base_price: 50, prices: { "2030": 60, "2040": 80 } };
+    expect(utilityPrice(series, 2025)).toBe(60);
+    expect(utilityPrice(series, 2030)).toBe(60);
+    expect(utilityPrice(series, 2039)).toBe(60);
+    expect(utilityPrice(series, 2045)).toBe(80);
+    expect(utilityPrice({ base_price: 50, prices: {} }, 2030)).toBe(50);
+  });
+
+  it("indexes each year's priced utility and the lifetime totals", () => {
+    const response = {
+      assets: ["indexed", "flat"].map((id) =>
+        mockAssetEstimate(id, [year(2030), year(2031), year(2032)]),
+      ),
+    };
+
+    const result = applyUtilityPrices(response, [
+      metadata("indexed", {
+        electrical_power: {
+          base_price: 50,
+          prices: { "2030": 50, "2031": 75, "2032": 100 },
+        },
+      }),
+      metadata("flat"),
+    ]);
+
+    const [indexed, flat] = result.assets;
+    expect(
+      indexed.costs_by_year.map(
+        (y) => y.costs_in_year.variable_opex_cost.electrical_power,
+      ),
+    ).toEqual([10, 15, 20]);
+    expect(
+      indexed.costs_by_year[2].dcf_costs_in_year.variable_opex_cost,
+    ).toEqual(expect.objectContaining({ tariff: 50, electrical_power: 10 }));
+    expect(
+      indexed.lifetime_costs.variable_opex_cost.electrical_power,
+    ).toBeCloseTo(45);
+    expect(indexed.lifetime_costs.variable_opex_cost.tariff).toBeCloseTo(300);
+    expect(flat).toEqual(response.assets[1]);
+  });
+});
@@ -0,0 +1,110 @@
+/**
+ * Utility price indexation.
+ *
+ * The costing server prices each kind of variable opex (electricity, cooling
+ * water, natural gas, ...) at one flat per-unit price for every operating
+ * year. A price series gives the per-unit price by year alongside the base
+ * price the library costs at, so 2030 power can cost less than 2045 power.
+ * Each year's variable opex of that kind in `costs_by_year` is scaled by the
+ * year's price over the base price and the lifetime totals are adjusted to
+ * match, as for throughput ramp-up.
+ */
+
+import type { AssetMetadata } from "./adapter";
+import type { CostEstimateResponse, VariableOpexCostEstimate } from "./types";
+import { scaleYears, type VariableOpexFactors } from "./throughput-profile";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/** Kinds of variable opex that are priced per unit of a utility */
+export const UTILITY_PRICE_KINDS = [
+  "electrical_power",
+  "cooling_water",
+  "natural_gas",
+  "steam_hp_superheated",
+  "steam_lp_saturated",
+] as const satisfies readonly (keyof VariableOpexCostEstimate)[];
+
+export type UtilityPriceKind = (typeof UTILITY_PRICE_KINDS)[number];
+
+export type UtilityPriceSeries = {
+  /** Per-unit price the library's variable opex is costed at */
+  base_price: number;
+  /** Per-unit price by year, in the same units as base_price */
+  prices: Record<string, number>;
+};
+
+/** Price series by kind of utility */
+export type UtilityPrices = Partial<Record<UtilityPriceKind, UtilityPriceSeries>>;
+
+// ============================================================================
+// Prices
+// ============================================================================
+
+/**
+ * A yearly series' value in a year: the value of the latest listed year at
+ * or before it, or the earliest listed value for years before the series.
+ * Undefined when the series lists no years.
+ */
+export function valueInYear(
+  series: Record<string, number>,
+  year: number,
+): number | undefined {
+  const years = Object.keys(series)
+    .map(Number)
+    .sort((a, b) => a - b);
+  if (years.length === 0) return undefined;
+  const listed = years.filter((y) => y <= year).pop() ?? years[0];
+  return series[String(listed)];
+}
+
+/** A series' price in a year; the base price when it lists no years */
+export function utilityPrice(series: UtilityPriceSeries, year: number): number {
+  return valueInYear(series.prices, year) ?? series.base_price;
+}
+
+/** Multiplier for each priced kind of variable opex in a year */
+export function utilityPriceFactors(
+  prices: UtilityPrices,
+  year: number,
+): VariableOpexFactors {
+  return Object.fromEntries(
+    Object.entries(prices).flatMap(([key, series]) =>
+      series ? [[key, utilityPrice(series, year) / series.base_price]] : [],
+    ),
+  );
+}
+
+/**
+ * Apply each asset's utility price series (from its provenance) to the
+ * costing server's variable opex. Assets without prices are unchanged.
+ */
+export function applyUtilityPrices(
+  response: CostEstimateResponse,
+  assetMetadata: AssetMetadata[],
+): CostEstimateResponse {
+  const pricesByAsset = new Map(
+    assetMetadata.flatMap((m) =>
+      m.provenance?.utilityPrices
+        ? [[m.assetId, m.provenance.utilityPrices] as const]
+        : [],
+    ),
+  );
+  if (pricesByAsset.size === 0) return response;
+
+  return {
+    ...response,
+    assets: response.assets.map((asset) => {
+      const prices = pricesByAsset.get(asset.id);
+      if (!prices) return asset;
+      const factor = (year: number) => utilityPriceFactors(prices, year);
+
+      return {
+        ...scaleYears(asset, factor),
+        cost_items: asset.cost_items.map((item) => scaleYears(item, factor)),
+      };
+    }),
+  };
+}
@@ -0,0 +1,253 @@
+import { describe, it, expect } from "vitest";
+import { transformCostingResponse } from "./adapter";
+import { applyWhatIf, WhatIfError } from "./what-if";
+import { applyNominalBasis, type PriceBasis } from "./price-basis";
+import {
+  mockAssetEstimate,
+  mockCostItem,
+  mockYearCosts,
+} from "./mocks/cost-estimate-response";
+import type { AssetMetadata } from "./adapter";
+import type { StoredEstimate } from "./estimate-store";
+import type {
+  AssetParameters,
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostLibrary,
+} from "./types";
+
+const library = {
+  modules: [],
+  currency_conversion: { base_currency: "USD", rates: { USD: 1, GBP: 1.25 } },
+} as CostLibrary;
+
+// Capex in 2025, opex in 2026 and 2027, discounted at 10% from 2025
+const costingResponse: CostEstimateResponse = {
+  assets: [
+    mockAssetEstimate(
+      "capture",
+      [
+        mockYearCosts(2025, {
+          direct_equipment_cost: 100,
+          total_installed_cost: 300,
+          lang_factored_capital_cost: { piping: 200, contingency: 50 },
+        }),
+        mockYearCosts(
+          2026,
+          { fixed_opex_cost: { maintenance: 110 } },
+          { fixed_opex_cost: { maintenance: 100 } },
+        ),
+        mockYearCosts(
+          2027,
+          { fixed_opex_cost: { maintenance: 121 } },
+          { fixed_opex_cost: { maintenance: 100 } },
+        ),
+      ],
+      [mockCostItem("b/blocks/0/Item 023", { direct_equipment_cost: 100 })],
+    ),
+  ],
+};
+
+const costingRequest = {
+  assets: [
+    {
+      id: "capture",
+      discount_rate: 0.1,
+      capex_lang_factors: { contingency: 0.5 },
+      cost_items: [],
+    } as unknown as AssetParameters,
+  ],
+} as CostEstimateRequest;
+
+const assetMetadata = [
+  { assetId: "capture", name: "Capture", usingDefaults: [] },
+] as unknown as AssetMetadata[];
+
+function stored(withIntermediate = true): StoredEstimate {
+  return {
+    id: "baseline",
+    createdAt: "2026-01-01T00:00:00.000Z",
+    libraryId: "test",
+    libraryHash: "hash",
+    request: {} as StoredEstimate["request"],
+    result: transformCostingResponse(costingResponse, assetMetadata, "USD"),
+    costItemIds: { capture: ["Item 023"] },
+    ...(withIntermediate && {
+      intermediate: { costingRequest, costingResponse, assetMetadata },
+    }),
+  };
+}
+
+function whatIf(overrides: Parameters<typeof applyWhatIf>[2]) {
+  const run = applyWhatIf(stored(), library, overrides);
+  return transformCostingResponse(
+    run.costingResponse,
+    run.assetMetadata,
+    run.currency,
+  );
+}
+
+describe("what-if", () => {
+  it("reproduces the saved estimate without overrides", () => {
+    const result = whatIf({});
+    expect(result.lifetimeCosts.total).toBeCloseTo(531);
+    expect(result.lifetimeNpcCosts.total).toBeCloseTo(500);
+  });
+
+  it("rediscounts stored yearly costs at a new rate", () => {
+    expect(whatIf({ discountRate: 0 }).lifetimeNpcCosts.total).toBeCloseTo(
+      531,
+    );
+    expect(whatIf({ discountRate: 0.21 }).lifetimeNpcCosts.total).toBeCloseTo(
+      300 + 110 / 1.21 + 121 / 1.21 ** 2,
+    );
+    // Undiscounted costs don't change
+    expect(whatIf({ discountRate: 0.21 }).lifetimeCosts.total).toBeCloseTo(
+      531,
+    );
+  });
+
+  it("rediscounts nominal estimates at nominal rates", () => {
+    const priceBasis: PriceBasis = {
+      mode: "nominal",
+      escalationRate: 0.02,
+      priceBaseYear: 2025,
+      discountRates: "real",
+    };
+    const request = {
+      assets: [
+        {
+          ...costingRequest.assets[0],
+          timeline: { construction_start: 2025 },
+        } as unknown as AssetParameters,
+      ],
+    } as CostEstimateRequest;
+    const nominalResponse = applyNominalBasis(
+      costingResponse,
+      request,
+      0.02,
+      2025,
+    );
+    const estimate: StoredEstimate = {
+      ...stored(),
+      result: {
+        ...transformCostingResponse(nominalResponse, assetMetadata, "USD", {
+          priceBasis,
+        }),
+        priceBasis,
+      },
+      intermediate: {
+        costingRequest: request,
+        costingResponse: nominalResponse,
+        assetMetadata,
+      },
+    };
+    const nominalWhatIf = (discountRate?: number) => {
+      const run = applyWhatIf(estimate, library, { discountRate });
+      return transformCostingResponse(
+        run.costingResponse,
+        run.assetMetadata,
+        run.currency,
+        { priceBasis },
+      );
+    };
+
+    expect(nominalWhatIf().lifetimeNpcCosts.total).toBeCloseTo(
+      estimate.result.lifetimeNpcCosts.total,
+    );
+    // Nominal costs discounted at the nominal rate give the real NPC
+    expect(nominalWhatIf(0.21).lifetimeNpcCosts.total).toBeCloseTo(
+      whatIf({ discountRate: 0.21 }).lifetimeNpcCosts.total,
+    );
+    expect(nominalWhatIf(0.21).lifetimeCosts.total).toBeCloseTo(
+      300 + 110 * 1.02 + 121 * 1.02 ** 2,
+    );
+  });
+
+  it("discounts tonnes at the overridden rate", () => {
+    const estimate = stored();
+    estimate.intermediate!.assetMetadata = [
+      {
+        ...assetMetadata[0],
+        kpiInputs: {
+          assetType: "transport",
+          throughput: 1000,
+          lengthKm: 1,
+          timeline: {
+            construction_start: 2025,
+            construction_finish: 2025,
+            operation_start: 2026,
+            operation_finish: 2027,
+            decommissioning_start: 2028,
+            decommissioning_finish: 2028,
+          },
+          discountRate: 0.1,
+        },
+      } as AssetMetadata,
+    ];
+
+    const run = applyWhatIf(estimate, library, { discountRate: 0.21 });
+
+    expect(run.assetMetadata[0].kpiInputs?.discountRate).toBe(0.21);
+    const result = transformCostingResponse(
+      run.costingResponse,
+      run.assetMetadata,
+      run.currency,
+    );
+    expect(result.assets[0].unitTransportCost?.levelized).toBeCloseTo(
+      (300 + 110 / 1.21 + 121 / 1.21 ** 2) /
+        (1000 / 1.21 + 1000 / 1.21 ** 2),
+    );
+  });
+
+  it("rescales contingency and converts currency", () => {
+    const result = whatIf({ contingency: 1, targetCurrency: "GBP" });
+    expect(result.currency).toBe("GBP");
+    expect(
+      result.lifetimeCosts.langFactoredCapitalCost.contingency,
+    ).toBeCloseTo(80);
+    expect(result.lifetimeCosts.totalInstalledCost).toBeCloseTo(240);
+    expect(result.assets[0].blocks[0].directEquipmentCost).toBeCloseTo(80);
+
+    const run = applyWhatIf(stored(), library, { contingency: 1 });
+    expect(run.request.assets[0].capex_lang_factors.contingency).toBe(1);
+  });
+
+  it("converts currency at the rates of the estimate's scenario", () => {
+    const estimate = stored();
+    estimate.request = {
+      scenario: { exchangeRates: { "GBP/USD": 1.5 } },
+    } as StoredEstimate["request"];
+
+    const run = applyWhatIf(estimate, library, { targetCurrency: "GBP" });
+
+    expect(
+      transformCostingResponse(
+        run.costingResponse,
+        run.assetMetadata,
+        run.currency,
+      ).lifetimeCosts.total,
+    ).toBeCloseTo(531 / 1.5);
+  });
+
+  it("rejects overrides it can't apply without a re-run", () => {
+    expect(() => applyWhatIf(stored(false), library, {})).toThrow(
+      /saved without/,
+    );
+    expect(() =>
+      applyWhatIf(stored(), library, { targetCurrency: "EUR" }),
+    ).toThrow(WhatIfError);
+    const withoutContingency = stored();
+    withoutContingency.intermediate!.costingRequest = {
+      assets: [
+        {
+          ...costingRequest.assets[0],
+          capex_lang_factors: { contingency: 0 },
+        } as unknown as AssetParameters,
+      ],
+    };
+    expect(() =>
+      applyWhatIf(withoutContingency, library, { contingency: 0.2 }),
+    ).toThrow(/without contingency/);
+  });
+});
@@ -0,0 +1,324 @@
+/**
+ * What-if overlays on saved estimates.
+ *
+ * Changing the discount rate, the Lang contingency factor or the result
+ * currency doesn't change how cost curves evaluate, so these overrides are
+ * applied to the cost item results stored with a saved estimate instead of
+ * calling the costing server again. Only the aggregation and discounting
+ * stages are redone, which makes slider-style what-ifs near-instant.
+ *
+ * The number of years each cost was discounted by is recovered from the
+ * stored discounted and undiscounted costs, so the overlay follows whatever
+ * discounting convention the costing server used. Where that can't be
+ * recovered (a zero rate or a year without costs) years are counted from the
+ * asset's first year. Estimates on a nominal price basis store nominal
+ * costs discounted at nominal rates, so their years are recovered and
+ * rediscounted at the nominal rates matching the real ones.
+ */
+
+import type { AssetMetadata } from "./adapter";
+import type { EstimateIntermediate, StoredEstimate } from "./estimate-store";
+import { discountRateFor, REAL_PRICE_BASIS } from "./price-basis";
+import { resolveScenario, ScenarioError } from "./scenario";
+import type {
+  AssetCostEstimate,
+  AssetParameters,
+  CostEstimateRequest,
+  CostEstimateResponse,
+  CostLibrary,
+  LangFactoredCostEstimate,
+} from "./types";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export type WhatIfOverrides = {
+  /** Discount rate for every asset */
+  discountRate?: number;
+  /** Lang contingency factor for every asset */
+  contingency?: number;
+  /** Add Lang-factored contingency to totals (default: as saved) */
+  includeContingencyInTotals?: boolean;
+  /** Result currency (default: as saved) */
+  targetCurrency?: string;
+};
+
+/** A costing run rebuilt from stored results, ready to transform */
+export type WhatIfRun = {
+  request: CostEstimateRequest;
+  costingResponse: CostEstimateResponse;
+  assetMetadata: AssetMetadata[];
+  currency: string;
+};
+
+export class WhatIfError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "WhatIfError";
+  }
+}
+
+// ============================================================================
+// Cost arithmetic
+// ============================================================================
+
+// Costing server cost objects are trees of amounts (nulls for cost items
+// that don't report a figure), so they can be scaled and summed generically.
+
+function scaleCosts<T>(costs: T, factor: number): T {
+  if (typeof costs === "number") return (costs * factor) as T;
+  if (typeof costs !== "object" || costs === null) return costs;
+  return Object.fromEntries(
+    Object.entries(costs).map(([key, value]) => [
+      key,
+      scaleCosts(value, factor),
+    ]),
+  ) as T;
+}
+
+function addCosts<T>(a: T, b: T): T {
+  if (typeof a === "number" || typeof b === "number") {
+    return (((a as number | null) ?? 0) + ((b as number | null) ?? 0)) as T;
+  }
+  if (typeof a !== "object" || a === null) return b;
+  return Object.fromEntries(
+    Object.entries(a).map(([key, value]) => [
+      key,
+      addCosts(value, (b as Record<string, unknown>)[key]),
+    ]),
+  ) as T;
+}
+
+function sumLeaves(costs: unknown): number {
+  if (typeof costs === "number") return costs;
+  if (typeof costs !== "object" || costs === null) return 0;
+  return Object.values(costs).reduce<number>((s, v) => s + sumLeaves(v), 0);
+}
+
+/**
+ * Years each of an asset's yearly costs was discounted by, keyed by year.
+ */
+function discountYears(
+  asset: AssetCostEstimate,
+  rate: number,
+): Map<number, number> {
+  const first = asset.costs_by_year[0]?.year ?? 0;
+  return new Map(
+    asset.costs_by_year.map(({ year, costs_in_year, dcf_costs_in_year }) => {
+      const ratio = sumLeaves(costs_in_year) / sumLeaves(dcf_costs_in_year);
+      const years =
+        rate > 0 && Number.isFinite(ratio) && ratio > 0
+          ? Math.log(ratio) / Math.log(1 + rate)
+          : year - first;
+      return [year, years];
+    }),
+  );
+}
+
+// ============================================================================
+// Overlay
+// ============================================================================
+
+/**
+ * Currency rates the estimate was costed at: its scenario's, if it was
+ * costed under one, otherwise the library's.
+ */
+function estimateRates(
+  estimate: StoredEstimate,
+  library: CostLibrary,
+): Record<string, number> | undefined {
+  const scenario = estimate.request.scenario;
+  if (!scenario) return library.currency_conversion?.rates;
+  try {
+    return resolveScenario(scenario, library).rates;
+  } catch (error) {
+    if (error instanceof ScenarioError) throw new WhatIfError(error.message);
+    throw error;
+  }
+}
+
+function currencyFactor(
+  rates: Record<string, number> | undefined,
+  from: string,
+  to: string,
+): number {
+  if (from === to) return 1;
+  const fromRate = rates?.[from];
+  const toRate = rates?.[to];
+  if (fromRate === undefined || toRate === undefined) {
+    throw new WhatIfError(
+      `The estimate's library has no conversion from ${from} to ${to}`,
+    );
+  }
+  return fromRate / toRate;
+}
+
+function contingencyFactor(
+  parameters: AssetParameters | undefined,
+  contingency: number | undefined,
+): number {
+  const saved = parameters?.capex_lang_factors.contingency;
+  if (contingency === undefined || saved === contingency) return 1;
+  if (!saved) {
+    throw new WhatIfError(
+      `Asset ${parameters?.id ?? "(unknown)"} was costed without contingency; ` +
+        "re-run the estimate to add it",
+    );
+  }
+  return contingency / saved;
+}
+
+/**
+ * Apply a scale to an asset's contingency, a currency factor to all its
+ * amounts, and rediscount its yearly costs at `rate`.
+ */
+function overlayAsset(
+  asset: AssetCostEstimate,
+  savedRate: number,
+  rate: number,
+  contingencyScale: number,
+  currencyScale: number,
+): AssetCostEstimate {
+  const years = discountYears(asset, savedRate);
+  const firstYear = asset.costs_by_year[0]?.year ?? 0;
+  const discount = (year: number) =>
+    Math.pow(1 + rate, -(years.get(year) ?? year - firstYear));
+  const withContingency = <
+    T extends { lang_factored_capital_cost: LangFactoredCostEstimate },
+  >(
+    costs: T,
+  ) => {
+    const scaled = scaleCosts(costs, currencyScale);
+    return {
+      ...scaled,
+      lang_factored_capital_cost: {
+        ...scaled.lang_factored_capital_cost,
+        contingency:
+          scaled.lang_factored_capital_cost.contingency * contingencyScale,
+      },
+    };
+  };
+
+  const costsByYear = asset.costs_by_year.map((y) => {
+    const costs = withContingency(y.costs_in_year);
+    return {
+      year: y.year,
+      costs_in_year: costs,
+      dcf_costs_in_year: scaleCosts(costs, discount(y.year)),
+    };
+  });
+
+  return {
+    id: asset.id,
+    costs: withContingency(asset.costs),
+    costs_by_year: costsByYear,
+    lifetime_costs: withContingency(asset.lifetime_costs),
+    lifetime_dcf_costs:
+      costsByYear.length > 0
+        ? costsByYear.map((y) => y.dcf_costs_in_year).reduce(addCosts)
+        : scaleCosts(asset.lifetime_dcf_costs, currencyScale),
+    cost_items: asset.cost_items.map((item) => {
+      const itemYears = item.costs_by_year.map((y) => {
+        const costs = scaleCosts(y.costs_in_year, currencyScale);
+        return {
+          year: y.year,
+          costs_in_year: costs,
+          dcf_costs_in_year: scaleCosts(costs, discount(y.year)),
+        };
+      });
+      return {
+        ...item,
+        costs: scaleCosts(item.costs, currencyScale),
+        costs_by_year: itemYears,
+        lifetime_costs: scaleCosts(item.lifetime_costs, currencyScale),
+        lifetime_dcf_costs:
+          itemYears.length > 0
+            ? itemYears.map((y) => y.dcf_costs_in_year).reduce(addCosts)
+            : scaleCosts(item.lifetime_dcf_costs, currencyScale),
+      };
+    }),
+  };
+}
+
+/**
+ * Rebuild a saved estimate's costing run with factor-level overrides
+ * applied to its stored cost item results.
+ *
+ * @param library - The library snapshot the estimate was computed with,
+ *   used for currency rates (under the estimate's scenario, if any)
+ * @throws WhatIfError if the estimate has no stored results, a currency
+ *   can't be converted, or an override needs a full re-run
+ */
+export function applyWhatIf(
+  estimate: StoredEstimate,
+  library: CostLibrary,
+  overrides: WhatIfOverrides,
+): WhatIfRun {
+  const intermediate: EstimateIntermediate | undefined = estimate.intermediate;
+  if (!intermediate) {
+    throw new WhatIfError(
+      `Estimate ${estimate.id} was saved without its cost item results; ` +
+        "save it again to use what-ifs",
+    );
+  }
+
+  const currency = overrides.targetCurrency ?? estimate.result.currency;
+  const currencyScale = currencyFactor(
+    estimateRates(estimate, library),
+    estimate.result.currency,
+    currency,
+  );
+  const parameters = new Map(
+    intermediate.costingRequest.assets.map((a) => [a.id, a]),
+  );
+  const basis = estimate.result.priceBasis ?? REAL_PRICE_BASIS;
+
+  const assets = intermediate.costingResponse.assets.map((asset) => {
+    const params = parameters.get(asset.id);
+    const savedRate = params?.discount_rate ?? 0;
+    return overlayAsset(
+      asset,
+      discountRateFor(basis, savedRate),
+      discountRateFor(basis, overrides.discountRate ?? savedRate),
+      contingencyFactor(params, overrides.contingency),
+      currencyScale,
+    );
+  });
+
+  // Report the overridden factors as the ones the assets were costed with
+  const withFactors = (a: AssetParameters): AssetParameters => ({
+    ...a,
+    discount_rate: overrides.discountRate ?? a.discount_rate,
+    capex_lang_factors: {
+      ...a.capex_lang_factors,
+      contingency: overrides.contingency ?? a.capex_lang_factors.contingency,
+    },
+  });
+  const assetMetadata = intermediate.assetMetadata.map((m) => ({
+    ...m,
+    // Tonnes and carbon value are discounted at the asset's rate too
+    ...(m.kpiInputs &&
+      overrides.discountRate !== undefined && {
+        kpiInputs: { ...m.kpiInputs, discountRate: overrides.discountRate },
+      }),
+    ...(m.provenance && {
+      provenance: {
+        ...m.provenance,
+        capexLangFactors: {
+          ...m.provenance.capexLangFactors,
+          contingency:
+            overrides.contingency ??
+            m.provenance.capexLangFactors.contingency,
+        },
+      },
+    }),
+  }));
+
+  return {
+    request: { assets: intermediate.costingRequest.assets.map(withFactors) },
+    costingResponse: { assets },
+    assetMetadata,
+    currency,
+  };
+}
@@ -6,6 +6,7 @@ import {
   listBlockTypes,
 } from "./effectSchemas";
 import { getDagger } from "../utils/getDagger";
+import { readTextFile } from "../utils/text-file";
 
 function resolvePath(relativePath: string): string {
   return path.resolve(process.cwd(), relativePath);
@@ -24,7 +25,7 @@ async function readNetworkFiles(networkPath: string): Promise<{
   for (const entry of entries) {
     if (entry.isFile() && entry.name.endsWith(".toml")) {
       const filePath = path.join(absolutePath, entry.name);
-      const content = await fs.readFile(filePath, "utf-8");
+      const content = await readTextFile(filePath);
       files[entry.name] = content;
 
       if (entry.name === "config.toml") {
@@ -15,6 +15,7 @@ import { parseValue, convertToNumber } from "./valueParser";
 import { getDagger } from "../utils/getDagger";
 import { resolveNetworkPath } from "../utils/network-path";
 import { networkDataToTomlFiles } from "./networkToToml";
+import { readTextFile } from "../utils/text-file";
 
 type NetworkFiles = {
   files: Record<string, string>;
@@ -31,7 +32,7 @@ async function readNetworkFiles(networkPath: string): Promise<NetworkFiles> {
   for (const entry of entries) {
     if (entry.isFile() && entry.name.endsWith(".toml")) {
       const filePath = path.join(absolutePath, entry.name);
-      const content = await fs.readFile(filePath, "utf-8");
+      const content = await readTextFile(filePath);
       files[entry.name] = content;
 
       if (entry.name === "config.toml") {
@@ -1,6 +1,7 @@
 import * as path from "path";
 import * as fs from "fs/promises";
 import { getDagger } from "../utils/getDagger";
+import { readTextFile } from "../utils/text-file";
 
 function resolvePath(relativePath: string): string {
   // If path is already absolute, use it as-is
@@ -26,7 +27,7 @@ async function readNetworkFiles(networkPath: string): Promise<{
   for (const entry of entries) {
     if (entry.isFile() && entry.name.endsWith(".toml")) {
       const filePath = path.join(absolutePath, entry.name);
-      const content = await fs.readFile(filePath, "utf-8");
+      const content = await readTextFile(filePath);
 
       if (entry.name === "config.toml") {
         configContent = content;
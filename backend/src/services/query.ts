@@ -4,6 +4,7 @@ import dim from "./dim";
 import { formatQueryResult } from "./unitFormatter";
 import { getBlockSchemaProperties } from "./effectSchemaProperties";
 import { getDagger } from "../utils/getDagger";
+import { readTextFile } from "../utils/text-file";
 
 function resolvePath(relativePath: string): string {
   // If path is already absolute, use it as-is
@@ -32,7 +33,7 @@ async function readNetworkFiles(networkPath: string): Promise<{
   for (const entry of entries) {
     if (entry.isFile() && entry.name.endsWith(".toml")) {
       const filePath = path.join(absolutePath, entry.name);
-      const content = await fs.readFile(filePath, "utf-8");
+      const content = await readTextFile(filePath);
 
       if (entry.name === "config.toml") {
         configContent = content;
@@ -16,6 +16,7 @@ import {
 import dim from "../dim";
 import { getDagger } from "../../utils/getDagger";
 import { Conditions, UnitValue } from "./types";
+import { readTextFile } from "../../utils/text-file";
 
 // ============================================================================
 // Types
@@ -297,7 +298,7 @@ async function readNetworkFiles(networkPath: string): Promise<NetworkFiles> {
   for (const entry of entries) {
     if (entry.isFile() && entry.name.endsWith(".toml")) {
       const filePath = path.join(absolutePath, entry.name);
-      const content = await fs.readFile(filePath, "utf-8");
+      const content = await readTextFile(filePath);
       files[entry.name] = content;
 
       if (entry.name === "config.toml") {
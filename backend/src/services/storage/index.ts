@@ -0,0 +1,15 @@
+/**
+ * Pluggable persistence for estimates, drafts, history and the audit log.
+ */
+
+export {
+  getStorage,
+  storageBackendFromEnv,
+  FileStorage,
+  MemoryStorage,
+  SqliteStorage,
+  StorageConflictError,
+  STORAGE_BACKENDS,
+  type Storage,
+  type StorageBackend,
+} from "./storage";
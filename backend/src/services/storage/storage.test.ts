@@ -0,0 +1,135 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import { existsSync } from "fs";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { createRequire } from "module";
+import {
+  FileStorage,
+  MemoryStorage,
+  SqliteStorage,
+  StorageConflictError,
+  storageBackendFromEnv,
+  type Storage,
+} from "./storage";
+
+// node:sqlite arrived in Node.js 22.13
+const hasSqlite = (() => {
+  try {
+    createRequire(import.meta.url)("node:sqlite");
+    return true;
+  } catch {
+    return false;
+  }
+})();
+
+const backends: Array<[string, () => Storage]> = [
+  ["file", () => new FileStorage()],
+  ...(hasSqlite
+    ? [["sqlite", () => new SqliteStorage()] as [string, () => Storage]]
+    : []),
+  ["memory", () => new MemoryStorage()],
+];
+
+describe.each(backends)("%s storage", (backend, create) => {
+  let dataRoot: string;
+  let storage: Storage;
+
+  beforeEach(async () => {
+    dataRoot = await mkdtemp(join(tmpdir(), "storage-"));
+    vi.spyOn(process, "cwd").mockReturnValue(dataRoot);
+    storage = create();
+  });
+
+  afterEach(async () => {
+    vi.restoreAllMocks();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("stores, lists and deletes records", async () => {
+    expect(await storage.get("drafts", "V1.3")).toBeUndefined();
+    expect(await storage.keys("drafts")).toEqual([]);
+
+    await storage.put("drafts", "V1.3", { modules: [] });
+    await storage.put("history/V1.3", "1", { version: 1 });
+
+    expect(await storage.get("drafts", "V1.3")).toEqual({ modules: [] });
+    expect(await storage.keys("drafts")).toEqual(["V1.3"]);
+    expect(await storage.keys("history/V1.3")).toEqual(["1"]);
+
+    expect(await storage.delete("drafts", "V1.3")).toBe(true);
+    expect(await storage.delete("drafts", "V1.3")).toBe(false);
+    expect(await storage.get("drafts", "V1.3")).toBeUndefined();
+  });
+
+  it("refuses to replace a record created with create", async () => {
+    await storage.put("estimates", "e1", { n: 1 }, { create: true });
+
+    await expect(
+      storage.put("estimates", "e1", { n: 2 }, { create: true }),
+    ).rejects.toBeInstanceOf(StorageConflictError);
+    await storage.put("estimates", "e1", { n: 3 });
+    expect(await storage.get("estimates", "e1")).toEqual({ n: 3 });
+  });
+
+  it("appends to logs in order", async () => {
+    expect(await storage.readLog("audit/log")).toEqual([]);
+
+    await Promise.all([1, 2, 3].map((n) => storage.append("audit/log", { n })));
+
+    expect(await storage.readLog("audit/log")).toEqual([
+      { n: 1 },
+      { n: 2 },
+      { n: 3 },
+    ]);
+  });
+
+  it("rejects names that could escape the data directory", async () => {
+    await expect(storage.get("drafts", "../x")).rejects.toThrow(
+      "Invalid storage key",
+    );
+    await expect(storage.keys("drafts/..")).rejects.toThrow(
+      "Invalid storage collection",
+    );
+  });
+
+  if (backend === "file") {
+    it("keeps the data directory layout", async () => {
+      await storage.put("costing-drafts", "V1.3", { modules: [] });
+      await storage.append("audit/audit-log", { n: 1 });
+
+      const read = (path: string) =>
+        readFile(join(dataRoot, "data", path), "utf-8");
+      expect(await read("costing-drafts/V1.3.json")).toBe(
+        '{"modules":[]}\n',
+      );
+      expect(await read("audit/audit-log.jsonl")).toBe('{"n":1}\n');
+    });
+  }
+
+  if (backend === "sqlite") {
+    it("keeps everything in one database in the data directory", async () => {
+      await storage.put("costing-drafts", "V1.3", { modules: [] });
+      await storage.append("audit/audit-log", { n: 1 });
+
+      const reopened = new SqliteStorage();
+      expect(existsSync(join(dataRoot, "data", "storage.sqlite"))).toBe(true);
+      expect(await reopened.get("costing-drafts", "V1.3")).toEqual({
+        modules: [],
+      });
+      expect(await reopened.readLog("audit/audit-log")).toEqual([{ n: 1 }]);
+    });
+  }
+});
+
+describe("storageBackendFromEnv", () => {
+  it("defaults to file and rejects unknown backends", () => {
+    expect(storageBackendFromEnv({})).toBe("file");
+    expect(storageBackendFromEnv({ STORAGE_BACKEND: "memory" })).toBe(
+      "memory",
+    );
+    expect(() =>
+      storageBackendFromEnv({ STORAGE_BACKEND: "postgres" }),
+    ).toThrow("STORAGE_BACKEND must be one of file, sqlite, memory");
+  });
+});
@@ -0,0 +1,459 @@
+/**
+ * Persistence for saved estimates, library drafts, version history and the
+ * audit log.
+ *
+ * Services store JSON records by collection and key, and append entries to
+ * logs, through the Storage interface rather than the file system, so the
+ * backend can be chosen per deployment with STORAGE_BACKEND:
+ *
+ * - `file` (default): one JSON file per record and one JSON-lines file per
+ *   log, under the data directory
+ * - `sqlite`: one SQLite database, `storage.sqlite` in the data directory,
+ *   for desktop and single-server deployments that would rather back up
+ *   one file. Uses Node's built-in node:sqlite, so needs Node.js 22.13 or
+ *   later
+ * - `memory`: kept in the process and lost on restart, for tests and
+ *   throwaway servers
+ *
+ * Collections are `/`-separated names such as `costing-history/V1.3`.
+ */
+
+import { appendFile, mkdir, readFile, readdir, rm, writeFile } from "fs/promises";
+import { mkdirSync } from "fs";
+import { createRequire } from "module";
+import { dirname, join } from "path";
+import type { DatabaseSync } from "node:sqlite";
+import { getDataDir } from "../../utils/data-dir";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+export const STORAGE_BACKENDS = ["file", "sqlite", "memory"] as const;
+
+export type StorageBackend = (typeof STORAGE_BACKENDS)[number];
+
+export interface Storage {
+  readonly backend: StorageBackend;
+  /** A record, or undefined if there is none */
+  get<T>(collection: string, key: string): Promise<T | undefined>;
+  /**
+   * Write a record. With `create`, throws StorageConflictError instead of
+   * replacing an existing record.
+   */
+  put(
+    collection: string,
+    key: string,
+    value: unknown,
+    options?: { create?: boolean },
+  ): Promise<void>;
+  /** Remove a record; false if there was none */
+  delete(collection: string, key: string): Promise<boolean>;
+  /** Keys of the records in a collection, in no particular order */
+  keys(collection: string): Promise<string[]>;
+  /** Append an entry to a log */
+  append(log: string, entry: unknown): Promise<void>;
+  /** A log's entries, oldest first */
+  readLog<T>(log: string): Promise<T[]>;
+}
+
+/**
+ * Raised by `put` with `create` when the record already exists.
+ */
+export class StorageConflictError extends Error {
+  constructor(
+    readonly collection: string,
+    readonly key: string,
+  ) {
+    super(`${collection}/${key} already exists`);
+    this.name = "StorageConflictError";
+  }
+}
+
+// ============================================================================
+// Names
+// ============================================================================
+
+/**
+ * Collection names and keys become file names, so reject anything that could
+ * step outside the data directory.
+ */
+function isSafeName(segment: string): boolean {
+  return /^[\w.-]+$/.test(segment) && !/^\.+$/.test(segment);
+}
+
+function assertKey(key: string): void {
+  if (!isSafeName(key)) throw new Error(`Invalid storage key ${key}`);
+}
+
+/** Collection and log names may have several `/`-separated segments */
+function assertCollection(name: string): void {
+  if (!name.split("/").every(isSafeName)) {
+    throw new Error(`Invalid storage collection ${name}`);
+  }
+}
+
+// ============================================================================
+// File backend
+// ============================================================================
+
+function isErrno(error: unknown, code: string): boolean {
+  return (error as NodeJS.ErrnoException)?.code === code;
+}
+
+/**
+ * Records as `<dataDir>/<collection>/<key>.json`, logs as
+ * `<dataDir>/<log>.jsonl`. The data directory is resolved on every call.
+ */
+export class FileStorage implements Storage {
+  readonly backend = "file";
+  // Appends are chained so concurrent requests can't interleave lines
+  private pendingAppend: Promise<void> = Promise.resolve();
+
+  private recordPath(collection: string, key: string): string {
+    assertCollection(collection);
+    assertKey(key);
+    return join(getDataDir(), collection, `${key}.json`);
+  }
+
+  private logPath(log: string): string {
+    assertCollection(log);
+    return join(getDataDir(), `${log}.jsonl`);
+  }
+
+  async get<T>(collection: string, key: string): Promise<T | undefined> {
+    try {
+      const path = this.recordPath(collection, key);
+      return JSON.parse(await readFile(path, "utf-8"));
+    } catch (error) {
+      if (isErrno(error, "ENOENT")) return undefined;
+      throw error;
+    }
+  }
+
+  async put(
+    collection: string,
+    key: string,
+    value: unknown,
+    options: { create?: boolean } = {},
+  ): Promise<void> {
+    const path = this.recordPath(collection, key);
+    await mkdir(dirname(path), { recursive: true });
+    try {
+      // "wx" fails rather than overwrite an existing record
+      await writeFile(path, JSON.stringify(value) + "\n", {
+        flag: options.create ? "wx" : "w",
+      });
+    } catch (error) {
+      if (isErrno(error, "EEXIST")) {
+        throw new StorageConflictError(collection, key);
+      }
+      throw error;
+    }
+  }
+
+  async delete(collection: string, key: string): Promise<boolean> {
+    try {
+      await rm(this.recordPath(collection, key));
+      return true;
+    } catch (error) {
+      if (isErrno(error, "ENOENT")) return false;
+      throw error;
+    }
+  }
+
+  async keys(collection: string): Promise<string[]> {
+    assertCollection(collection);
+    let files: string[];
+    try {
+      files = await readdir(join(getDataDir(), collection));
+    } catch (error) {
+      if (isErrno(error, "ENOENT")) return [];
+      throw error;
+    }
+    return files
+      .filter((f) => f.endsWith(".json"))
+      .map((f) => f.slice(0, -".json".length));
+  }
+
+  append(log: string, entry: unknown): Promise<void> {
+    const path = this.logPath(log);
+    const write = this.pendingAppend.then(async () => {
+      await mkdir(dirname(path), { recursive: true });
+      await appendFile(path, JSON.stringify(entry) + "\n", { flag: "a" });
+    });
+    this.pendingAppend = write.catch(() => undefined);
+    return write;
+  }
+
+  async readLog<T>(log: string): Promise<T[]> {
+    let content: string;
+    try {
+      content = await readFile(this.logPath(log), "utf-8");
+    } catch (error) {
+      if (isErrno(error, "ENOENT")) return [];
+      throw error;
+    }
+    return content
+      .split("\n")
+      .filter((line) => line.trim().length > 0)
+      .map((line) => JSON.parse(line) as T);
+  }
+}
+
+// ============================================================================
+// Memory backend
+// ============================================================================
+
+/**
+ * Records and logs held in the process. Values are stored serialized, so
+ * callers never share objects with the store.
+ */
+export class MemoryStorage implements Storage {
+  readonly backend = "memory";
+  private records = new Map<string, Map<string, string>>();
+  private logs = new Map<string, string[]>();
+
+  private collection(name: string): Map<string, string> {
+    assertCollection(name);
+    let collection = this.records.get(name);
+    if (!collection) {
+      collection = new Map();
+      this.records.set(name, collection);
+    }
+    return collection;
+  }
+
+  async get<T>(collection: string, key: string): Promise<T | undefined> {
+    assertKey(key);
+    const json = this.collection(collection).get(key);
+    return json === undefined ? undefined : JSON.parse(json);
+  }
+
+  async put(
+    collection: string,
+    key: string,
+    value: unknown,
+    options: { create?: boolean } = {},
+  ): Promise<void> {
+    assertKey(key);
+    const records = this.collection(collection);
+    if (options.create && records.has(key)) {
+      throw new StorageConflictError(collection, key);
+    }
+    records.set(key, JSON.stringify(value));
+  }
+
+  async delete(collection: string, key: string): Promise<boolean> {
+    assertKey(key);
+    return this.collection(collection).delete(key);
+  }
+
+  async keys(collection: string): Promise<string[]> {
+    return Array.from(this.collection(collection).keys());
+  }
+
+  async append(log: string, entry: unknown): Promise<void> {
+    assertCollection(log);
+    const entries = this.logs.get(log) ?? [];
+    entries.push(JSON.stringify(entry));
+    this.logs.set(log, entries);
+  }
+
+  async readLog<T>(log: string): Promise<T[]> {
+    assertCollection(log);
+    return (this.logs.get(log) ?? []).map((json) => JSON.parse(json) as T);
+  }
+}
+
+// ============================================================================
+// SQLite backend
+// ============================================================================
+
+/**
+ * Schema changes in order. PRAGMA user_version records how many have been
+ * applied, so a database is brought up to date when it is opened.
+ */
+const SQLITE_MIGRATIONS = [
+  `CREATE TABLE records (
+     collection TEXT NOT NULL,
+     key TEXT NOT NULL,
+     value TEXT NOT NULL,
+     PRIMARY KEY (collection, key)
+   );
+   CREATE TABLE log_entries (
+     id INTEGER PRIMARY KEY AUTOINCREMENT,
+     log TEXT NOT NULL,
+     entry TEXT NOT NULL
+   );
+   CREATE INDEX log_entries_by_log ON log_entries (log, id);`,
+];
+
+// Loaded on demand so the other backends still run on Node.js releases
+// without node:sqlite
+const requireBuiltin = createRequire(import.meta.url);
+
+function loadSqlite(): typeof import("node:sqlite") | undefined {
+  try {
+    return requireBuiltin("node:sqlite");
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Records and logs in `<dataDir>/storage.sqlite`, which is created and
+ * migrated on first use. The data directory is resolved on every call, and
+ * each database stays open for the life of the process. Statements run
+ * synchronously, which keeps every write atomic without further locking.
+ */
+export class SqliteStorage implements Storage {
+  readonly backend = "sqlite";
+  private databases = new Map<string, DatabaseSync>();
+
+  private database(): DatabaseSync {
+    const path = join(getDataDir(), "storage.sqlite");
+    let database = this.databases.get(path);
+    if (!database) {
+      const sqlite = loadSqlite();
+      if (!sqlite) {
+        throw new Error("The sqlite storage backend needs Node.js 22.13+");
+      }
+      mkdirSync(dirname(path), { recursive: true });
+      database = new sqlite.DatabaseSync(path);
+      database.exec("PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;");
+      migrate(database);
+      this.databases.set(path, database);
+    }
+    return database;
+  }
+
+  async get<T>(collection: string, key: string): Promise<T | undefined> {
+    assertCollection(collection);
+    assertKey(key);
+    const row = this.database()
+      .prepare("SELECT value FROM records WHERE collection = ? AND key = ?")
+      .get(collection, key);
+    return row === undefined ? undefined : JSON.parse(String(row.value));
+  }
+
+  async put(
+    collection: string,
+    key: string,
+    value: unknown,
+    options: { create?: boolean } = {},
+  ): Promise<void> {
+    assertCollection(collection);
+    assertKey(key);
+    const onConflict = options.create
+      ? "DO NOTHING"
+      : "DO UPDATE SET value = excluded.value";
+    const { changes } = this.database()
+      .prepare(
+        "INSERT INTO records (collection, key, value) VALUES (?, ?, ?) " +
+          `ON CONFLICT (collection, key) ${onConflict}`,
+      )
+      .run(collection, key, JSON.stringify(value));
+    if (Number(changes) === 0) {
+      throw new StorageConflictError(collection, key);
+    }
+  }
+
+  async delete(collection: string, key: string): Promise<boolean> {
+    assertCollection(collection);
+    assertKey(key);
+    const { changes } = this.database()
+      .prepare("DELETE FROM records WHERE collection = ? AND key = ?")
+      .run(collection, key);
+    return Number(changes) > 0;
+  }
+
+  async keys(collection: string): Promise<string[]> {
+    assertCollection(collection);
+    return this.database()
+      .prepare("SELECT key FROM records WHERE collection = ?")
+      .all(collection)
+      .map((row) => String(row.key));
+  }
+
+  async append(log: string, entry: unknown): Promise<void> {
+    assertCollection(log);
+    this.database()
+      .prepare("INSERT INTO log_entries (log, entry) VALUES (?, ?)")
+      .run(log, JSON.stringify(entry));
+  }
+
+  async readLog<T>(log: string): Promise<T[]> {
+    assertCollection(log);
+    return this.database()
+      .prepare("SELECT entry FROM log_entries WHERE log = ? ORDER BY id")
+      .all(log)
+      .map((row) => JSON.parse(String(row.entry)) as T);
+  }
+}
+
+/**
+ * Apply the migrations a database hasn't had yet, each in a transaction.
+ */
+function migrate(database: DatabaseSync): void {
+  const applied = Number(
+    database.prepare("PRAGMA user_version").get()?.user_version ?? 0,
+  );
+  SQLITE_MIGRATIONS.slice(applied).forEach((migration, i) => {
+    database.exec("BEGIN");
+    try {
+      database.exec(migration);
+      database.exec(`PRAGMA user_version = ${applied + i + 1}`);
+      database.exec("COMMIT");
+    } catch (error) {
+      database.exec("ROLLBACK");
+      throw error;
+    }
+  });
+}
+
+// ============================================================================
+// Selection
+// ============================================================================
+
+/**
+ * The backend named by STORAGE_BACKEND (default: file).
+ *
+ * @throws Error for an unknown backend, or sqlite on a Node.js release
+ *   without node:sqlite
+ */
+export function storageBackendFromEnv(
+  env: Record<string, string | undefined>,
+): StorageBackend {
+  const backend = env.STORAGE_BACKEND || "file";
+  if (!(STORAGE_BACKENDS as readonly string[]).includes(backend)) {
+    throw new Error(
+      `STORAGE_BACKEND must be one of ${STORAGE_BACKENDS.join(", ")}`,
+    );
+  }
+  if (backend === "sqlite" && !loadSqlite()) {
+    throw new Error("STORAGE_BACKEND sqlite needs Node.js 22.13 or later");
+  }
+  return backend as StorageBackend;
+}
+
+const instances = new Map<StorageBackend, Storage>();
+
+/**
+ * The configured storage. One instance per backend is kept for the life of
+ * the process.
+ */
+export function getStorage(): Storage {
+  const backend = storageBackendFromEnv(process.env);
+  let storage = instances.get(backend);
+  if (!storage) {
+    storage =
+      backend === "memory"
+        ? new MemoryStorage()
+        : backend === "sqlite"
+          ? new SqliteStorage()
+          : new FileStorage();
+    instances.set(backend, storage);
+  }
+  return storage;
+}
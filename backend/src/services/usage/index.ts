@@ -0,0 +1,28 @@
+/**
+ * Per-caller usage accounting and quotas.
+ */
+
+export {
+  USAGE_METRICS,
+  usagePeriod,
+  usagePeriodEnd,
+  recordUsage,
+  getUsage,
+  listUsage,
+  flushUsage,
+  clearUsageCache,
+  type UsageMetric,
+  type UsageCounters,
+} from "./usage";
+
+export {
+  parseQuotas,
+  quotaPolicyFor,
+  evaluateQuotas,
+  type Quota,
+  type QuotaPolicy,
+  type QuotaConfig,
+  type QuotaStatus,
+} from "./quotas";
+
+export { meterUsage, type MeterOptions } from "./middleware";
@@ -0,0 +1,81 @@
+/**
+ * Usage metering middleware.
+ *
+ * Counts each request against the caller, rejects callers over a hard
+ * quota with 429, and reports the request quota in `X-RateLimit-*` headers.
+ * Register after `authenticate`.
+ */
+
+import type { MiddlewareHandler } from "hono";
+import {
+  evaluateQuotas,
+  parseQuotas,
+  quotaPolicyFor,
+  type QuotaConfig,
+} from "./quotas";
+import { getUsage, recordUsage, usagePeriodEnd } from "./usage";
+
+export type MeterOptions = {
+  quotas?: QuotaConfig;
+};
+
+export function meterUsage(
+  options: MeterOptions = { quotas: parseQuotas(process.env.QUOTAS) },
+): MiddlewareHandler {
+  const quotas = options.quotas ?? {};
+
+  return async (c, next) => {
+    const principal = c.get("principal");
+    if (!principal) return next();
+
+    const policy = quotaPolicyFor(quotas, principal.id);
+    const resetSeconds = Math.ceil(
+      (usagePeriodEnd().getTime() - Date.now()) / 1000,
+    );
+
+    const exceeded = evaluateQuotas(getUsage(principal.id), policy).filter(
+      (status) => status.hardExceeded,
+    );
+    if (exceeded.length > 0) {
+      c.header("Retry-After", String(resetSeconds));
+      return c.json(
+        {
+          error: "Quota exceeded",
+          message: `${principal.id} has reached its ${exceeded
+            .map((s) => s.metric)
+            .join(", ")} quota for this month`,
+          quotas: exceeded,
+        },
+        429,
+      );
+    }
+
+    const cpuStart = process.cpuUsage();
+    await next();
+    const cpu = process.cpuUsage(cpuStart);
+
+    const usage = recordUsage(principal.id, {
+      requests: 1,
+      cpuSeconds: (cpu.user + cpu.system) / 1e6,
+    });
+    const statuses = evaluateQuotas(usage, policy);
+
+    const requests = statuses.find((s) => s.metric === "requests");
+    const limit = requests?.hard ?? requests?.soft;
+    if (requests && limit !== undefined) {
+      c.header("X-RateLimit-Limit", String(limit));
+      c.header(
+        "X-RateLimit-Remaining",
+        String(Math.max(0, limit - requests.used)),
+      );
+      c.header("X-RateLimit-Reset", String(resetSeconds));
+    }
+    const warnings = statuses.filter((s) => s.softExceeded);
+    if (warnings.length > 0) {
+      c.header(
+        "X-Quota-Warning",
+        warnings.map((s) => `${s.metric} ${s.used}/${s.soft}`).join(", "),
+      );
+    }
+  };
+}
@@ -0,0 +1,96 @@
+/**
+ * Usage quotas.
+ *
+ * Configured with the QUOTAS environment variable as JSON mapping principal
+ * IDs to per-metric limits, with "default" applying to everyone else:
+ *
+ *   {"default": {"requests": {"soft": 5000, "hard": 10000}},
+ *    "ci": {"assets": {"hard": 200000}}}
+ *
+ * Exceeding a soft limit adds a warning header; reaching a hard limit
+ * rejects requests until the period ends. Limits are per calendar month.
+ */
+
+import { USAGE_METRICS, type UsageCounters, type UsageMetric } from "./usage";
+
+export type Quota = { soft?: number; hard?: number };
+
+export type QuotaPolicy = Partial<Record<UsageMetric, Quota>>;
+
+export type QuotaConfig = Record<string, QuotaPolicy>;
+
+export type QuotaStatus = {
+  metric: UsageMetric;
+  used: number;
+  soft?: number;
+  hard?: number;
+  softExceeded: boolean;
+  hardExceeded: boolean;
+};
+
+/**
+ * Parse a QUOTAS value. Throws on malformed JSON, unknown metrics or
+ * non-positive limits.
+ */
+export function parseQuotas(value: string | undefined): QuotaConfig {
+  if (!value?.trim()) return {};
+
+  const parsed: unknown = JSON.parse(value);
+  if (typeof parsed !== "object" || parsed === null || Array.isArray(parsed)) {
+    throw new Error("QUOTAS must be a JSON object of principal IDs");
+  }
+
+  for (const [principal, policy] of Object.entries(parsed)) {
+    if (typeof policy !== "object" || policy === null) {
+      throw new Error(`Quota for ${principal} must be an object`);
+    }
+    for (const [metric, quota] of Object.entries(policy)) {
+      if (!(USAGE_METRICS as readonly string[]).includes(metric)) {
+        throw new Error(
+          `Quota for ${principal} has unknown metric "${metric}". Valid metrics: ${USAGE_METRICS.join(", ")}`,
+        );
+      }
+      for (const limit of ["soft", "hard"] as const) {
+        const n = (quota as Quota)?.[limit];
+        if (n !== undefined && !(typeof n === "number" && n > 0)) {
+          throw new Error(
+            `${limit} ${metric} quota for ${principal} must be a positive number`,
+          );
+        }
+      }
+    }
+  }
+  return parsed as QuotaConfig;
+}
+
+/**
+ * The limits that apply to a principal: its own, falling back per metric to
+ * the default policy.
+ */
+export function quotaPolicyFor(
+  config: QuotaConfig,
+  principalId: string,
+): QuotaPolicy {
+  return { ...config.default, ...config[principalId] };
+}
+
+/**
+ * Compare usage against each limited metric.
+ */
+export function evaluateQuotas(
+  usage: UsageCounters,
+  policy: QuotaPolicy,
+): QuotaStatus[] {
+  return USAGE_METRICS.filter((metric) => policy[metric]).map((metric) => {
+    const { soft, hard } = policy[metric]!;
+    const used = usage[metric];
+    return {
+      metric,
+      used,
+      ...(soft !== undefined && { soft }),
+      ...(hard !== undefined && { hard }),
+      softExceeded: soft !== undefined && used > soft,
+      hardExceeded: hard !== undefined && used >= hard,
+    };
+  });
+}
@@ -0,0 +1,123 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import { mkdtemp, readFile, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { Hono } from "hono";
+import type { Principal } from "../auth";
+import { meterUsage } from "./middleware";
+import { evaluateQuotas, parseQuotas, quotaPolicyFor } from "./quotas";
+import {
+  clearUsageCache,
+  flushUsage,
+  getUsage,
+  recordUsage,
+  usagePeriod,
+  usagePeriodEnd,
+} from "./usage";
+
+describe("usage", () => {
+  let dataRoot: string;
+
+  beforeEach(async () => {
+    dataRoot = await mkdtemp(join(tmpdir(), "usage-"));
+    vi.spyOn(process, "cwd").mockReturnValue(dataRoot);
+    clearUsageCache();
+  });
+
+  afterEach(async () => {
+    clearUsageCache();
+    vi.restoreAllMocks();
+    await rm(dataRoot, { recursive: true, force: true });
+  });
+
+  it("counts usage per principal per month", async () => {
+    const march = new Date("2026-03-31T23:00:00Z");
+    recordUsage("ci", { requests: 1, assets: 12 }, march);
+    recordUsage("ci", { requests: 1, cpuSeconds: 0.5 }, march);
+    recordUsage("ci", { requests: 1 }, new Date("2026-04-01T00:00:00Z"));
+
+    expect(getUsage("ci", "2026-03")).toEqual({
+      requests: 2,
+      assets: 12,
+      cpuSeconds: 0.5,
+    });
+    expect(usagePeriodEnd(march).toISOString()).toBe(
+      "2026-04-01T00:00:00.000Z",
+    );
+
+    await flushUsage();
+    const saved = JSON.parse(
+      await readFile(join(dataRoot, "data/usage/2026-03.json"), "utf-8"),
+    );
+    expect(saved.ci.assets).toBe(12);
+    clearUsageCache();
+    expect(getUsage("ci", "2026-04").requests).toBe(1);
+  });
+
+  it("parses quotas and falls back to the default policy", () => {
+    const quotas = parseQuotas(
+      '{"default": {"requests": {"soft": 10, "hard": 20}}, "ci": {"assets": {"hard": 5}}}',
+    );
+
+    expect(quotaPolicyFor(quotas, "ci")).toEqual({
+      requests: { soft: 10, hard: 20 },
+      assets: { hard: 5 },
+    });
+    expect(() => parseQuotas('{"ci": {"minutes": {"hard": 5}}}')).toThrow(
+      /unknown metric/,
+    );
+    expect(() => parseQuotas('{"ci": {"assets": {"hard": -1}}}')).toThrow(
+      /positive/,
+    );
+  });
+
+  it("flags soft and hard limits", () => {
+    const [status] = evaluateQuotas(
+      { requests: 15, assets: 0, cpuSeconds: 0 },
+      { requests: { soft: 10, hard: 15 } },
+    );
+
+    expect(status).toMatchObject({ softExceeded: true, hardExceeded: true });
+  });
+
+  describe("meterUsage", () => {
+    const ci: Principal = { id: "ci", role: "estimator", via: "api-key" };
+
+    function app() {
+      const app = new Hono();
+      app.use("/*", async (c, next) => {
+        c.set("principal", ci);
+        return next();
+      });
+      app.use(
+        "/*",
+        meterUsage({
+          quotas: { ci: { requests: { soft: 1, hard: 2 } } },
+        }),
+      );
+      app.get("/estimate", (c) => c.json({ ok: true }));
+      return app;
+    }
+
+    it("reports the request quota and warns past the soft limit", async () => {
+      const server = app();
+      const first = await server.request("/estimate");
+      const second = await server.request("/estimate");
+
+      expect(first.headers.get("X-RateLimit-Limit")).toBe("2");
+      expect(first.headers.get("X-RateLimit-Remaining")).toBe("1");
+      expect(first.headers.get("X-Quota-Warning")).toBeNull();
+      expect(second.headers.get("X-Quota-Warning")).toBe("requests 2/1");
+    });
+
+    it("rejects requests once the hard limit is reached", async () => {
+      recordUsage("ci", { requests: 2 });
+
+      const res = await app().request("/estimate");
+
+      expect(res.status).toBe(429);
+      expect(Number(res.headers.get("Retry-After"))).toBeGreaterThan(0);
+      expect(getUsage("ci", usagePeriod()).requests).toBe(2);
+    });
+  });
+});
@@ -0,0 +1,136 @@
+/**
+ * Per-caller usage accounting, used to charge back compute on the shared
+ * costing service.
+ *
+ * Usage is counted per principal (API key name or token subject) per
+ * calendar month (UTC). Counters are kept in memory and written to
+ * usage/<YYYY-MM>.json in the data directory shortly after they change, so
+ * they survive restarts.
+ */
+
+import { existsSync, mkdirSync, readFileSync } from "fs";
+import { writeFile } from "fs/promises";
+import { join } from "path";
+import { getDataDir } from "../../utils/data-dir";
+
+export const USAGE_METRICS = ["requests", "assets", "cpuSeconds"] as const;
+
+export type UsageMetric = (typeof USAGE_METRICS)[number];
+
+/**
+ * - requests: API requests made
+ * - assets: assets costed by estimates
+ * - cpuSeconds: CPU time spent in this server on the caller's requests,
+ *   plus time the costing server spent computing the caller's estimates
+ */
+export type UsageCounters = Record<UsageMetric, number>;
+
+const SAVE_DELAY_MS = 5000;
+
+const periods = new Map<string, Map<string, UsageCounters>>();
+const dirty = new Set<string>();
+let saveTimer: ReturnType<typeof setTimeout> | undefined;
+
+/**
+ * The accounting period containing `now`, as "YYYY-MM".
+ */
+export function usagePeriod(now: Date = new Date()): string {
+  return now.toISOString().slice(0, 7);
+}
+
+/**
+ * When the period containing `now` ends and counters start again.
+ */
+export function usagePeriodEnd(now: Date = new Date()): Date {
+  return new Date(Date.UTC(now.getUTCFullYear(), now.getUTCMonth() + 1, 1));
+}
+
+function usagePath(period: string): string {
+  return join(getDataDir(), "usage", `${period}.json`);
+}
+
+function periodUsage(period: string): Map<string, UsageCounters> {
+  let usage = periods.get(period);
+  if (!usage) {
+    const path = usagePath(period);
+    const saved: Record<string, UsageCounters> = existsSync(path)
+      ? JSON.parse(readFileSync(path, "utf-8"))
+      : {};
+    usage = new Map(Object.entries(saved));
+    periods.set(period, usage);
+  }
+  return usage;
+}
+
+function emptyCounters(): UsageCounters {
+  return { requests: 0, assets: 0, cpuSeconds: 0 };
+}
+
+/**
+ * Add to a principal's usage in the current period.
+ */
+export function recordUsage(
+  principalId: string,
+  delta: Partial<UsageCounters>,
+  now: Date = new Date(),
+): UsageCounters {
+  const period = usagePeriod(now);
+  const usage = periodUsage(period);
+  const counters = usage.get(principalId) ?? emptyCounters();
+  for (const metric of USAGE_METRICS) {
+    counters[metric] += delta[metric] ?? 0;
+  }
+  usage.set(principalId, counters);
+
+  dirty.add(period);
+  saveTimer ??= setTimeout(() => {
+    saveTimer = undefined;
+    flushUsage().catch((err) => console.error("Failed to save usage:", err));
+  }, SAVE_DELAY_MS);
+  saveTimer.unref?.();
+  return counters;
+}
+
+/**
+ * A principal's usage in a period (default: the current one).
+ */
+export function getUsage(
+  principalId: string,
+  period: string = usagePeriod(),
+): UsageCounters {
+  return { ...(periodUsage(period).get(principalId) ?? emptyCounters()) };
+}
+
+/**
+ * Every principal's usage in a period, by principal ID.
+ */
+export function listUsage(
+  period: string = usagePeriod(),
+): Record<string, UsageCounters> {
+  return Object.fromEntries(periodUsage(period));
+}
+
+/**
+ * Write changed periods to disk.
+ */
+export async function flushUsage(): Promise<void> {
+  const changed = [...dirty];
+  dirty.clear();
+  for (const period of changed) {
+    mkdirSync(join(getDataDir(), "usage"), { recursive: true });
+    await writeFile(
+      usagePath(period),
+      JSON.stringify(listUsage(period), null, 2) + "\n",
+    );
+  }
+}
+
+/**
+ * Forget in-memory usage (useful for testing).
+ */
+export function clearUsageCache(): void {
+  periods.clear();
+  dirty.clear();
+  if (saveTimer) clearTimeout(saveTimer);
+  saveTimer = undefined;
+}
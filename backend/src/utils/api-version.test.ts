@@ -0,0 +1,83 @@
+import { describe, it, expect } from "vitest";
+import { Hono } from "hono";
+import { apiVersioning, parseApiPath } from "./api-version";
+
+describe("api-version", () => {
+  it("splits API paths into version and route", () => {
+    expect(parseApiPath("/api/v2/operations/costing/estimate")).toEqual({
+      version: "v2",
+      route: "/operations/costing/estimate",
+    });
+    expect(parseApiPath("/api/network")).toEqual({ route: "/network" });
+    expect(parseApiPath("/api/v1")).toEqual({ version: "v1", route: "/" });
+    expect(parseApiPath("/health")).toBeUndefined();
+  });
+
+  describe("apiVersioning", () => {
+    const app = new Hono();
+    app.use(
+      "/api/*",
+      apiVersioning({
+        v1: { status: "stable", adapters: [] },
+        v2: {
+          status: "preview",
+          adapters: [
+            {
+              route: "POST /estimates/:id",
+              // v2 calls the currency "currencyCode"
+              request: (body) => {
+                const { currencyCode, ...rest } = body as Record<
+                  string,
+                  unknown
+                >;
+                return { ...rest, currency: currencyCode };
+              },
+              response: (body) => {
+                const { currency, ...rest } = body as Record<string, unknown>;
+                return { ...rest, currencyCode: currency };
+              },
+            },
+          ],
+        },
+      }),
+    );
+    const api = new Hono();
+    api.post("/estimates/:id", async (c) => {
+      const body = await c.req.json();
+      return c.json({ id: c.req.param("id"), currency: body.currency });
+    });
+    for (const prefix of ["/api/v1", "/api/v2", "/api"]) {
+      app.route(prefix, api);
+    }
+
+    const post = (path: string, body: unknown) =>
+      app.request(path, {
+        method: "POST",
+        headers: { "content-type": "application/json" },
+        body: JSON.stringify(body),
+      });
+
+    it("serves unprefixed and v1 paths with the current contract", async () => {
+      for (const path of ["/api/estimates/a", "/api/v1/estimates/a"]) {
+        const res = await post(path, { currency: "EUR" });
+        expect(res.headers.get("API-Version")).toBe("v1");
+        expect(await res.json()).toEqual({ id: "a", currency: "EUR" });
+      }
+    });
+
+    it("adapts requests and responses for other versions", async () => {
+      const res = await post("/api/v2/estimates/a", { currencyCode: "EUR" });
+      expect(res.headers.get("API-Version")).toBe("v2");
+      expect(await res.json()).toEqual({ id: "a", currencyCode: "EUR" });
+    });
+
+    it("rejects unknown versions", async () => {
+      const res = await post("/api/v9/estimates/a", {});
+      expect(res.status).toBe(404);
+      expect(await res.json()).toMatchObject({
+        error: "Unknown API version",
+        supportedVersions: ["v1", "v2"],
+      });
+    });
+  });
+});
@@ -0,0 +1,164 @@
+/**
+ * API versioning.
+ *
+ * The API is served under explicit version prefixes (`/api/v1/...`,
+ * `/api/v2/...`), all routed to the same handlers. Unprefixed `/api/...`
+ * paths are v1, so existing clients keep working.
+ *
+ * Handlers implement the current surface. When a version's contract differs
+ * from it, the version gets adapters that upgrade its request bodies to the
+ * current shape and downgrade JSON responses back to its own, so breaking
+ * changes can land in a new version without touching older clients.
+ */
+
+import type { MiddlewareHandler } from "hono";
+
+// ============================================================================
+// Versions
+// ============================================================================
+
+export type ApiAdapter = {
+  /**
+   * Route the adapter applies to, relative to the version prefix, e.g.
+   * "POST /operations/costing/estimate". `:name` segments match any value.
+   */
+  route: string;
+  /** Convert a JSON request body from this version's shape to the current */
+  request?: (body: unknown) => unknown;
+  /** Convert a JSON response body from the current shape to this version's */
+  response?: (body: unknown, status: number) => unknown;
+};
+
+export type ApiVersionInfo = {
+  /** Preview versions may still change */
+  status: "stable" | "preview";
+  adapters: ApiAdapter[];
+};
+
+export const API_VERSIONS = {
+  v1: { status: "stable", adapters: [] },
+  v2: { status: "preview", adapters: [] },
+} satisfies Record<string, ApiVersionInfo>;
+
+export type ApiVersion = keyof typeof API_VERSIONS;
+
+/** Version served for unprefixed /api paths */
+export const DEFAULT_API_VERSION: ApiVersion = "v1";
+
+declare module "hono" {
+  interface ContextVariableMap {
+    apiVersion: string;
+  }
+}
+
+// ============================================================================
+// Routing
+// ============================================================================
+
+/**
+ * Split an API path into its version and the path within the version.
+ * Returns undefined for paths outside /api.
+ */
+export function parseApiPath(
+  path: string,
+): { version?: string; route: string } | undefined {
+  const match = /^\/api(?:\/(v\d+))?(\/.*)?$/.exec(path);
+  if (!match) return undefined;
+  return {
+    ...(match[1] !== undefined && { version: match[1] }),
+    route: match[2] ?? "/",
+  };
+}
+
+function routeMatches(
+  adapterRoute: string,
+  method: string,
+  path: string,
+): boolean {
+  const [adapterMethod, pattern] = adapterRoute.split(" ");
+  if (adapterMethod !== method) return false;
+  const expected = pattern.split("/");
+  const actual = path.replace(/\/$/, "").split("/");
+  return (
+    expected.length === actual.length &&
+    expected.every((s, i) => s.startsWith(":") || s === actual[i])
+  );
+}
+
+function isJson(contentType: string | null | undefined): boolean {
+  return (
+    !!contentType &&
+    /^application\/(?:[\w.-]+\+)?json/.test(contentType.toLowerCase())
+  );
+}
+
+/**
+ * Resolve the API version of each /api request, reject unknown versions,
+ * and apply the version's adapters. Responses carry an `API-Version`
+ * header.
+ */
+export function apiVersioning(
+  versions: Record<string, ApiVersionInfo> = API_VERSIONS,
+): MiddlewareHandler {
+  return async (c, next) => {
+    const parsed = parseApiPath(c.req.path);
+    if (!parsed) return next();
+
+    const version = parsed.version ?? DEFAULT_API_VERSION;
+    const info = versions[version];
+    if (!info) {
+      return c.json(
+        {
+          error: "Unknown API version",
+          message: `API version ${version} does not exist`,
+          supportedVersions: Object.keys(versions),
+        },
+        404,
+      );
+    }
+    c.set("apiVersion", version);
+
+    const adapters = info.adapters.filter((a) =>
+      routeMatches(a.route, c.req.method, parsed.route),
+    );
+
+    for (const adapter of adapters) {
+      if (!adapter.request || !isJson(c.req.header("content-type"))) continue;
+      const body = await c.req.raw.json().catch(() => undefined);
+      if (body === undefined) break;
+      const headers = new Headers(c.req.raw.headers);
+      headers.delete("content-length");
+      c.req.raw = new Request(c.req.raw.url, {
+        method: c.req.raw.method,
+        headers,
+        body: JSON.stringify(adapter.request(body)),
+      });
+    }
+
+    await next();
+
+    const responseAdapters = adapters.filter((a) => a.response);
+    if (
+      responseAdapters.length > 0 &&
+      isJson(c.res.headers.get("content-type"))
+    ) {
+      let body = await c.res
+        .clone()
+        .json()
+        .catch(() => undefined);
+      if (body !== undefined) {
+        // Apply in reverse so each adapter sees the shape it upgraded to
+        for (const adapter of [...responseAdapters].reverse()) {
+          body = adapter.response!(body, c.res.status);
+        }
+        const headers = new Headers(c.res.headers);
+        headers.delete("content-length");
+        // Clear first so Hono doesn't copy the old headers across
+        const status = c.res.status;
+        c.res = undefined;
+        c.res = new Response(JSON.stringify(body), { status, headers });
+      }
+    }
+    c.header("API-Version", version);
+  };
+}
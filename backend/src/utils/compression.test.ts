@@ -0,0 +1,148 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { Hono } from "hono";
+import { brotliDecompressSync, gzipSync } from "zlib";
+import {
+  compressResponses,
+  decompressRequests,
+  getCompressionStats,
+  negotiateEncoding,
+  resetCompressionStats,
+} from "./compression";
+
+describe("compression", () => {
+  const large = { values: Array.from({ length: 500 }, (_, i) => i) };
+
+  const app = new Hono();
+  app.use("/*", compressResponses({ threshold: 256 }));
+  app.use("/*", decompressRequests());
+  app.get("/large", (c) => c.json(large));
+  app.get("/small", (c) => c.json({ ok: true }));
+  app.post("/echo", async (c) => c.json(await c.req.json()));
+
+  beforeEach(() => {
+    resetCompressionStats();
+  });
+
+  describe("negotiateEncoding", () => {
+    it("prefers brotli and honours q=0", () => {
+      expect(negotiateEncoding("gzip, deflate, br")).toBe("br");
+      expect(negotiateEncoding("gzip, br;q=0")).toBe("gzip");
+      expect(negotiateEncoding("*")).toBe("gzip");
+      expect(negotiateEncoding("gzip;q=0, *")).toBeUndefined();
+      expect(negotiateEncoding("identity")).toBeUndefined();
+      expect(negotiateEncoding(undefined)).toBeUndefined();
+    });
+  });
+
+  it("compresses responses above the threshold", async () => {
+    const res = await app.request("/large", {
+      headers: { "Accept-Encoding": "br" },
+    });
+
+    expect(res.headers.get("content-encoding")).toBe("br");
+    const body = brotliDecompressSync(Buffer.from(await res.arrayBuffer()));
+    expect(JSON.parse(body.toString())).toEqual(large);
+
+    const stats = getCompressionStats();
+    expect(stats.responses).toBe(1);
+    expect(stats.ratio).toBeLessThan(1);
+  });
+
+  it("leaves small responses uncompressed", async () => {
+    const res = await app.request("/small", {
+      headers: { "Accept-Encoding": "gzip" },
+    });
+
+    expect(res.headers.get("content-encoding")).toBeNull();
+    expect(getCompressionStats().ratio).toBeNull();
+  });
+
+  it("varies every compressible response on Accept-Encoding", async () => {
+    for (const [path, acceptEncoding] of [
+      ["/large", "br"],
+      ["/large", "identity"],
+      ["/small", "gzip"],
+    ]) {
+      const res = await app.request(path, {
+        headers: { "Accept-Encoding": acceptEncoding },
+      });
+      expect(res.headers.get("vary")).toBe("Accept-Encoding");
+    }
+  });
+
+  it("decodes gzip request bodies", async () => {
+    const res = await app.request("/echo", {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/json",
+        "Content-Encoding": "gzip",
+      },
+      body: gzipSync(JSON.stringify({ libraryId: "V1.1_working" })),
+    });
+
+    expect(await res.json()).toEqual({ libraryId: "V1.1_working" });
+  });
+
+  it("rejects bodies that decode past the limit", async () => {
+    const limited = new Hono();
+    limited.use("/*", decompressRequests({ maxDecodedBytes: 1024 * 1024 }));
+    limited.post("/echo", async (c) => c.json(await c.req.json()));
+    // 16 MB of zeros gzips to about 16 KB
+    const bomb = gzipSync(Buffer.alloc(16 * 1024 * 1024));
+
+    const res = await limited.request("/echo", {
+      method: "POST",
+      headers: { "Content-Encoding": "gzip" },
+      body: bomb,
+    });
+
+    expect(bomb.byteLength).toBeLessThan(64 * 1024);
+    expect(res.status).toBe(413);
+  });
+
+  it("rejects encoded bodies over the limit", async () => {
+    const limited = new Hono();
+    limited.use("/*", decompressRequests({ maxBodyBytes: 16 }));
+    limited.post("/echo", async (c) => c.json(await c.req.json()));
+
+    const res = await limited.request("/echo", {
+      method: "POST",
+      headers: { "Content-Encoding": "gzip" },
+      body: gzipSync(JSON.stringify({ libraryId: "V1.1_working" })),
+    });
+
+    expect(res.status).toBe(413);
+  });
+
+  it("rejects uncompressed bodies over the limit", async () => {
+    const limited = new Hono();
+    limited.use("/*", decompressRequests({ maxBodyBytes: 16 }));
+    limited.post("/echo", async (c) => c.json(await c.req.json()));
+    const body = JSON.stringify({ libraryId: "V1.1_working" });
+
+    const declared = await limited.request("/echo", {
+      method: "POST",
+      headers: { "Content-Length": String(body.length) },
+      body,
+    });
+    const chunked = await limited.request("/echo", { method: "POST", body });
+    const small = await limited.request("/echo", {
+      method: "POST",
+      body: "{}",
+    });
+
+    expect(declared.status).toBe(413);
+    expect(chunked.status).toBe(413);
+    expect(await small.json()).toEqual({});
+  });
+
+  it("rejects unsupported request encodings", async () => {
+    const res = await app.request("/echo", {
+      method: "POST",
+      headers: { "Content-Encoding": "zstd" },
+      body: "{}",
+    });
+
+    expect(res.status).toBe(415);
+  });
+});
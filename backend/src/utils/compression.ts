@@ -0,0 +1,281 @@
+/**
+ * Request and response body compression.
+ *
+ * Estimate payloads for large networks run to tens of MB of JSON, which
+ * compresses roughly tenfold. Responses above a size threshold are encoded
+ * with brotli or gzip depending on Accept-Encoding, and request bodies sent
+ * with Content-Encoding gzip, deflate or br are decoded before routes read
+ * them. Decoding runs before authentication, so both the body as sent and
+ * its decoded size are capped: a few KB of gzip can otherwise inflate to
+ * gigabytes on the event loop.
+ */
+
+import type { MiddlewareHandler } from "hono";
+import {
+  brotliCompressSync,
+  brotliDecompressSync,
+  constants,
+  gunzipSync,
+  gzipSync,
+  inflateSync,
+} from "zlib";
+
+export type Encoding = "br" | "gzip";
+
+export type CompressionOptions = {
+  /** Minimum response size in bytes worth compressing (default: 1024) */
+  threshold?: number;
+};
+
+export type DecompressionOptions = {
+  /** Largest request body as sent, encoded or not, in bytes (default: 25 MB) */
+  maxBodyBytes?: number;
+  /** Largest decoded request body in bytes (default: 100 MB) */
+  maxDecodedBytes?: number;
+};
+
+export type CompressionStats = {
+  /** Responses sent compressed */
+  responses: number;
+  bytesIn: number;
+  bytesOut: number;
+  /** bytesOut / bytesIn, or null before anything was compressed */
+  ratio: number | null;
+};
+
+const stats = { responses: 0, bytesIn: 0, bytesOut: 0 };
+
+/**
+ * Totals over all responses compressed since startup.
+ */
+export function getCompressionStats(): CompressionStats {
+  return {
+    ...stats,
+    ratio: stats.bytesIn > 0 ? stats.bytesOut / stats.bytesIn : null,
+  };
+}
+
+/**
+ * Reset compression totals (useful for testing).
+ */
+export function resetCompressionStats(): void {
+  stats.responses = 0;
+  stats.bytesIn = 0;
+  stats.bytesOut = 0;
+}
+
+/**
+ * Pick a response encoding from an Accept-Encoding header, preferring brotli.
+ * Encodings with q=0 are treated as refused, and `*` only stands for gzip
+ * when gzip isn't listed itself.
+ */
+export function negotiateEncoding(
+  acceptEncoding: string | undefined,
+): Encoding | undefined {
+  const quality = new Map(
+    (acceptEncoding ?? "")
+      .split(",")
+      .map((part) => part.trim().split(";"))
+      .filter(([name]) => name.trim() !== "")
+      .map(([name, q]) => [
+        name.trim().toLowerCase(),
+        q ? parseFloat(q.trim().replace(/^q=/, "")) : 1,
+      ]),
+  );
+  const accepts = (encoding: string) =>
+    (quality.get(encoding) ?? 0) > 0 ||
+    (!quality.has(encoding) && (quality.get("*") ?? 0) > 0);
+  if ((quality.get("br") ?? 0) > 0) return "br";
+  if (accepts("gzip")) return "gzip";
+  return undefined;
+}
+
+function compress(body: Uint8Array, encoding: Encoding): Uint8Array {
+  return encoding === "br"
+    ? brotliCompressSync(body, {
+        params: {
+          [constants.BROTLI_PARAM_MODE]: constants.BROTLI_MODE_TEXT,
+          [constants.BROTLI_PARAM_QUALITY]: 5,
+        },
+      })
+    : gzipSync(body);
+}
+
+type Decompressor = (
+  body: Uint8Array,
+  options: { maxOutputLength: number },
+) => Uint8Array;
+
+const DECOMPRESSORS: Record<string, Decompressor> = {
+  gzip: gunzipSync,
+  "x-gzip": gunzipSync,
+  deflate: inflateSync,
+  br: brotliDecompressSync,
+};
+
+/** Thrown while reading a body larger than the limit */
+class BodyTooLargeError extends Error {}
+
+/**
+ * Read a request body, giving up as soon as it passes `limit` bytes.
+ */
+async function readBody(request: Request, limit: number): Promise<Uint8Array> {
+  const chunks: Uint8Array[] = [];
+  let length = 0;
+  if (request.body) {
+    for await (const chunk of request.body as AsyncIterable<Uint8Array>) {
+      length += chunk.byteLength;
+      if (length > limit) throw new BodyTooLargeError();
+      chunks.push(chunk);
+    }
+  }
+  const body = new Uint8Array(length);
+  let offset = 0;
+  for (const chunk of chunks) {
+    body.set(chunk, offset);
+    offset += chunk.byteLength;
+  }
+  return body;
+}
+
+/**
+ * Decode request bodies sent with a Content-Encoding. Bodies over
+ * `maxBodyBytes` as sent, encoded or not, or that decode to more than
+ * `maxDecodedBytes`, are rejected with 413.
+ */
+export function decompressRequests(
+  options: DecompressionOptions = {},
+): MiddlewareHandler {
+  const maxBodyBytes = options.maxBodyBytes ?? 25 * 1024 * 1024;
+  const maxDecodedBytes = options.maxDecodedBytes ?? 100 * 1024 * 1024;
+
+  return async (c, next) => {
+    const tooLarge = (limit: number, what: string) =>
+      c.json(
+        {
+          error: "Request body too large",
+          message: `${what} request bodies are limited to ${limit} bytes`,
+        },
+        413,
+      );
+    const contentLength = c.req.header("content-length");
+    const contentEncoding = c.req
+      .header("content-encoding")
+      ?.trim()
+      .toLowerCase();
+    if (!contentEncoding || contentEncoding === "identity") {
+      if (Number(contentLength) > maxBodyBytes) {
+        return tooLarge(maxBodyBytes, "Uncompressed");
+      }
+      // A chunked body has no length to check up front, so read it here
+      if (contentLength === undefined && c.req.raw.body) {
+        let body: Uint8Array;
+        try {
+          body = await readBody(c.req.raw, maxBodyBytes);
+        } catch (error) {
+          if (error instanceof BodyTooLargeError) {
+            return tooLarge(maxBodyBytes, "Uncompressed");
+          }
+          throw error;
+        }
+        c.req.raw = new Request(c.req.raw.url, {
+          method: c.req.raw.method,
+          headers: c.req.raw.headers,
+          body,
+        });
+      }
+    } else {
+      const decompress = DECOMPRESSORS[contentEncoding];
+      if (!decompress) {
+        return c.json(
+          {
+            error: "Unsupported Content-Encoding",
+            message: `Request bodies may be gzip, deflate or br encoded, not ${contentEncoding}`,
+          },
+          415,
+        );
+      }
+      if (Number(contentLength) > maxBodyBytes) {
+        return tooLarge(maxBodyBytes, "Encoded");
+      }
+
+      let body: Uint8Array;
+      try {
+        body = decompress(await readBody(c.req.raw, maxBodyBytes), {
+          maxOutputLength: maxDecodedBytes,
+        });
+      } catch (error) {
+        if (error instanceof BodyTooLargeError) {
+          return tooLarge(maxBodyBytes, "Encoded");
+        }
+        if ((error as NodeJS.ErrnoException).code === "ERR_BUFFER_TOO_LARGE") {
+          return tooLarge(maxDecodedBytes, "Decoded");
+        }
+        return c.json(
+          {
+            error: "Invalid request body",
+            message: `Failed to decode ${contentEncoding} body: ${
+              error instanceof Error ? error.message : String(error)
+            }`,
+          },
+          400,
+        );
+      }
+      const headers = new Headers(c.req.raw.headers);
+      headers.delete("content-encoding");
+      headers.set("content-length", String(body.byteLength));
+      c.req.raw = new Request(c.req.raw.url, {
+        method: c.req.raw.method,
+        headers,
+        body,
+      });
+    }
+
+    await next();
+  };
+}
+
+/**
+ * Compress responses of at least `threshold` bytes for clients that accept
+ * brotli or gzip. Every response that could have been compressed varies on
+ * Accept-Encoding, so caches don't serve one client's encoding to another.
+ */
+export function compressResponses(
+  options: CompressionOptions = {},
+): MiddlewareHandler {
+  const threshold = options.threshold ?? 1024;
+
+  return async (c, next) => {
+    await next();
+
+    const { res } = c;
+    if (
+      !res.body ||
+      res.headers.has("content-encoding") ||
+      res.headers.get("content-type")?.startsWith("text/event-stream")
+    ) {
+      return;
+    }
+    if (!/\baccept-encoding\b/i.test(res.headers.get("vary") ?? "")) {
+      c.header("Vary", "Accept-Encoding", { append: true });
+    }
+
+    const encoding = negotiateEncoding(c.req.header("accept-encoding"));
+    if (!encoding) return;
+    const body = new Uint8Array(await c.res.clone().arrayBuffer());
+    if (body.byteLength < threshold) return;
+
+    const compressed = compress(body, encoding);
+    stats.responses++;
+    stats.bytesIn += body.byteLength;
+    stats.bytesOut += compressed.byteLength;
+
+    const headers = new Headers(c.res.headers);
+    headers.set("content-encoding", encoding);
+    headers.set("content-length", String(compressed.byteLength));
+    // Clear first so Hono doesn't copy the old headers onto the new response
+    const { status } = c.res;
+    c.res = undefined;
+    c.res = new Response(compressed, { status, headers });
+  };
+}
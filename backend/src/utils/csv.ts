@@ -0,0 +1,64 @@
+/**
+ * Minimal RFC 4180 CSV reading and writing.
+ *
+ * Handles quoted fields containing delimiters, quotes and line breaks, which
+ * spreadsheet exports produce for free-text columns.
+ */
+
+/**
+ * Parse delimited text into rows of fields. Blank lines are skipped.
+ */
+export function parseCsv(text: string, delimiter = ","): string[][] {
+  const rows: string[][] = [];
+  let row: string[] = [];
+  let field = "";
+  let inQuotes = false;
+
+  const endRow = () => {
+    row.push(field);
+    if (row.length > 1 || row[0] !== "") rows.push(row);
+    row = [];
+    field = "";
+  };
+
+  for (let i = 0; i < text.length; i++) {
+    const char = text[i];
+
+    if (inQuotes) {
+      if (char === '"' && text[i + 1] === '"') {
+        field += '"';
+        i++;
+      } else if (char === '"') {
+        inQuotes = false;
+      } else {
+        field += char;
+      }
+    } else if (char === '"' && field === "") {
+      inQuotes = true;
+    } else if (char === delimiter) {
+      row.push(field);
+      field = "";
+    } else if (char === "\n" || char === "\r") {
+      if (char === "\r" && text[i + 1] === "\n") i++;
+      endRow();
+    } else {
+      field += char;
+    }
+  }
+
+  if (field !== "" || row.length > 0) endRow();
+  return rows;
+}
+
+/**
+ * Format rows as delimited text, quoting fields where needed.
+ */
+export function formatCsv(rows: unknown[][], delimiter = ","): string {
+  const quote = (value: unknown) => {
+    const text = value === null || value === undefined ? "" : String(value);
+    return text.includes(delimiter) || /["\r\n]/.test(text)
+      ? `"${text.replace(/"/g, '""')}"`
+      : text;
+  };
+  return rows.map((row) => row.map(quote).join(delimiter)).join("\n") + "\n";
+}
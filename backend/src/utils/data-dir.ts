@@ -0,0 +1,23 @@
+/**
+ * Location of the server's writable data: cost libraries, drafts, version
+ * history and the audit log.
+ */
+
+import { resolve } from "path";
+
+/**
+ * The data directory: DATA_DIR if set, otherwise `data` under the working
+ * directory (the backend directory in development).
+ */
+export function getDataDir(): string {
+  return process.env.DATA_DIR
+    ? resolve(process.env.DATA_DIR)
+    : resolve(process.cwd(), "data");
+}
+
+/**
+ * The data bundled with the server, used to seed an empty data directory.
+ */
+export function getBundledDataDir(): string {
+  return resolve(process.cwd(), "data");
+}
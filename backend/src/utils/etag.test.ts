@@ -0,0 +1,36 @@
+import { describe, it, expect } from "vitest";
+import { Hono } from "hono";
+import { etagMatches, notModified } from "./etag";
+
+describe("etag", () => {
+  describe("etagMatches", () => {
+    it("matches listed, weak and wildcard tags", () => {
+      expect(etagMatches('"abc"', '"abc"')).toBe(true);
+      expect(etagMatches('"x", W/"abc"', '"abc"')).toBe(true);
+      expect(etagMatches("*", '"abc"')).toBe(true);
+      expect(etagMatches('"x"', '"abc"')).toBe(false);
+      expect(etagMatches(undefined, '"abc"')).toBe(false);
+    });
+  });
+
+  describe("notModified", () => {
+    const app = new Hono();
+    app.get("/library", (c) => notModified(c, "abc") ?? c.json({ id: 1 }));
+
+    it("serves the body with an ETag", async () => {
+      const res = await app.request("/library");
+
+      expect(res.status).toBe(200);
+      expect(res.headers.get("etag")).toBe('"abc"');
+    });
+
+    it("returns 304 when the cached copy is current", async () => {
+      const res = await app.request("/library", {
+        headers: { "If-None-Match": '"abc"' },
+      });
+
+      expect(res.status).toBe(304);
+      expect(res.headers.get("etag")).toBe('"abc"');
+    });
+  });
+});
@@ -0,0 +1,33 @@
+/**
+ * Conditional GET helpers for content-hashed resources.
+ */
+
+import type { Context } from "hono";
+
+/**
+ * Whether an If-None-Match header matches an entity tag. Weak comparison,
+ * as RFC 9110 requires for If-None-Match.
+ */
+export function etagMatches(
+  ifNoneMatch: string | undefined,
+  etag: string,
+): boolean {
+  if (!ifNoneMatch) return false;
+  if (ifNoneMatch.trim() === "*") return true;
+  const strip = (tag: string) => tag.trim().replace(/^W\//, "");
+  return ifNoneMatch.split(",").some((tag) => strip(tag) === strip(etag));
+}
+
+/**
+ * Set the ETag for a response derived from content with the given hash, and
+ * return a 304 response if the client's cached copy is current.
+ */
+export function notModified(c: Context, hash: string): Response | undefined {
+  const etag = `"${hash}"`;
+  c.header("ETag", etag);
+  c.header("Cache-Control", "no-cache");
+  if (etagMatches(c.req.header("if-none-match"), etag)) {
+    return c.body(null, 304);
+  }
+  return undefined;
+}
@@ -0,0 +1,45 @@
+import { describe, it, expect } from "vitest";
+import { writeParquet, type ParquetColumn } from "./parquet";
+
+const columns: ParquetColumn[] = [
+  { name: "Asset", type: "string" },
+  { name: "Year", type: "int32" },
+  { name: "Value (USD)", type: "double" },
+];
+
+function footer(file: Buffer): Buffer {
+  const length = file.readUInt32LE(file.byteLength - 8);
+  return file.subarray(file.byteLength - 8 - length, file.byteLength - 8);
+}
+
+describe("parquet", () => {
+  it("frames the column pages and footer with the Parquet magic", () => {
+    const file = writeParquet(columns, [
+      ["Capture", 2025, 1000.5],
+      ["Pipe", 2026, 12],
+    ]);
+
+    expect(file.subarray(0, 4).toString()).toBe("PAR1");
+    expect(file.subarray(-4).toString()).toBe("PAR1");
+    for (const { name } of columns) {
+      expect(footer(file).includes(Buffer.from(name))).toBe(true);
+    }
+  });
+
+  it("writes the schema of an empty table", () => {
+    const file = writeParquet(columns, []);
+
+    // Magic, footer, footer length and magic only
+    expect(file.byteLength).toBe(4 + footer(file).byteLength + 8);
+    expect(footer(file).includes(Buffer.from("Value (USD)"))).toBe(true);
+  });
+
+  it("rejects values that don't fit their column", () => {
+    expect(() => writeParquet(columns, [["Capture", 2025.5, 1]])).toThrow(
+      /Year has a non-integer value/,
+    );
+    expect(() => writeParquet(columns, [["Capture", 2025, "n/a"]])).toThrow(
+      /Value \(USD\) has a non-numeric value/,
+    );
+  });
+});
@@ -0,0 +1,292 @@
+/**
+ * Minimal Apache Parquet writing.
+ *
+ * Writes a table of flat, required columns as a single row group with one
+ * gzip-compressed, PLAIN-encoded data page per column, so tools such as
+ * pandas and DuckDB load results with their column types intact. Nested,
+ * optional and dictionary-encoded columns aren't supported.
+ *
+ * The file metadata is Thrift compact protocol, written by hand for the few
+ * structs a file needs (see parquet.thrift in the Parquet format spec).
+ */
+
+import { gzipSync } from "zlib";
+
+export type ParquetType = "string" | "int32" | "double";
+
+export type ParquetColumn = {
+  name: string;
+  type: ParquetType;
+};
+
+const MAGIC = Buffer.from("PAR1");
+
+// parquet.thrift enums
+const PHYSICAL_TYPES: Record<ParquetType, number> = {
+  int32: 1,
+  double: 5,
+  string: 6, // BYTE_ARRAY
+};
+const REQUIRED = 0;
+const CONVERTED_UTF8 = 0;
+const PLAIN = 0;
+const GZIP = 2;
+const DATA_PAGE = 0;
+const RLE = 3;
+
+// ============================================================================
+// Thrift compact protocol
+// ============================================================================
+
+const I32 = 5;
+const I64 = 6;
+const BINARY = 8;
+const LIST = 9;
+const STRUCT = 12;
+
+/**
+ * Writes one Thrift struct in the compact protocol. Fields must be written
+ * in increasing ID order within each struct.
+ */
+class CompactWriter {
+  private readonly bytes: number[] = [];
+  private readonly lastFieldIds: number[] = [0];
+
+  i32(id: number, value: number): this {
+    this.fieldHeader(id, I32);
+    this.zigzag(value);
+    return this;
+  }
+
+  i64(id: number, value: number): this {
+    this.fieldHeader(id, I64);
+    this.zigzag(value);
+    return this;
+  }
+
+  string(id: number, value: string): this {
+    this.fieldHeader(id, BINARY);
+    this.binary(value);
+    return this;
+  }
+
+  struct(id: number, write: (w: CompactWriter) => void = () => {}): this {
+    this.fieldHeader(id, STRUCT);
+    this.nested(write);
+    return this;
+  }
+
+  i32List(id: number, values: number[]): this {
+    this.fieldHeader(id, LIST);
+    this.listHeader(values.length, I32);
+    values.forEach((value) => this.zigzag(value));
+    return this;
+  }
+
+  stringList(id: number, values: string[]): this {
+    this.fieldHeader(id, LIST);
+    this.listHeader(values.length, BINARY);
+    values.forEach((value) => this.binary(value));
+    return this;
+  }
+
+  structList<T>(
+    id: number,
+    items: T[],
+    write: (w: CompactWriter, item: T) => void,
+  ): this {
+    this.fieldHeader(id, LIST);
+    this.listHeader(items.length, STRUCT);
+    items.forEach((item) => this.nested((w) => write(w, item)));
+    return this;
+  }
+
+  /** End the top-level struct and return its bytes */
+  finish(): Buffer {
+    this.bytes.push(0);
+    return Buffer.from(this.bytes);
+  }
+
+  private nested(write: (w: CompactWriter) => void) {
+    this.lastFieldIds.push(0);
+    write(this);
+    this.bytes.push(0);
+    this.lastFieldIds.pop();
+  }
+
+  private fieldHeader(id: number, type: number) {
+    const delta = id - this.lastFieldIds[this.lastFieldIds.length - 1];
+    if (delta > 0 && delta <= 15) {
+      this.bytes.push((delta << 4) | type);
+    } else {
+      this.bytes.push(type);
+      this.zigzag(id);
+    }
+    this.lastFieldIds[this.lastFieldIds.length - 1] = id;
+  }
+
+  private listHeader(size: number, type: number) {
+    if (size < 15) {
+      this.bytes.push((size << 4) | type);
+    } else {
+      this.bytes.push(0xf0 | type);
+      this.varint(size);
+    }
+  }
+
+  private binary(value: string) {
+    const bytes = Buffer.from(value, "utf-8");
+    this.varint(bytes.byteLength);
+    bytes.forEach((byte) => this.bytes.push(byte));
+  }
+
+  private zigzag(value: number) {
+    this.varint(value >= 0 ? value * 2 : -value * 2 - 1);
+  }
+
+  // Arithmetic rather than bit shifts, so i64 values past 2^31 survive
+  private varint(value: number) {
+    while (value >= 0x80) {
+      this.bytes.push((value % 0x80) | 0x80);
+      value = Math.floor(value / 0x80);
+    }
+    this.bytes.push(value);
+  }
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/**
+ * PLAIN encoding of a column's values: little-endian numbers, and strings
+ * as a 4-byte length followed by their UTF-8 bytes.
+ *
+ * @throws Error if a value doesn't fit the column's type
+ */
+function plainValues(
+  column: ParquetColumn,
+  values: Array<string | number>,
+): Buffer {
+  const number = (value: string | number) => {
+    if (typeof value !== "number") {
+      throw new Error(`Column ${column.name} has a non-numeric value`);
+    }
+    return value;
+  };
+
+  switch (column.type) {
+    case "int32": {
+      const data = Buffer.alloc(values.length * 4);
+      values.forEach((value, i) => {
+        if (!Number.isInteger(number(value))) {
+          throw new Error(`Column ${column.name} has a non-integer value`);
+        }
+        data.writeInt32LE(value as number, i * 4);
+      });
+      return data;
+    }
+    case "double": {
+      const data = Buffer.alloc(values.length * 8);
+      values.forEach((value, i) => data.writeDoubleLE(number(value), i * 8));
+      return data;
+    }
+    case "string":
+      return Buffer.concat(
+        values.flatMap((value) => {
+          const bytes = Buffer.from(String(value), "utf-8");
+          const length = Buffer.alloc(4);
+          length.writeUInt32LE(bytes.byteLength);
+          return [length, bytes];
+        }),
+      );
+  }
+}
+
+/**
+ * A column's schema element. Strings are marked UTF-8 so readers decode
+ * them as text rather than bytes.
+ */
+function columnSchema(w: CompactWriter, column: ParquetColumn) {
+  w.i32(1, PHYSICAL_TYPES[column.type])
+    .i32(3, REQUIRED)
+    .string(4, column.name);
+  if (column.type === "string") {
+    w.i32(6, CONVERTED_UTF8).struct(10, (logical) => logical.struct(1));
+  }
+}
+
+/**
+ * Write rows as a Parquet file. Every row has one value per column, in
+ * column order.
+ *
+ * @throws Error if a value doesn't fit its column's type
+ */
+export function writeParquet(
+  columns: ParquetColumn[],
+  rows: Array<Array<string | number>>,
+): Buffer {
+  const parts: Buffer[] = [MAGIC];
+  let offset = MAGIC.byteLength;
+
+  const chunks = rows.length
+    ? columns.map((column, c) => {
+        const data = plainValues(column, rows.map((row) => row[c]));
+        const compressed = gzipSync(data);
+        const header = new CompactWriter()
+          .i32(1, DATA_PAGE)
+          .i32(2, data.byteLength)
+          .i32(3, compressed.byteLength)
+          .struct(5, (w) =>
+            w.i32(1, rows.length).i32(2, PLAIN).i32(3, RLE).i32(4, RLE),
+          )
+          .finish();
+        parts.push(header, compressed);
+        const chunk = {
+          column,
+          pageOffset: offset,
+          uncompressedSize: header.byteLength + data.byteLength,
+          compressedSize: header.byteLength + compressed.byteLength,
+        };
+        offset += chunk.compressedSize;
+        return chunk;
+      })
+    : [];
+
+  const footer = new CompactWriter()
+    .i32(1, 1)
+    // The schema is the root element followed by the columns
+    .structList(2, [undefined, ...columns], (w, column) =>
+      column
+        ? columnSchema(w, column)
+        : w.string(4, "schema").i32(5, columns.length),
+    )
+    .i64(3, rows.length)
+    .structList(4, chunks.length ? [chunks] : [], (w, rowGroup) =>
+      w
+        .structList(1, rowGroup, (w, chunk) =>
+          w.i64(2, chunk.pageOffset).struct(3, (meta) =>
+            meta
+              .i32(1, PHYSICAL_TYPES[chunk.column.type])
+              .i32List(2, [PLAIN])
+              .stringList(3, [chunk.column.name])
+              .i32(4, GZIP)
+              .i64(5, rows.length)
+              .i64(6, chunk.uncompressedSize)
+              .i64(7, chunk.compressedSize)
+              .i64(9, chunk.pageOffset),
+          ),
+        )
+        .i64(
+          2,
+          rowGroup.reduce((sum, chunk) => sum + chunk.uncompressedSize, 0),
+        )
+        .i64(3, rows.length),
+    )
+    .finish();
+
+  const footerLength = Buffer.alloc(4);
+  footerLength.writeUInt32LE(footer.byteLength);
+  parts.push(footer, footerLength, MAGIC);
+  return Buffer.concat(parts);
+}
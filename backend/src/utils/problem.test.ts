@@ -0,0 +1,81 @@
+import { describe, it, expect } from "vitest";
+import { Hono } from "hono";
+import { problemJson, toProblem } from "./problem";
+
+describe("problem", () => {
+  describe("toProblem", () => {
+    it("classifies known route errors and keeps the body", () => {
+      const body = {
+        error: "No costable assets found",
+        message: "The network contains no blocks",
+        assetCount: 0,
+      };
+
+      expect(toProblem(body, 400, "/api/operations/costing/estimate")).toEqual({
+        type: "/problems/missing-properties",
+        title: "Network has no costable assets",
+        status: 400,
+        detail: "The network contains no blocks",
+        instance: "/api/operations/costing/estimate",
+        extensions: body,
+      });
+    });
+
+    it("falls back to the status for unknown errors", () => {
+      expect(toProblem({ error: "Asset not found" }, 404)).toMatchObject({
+        type: "/problems/not-found",
+        detail: "Asset not found",
+      });
+      expect(toProblem({ error: "Query failed" }, 500).type).toBe(
+        "/problems/internal-error",
+      );
+    });
+
+    it("tells currency errors from other invalid requests", () => {
+      expect(
+        toProblem(
+          {
+            error: "Invalid currency",
+            message: "The library has no exchange rate for XYZ",
+          },
+          400,
+        ),
+      ).toMatchObject({
+        type: "/problems/unknown-currency",
+        title: "Unknown currency",
+      });
+    });
+  });
+
+  describe("problemJson", () => {
+    const app = new Hono();
+    app.use("/*", problemJson());
+    app.get("/library", (c) =>
+      c.json({ error: "Failed to get library", message: "ENOENT" }, 404),
+    );
+    app.get("/import", (c) => c.json({ isValid: false }, 422));
+    app.get("/ok", (c) => c.json({ error: "not an error" }));
+
+    it("rewrites error responses as problem+json", async () => {
+      const res = await app.request("/library");
+
+      expect(res.status).toBe(404);
+      expect(res.headers.get("content-type")).toBe("application/problem+json");
+      expect(await res.json()).toMatchObject({
+        type: "/problems/library-not-found",
+        status: 404,
+        detail: "ENOENT",
+        instance: "/library",
+        extensions: { error: "Failed to get library" },
+      });
+    });
+
+    it("leaves other responses untouched", async () => {
+      const invalid = await app.request("/import");
+      expect(await invalid.json()).toEqual({ isValid: false });
+
+      const ok = await app.request("/ok");
+      expect(ok.headers.get("content-type")).toMatch(/^application\/json/);
+    });
+  });
+});
@@ -0,0 +1,165 @@
+/**
+ * RFC 7807 problem details for error responses.
+ *
+ * Routes keep returning `{ error, message, ... }` bodies; the problemJson
+ * middleware rewrites every such error response into
+ * `application/problem+json` with a stable `type` per error kind, and keeps
+ * the original body under `extensions` so existing fields stay available.
+ */
+
+import type { MiddlewareHandler } from "hono";
+
+// ============================================================================
+// Error Kinds
+// ============================================================================
+
+export const PROBLEM_KINDS = {
+  "invalid-request": "Invalid request",
+  "missing-properties": "Network has no costable assets",
+  "library-not-found": "Cost library not found",
+  "unknown-currency": "Unknown currency",
+  "not-found": "Not found",
+  unauthorized: "Unauthorized",
+  conflict: "Conflict",
+  "validation-failed": "Validation failed",
+  forbidden: "Forbidden",
+  timeout: "Estimate timed out",
+  "quota-exceeded": "Quota exceeded",
+  "costing-server-error": "Costing server error",
+  "costing-server-unavailable": "Costing server unavailable",
+  "snapshot-server-error": "Snapshot server error",
+  "snapshot-server-unavailable": "Snapshot server unavailable",
+  "internal-error": "Internal error",
+} as const;
+
+export type ProblemKind = keyof typeof PROBLEM_KINDS;
+
+/** Route error titles whose kind can't be told from the status alone */
+const KIND_BY_ERROR: Record<string, ProblemKind> = {
+  "No costable assets found": "missing-properties",
+  "Failed to get library": "library-not-found",
+  "Failed to get library defaults": "library-not-found",
+  "Invalid currency": "unknown-currency",
+  "Costing server error": "costing-server-error",
+  "Costing server unavailable": "costing-server-unavailable",
+  "Snapshot server error": "snapshot-server-error",
+  "Snapshot server unavailable": "snapshot-server-unavailable",
+};
+
+const KIND_BY_STATUS: Record<number, ProblemKind> = {
+  400: "invalid-request",
+  401: "unauthorized",
+  403: "forbidden",
+  404: "not-found",
+  408: "timeout",
+  409: "conflict",
+  415: "invalid-request",
+  422: "validation-failed",
+  429: "quota-exceeded",
+};
+
+export const PROBLEM_CONTENT_TYPE = "application/problem+json";
+
+/**
+ * The `type` URI for an error kind. Relative, so it resolves against
+ * whichever host served the response.
+ */
+export function problemType(kind: ProblemKind): string {
+  return `/problems/${kind}`;
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+export type ProblemDetails = {
+  type: string;
+  title: string;
+  status: number;
+  detail?: string;
+  instance?: string;
+  /** The route's original error body */
+  extensions: Record<string, unknown>;
+};
+
+/**
+ * Classify a route error body by its `error` title, falling back to the
+ * HTTP status.
+ */
+export function problemKind(
+  body: { error: string },
+  status: number,
+): ProblemKind {
+  return (
+    KIND_BY_ERROR[body.error] ??
+    KIND_BY_STATUS[status] ??
+    (status === 502 ? "costing-server-error" : "internal-error")
+  );
+}
+
+/**
+ * Convert a route error body (`{ error, message?, ... }`) to problem details.
+ */
+export function toProblem(
+  body: { error: string } & Record<string, unknown>,
+  status: number,
+  instance?: string,
+): ProblemDetails {
+  const kind = problemKind(body, status);
+  return {
+    type: problemType(kind),
+    title: PROBLEM_KINDS[kind],
+    status,
+    detail: typeof body.message === "string" ? body.message : body.error,
+    ...(instance !== undefined && { instance }),
+    extensions: body,
+  };
+}
+
+function isRouteError(
+  body: unknown,
+): body is { error: string } & Record<string, unknown> {
+  return (
+    typeof body === "object" &&
+    body !== null &&
+    typeof (body as Record<string, unknown>).error === "string"
+  );
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/**
+ * Rewrite JSON error responses (status >= 400 with a string `error` field)
+ * as problem+json. Other responses pass through untouched.
+ */
+export function problemJson(): MiddlewareHandler {
+  return async (c, next) => {
+    await next();
+
+    const { res } = c;
+    if (
+      res.status < 400 ||
+      !res.headers.get("content-type")?.startsWith("application/json")
+    ) {
+      return;
+    }
+
+    const body = await res
+      .clone()
+      .json()
+      .catch(() => undefined);
+    if (!isRouteError(body)) return;
+
+    const headers = new Headers(res.headers);
+    headers.set("content-type", PROBLEM_CONTENT_TYPE);
+    headers.delete("content-length");
+    // Clear first so Hono doesn't copy the old headers onto the new response
+    c.res = undefined;
+    c.res = new Response(
+      JSON.stringify(toProblem(body, res.status, c.req.path)),
+      { status: res.status, headers },
+    );
+  };
+}
@@ -0,0 +1,69 @@
+/**
+ * Request logging.
+ *
+ * Writes one line per API request with the method, path, status, duration
+ * and the authenticated user, so activity can be traced back to a person.
+ * Lines go to stdout, or are appended to REQUEST_LOG_FILE (relative to the
+ * data directory) when set. Disabled with REQUEST_LOG=off.
+ */
+
+import { createWriteStream, mkdirSync } from "fs";
+import { dirname, resolve } from "path";
+import type { MiddlewareHandler } from "hono";
+import { getDataDir } from "./data-dir";
+
+export type RequestLogEntry = {
+  method: string;
+  path: string;
+  status: number;
+  durationMs: number;
+  /** Principal ID, or undefined if the request was rejected before auth */
+  user?: string;
+  via?: string;
+};
+
+export function formatRequestLog(entry: RequestLogEntry): string {
+  const user = entry.user ? ` user=${entry.user} via=${entry.via}` : "";
+  return `${entry.method} ${entry.path} ${entry.status} ${entry.durationMs}ms${user}`;
+}
+
+function defaultWriter(): (line: string) => void {
+  const file = process.env.REQUEST_LOG_FILE;
+  if (!file) return console.log;
+
+  const path = resolve(getDataDir(), file);
+  mkdirSync(dirname(path), { recursive: true });
+  const stream = createWriteStream(path, { flags: "a" });
+  return (line) => {
+    stream.write(`${new Date().toISOString()} ${line}\n`);
+  };
+}
+
+/**
+ * Log each request once it completes. Register before `authenticate`; the
+ * principal it sets is read after the request has been handled.
+ */
+export function requestLog(
+  write?: (line: string) => void,
+): MiddlewareHandler {
+  if (process.env.REQUEST_LOG === "off") {
+    return (_c, next) => next();
+  }
+  const log = write ?? defaultWriter();
+
+  return async (c, next) => {
+    const start = performance.now();
+    await next();
+    const principal = c.get("principal");
+    log(
+      formatRequestLog({
+        method: c.req.method,
+        path: c.req.path,
+        status: c.res.status,
+        durationMs: Math.round(performance.now() - start),
+        user: principal?.id,
+        via: principal?.via,
+      }),
+    );
+  };
+}
@@ -0,0 +1,52 @@
+import { describe, it, expect, vi, afterEach } from "vitest";
+import { mkdtemp, rm, writeFile } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import { decodeText, readTextFile } from "./text-file";
+
+const toml = 'label = "Compressor à vis"\n';
+
+describe("text-file", () => {
+  afterEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it("decodes UTF-8 with and without a byte order mark", () => {
+    const utf8 = new TextEncoder().encode(toml);
+    expect(decodeText(utf8)).toEqual({ text: toml, encoding: "utf-8" });
+    expect(decodeText(new Uint8Array([0xef, 0xbb, 0xbf, ...utf8]))).toEqual({
+      text: toml,
+      encoding: "utf-8-bom",
+    });
+  });
+
+  it("decodes UTF-16 by its byte order mark", () => {
+    const le = Buffer.concat([
+      Buffer.from([0xff, 0xfe]),
+      Buffer.from(toml, "utf16le"),
+    ]);
+    expect(decodeText(le)).toEqual({ text: toml, encoding: "utf-16le" });
+  });
+
+  it("falls back to Windows-1252 for invalid UTF-8", () => {
+    // "à" is 0xe0 and "€" is 0x80 in Windows-1252
+    const bytes = Buffer.from('label = "Compressor \xe0 vis \x80"\n', "latin1");
+    expect(decodeText(bytes)).toEqual({
+      text: 'label = "Compressor à vis €"\n',
+      encoding: "windows-1252",
+    });
+  });
+
+  it("warns when a file isn't plain UTF-8", async () => {
+    const dir = await mkdtemp(join(tmpdir(), "text-file-"));
+    const warn = vi.spyOn(console, "warn").mockImplementation(() => {});
+    try {
+      const file = join(dir, "branch-1.toml");
+      await writeFile(file, "\ufeff" + toml);
+      expect(await readTextFile(file)).toBe(toml);
+      expect(warn).toHaveBeenCalledWith(expect.stringContaining("utf-8-bom"));
+    } finally {
+      await rm(dir, { recursive: true, force: true });
+    }
+  });
+});
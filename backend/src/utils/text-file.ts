@@ -0,0 +1,65 @@
+/**
+ * Reading hand-edited text files whatever their encoding.
+ *
+ * Network TOML files edited on Windows arrive as UTF-8 with a byte order
+ * mark, UTF-16, or Windows-1252. Reading them as plain UTF-8 leaves a BOM in
+ * front of the first key or replaces accented characters, so the parser
+ * fails or reads wrong values. Files are decoded to a string based on their
+ * BOM, falling back to Windows-1252 when they aren't valid UTF-8.
+ */
+
+import { readFile } from "fs/promises";
+
+export type TextEncoding = "utf-8" | "utf-8-bom" | "utf-16le" | "utf-16be";
+
+export type DecodedText = {
+  text: string;
+  encoding: TextEncoding | "windows-1252";
+};
+
+/**
+ * Decode file contents to a string, stripping any byte order mark.
+ */
+export function decodeText(bytes: Uint8Array): DecodedText {
+  if (bytes[0] === 0xef && bytes[1] === 0xbb && bytes[2] === 0xbf) {
+    return {
+      text: new TextDecoder("utf-8").decode(bytes.subarray(3)),
+      encoding: "utf-8-bom",
+    };
+  }
+  if (bytes[0] === 0xff && bytes[1] === 0xfe) {
+    return {
+      text: new TextDecoder("utf-16le").decode(bytes.subarray(2)),
+      encoding: "utf-16le",
+    };
+  }
+  if (bytes[0] === 0xfe && bytes[1] === 0xff) {
+    return {
+      text: new TextDecoder("utf-16be").decode(bytes.subarray(2)),
+      encoding: "utf-16be",
+    };
+  }
+  try {
+    return {
+      text: new TextDecoder("utf-8", { fatal: true }).decode(bytes),
+      encoding: "utf-8",
+    };
+  } catch {
+    // Not UTF-8: assume the Windows default code page
+    return {
+      text: new TextDecoder("windows-1252").decode(bytes),
+      encoding: "windows-1252",
+    };
+  }
+}
+
+/**
+ * Read a text file as a string, warning when it isn't plain UTF-8.
+ */
+export async function readTextFile(path: string): Promise<string> {
+  const { text, encoding } = decodeText(await readFile(path));
+  if (encoding !== "utf-8") {
+    console.warn(`${path} is encoded as ${encoding}; decoded to UTF-8`);
+  }
+  return text;
+}
@@ -0,0 +1,8 @@
+/**
+ * Service identity reported by /health and /version.
+ *
+ * Keep VERSION in sync with package.json - desktop hosts compare it against
+ * the compatibility range they ship with.
+ */
+export const SERVICE_NAME = "dagger-api";
+export const VERSION = "0.1.0";
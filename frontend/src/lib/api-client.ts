@@ -145,7 +145,8 @@ export async function getNetwork(networkId: string): Promise<NetworkResponse> {
         status: response.status,
       }));
       throw new Error(
-        error.message ||
+        error.detail ||
+          error.message ||
           error.error ||
           `Request failed with status ${response.status}`,
       );
@@ -177,7 +178,8 @@ export async function getNetworkNodes(networkId: string, nodeType?: string) {
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -208,7 +210,8 @@ export async function getNetworkEdges(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -252,7 +255,8 @@ export async function getAvailablePresets(): Promise<
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -44,7 +44,8 @@ export async function runCostingEstimate(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -83,7 +84,8 @@ export async function validateCostingNetworkWithSource(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -117,7 +119,8 @@ export async function listCostLibraries(): Promise<CostLibrary[]> {
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -146,7 +149,8 @@ export async function getCostLibraryTypes(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -174,7 +178,8 @@ export async function getCostLibraryModules(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -340,7 +345,8 @@ export async function validateSnapshotNetwork(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -393,7 +399,8 @@ export async function runSnapshot(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
@@ -425,7 +432,8 @@ export async function runSnapshotRaw(
       status: response.status,
     }));
     throw new Error(
-      error.message ||
+      error.detail ||
+        error.message ||
         error.error ||
         `Request failed with status ${response.status}`,
     );
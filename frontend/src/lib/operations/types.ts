@@ -302,9 +302,15 @@ export type LifetimeCosts = {
   directEquipmentCost: number;
   langFactoredCapitalCost: LangFactoredCosts;
   totalInstalledCost: number;
+  totalInstalledCostExclContingency: number;
+  totalInstalledCostInclContingency: number;
+  /** Contingency included in total */
+  contingencyApplied: number;
   fixedOpexCost: FixedOpexCosts;
   variableOpexCost: VariableOpexCosts;
   decommissioningCost: number;
+  /** Sum of installed cost, OPEX and decommissioning */
+  total: number;
 };
 
 /**